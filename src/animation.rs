@@ -0,0 +1,264 @@
+//! Plain text animations of 2-dimensional games of life
+
+use crate::life::Life;
+use crate::region::Region;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// ANSI codes moving the cursor home and clearing the screen.
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// ANSI colours of alive cells by age: bright red when just born, then bright yellow, green, cyan and finally blue.
+const AGE_COLOURS: [(u64, &str); 5] = [(0, "91"), (1, "93"), (3, "32"), (7, "36"), (u64::MAX, "34")];
+
+/// ANSI colour of the dead cells that recently died, dark grey.
+const DECAY_COLOUR: &str = "90";
+
+/// The number of generations a dead cell keeps the decay colour.
+const DECAY_GENERATIONS: u64 = 2;
+
+/// Writer of successive frames of a viewport as plain text, turning a terminal or a log file into an animation
+///
+/// Every frame starts with a `!Generation: N` line followed by the rows of the viewport, top row first.
+/// With [set_clear_screen](Self::set_clear_screen), each frame is preceded by ANSI codes clearing the terminal,
+/// so the frames replace each other instead of scrolling. With [set_age_colours](Self::set_age_colours), cells are coloured
+/// with ANSI codes by the number of generations they have been alive, and recently died cells are dark grey,
+/// so still lifes settle into blue while oscillators flash and spaceships leave a trail.
+/// # Example
+/// ```
+/// use ndlife::animation::AsciiAnimator;
+/// use ndlife::life::conways_game_of_life;
+/// use std::time::Duration;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut animator = AsciiAnimator::new(Vec::new(), [0..=2, -1..=1]);
+/// animator.animate(&mut life, 1, Duration::ZERO).unwrap();
+/// assert_eq!(animator.frames(), 2);
+///
+/// let text = String::from_utf8(animator.into_inner()).unwrap();
+/// assert_eq!(text, "!Generation: 0\n...\nOOO\n...\n!Generation: 1\n.O.\n.O.\n.O.\n");
+/// ```
+#[derive(Debug)]
+pub struct AsciiAnimator<W: Write> {
+    /// The output the frames are written to.
+    output: W,
+    /// The viewport of the frames.
+    region: Region<2>,
+    /// Whether every frame clears the screen first.
+    clear_screen: bool,
+    /// The characters of alive and dead cells.
+    characters: (char, char),
+    /// The number of written frames.
+    frames: u64,
+    /// Whether cells are coloured by age.
+    age_colours: bool,
+    /// The generation since which each alive cell was seen alive in the frames.
+    births: HashMap<[i64; 2], u64>,
+    /// The generation at which each recently died cell was first seen dead.
+    deaths: HashMap<[i64; 2], u64>,
+}
+impl<W: Write> AsciiAnimator<W> {
+    /// Create an animator writing frames of a viewport with `O` for alive cells and `.` for dead cells, without clearing the screen.
+    /// # Arguments
+    /// * `output` - The output to write the frames to, such as the standard output or a file.
+    /// * `region` - The viewport, such as the inclusive range of coordinates along each axis.
+    pub fn new(output: W, region: impl Into<Region<2>>) -> Self {
+        Self {
+            output,
+            region: region.into(),
+            clear_screen: false,
+            characters: ('O', '.'),
+            frames: 0,
+            age_colours: false,
+            births: HashMap::new(),
+            deaths: HashMap::new(),
+        }
+    }
+
+    /// Get the viewport of the frames.
+    pub fn region(&self) -> Region<2> {
+        self.region
+    }
+
+    /// Set the viewport of the next frames.
+    pub fn set_region(&mut self, region: impl Into<Region<2>>) {
+        self.region = region.into();
+    }
+
+    /// Set whether every frame clears the screen with ANSI codes first.
+    pub fn set_clear_screen(&mut self, clear_screen: bool) {
+        self.clear_screen = clear_screen;
+    }
+
+    /// Set the characters of alive and dead cells.
+    pub fn set_characters(&mut self, alive: char, dead: char) {
+        self.characters = (alive, dead);
+    }
+
+    /// Set whether cells are coloured with ANSI codes by age, which terminals show but text files do not.
+    ///
+    /// Ages are counted from the frame a cell was first seen alive, so frames should be written every generation
+    /// for the ages to be exact; cells alive when the colours are enabled count as just born.
+    pub fn set_age_colours(&mut self, age_colours: bool) {
+        self.age_colours = age_colours;
+        self.births.clear();
+        self.deaths.clear();
+    }
+
+    /// Get the number of written frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Write a frame of the current generation and flush the output.
+    /// # Arguments
+    /// * `life` - The game of life to draw.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn write_frame(&mut self, life: &Life<2>) -> io::Result<()> {
+        let (alive, dead) = self.characters;
+        let mut frame = String::new();
+        if self.clear_screen {
+            frame.push_str(CLEAR_SCREEN);
+        }
+        frame.push_str(&format!("!Generation: {}\n", life.age()));
+        if self.age_colours {
+            self.track_ages(life);
+        }
+        for (first, row) in life.view(self.region).rows() {
+            if !self.age_colours {
+                frame.extend(row.map(|state| if state { alive } else { dead }));
+                frame.push('\n');
+                continue;
+            }
+            let mut current = "0";
+            for (x, state) in (first[0]..).zip(row) {
+                let colour = self.colour([x, first[1]], state, life.age());
+                if colour != current {
+                    frame.push_str(&format!("\x1b[{}m", colour));
+                    current = colour;
+                }
+                frame.push(if state { alive } else { dead });
+            }
+            if current != "0" {
+                frame.push_str("\x1b[0m");
+            }
+            frame.push('\n');
+        }
+        self.output.write_all(frame.as_bytes())?;
+        self.output.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Write a frame of the current generation, then advance and write a frame after each generation.
+    /// # Arguments
+    /// * `life` - The game of life to animate.
+    /// * `generations` - The number of generations to advance.
+    /// * `delay` - How long to wait before each generation, zero for no waiting.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn animate(&mut self, life: &mut Life<2>, generations: u64, delay: Duration) -> io::Result<()> {
+        self.write_frame(life)?;
+        for _ in 0..generations {
+            if !delay.is_zero() {
+                thread::sleep(delay);
+            }
+            life.next_generation();
+            self.write_frame(life)?;
+        }
+        Ok(())
+    }
+
+    /// Update the generations since which the cells are alive or dead with the current generation.
+    fn track_ages(&mut self, life: &Life<2>) {
+        let age = life.age();
+        let births = life.alive_cells().iter().map(|cell| (*cell, self.births.get(cell).copied().unwrap_or(age))).collect();
+        for (cell, _) in std::mem::replace(&mut self.births, births) {
+            if !self.births.contains_key(&cell) {
+                self.deaths.insert(cell, age);
+            }
+        }
+        self.deaths
+            .retain(|cell, died| !self.births.contains_key(cell) && age.saturating_sub(*died) < DECAY_GENERATIONS);
+    }
+
+    /// Get the ANSI colour of a cell, `0` for the default colour.
+    fn colour(&self, cell: [i64; 2], state: bool, age: u64) -> &'static str {
+        if state {
+            let alive_for = self.births.get(&cell).map_or(0, |born| age.saturating_sub(*born));
+            AGE_COLOURS.iter().find(|(max_age, _)| alive_for <= *max_age).map_or("0", |(_, colour)| colour)
+        } else if self.deaths.contains_key(&cell) {
+            DECAY_COLOUR
+        } else {
+            "0"
+        }
+    }
+
+    /// Get the output back, consuming the animator.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_write_frame() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 1]].into_iter().collect());
+        let mut animator = AsciiAnimator::new(Vec::new(), [0..=1, 0..=1]);
+        animator.set_clear_screen(true);
+        animator.set_characters('#', ' ');
+        animator.write_frame(&life).unwrap();
+        animator.set_clear_screen(false);
+        animator.set_region([1..=1, 1..=1]);
+        assert_eq!(animator.region(), Region::new([1, 1], [1, 1]));
+        animator.write_frame(&life).unwrap();
+        assert_eq!(
+            String::from_utf8(animator.into_inner()).unwrap(),
+            "\x1b[H\x1b[2J!Generation: 0\n #\n# \n!Generation: 0\n#\n"
+        );
+    }
+
+    #[test]
+    fn test_age_colours() {
+        let mut life = conways_game_of_life();
+        // block and blinker
+        life.set_alive_cells([[0, 0], [0, 1], [1, 0], [1, 1], [4, 0], [5, 0], [6, 0]].into_iter().collect());
+        let mut animator = AsciiAnimator::new(Vec::new(), [0..=6, 0..=0]);
+        animator.set_age_colours(true);
+        animator.animate(&mut life, 8, Duration::ZERO).unwrap();
+        let text = String::from_utf8(animator.into_inner()).unwrap();
+        let rows: Vec<&str> = text.lines().skip(1).step_by(2).collect();
+        assert_eq!(rows[0], "\x1b[91mOO\x1b[0m..\x1b[91mOOO\x1b[0m");
+        assert_eq!(rows[1], "\x1b[93mOO\x1b[0m..\x1b[90m.\x1b[93mO\x1b[90m.\x1b[0m");
+        assert_eq!(rows[2], "\x1b[32mOO\x1b[0m..\x1b[91mO\x1b[32mO\x1b[91mO\x1b[0m");
+        assert_eq!(rows[8], "\x1b[34mOO\x1b[0m..\x1b[91mO\x1b[34mO\x1b[91mO\x1b[0m");
+    }
+
+    #[test]
+    fn test_write_error() {
+        struct Failing;
+        impl Write for Failing {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut life = conways_game_of_life();
+        let mut animator = AsciiAnimator::new(Failing, [0..=1, 0..=1]);
+        assert_eq!(animator.animate(&mut life, 5, Duration::ZERO).unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(animator.frames(), 0);
+        assert_eq!(life.age(), 0);
+    }
+}
@@ -0,0 +1,411 @@
+//! Interactive terminal viewer of 2-dimensional patterns, enabled by the `tui` feature
+//!
+//! The terminal is switched to raw mode with `stty`, so the viewer runs on Unix-like systems.
+
+use ndlife::life::Life;
+use ndlife::pattern::Pattern;
+use ndlife::region::Region;
+use ndlife::ticker::{CatchUp, Ticker};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Usage of the viewer.
+const USAGE: &str = "\
+Usage: ndlife-tui [-r <RULESTRING>] [PATTERN]
+
+Explore a 2-dimensional pattern in plaintext format interactively.
+
+Keys:
+  space          play / pause
+  n              advance one generation
+  + / -          double / halve the speed
+  arrows, hjkl   move the cursor (the view follows it)
+  HJKL           pan the view by 8 cells
+  t, enter       toggle the cell under the cursor
+  c              centre the view on the cursor
+  q              quit";
+
+/// The slowest and fastest speeds, in generations per second.
+const SPEEDS: std::ops::RangeInclusive<u32> = 1..=1024;
+
+/// Key pressed by the user
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Key {
+    /// A printable character or control key.
+    Char(char),
+    /// An arrow key, as a unit step `[dx, dy]` with `y` up.
+    Arrow([i64; 2]),
+}
+
+/// State of the viewer
+#[derive(Debug)]
+struct App {
+    /// The game of life being explored.
+    life: Life<2>,
+    /// The cell under the cursor.
+    cursor: [i64; 2],
+    /// The bottom-left cell of the view.
+    corner: [i64; 2],
+    /// Whether the game of life advances on its own.
+    running: bool,
+    /// The speed, in generations per second.
+    speed: u32,
+    /// The rate limiter advancing the game of life while running.
+    ticker: Ticker,
+}
+impl App {
+    /// Start exploring a game of life with the view centred on the origin.
+    fn new(life: Life<2>, size: [i64; 2]) -> Self {
+        let speed = 8;
+        let mut app = Self {
+            life,
+            cursor: [0, 0],
+            corner: [0, 0],
+            running: false,
+            speed,
+            ticker: Ticker::per_second(speed, CatchUp::Burst { max_generations: 8 }),
+        };
+        app.centre(size);
+        app
+    }
+
+    /// Handle a key press.
+    /// # Returns
+    /// Whether the viewer should quit.
+    fn handle_key(&mut self, key: Key, size: [i64; 2]) -> bool {
+        match key {
+            Key::Char('q') | Key::Char('\u{3}') => return true,
+            Key::Char(' ') => {
+                self.running = !self.running;
+                self.ticker.reset();
+            }
+            Key::Char('n') => self.life.next_generation(),
+            Key::Char('+') | Key::Char('=') => self.set_speed(self.speed.saturating_mul(2)),
+            Key::Char('-') => self.set_speed(self.speed / 2),
+            Key::Char('t') | Key::Char('\r') | Key::Char('\n') => {
+                self.life.toggle_cell(&self.cursor);
+            }
+            Key::Char('c') => self.centre(size),
+            Key::Char(character @ ('H' | 'J' | 'K' | 'L')) => {
+                let [dx, dy] = direction(character.to_ascii_lowercase()).expect("HJKL are directions");
+                self.corner = [self.corner[0] + 8 * dx, self.corner[1] + 8 * dy];
+                self.cursor = [self.cursor[0] + 8 * dx, self.cursor[1] + 8 * dy];
+            }
+            Key::Char(character) => {
+                if let Some(step) = direction(character) {
+                    self.move_cursor(step, size);
+                }
+            }
+            Key::Arrow(step) => self.move_cursor(step, size),
+        }
+        false
+    }
+
+    /// Set the speed, clamped to the supported range.
+    fn set_speed(&mut self, speed: u32) {
+        self.speed = speed.clamp(*SPEEDS.start(), *SPEEDS.end());
+        self.ticker.set_interval(Duration::from_secs(1) / self.speed);
+    }
+
+    /// Move the cursor by a step, panning the view to keep the cursor inside it.
+    fn move_cursor(&mut self, step: [i64; 2], size: [i64; 2]) {
+        for axis in 0..2 {
+            self.cursor[axis] += step[axis];
+            self.corner[axis] = self.corner[axis].clamp(self.cursor[axis] - size[axis] + 1, self.cursor[axis]);
+        }
+    }
+
+    /// Centre the view on the cursor.
+    fn centre(&mut self, size: [i64; 2]) {
+        self.corner = [self.cursor[0] - size[0] / 2, self.cursor[1] - size[1] / 2];
+    }
+
+    /// Advance the game of life by the generations due, if running.
+    fn tick(&mut self) {
+        if self.running {
+            self.ticker.tick(&mut self.life);
+        }
+    }
+
+    /// Render the view and a status line.
+    /// # Arguments
+    /// * `size` - The width and height of the view in cells.
+    fn render(&self, size: [i64; 2]) -> String {
+        let region = Region::new(self.corner, [self.corner[0] + size[0] - 1, self.corner[1] + size[1] - 1]);
+        let mut screen = String::from("\x1b[H");
+        for (start, row) in self.life.view(region).rows() {
+            for (x, alive) in (start[0]..).zip(row) {
+                let cell = if alive { '█' } else { ' ' };
+                if [x, start[1]] == self.cursor {
+                    screen.push_str("\x1b[7m");
+                    screen.push(if alive { '█' } else { '+' });
+                    screen.push_str("\x1b[0m");
+                } else {
+                    screen.push(cell);
+                }
+            }
+            screen.push_str("\x1b[K\r\n");
+        }
+        screen.push_str(&format!(
+            "\x1b[7m generation {} | population {} | {} gen/s | {} | cursor {:?} | q quits \x1b[0m\x1b[K",
+            self.life.age(),
+            self.life.alive_cells().len(),
+            self.speed,
+            if self.running { "running" } else { "paused" },
+            self.cursor
+        ));
+        screen
+    }
+}
+
+/// Get the unit step of a vi-style direction key, with `y` up.
+fn direction(key: char) -> Option<[i64; 2]> {
+    match key {
+        'h' => Some([-1, 0]),
+        'j' => Some([0, -1]),
+        'k' => Some([0, 1]),
+        'l' => Some([1, 0]),
+        _ => None,
+    }
+}
+
+/// Decode the keys in bytes read from the terminal.
+fn decode_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i..] {
+            [0x1b, b'[', arrow @ (b'A' | b'B' | b'C' | b'D'), ..] => {
+                keys.push(Key::Arrow(match arrow {
+                    b'A' => [0, 1],
+                    b'B' => [0, -1],
+                    b'C' => [1, 0],
+                    _ => [-1, 0],
+                }));
+                i += 3;
+            }
+            [byte, ..] => {
+                keys.push(Key::Char(byte as char));
+                i += 1;
+            }
+            [] => unreachable!("the loop stops at the end of the bytes"),
+        }
+    }
+    keys
+}
+
+/// Terminal in raw mode, restored to its previous settings when dropped.
+struct RawTerminal {
+    /// The settings before entering raw mode, as printed by `stty -g`.
+    saved: String,
+}
+impl RawTerminal {
+    /// Switch the terminal to raw mode and the alternate screen.
+    fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(Self { saved: saved.trim().to_string() })
+    }
+
+    /// Get the size of the terminal as `[columns, rows]`, or 80x24 if it is unknown.
+    fn size() -> [i64; 2] {
+        stty(&["size"])
+            .ok()
+            .and_then(|size| {
+                let mut parts = size.split_whitespace().map(|part| part.parse::<i64>().ok());
+                let (rows, columns) = (parts.next()??, parts.next()??);
+                Some([columns, rows])
+            })
+            .unwrap_or([80, 24])
+    }
+}
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Run `stty` on the terminal of the standard input.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).stderr(Stdio::null()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed, is the standard input a terminal?"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Load the game of life from the command line arguments.
+/// # Returns
+/// [Some] game of life, or [None] if help was requested.
+fn load(args: impl IntoIterator<Item = String>) -> Result<Option<Life<2>>, Box<dyn Error>> {
+    let mut args = args.into_iter();
+    let mut rule = String::from("B3/S23");
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-r" | "--rule" => rule = args.next().ok_or("missing value for --rule")?,
+            option if option.starts_with('-') => return Err(format!("unknown option {}", option).into()),
+            _ => path = Some(arg),
+        }
+    }
+    let mut life = Life::<2>::empty_with_rulestring(&rule).map_err(|error| format!("invalid rule {:?}: {}", rule, error))?;
+    if let Some(path) = path {
+        let text = fs::read_to_string(&path).map_err(|error| format!("cannot read {}: {}", path, error))?;
+        let pattern: Pattern<2> = text.parse().map_err(|error| format!("cannot parse {}: {}", path, error))?;
+        life.set_alive_cells(pattern.into_cells());
+    }
+    Ok(Some(life))
+}
+
+/// Run the viewer until the user quits.
+fn run(life: Life<2>) -> io::Result<()> {
+    let terminal = RawTerminal::enter()?;
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 64];
+        while let Ok(read @ 1..) = io::stdin().read(&mut buffer) {
+            if decode_keys(&buffer[..read]).into_iter().any(|key| sender.send(key).is_err()) {
+                break;
+            }
+        }
+    });
+
+    let view_size = |[columns, rows]: [i64; 2]| [columns.max(1), (rows - 1).max(1)];
+    let mut app = App::new(life, view_size(RawTerminal::size()));
+    let mut stdout = io::stdout();
+    loop {
+        let size = view_size(RawTerminal::size());
+        stdout.write_all(app.render(size).as_bytes())?;
+        stdout.flush()?;
+        // wake up for the next generation, or poll the keys slowly while paused
+        let timeout = if app.running {
+            app.ticker.time_until_next(std::time::Instant::now())
+        } else {
+            Duration::from_millis(250)
+        };
+        match keys.recv_timeout(timeout.max(Duration::from_millis(1))) {
+            Ok(key) => {
+                if app.handle_key(key, size) {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        app.tick();
+    }
+    drop(terminal);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match load(std::env::args().skip(1)) {
+        Ok(Some(life)) => match run(life) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: [i64; 2] = [5, 3];
+
+    fn app() -> App {
+        let mut life = Life::<2>::empty_with_rulestring("B3/S23").unwrap();
+        life.set_alive_cells([[-1, 0], [0, 0], [1, 0]].into_iter().collect());
+        App::new(life, SIZE)
+    }
+
+    #[test]
+    fn test_decode_keys() {
+        assert_eq!(
+            decode_keys(b"q\x1b[A\x1b[Dx\x1b"),
+            vec![Key::Char('q'), Key::Arrow([0, 1]), Key::Arrow([-1, 0]), Key::Char('x'), Key::Char('\x1b')]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let app = app();
+        assert_eq!(app.corner, [-2, -1]);
+        let screen = app.render(SIZE);
+        let rows: Vec<&str> = screen.trim_start_matches("\x1b[H").split("\x1b[K\r\n").collect();
+        assert_eq!(rows[0], "     ");
+        assert_eq!(rows[1], " █\x1b[7m█\x1b[0m█ ");
+        assert_eq!(rows[2], "     ");
+        assert!(rows[3].contains("generation 0 | population 3 | 8 gen/s | paused | cursor [0, 0]"));
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let mut app = app();
+        assert!(!app.handle_key(Key::Char('n'), SIZE));
+        assert_eq!(app.life.age(), 1);
+        assert!(app.life.get_cell(&[0, 1]));
+
+        // toggling under the cursor
+        app.handle_key(Key::Char('l'), SIZE);
+        app.handle_key(Key::Char('t'), SIZE);
+        assert!(app.life.get_cell(&[1, 0]));
+        app.handle_key(Key::Char('\r'), SIZE);
+        assert!(!app.life.get_cell(&[1, 0]));
+
+        // the view follows the cursor
+        for _ in 0..3 {
+            app.handle_key(Key::Arrow([1, 0]), SIZE);
+        }
+        assert_eq!(app.cursor, [4, 0]);
+        assert_eq!(app.corner, [0, -1]);
+        app.handle_key(Key::Char('K'), SIZE);
+        assert_eq!((app.cursor, app.corner), ([4, 8], [0, 7]));
+        app.handle_key(Key::Char('c'), SIZE);
+        assert_eq!(app.corner, [2, 7]);
+
+        // speed and play / pause
+        app.handle_key(Key::Char('+'), SIZE);
+        assert_eq!(app.speed, 16);
+        assert_eq!(app.ticker.interval(), Duration::from_millis(62) + Duration::from_micros(500));
+        for _ in 0..10 {
+            app.handle_key(Key::Char('-'), SIZE);
+        }
+        assert_eq!(app.speed, 1);
+        app.handle_key(Key::Char(' '), SIZE);
+        assert!(app.running);
+        app.handle_key(Key::Char(' '), SIZE);
+        assert!(!app.running);
+        assert!(app.handle_key(Key::Char('q'), SIZE));
+    }
+
+    #[test]
+    fn test_load() {
+        assert!(load([String::from("--help")]).unwrap().is_none());
+        let life = load([String::from("-r"), String::from("B36/S23")]).unwrap().unwrap();
+        assert_eq!(life.birth_rules(), &[3, 6].into_iter().collect());
+        assert!(load([String::from("-r"), String::from("X")]).is_err());
+        assert!(load([String::from("/nonexistent/pattern.cells")]).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! Windowed viewer of 3-dimensional games of life, enabled by the `viewer3d` feature
+//!
+//! The alive cells are drawn as instanced cubes with [three-d](https://docs.rs/three-d), seen through an [OrbitCamera].
+//! The window needs OpenGL.
+
+use ndlife::life::Life;
+use ndlife::orbit::OrbitCamera;
+use ndlife::ticker::{CatchUp, Ticker};
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+use std::time::Duration;
+use three_d::{
+    radians, vec3, AmbientLight, Camera, ClearState, CpuMaterial, CpuMesh, DirectionalLight, Event, FrameOutput, Gm, InstancedMesh, Instances, Key, Mat4, MouseButton,
+    PhysicalMaterial, Srgba, Window, WindowSettings,
+};
+
+/// Usage of the viewer.
+const USAGE: &str = "\
+Usage: ndlife-viewer3d [-r <RULESTRING>] [-s <SEED>] [CELLS]
+
+View a 3-dimensional game of life as cubes in a window. CELLS is a file with one alive cell per line,
+as the three coordinates `x y z`, where lines starting with `#` are comments. Without it, the game of
+life starts from a random soup.
+
+Controls:
+  drag           orbit the camera around the cells
+  scroll         zoom in / out
+  space          play / pause
+  n              advance one generation
+  + / -          double / halve the speed
+  f              frame the alive cells
+  q, escape      quit";
+
+/// The rules without a `-r` option.
+const DEFAULT_RULE: &str = "B5/S4-6";
+
+/// The slowest and fastest speeds, in generations per second.
+const SPEEDS: std::ops::RangeInclusive<u32> = 1..=1024;
+
+/// The angle the camera turns by per logical pixel dragged, in radians.
+const RADIANS_PER_PIXEL: f64 = 0.01;
+
+/// The scroll distance that zooms in twice as close, in logical pixels.
+const SCROLL_PER_DOUBLING: f64 = 100.0;
+
+/// The half width of a drawn cube; the unit cube mesh spans -1 to 1, and gaps between the cubes show their edges.
+const CUBE_SCALE: f32 = 0.45;
+
+/// State of the viewer
+#[derive(Debug)]
+struct App {
+    /// The game of life being viewed.
+    life: Life<3>,
+    /// The camera the cells are seen through.
+    camera: OrbitCamera,
+    /// Whether the game of life advances on its own.
+    running: bool,
+    /// The speed, in generations per second.
+    speed: u32,
+    /// The rate limiter advancing the game of life while running.
+    ticker: Ticker,
+    /// Whether the alive cells changed since the cubes were last built.
+    changed: bool,
+}
+impl App {
+    /// Start viewing a game of life, with the camera framing its alive cells.
+    fn new(life: Life<3>) -> Self {
+        let speed = 4;
+        let mut camera = OrbitCamera::new();
+        camera.fit(&life);
+        Self {
+            life,
+            camera,
+            running: false,
+            speed,
+            ticker: Ticker::per_second(speed, CatchUp::Burst { max_generations: 4 }),
+            changed: true,
+        }
+    }
+
+    /// Handle an input event of the window.
+    /// # Returns
+    /// Whether the viewer should quit.
+    fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::KeyPress { kind, .. } => match kind {
+                Key::Q | Key::Escape => return true,
+                Key::Space => {
+                    self.running = !self.running;
+                    self.ticker.reset();
+                }
+                Key::N => {
+                    self.life.next_generation();
+                    self.changed = true;
+                }
+                Key::Plus | Key::Equals => self.set_speed(self.speed.saturating_mul(2)),
+                Key::Minus => self.set_speed(self.speed / 2),
+                Key::F => self.camera.fit(&self.life),
+                _ => {}
+            },
+            Event::MouseMotion {
+                button: Some(MouseButton::Left),
+                delta: (dx, dy),
+                ..
+            } => self.camera.orbit(-f64::from(dx) * RADIANS_PER_PIXEL, f64::from(dy) * RADIANS_PER_PIXEL),
+            Event::MouseWheel { delta: (_, dy), .. } => self.camera.zoom((f64::from(dy) / SCROLL_PER_DOUBLING).exp2()),
+            _ => {}
+        }
+        false
+    }
+
+    /// Set the speed, clamped to the supported range.
+    fn set_speed(&mut self, speed: u32) {
+        self.speed = speed.clamp(*SPEEDS.start(), *SPEEDS.end());
+        self.ticker.set_interval(Duration::from_secs(1) / self.speed);
+    }
+
+    /// Advance the game of life by the generations due, if running.
+    fn tick(&mut self) {
+        if self.running && self.ticker.tick(&mut self.life) > 0 {
+            self.changed = true;
+        }
+    }
+
+    /// Get the transformations placing a cube on every alive cell, the centre of cell `c` being at `c + 0.5`.
+    fn instances(&self) -> Instances {
+        let transformations = self
+            .life
+            .alive_cells()
+            .iter()
+            .map(|cell| {
+                let [x, y, z] = cell.map(|coordinate| coordinate as f32 + 0.5);
+                Mat4::from_translation(vec3(x, y, z)) * Mat4::from_scale(CUBE_SCALE)
+            })
+            .collect();
+        Instances {
+            transformations,
+            ..Default::default()
+        }
+    }
+
+    /// Get the status shown in the title of the window.
+    fn title(&self) -> String {
+        format!(
+            "ndlife-viewer3d | generation {} | population {} | {} gen/s | {}",
+            self.life.age(),
+            self.life.alive_cells().len(),
+            self.speed,
+            if self.running { "running" } else { "paused" }
+        )
+    }
+}
+
+/// Parse the alive cells of a file, one `x y z` cell per line.
+fn parse_cells(text: &str) -> Result<Vec<[i64; 3]>, String> {
+    let mut cells = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let coordinates: Vec<i64> = line
+            .split(|character: char| character.is_whitespace() || character == ',')
+            .filter(|coordinate| !coordinate.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("line {}: invalid coordinate", index + 1))?;
+        let cell: [i64; 3] = coordinates.try_into().map_err(|_| format!("line {}: expected 3 coordinates", index + 1))?;
+        cells.push(cell);
+    }
+    Ok(cells)
+}
+
+/// Parse the arguments and load the game of life.
+/// # Returns
+/// The game of life, or [None] if help was requested.
+fn load(args: impl IntoIterator<Item = String>) -> Result<Option<Life<3>>, Box<dyn Error>> {
+    let mut args = args.into_iter();
+    let mut rule = DEFAULT_RULE.to_string();
+    let mut seed = 1;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-r" | "--rule" => rule = args.next().ok_or("missing value for --rule")?,
+            "-s" | "--seed" => seed = args.next().ok_or("missing value for --seed")?.parse().map_err(|_| "invalid seed")?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg).into()),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err("more than one cells file given".into()),
+        }
+    }
+    let mut life = Life::<3>::empty_with_rulestring(&rule)?;
+    match path {
+        Some(path) => life.set_alive_cells(parse_cells(&fs::read_to_string(&path)?)?.into_iter().collect()),
+        None => life.randomize_region([-6..=6, -6..=6, -6..=6], 0.3, seed),
+    }
+    Ok(Some(life))
+}
+
+/// Open the window and run the viewer until it is closed.
+fn run(life: Life<3>) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(life);
+    let window = Window::new(WindowSettings {
+        title: app.title(),
+        initial_size: Some((1024, 768)),
+        ..Default::default()
+    })?;
+    let context = window.gl();
+    let mut camera = Camera::new_perspective(
+        window.viewport(),
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+        radians(app.camera.field_of_view() as f32),
+        0.1,
+        1000.0,
+    );
+    let material = CpuMaterial {
+        albedo: Srgba::new_opaque(90, 170, 250),
+        ..Default::default()
+    };
+    let mut cubes = Gm::new(
+        InstancedMesh::new(&context, &app.instances(), &CpuMesh::cube()),
+        PhysicalMaterial::new_opaque(&context, &material),
+    );
+    let ambient = AmbientLight::new(&context, 0.4, Srgba::WHITE);
+    let sun = DirectionalLight::new(&context, 2.0, Srgba::WHITE, vec3(-0.4, -0.6, -1.0));
+    let mut title = app.title();
+
+    window.render_loop(move |frame| {
+        let mut quit = false;
+        for event in &frame.events {
+            quit |= app.handle_event(event);
+        }
+        app.tick();
+        if std::mem::take(&mut app.changed) {
+            cubes.geometry.set_instances(&app.instances());
+        }
+        if app.title() != title {
+            title = app.title();
+            println!("{}", title);
+        }
+
+        let [position, target] = [app.camera.position(), app.camera.target()].map(|point| vec3(point[0] as f32, point[1] as f32, point[2] as f32));
+        camera.set_viewport(frame.viewport);
+        camera.set_view(position, target, vec3(0.0, 0.0, 1.0));
+        let far = (app.camera.distance() * 4.0 + 100.0) as f32;
+        camera.set_perspective_projection(radians(app.camera.field_of_view() as f32), 0.1, far);
+        let visible = (!app.life.alive_cells().is_empty()).then_some(&cubes);
+        frame
+            .screen()
+            .clear(ClearState::color_and_depth(0.05, 0.05, 0.08, 1.0, 1.0))
+            .render(&camera, visible, &[&ambient, &sun]);
+        FrameOutput { exit: quit, ..Default::default() }
+    });
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match load(std::env::args().skip(1)) {
+        Ok(Some(life)) => match run(life) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use three_d::Modifiers;
+
+    fn app() -> App {
+        let mut life = Life::<3>::empty_with_rulestring(DEFAULT_RULE).unwrap();
+        life.set_alive_cells([[0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0], [0, 0, 1]].into_iter().collect());
+        App::new(life)
+    }
+
+    fn key(kind: Key) -> Event {
+        Event::KeyPress {
+            kind,
+            modifiers: Modifiers::default(),
+            handled: false,
+        }
+    }
+
+    #[test]
+    fn test_handle_event() {
+        let mut app = app();
+        app.changed = false;
+        assert!(!app.handle_event(&key(Key::N)));
+        assert_eq!(app.life.age(), 1);
+        assert!(app.changed);
+
+        assert!(!app.handle_event(&key(Key::Space)));
+        assert!(app.running);
+        app.handle_event(&key(Key::Plus));
+        assert_eq!(app.speed, 8);
+        for _ in 0..4 {
+            app.handle_event(&key(Key::Minus));
+        }
+        assert_eq!(app.speed, 1);
+        assert!(app.title().contains("generation 1 | population"));
+        assert!(app.title().ends_with("1 gen/s | running"));
+
+        // dragging orbits and scrolling zooms, other mouse motion does nothing
+        let camera = app.camera;
+        let drag = |button| Event::MouseMotion {
+            button,
+            delta: (10.0, 5.0),
+            position: (0.0, 0.0).into(),
+            modifiers: Modifiers::default(),
+            handled: false,
+        };
+        app.handle_event(&drag(None));
+        assert_eq!(app.camera, camera);
+        app.handle_event(&drag(Some(MouseButton::Left)));
+        assert_ne!(app.camera.position(), camera.position());
+        assert_eq!(app.camera.distance(), camera.distance());
+        app.handle_event(&Event::MouseWheel {
+            delta: (0.0, 100.0),
+            position: (0.0, 0.0).into(),
+            modifiers: Modifiers::default(),
+            handled: false,
+        });
+        assert!((app.camera.distance() - camera.distance() / 2.0).abs() < 1e-9);
+
+        assert!(app.handle_event(&key(Key::Q)));
+        assert!(app.handle_event(&key(Key::Escape)));
+    }
+
+    #[test]
+    fn test_instances() {
+        let app = app();
+        let instances = app.instances();
+        assert_eq!(instances.transformations.len(), 5);
+        let mut centres: Vec<[f32; 3]> = instances
+            .transformations
+            .iter()
+            .map(|transformation| [transformation.w.x, transformation.w.y, transformation.w.z])
+            .collect();
+        centres.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(centres[0], [0.5, 0.5, 0.5]);
+        assert_eq!(instances.transformations[0].x.x, CUBE_SCALE);
+    }
+
+    #[test]
+    fn test_load() {
+        assert_eq!(parse_cells("# a comment\n0 0 0\n1, -2, 3\n\n"), Ok(vec![[0, 0, 0], [1, -2, 3]]));
+        assert_eq!(parse_cells("0 0"), Err("line 1: expected 3 coordinates".to_string()));
+        assert_eq!(parse_cells("0 0 0\n0 x 0"), Err("line 2: invalid coordinate".to_string()));
+
+        assert!(load(["--help".to_string()]).unwrap().is_none());
+        let life = load(["-r".to_string(), "B4/S4".to_string(), "-s".to_string(), "7".to_string()]).unwrap().unwrap();
+        assert_eq!(life.birth_rules(), &[4].into_iter().collect());
+        assert!(!life.alive_cells().is_empty());
+        assert!(life.alive_cells().iter().all(|cell| cell.iter().all(|coordinate| (-6..=6).contains(coordinate))));
+        assert!(load(["--unknown".to_string()]).is_err());
+        assert!(load(["-s".to_string(), "x".to_string()]).is_err());
+    }
+}
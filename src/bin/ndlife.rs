@@ -0,0 +1,245 @@
+//! Command line runner of 2-dimensional patterns, enabled by the `cli` feature
+
+use ndlife::life::Life;
+use ndlife::pattern::Pattern;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+/// Usage of the command line interface.
+const USAGE: &str = "\
+Usage: ndlife [OPTIONS] <PATTERN>
+
+Run a 2-dimensional pattern in plaintext format (`-` reads standard input).
+
+Options:
+  -r, --rule <RULESTRING>    Rules in B/S notation [default: B3/S23]
+  -g, --generations <N>      Number of generations to run [default: 0]
+  -e, --every <K>            Also write a snapshot every K generations
+  -f, --format <FORMAT>      Output format: plaintext or cells [default: plaintext]
+  -o, --output <FILE>        Write to a file instead of standard output
+  -h, --help                 Print this help";
+
+/// Format of the written generations
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Format {
+    /// Plaintext ASCII art, with the bounding box of the alive cells.
+    Plaintext,
+    /// One alive cell per line, as `x y`, in lexicographic order.
+    Cells,
+}
+
+/// Options of a run
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Config {
+    /// The path of the pattern, or `-` for standard input.
+    pattern: String,
+    /// The rules in B/S notation.
+    rule: String,
+    /// The number of generations to run.
+    generations: u64,
+    /// The number of generations between snapshots, if snapshots are written.
+    every: Option<u64>,
+    /// The output format.
+    format: Format,
+    /// The path of the output, or [None] for standard output.
+    output: Option<String>,
+}
+impl Config {
+    /// Parse the command line arguments, without the program name.
+    /// # Returns
+    /// [Ok] with [Some] config, [Ok] with [None] if help was requested, or an error message.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut args = args.into_iter();
+        let mut pattern = None;
+        let mut config = Self {
+            pattern: String::new(),
+            rule: String::from("B3/S23"),
+            generations: 0,
+            every: None,
+            format: Format::Plaintext,
+            output: None,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| format!("missing value for {}", name));
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-r" | "--rule" => config.rule = value(&arg)?,
+                "-g" | "--generations" => config.generations = parse_count(&arg, &value(&arg)?)?,
+                "-e" | "--every" => match parse_count(&arg, &value(&arg)?)? {
+                    0 => return Err(format!("{} must be positive", arg)),
+                    every => config.every = Some(every),
+                },
+                "-f" | "--format" => {
+                    config.format = match value(&arg)?.as_str() {
+                        "plaintext" => Format::Plaintext,
+                        "cells" => Format::Cells,
+                        format => return Err(format!("unknown format {:?}, expected plaintext or cells", format)),
+                    }
+                }
+                "-o" | "--output" => config.output = Some(value(&arg)?),
+                option if option.starts_with('-') && option != "-" => return Err(format!("unknown option {}", option)),
+                _ if pattern.is_some() => return Err(format!("unexpected argument {}", arg)),
+                _ => pattern = Some(arg),
+            }
+        }
+        config.pattern = pattern.ok_or("missing pattern")?;
+        Ok(Some(config))
+    }
+}
+
+/// Parse a non-negative count given to an option.
+fn parse_count(option: &str, value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {:?} for {}, expected a non-negative integer", value, option))
+}
+
+/// Write a generation of the game of life in a format, preceded by a comment with its age.
+fn write_generation(output: &mut impl Write, life: &Life<2>, format: Format) -> io::Result<()> {
+    writeln!(output, "!Generation: {}", life.age())?;
+    match format {
+        Format::Plaintext => {
+            let art = life.to_string();
+            if !art.is_empty() {
+                writeln!(output, "{}", art)?;
+            }
+        }
+        Format::Cells => {
+            for [x, y] in life.alive_cells_sorted() {
+                writeln!(output, "{} {}", x, y)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Load the pattern, run it and write the requested generations.
+fn run(config: &Config) -> Result<(), Box<dyn Error>> {
+    let text = if config.pattern == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        fs::read_to_string(&config.pattern).map_err(|error| format!("cannot read {}: {}", config.pattern, error))?
+    };
+    let pattern: Pattern<2> = text.parse().map_err(|error| format!("cannot parse {}: {}", config.pattern, error))?;
+    let mut life = Life::<2>::empty_with_rulestring(&config.rule).map_err(|error| format!("invalid rule {:?}: {}", config.rule, error))?;
+    life.set_alive_cells(pattern.into_cells());
+
+    let mut output: Box<dyn Write> = match &config.output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path).map_err(|error| format!("cannot create {}: {}", path, error))?)),
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+    for _ in 0..config.generations {
+        life.next_generation();
+        if config.every.is_some_and(|every| life.age() % every == 0 && life.age() < config.generations) {
+            write_generation(&mut output, &life, config.format)?;
+        }
+    }
+    write_generation(&mut output, &life, config.format)?;
+    output.flush()?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match Config::parse(std::env::args().skip(1)) {
+        Ok(Some(config)) => match run(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Config>, String> {
+        Config::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = parse(&["glider.cells"]).unwrap().unwrap();
+        assert_eq!(config.pattern, "glider.cells");
+        assert_eq!(config.rule, "B3/S23");
+        assert_eq!(config.generations, 0);
+        assert_eq!(config.every, None);
+        assert_eq!(config.format, Format::Plaintext);
+        assert_eq!(config.output, None);
+
+        let config = parse(&["-r", "B36/S23", "--generations", "100", "-e", "10", "-f", "cells", "-o", "out.txt", "-"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.pattern, "-");
+        assert_eq!(config.rule, "B36/S23");
+        assert_eq!(config.generations, 100);
+        assert_eq!(config.every, Some(10));
+        assert_eq!(config.format, Format::Cells);
+        assert_eq!(config.output.as_deref(), Some("out.txt"));
+
+        assert_eq!(parse(&["--help", "whatever"]), Ok(None));
+        assert_eq!(parse(&[]), Err(String::from("missing pattern")));
+        assert_eq!(parse(&["a", "b"]), Err(String::from("unexpected argument b")));
+        assert_eq!(parse(&["a", "-g"]), Err(String::from("missing value for -g")));
+        assert_eq!(parse(&["a", "-e", "0"]), Err(String::from("-e must be positive")));
+        assert_eq!(parse(&["a", "--speed"]), Err(String::from("unknown option --speed")));
+        assert!(parse(&["a", "-g", "-1"]).unwrap_err().contains("non-negative"));
+        assert!(parse(&["a", "-f", "rle"]).unwrap_err().contains("unknown format"));
+    }
+
+    #[test]
+    fn test_write_generation() {
+        let mut life = Life::<2>::empty_with_rulestring("B3/S23").unwrap();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        life.next_generation();
+
+        let mut output = Vec::new();
+        write_generation(&mut output, &life, Format::Plaintext).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "!Generation: 1\nO\nO\nO\n");
+        let mut output = Vec::new();
+        write_generation(&mut output, &life, Format::Cells).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "!Generation: 1\n1 -1\n1 0\n1 1\n");
+    }
+
+    #[test]
+    fn test_run() {
+        let directory = std::env::temp_dir().join(format!("ndlife-cli-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let input = directory.join("glider.cells");
+        let output = directory.join("out.cells");
+        fs::write(&input, "!Name: Glider\n.O.\n..O\nOOO\n").unwrap();
+
+        let config = Config {
+            pattern: input.to_string_lossy().into_owned(),
+            rule: String::from("B3/S23"),
+            generations: 8,
+            every: Some(4),
+            format: Format::Plaintext,
+            output: Some(output.to_string_lossy().into_owned()),
+        };
+        run(&config).unwrap();
+        let written = fs::read_to_string(&output).unwrap();
+        assert_eq!(written, "!Generation: 4\n.O.\n..O\nOOO\n!Generation: 8\n.O.\n..O\nOOO\n");
+
+        let invalid = Config {
+            rule: String::from("B3/X"),
+            ..config
+        };
+        assert!(run(&invalid).unwrap_err().to_string().starts_with("invalid rule"));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Viewports following moving patterns
+
+use crate::life::Life;
+use crate::region::Region;
+
+/// What a [Camera] follows
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Tracking {
+    /// The bounding box of the alive cells, inflated by the margin; the viewport grows and shrinks with the pattern.
+    BoundingBox,
+    /// The centre of mass of the alive cells; the viewport keeps its size.
+    CentreOfMass,
+}
+
+/// Viewport following the alive cells of a game of life from frame to frame
+///
+/// Every call to [update](Self::update) moves the viewport towards its target and returns the region to render,
+/// so a spaceship stays on screen however far it flies. With [smoothing](Self::set_smoothing), the viewport covers only
+/// part of the way each frame, which hides the jitter of oscillating shapes; without it, the viewport snaps to the target.
+/// The viewport is never smaller than its [size](Self::new), and stays in place while there are no alive cells.
+/// # Example
+/// ```
+/// use ndlife::camera::{Camera, Tracking};
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::region::Region;
+///
+/// let mut life = conways_game_of_life();
+/// // glider moving right and down
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+///
+/// let mut camera = Camera::new(Tracking::CentreOfMass, [9, 9]);
+/// assert_eq!(camera.update(&life), Region::new([-3, -3], [5, 5]));
+/// for _ in 0..400 {
+///     life.next_generation();
+///     let region = camera.update(&life);
+///     assert!(life.alive_cells().iter().all(|cell| region.contains(cell)));
+/// }
+/// assert_eq!(camera.update(&life), Region::new([97, -103], [105, -95]));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera<const N: usize> {
+    /// What the viewport follows.
+    tracking: Tracking,
+    /// The smallest number of cells along each axis of the viewport.
+    size: [u64; N],
+    /// The number of cells around the bounding box.
+    margin: u64,
+    /// The fraction of the distance to the target left after each frame.
+    smoothing: f64,
+    /// The lower and upper edges of the viewport along each axis, if it was ever placed.
+    edges: Option<([f64; N], [f64; N])>,
+}
+impl<const N: usize> Camera<N> {
+    /// Create a camera snapping to its target every frame, without margin.
+    /// # Arguments
+    /// * `tracking` - What the viewport follows.
+    /// * `size` - The number of cells along each axis of the viewport, the smallest size when following the bounding box.
+    pub fn new(tracking: Tracking, size: [u64; N]) -> Self {
+        Self {
+            tracking,
+            size,
+            margin: 0,
+            smoothing: 0.0,
+            edges: None,
+        }
+    }
+
+    /// Get what the viewport follows.
+    pub fn tracking(&self) -> Tracking {
+        self.tracking
+    }
+
+    /// Set what the viewport follows.
+    pub fn set_tracking(&mut self, tracking: Tracking) {
+        self.tracking = tracking;
+    }
+
+    /// Set the number of dead cells kept around the bounding box of the alive cells.
+    pub fn set_margin(&mut self, margin: u64) {
+        self.margin = margin;
+    }
+
+    /// Set the fraction of the distance to the target left after each frame, clamped between 0 (snapping) and 0.99.
+    pub fn set_smoothing(&mut self, smoothing: f64) {
+        self.smoothing = if smoothing.is_nan() { 0.0 } else { smoothing.clamp(0.0, 0.99) };
+    }
+
+    /// Get the region of the viewport, or [None] if it was never placed.
+    pub fn region(&self) -> Option<Region<N>> {
+        self.edges
+            .map(|(lower, upper)| Region::new(lower.map(|edge| round(edge) as i64), upper.map(|edge| (round(edge) as i64).saturating_sub(1))))
+    }
+
+    /// Snap the viewport to its target on the next frame, as after a new pattern is loaded.
+    pub fn reset(&mut self) {
+        self.edges = None;
+    }
+
+    /// Move the viewport towards its target for the current generation.
+    /// # Arguments
+    /// * `life` - The game of life to follow.
+    /// # Returns
+    /// The region to render, around the origin if there were never any alive cells.
+    pub fn update(&mut self, life: &Life<N>) -> Region<N> {
+        let target = self.target(life).or(self.edges).unwrap_or_else(|| window([0.5; N], self.size.map(|size| size as f64)));
+        let edges = match self.edges {
+            Some((lower, upper)) => (
+                std::array::from_fn(|axis| target.0[axis] + (lower[axis] - target.0[axis]) * self.smoothing),
+                std::array::from_fn(|axis| target.1[axis] + (upper[axis] - target.1[axis]) * self.smoothing),
+            ),
+            None => target,
+        };
+        self.edges = Some(edges);
+        self.region().expect("the edges were just set")
+    }
+
+    /// Get the lower and upper edges of the target viewport, or [None] if there are no alive cells.
+    fn target(&self, life: &Life<N>) -> Option<([f64; N], [f64; N])> {
+        let (centre, extent) = match self.tracking {
+            Tracking::BoundingBox => {
+                let bounds = Region::bounding(life.alive_cells())?.inflate(self.margin);
+                let (min, max) = (bounds.min(), bounds.max());
+                (
+                    std::array::from_fn(|axis| (min[axis] as f64 + max[axis] as f64 + 1.0) / 2.0),
+                    std::array::from_fn(|axis| (max[axis].abs_diff(min[axis]) as f64 + 1.0).max(self.size[axis] as f64)),
+                )
+            }
+            Tracking::CentreOfMass => {
+                if life.alive_cells().is_empty() {
+                    return None;
+                }
+                let mut sum = [0.0; N];
+                for cell in life.alive_cells() {
+                    for axis in 0..N {
+                        sum[axis] += cell[axis] as f64;
+                    }
+                }
+                let count = life.alive_cells().len() as f64;
+                (sum.map(|sum| sum / count + 0.5), self.size.map(|size| size as f64))
+            }
+        };
+        Some(window(centre, extent))
+    }
+}
+
+/// Get the lower and upper edges of a viewport of whole cells, so that a snapping viewport keeps its size.
+fn window<const N: usize>(centre: [f64; N], extent: [f64; N]) -> ([f64; N], [f64; N]) {
+    let lower: [f64; N] = std::array::from_fn(|axis| round(centre[axis] - extent[axis] / 2.0));
+    (lower, std::array::from_fn(|axis| lower[axis] + extent[axis]))
+}
+
+/// Round a coordinate to the nearest cell edge, halves upwards.
+fn round(coordinate: f64) -> f64 {
+    (coordinate + 0.5).floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    fn life(cells: impl IntoIterator<Item = [i64; 2]>) -> Life<2> {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells(cells.into_iter().collect());
+        life
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut camera = Camera::new(Tracking::BoundingBox, [4, 2]);
+        assert_eq!(camera.region(), None);
+        camera.set_margin(1);
+        assert_eq!(camera.update(&life([[0, 0], [9, 0]])), Region::new([-1, -1], [10, 1]));
+        // smaller than the smallest size
+        assert_eq!(camera.update(&life([[0, 0]])), Region::new([-1, -1], [2, 1]));
+        // no alive cells
+        assert_eq!(camera.update(&life([])), Region::new([-1, -1], [2, 1]));
+        camera.reset();
+        assert_eq!(camera.update(&life([])), Region::new([-1, 0], [2, 1]));
+        assert_eq!(camera.tracking(), Tracking::BoundingBox);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut camera = Camera::new(Tracking::CentreOfMass, [2, 2]);
+        camera.set_smoothing(0.5);
+        assert_eq!(camera.update(&life([[0, 0]])), Region::new([0, 0], [1, 1]));
+        let far = life([[16, 0]]);
+        assert_eq!(camera.update(&far), Region::new([8, 0], [9, 1]));
+        assert_eq!(camera.update(&far), Region::new([12, 0], [13, 1]));
+        for _ in 0..20 {
+            camera.update(&far);
+        }
+        assert_eq!(camera.update(&far), Region::new([16, 0], [17, 1]));
+
+        camera.set_smoothing(f64::NAN);
+        camera.set_tracking(Tracking::BoundingBox);
+        assert_eq!(camera.update(&life([[0, 0], [5, 5]])), Region::new([0, 0], [5, 5]));
+    }
+}
@@ -0,0 +1,250 @@
+//! Searches for catalysts, still lifes which take part in a reaction and are restored by it
+
+use crate::life::{Life, Stability, StabilityConfig};
+use crate::pattern::{normalized, Pattern};
+use crate::region::Region;
+use crate::symmetry::Symmetry;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Catalyst found by a [CatalystSearch]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catalysis<const N: usize> {
+    /// The cells of the catalyst, in place next to the reaction.
+    pub catalyst: Pattern<N>,
+    /// The first generation in which the catalyst changed the reaction.
+    pub contact: u64,
+    /// The first generation after the contact in which the catalyst was back in its cells, apart from the reaction.
+    pub recovery: u64,
+    /// The final state of the reaction with the catalyst, run to stability from the recovery.
+    pub stability: Stability,
+}
+
+/// Search for catalysts of a reaction among candidate still lifes
+///
+/// Every orientation of every candidate is placed at every offset of a region, with its minimum corner at the offset,
+/// and run with the reaction. A placement is accepted if the candidate changes the reaction, but not in the first
+/// generation (which would make it a part of the reaction), is then restored to its original cells apart from anything
+/// else, and is still there when the whole pattern is [stable](Life::run_until_stable) or the generations run out,
+/// as when the reaction keeps emitting spaceships.
+/// # Example
+/// ```
+/// use ndlife::catalyst::CatalystSearch;
+/// use ndlife::life::{conways_game_of_life, StabilityConfig};
+/// use ndlife::pattern::Pattern;
+/// use ndlife::spaceship::Spaceship;
+///
+/// let life = conways_game_of_life();
+/// // a glider flying south-east, and an eater and a block as candidates
+/// let eater: Pattern<2> = [[0, 3], [1, 3], [1, 2], [1, 1], [2, 0], [3, 0], [3, 1]].into_iter().collect();
+/// let block: Pattern<2> = [[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect();
+/// let mut search = CatalystSearch::new(&life, Spaceship::Glider.pattern(), [eater.clone(), block]);
+/// assert_eq!(search.candidates().len(), 9);
+/// search.set_stability(StabilityConfig { max_generations: 50, max_period: 4 });
+///
+/// // only an eater placed in the path of the glider can eat it
+/// let catalyses = search.search([4..=10, -10..=-4]);
+/// assert!(!catalyses.is_empty());
+/// assert!(catalyses.iter().all(|catalysis| catalysis.catalyst.is_congruent(&eater)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalystSearch<const N: usize> {
+    /// The birth rules of the reaction.
+    birth_rules: HashSet<usize>,
+    /// The survival rules of the reaction.
+    survival_rules: HashSet<usize>,
+    /// The reaction to catalyse.
+    reaction: Pattern<N>,
+    /// Every distinct orientation of the candidates, with their minimum corners at the origin.
+    candidates: Vec<Pattern<N>>,
+    /// The limits on running the reaction; the catalyst must recover within the maximum number of generations.
+    stability: StabilityConfig,
+    /// The number of threads trying placements.
+    threads: usize,
+}
+impl<const N: usize> CatalystSearch<N> {
+    /// Create a search for catalysts with the rules of a game of life and the default limits of stability,
+    /// on as many threads as the machine runs in parallel.
+    /// # Arguments
+    /// * `life` - The game of life whose rules the reaction follows.
+    /// * `reaction` - The reaction to catalyse, such as a spaceship or an unstable pattern.
+    /// * `catalysts` - The candidate still lifes; since every orientation is tried, candidates congruent to a previous one are ignored.
+    pub fn new(life: &Life<N>, reaction: Pattern<N>, catalysts: impl IntoIterator<Item = Pattern<N>>) -> Self {
+        let elements = Symmetry::SignedPermutations.elements::<N>().expect("signed permutations exist in every dimension");
+        let mut shapes: Vec<Pattern<N>> = Vec::new();
+        let mut candidates: Vec<Pattern<N>> = Vec::new();
+        for catalyst in catalysts {
+            if catalyst.is_empty() || shapes.iter().any(|shape| shape.is_congruent(&catalyst)) {
+                continue;
+            }
+            for element in &elements {
+                let candidate: Pattern<N> = normalized(catalyst.cells().iter().map(|cell| element.apply(cell))).into_iter().collect();
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+            shapes.push(catalyst);
+        }
+        Self {
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+            reaction,
+            candidates,
+            stability: StabilityConfig::default(),
+            threads: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Set the limits on running the reaction; the catalyst must recover within the maximum number of generations.
+    pub fn set_stability(&mut self, stability: StabilityConfig) {
+        self.stability = stability;
+    }
+
+    /// Set the number of threads trying placements, at least 1.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Get every distinct orientation of the candidates, with their minimum corners at the origin, in the order they are tried.
+    pub fn candidates(&self) -> &[Pattern<N>] {
+        &self.candidates
+    }
+
+    /// Try a catalyst in place next to the reaction.
+    /// # Arguments
+    /// * `catalyst` - The cells of the catalyst, in place.
+    /// # Returns
+    /// [Some] catalysis if the catalyst is accepted, or [None] if it does not touch the reaction, is a part of it or is not restored.
+    pub fn test(&self, catalyst: &Pattern<N>) -> Option<Catalysis<N>> {
+        if !self.reaction.cells().is_disjoint(catalyst.cells()) {
+            return None;
+        }
+        let surroundings: HashSet<[i64; N]> = catalyst.cells().iter().flat_map(Life::neighbours).filter(|cell| !catalyst.contains(cell)).collect();
+        let mut life = self.life(self.reaction.union(catalyst).into_cells());
+        let mut reaction = self.life(self.reaction.cells().clone());
+        let mut contact = None;
+        for generation in 1..=self.stability.max_generations {
+            life.next_generation();
+            match contact {
+                None => {
+                    reaction.next_generation();
+                    if life.alive_cells().len() != reaction.alive_cells().len() + catalyst.len()
+                        || !catalyst.cells().iter().chain(reaction.alive_cells()).all(|cell| life.alive_cells().contains(cell))
+                    {
+                        if generation == 1 {
+                            return None;
+                        }
+                        contact = Some(generation);
+                    }
+                }
+                Some(contact) => {
+                    let alive = life.alive_cells();
+                    if catalyst.cells().is_subset(alive) && surroundings.is_disjoint(alive) {
+                        let remaining = StabilityConfig {
+                            max_generations: self.stability.max_generations - generation,
+                            ..self.stability
+                        };
+                        let stability = life.run_until_stable(remaining);
+                        let alive = life.alive_cells();
+                        return (catalyst.cells().is_subset(alive) && surroundings.is_disjoint(alive)).then(|| Catalysis {
+                            catalyst: catalyst.clone(),
+                            contact,
+                            recovery: generation,
+                            stability,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Try every candidate in every orientation at every offset of a region, in parallel.
+    /// # Arguments
+    /// * `offsets` - The offsets of the minimum corners of the candidates, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The accepted catalyses, by offset in the order of [iter_cells](Region::iter_cells) and then by candidate.
+    pub fn search(&self, offsets: impl Into<Region<N>>) -> Vec<Catalysis<N>> {
+        let placements: Vec<([i64; N], &Pattern<N>)> = offsets
+            .into()
+            .iter_cells()
+            .flat_map(|offset| self.candidates.iter().map(move |candidate| (offset, candidate)))
+            .collect();
+        let next = AtomicUsize::new(0);
+        let placements = &placements;
+        let mut catalyses: Vec<(usize, Catalysis<N>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(placements.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut catalyses = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&(offset, candidate)) = placements.get(index) else {
+                                break catalyses;
+                            };
+                            let catalyst = candidate.cells().iter().map(|cell| std::array::from_fn(|axis| cell[axis] + offset[axis])).collect();
+                            if let Some(catalysis) = self.test(&catalyst) {
+                                catalyses.push((index, catalysis));
+                            }
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().expect("a catalyst search thread panicked")).collect()
+        });
+        catalyses.sort_unstable_by_key(|(index, _)| *index);
+        catalyses.into_iter().map(|(_, catalysis)| catalysis).collect()
+    }
+
+    /// Get a game of life with the rules of the reaction.
+    fn life(&self, alive_cells: HashSet<[i64; N]>) -> Life<N> {
+        Life::new_with_alive_cells(self.birth_rules.clone(), self.survival_rules.clone(), alive_cells).expect("the rules come from a game of life")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use crate::spaceship::Spaceship;
+
+    #[test]
+    fn test_candidates() {
+        let life = conways_game_of_life();
+        let beehive: Pattern<2> = [[1, 0], [2, 0], [0, 1], [3, 1], [1, 2], [2, 2]].into_iter().collect();
+        let turned: Pattern<2> = beehive.cells().iter().map(|&[x, y]| [y + 5, x - 3]).collect();
+        let search = CatalystSearch::new(&life, Spaceship::Glider.pattern(), [beehive, turned, Pattern::default()]);
+        assert_eq!(search.candidates().len(), 2);
+        assert!(search.candidates().iter().all(|candidate| candidate.bounding_box().unwrap().0 == [0, 0]));
+    }
+
+    #[test]
+    fn test_search() {
+        let life = conways_game_of_life();
+        let eater: Pattern<2> = [[0, 3], [1, 3], [1, 2], [1, 1], [2, 0], [3, 0], [3, 1]].into_iter().collect();
+        let mut search = CatalystSearch::new(&life, Spaceship::Glider.pattern(), [eater]);
+        search.set_stability(StabilityConfig {
+            max_generations: 50,
+            max_period: 4,
+        });
+        search.set_threads(3);
+        let catalyses = search.search([4..=10, -10..=-4]);
+        let mut serial = search.clone();
+        serial.set_threads(1);
+        assert_eq!(serial.search([4..=10, -10..=-4]), catalyses);
+        assert_eq!(catalyses.len(), 25);
+        for catalysis in &catalyses {
+            assert!(catalysis.contact > 1 && catalysis.contact < catalysis.recovery);
+            // the glider is eaten
+            assert!(matches!(catalysis.stability, Stability::StillLife { .. }));
+            assert_eq!(search.test(&catalysis.catalyst).as_ref(), Some(catalysis));
+        }
+
+        // too close to the glider, or too far from it
+        let block: Pattern<2> = [[3, 0], [4, 0], [3, 1], [4, 1]].into_iter().collect();
+        assert_eq!(search.test(&block), None);
+        let far: Pattern<2> = [[30, 0], [31, 0], [30, 1], [31, 1]].into_iter().collect();
+        assert_eq!(search.test(&far), None);
+    }
+}
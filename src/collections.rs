@@ -0,0 +1,9 @@
+//! Hash sets and maps of the standard library, or of [hashbrown](https://docs.rs/hashbrown) in the `alloc`-only configuration
+//!
+//! Both have the same interface, so the rest of the crate uses these and builds the same way in either configuration.
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{hash_set, HashMap, HashSet};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{hash_set, HashMap, HashSet};
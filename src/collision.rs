@@ -0,0 +1,348 @@
+//! Collisions of two objects and catalogues of their outcomes, the building blocks of syntheses
+
+use crate::life::{Life, Stability, StabilityConfig};
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::search::Object;
+use crate::sweep::csv_field;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Result of a [Collision]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CollisionOutcome<const N: usize> {
+    /// The objects are too close at the start to evolve separately for a generation.
+    Overlap,
+    /// The objects passed each other, or drifted apart, without interacting.
+    Miss,
+    /// The collision settled into objects, each movement and spaceship flying away included.
+    Products {
+        /// The objects, in order.
+        objects: Vec<Object<N>>,
+        /// The number of connected components which are not objects.
+        unidentified: usize,
+    },
+    /// The collision did not settle within the limits of stability.
+    Unsettled,
+}
+impl<const N: usize> fmt::Display for CollisionOutcome<N> {
+    /// Write the outcome as `overlap`, `miss`, `unsettled`, `nothing`, or the names of the products joined by ` + `,
+    /// followed by the number of unidentified components if any.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlap => f.write_str("overlap"),
+            Self::Miss => f.write_str("miss"),
+            Self::Unsettled => f.write_str("unsettled"),
+            Self::Products { objects, unidentified } => {
+                let mut products: Vec<String> = objects.iter().map(Object::to_string).collect();
+                if *unidentified > 0 {
+                    products.push(format!("{} unidentified", unidentified));
+                }
+                match products.is_empty() {
+                    true => f.write_str("nothing"),
+                    false => f.write_str(&products.join(" + ")),
+                }
+            }
+        }
+    }
+}
+
+/// Outcome of one setup in a [CollisionTable]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CollisionRow<const N: usize> {
+    /// The offset of the second object.
+    pub offset: [i64; N],
+    /// The number of generations the second object was advanced by before the collision.
+    pub phase: u64,
+    /// The result of the collision.
+    pub outcome: CollisionOutcome<N>,
+}
+
+/// Results of a [catalogue](Collision::catalogue), one row per offset and phase
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CollisionTable<const N: usize> {
+    /// The results, by offset in the order of [iter_cells](Region::iter_cells) and then by phase.
+    rows: Vec<CollisionRow<N>>,
+}
+impl<const N: usize> CollisionTable<N> {
+    /// Get the results, by offset in the order of [iter_cells](Region::iter_cells) and then by phase.
+    pub fn rows(&self) -> &[CollisionRow<N>] {
+        &self.rows
+    }
+
+    /// Count the setups giving each outcome other than [Overlap](CollisionOutcome::Overlap) and [Miss](CollisionOutcome::Miss).
+    /// # Returns
+    /// The outcomes and their counts, the most frequent first.
+    pub fn tally(&self) -> Vec<(&CollisionOutcome<N>, usize)> {
+        let mut counts: HashMap<&CollisionOutcome<N>, usize> = HashMap::new();
+        for row in &self.rows {
+            if !matches!(row.outcome, CollisionOutcome::Overlap | CollisionOutcome::Miss) {
+                *counts.entry(&row.outcome).or_insert(0) += 1;
+            }
+        }
+        let mut counts: Vec<(&CollisionOutcome<N>, usize)> = counts.into_iter().collect();
+        counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    }
+
+    /// Write the table as comma-separated values, with a header row.
+    ///
+    /// The columns are the offset, with its coordinates separated by dots, the phase and the outcome as it is displayed.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("offset,phase,outcome\n");
+        for row in &self.rows {
+            let offset: Vec<String> = row.offset.iter().map(i64::to_string).collect();
+            csv.push_str(&format!("{},{},{}\n", offset.join("."), row.phase, csv_field(&row.outcome.to_string())));
+        }
+        csv
+    }
+}
+
+/// Collision of two objects, such as a glider hitting a still life, under the rules of a game of life
+///
+/// The first object stays in place and the second is advanced by its phase, then moved by its offset. Both are run
+/// together until they are [stable](Life::run_until_stable), and the result is compared to running them apart:
+/// a collision which changed nothing is a [Miss](CollisionOutcome::Miss), and the others are classified by their
+/// [products](Life::identify_objects), among which the spaceships still flying away at the end of an unsettled run.
+/// A [catalogue](Self::catalogue) tries every offset of a region and every phase, in parallel.
+/// # Example
+/// ```
+/// use ndlife::collision::{Collision, CollisionOutcome};
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::pattern::Pattern;
+/// use ndlife::spaceship::Spaceship;
+///
+/// let life = conways_game_of_life();
+/// let block: Pattern<2> = [[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect();
+/// // a glider flying south-east towards the block
+/// let collision = Collision::new(&life, block, Spaceship::Glider.pattern());
+///
+/// assert_eq!(collision.run([-9, 0], 0), CollisionOutcome::Miss);
+/// assert_eq!(collision.run([0, 0], 0), CollisionOutcome::Overlap);
+///
+/// let table = collision.catalogue([-6..=-6, 2..=7]);
+/// assert_eq!(table.rows().len(), 24);
+/// // the glider can destroy the block
+/// assert!(table.rows().iter().any(|row| row.outcome.to_string() == "nothing"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision<const N: usize> {
+    /// The birth rules of the collisions.
+    birth_rules: HashSet<usize>,
+    /// The survival rules of the collisions.
+    survival_rules: HashSet<usize>,
+    /// The object staying in place.
+    first: Pattern<N>,
+    /// The object moved by the offset and advanced by the phase.
+    second: Pattern<N>,
+    /// The limits on running a collision to stability.
+    stability: StabilityConfig,
+    /// The number of threads running collisions.
+    threads: usize,
+}
+impl<const N: usize> Collision<N> {
+    /// Create a collision of two objects with the rules of a game of life and the default limits of stability,
+    /// cataloguing on as many threads as the machine runs in parallel.
+    /// # Arguments
+    /// * `life` - The game of life whose rules the collisions follow.
+    /// * `first` - The object staying in place.
+    /// * `second` - The object moved by the offset and advanced by the phase, usually a spaceship.
+    pub fn new(life: &Life<N>, first: Pattern<N>, second: Pattern<N>) -> Self {
+        Self {
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+            first,
+            second,
+            stability: StabilityConfig::default(),
+            threads: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Set the limits on running a collision to stability; its maximum period is also the largest period of the products.
+    pub fn set_stability(&mut self, stability: StabilityConfig) {
+        self.stability = stability;
+    }
+
+    /// Set the number of threads running collisions, at least 1.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Set up a collision.
+    /// # Arguments
+    /// * `offset` - The offset of the second object.
+    /// * `phase` - The number of generations the second object is advanced by.
+    /// # Returns
+    /// The game of life with both objects, at age 0.
+    pub fn setup(&self, offset: [i64; N], phase: u64) -> Life<N> {
+        let (first, second) = self.objects(offset, phase);
+        self.life(first.alive_cells().union(second.alive_cells()).copied().collect())
+    }
+
+    /// Run a collision to stability and classify its outcome.
+    /// # Arguments
+    /// * `offset` - The offset of the second object.
+    /// * `phase` - The number of generations the second object is advanced by.
+    pub fn run(&self, offset: [i64; N], phase: u64) -> CollisionOutcome<N> {
+        let (mut first, mut second) = self.objects(offset, phase);
+        if !first.alive_cells().is_disjoint(second.alive_cells()) {
+            return CollisionOutcome::Overlap;
+        }
+        let mut life = self.life(first.alive_cells().union(second.alive_cells()).copied().collect());
+        if life.next().alive_cells() != &union(&first.next(), &second.next()) {
+            return CollisionOutcome::Overlap;
+        }
+        let stability = life.run_until_stable(self.stability);
+        for _ in 0..life.age() {
+            first.next_generation();
+            second.next_generation();
+        }
+        if life.alive_cells() == &union(&first, &second) {
+            return CollisionOutcome::Miss;
+        }
+        let mut objects = Vec::new();
+        let mut unidentified = 0;
+        for (_, object) in life.identify_objects(self.stability.max_period) {
+            match object {
+                Some(object) => objects.push(object),
+                None => unidentified += 1,
+            }
+        }
+        if stability == Stability::Unsettled && unidentified > 0 {
+            return CollisionOutcome::Unsettled;
+        }
+        objects.sort_unstable();
+        CollisionOutcome::Products { objects, unidentified }
+    }
+
+    /// Run the collisions of every offset of a region, in every phase of the second object.
+    ///
+    /// The phases are those of the [period](Object::period) of the second object, or only phase 0 if it is not identified.
+    /// # Arguments
+    /// * `offsets` - The offsets of the second object, such as the inclusive range of coordinates along each axis.
+    pub fn catalogue(&self, offsets: impl Into<Region<N>>) -> CollisionTable<N> {
+        let second = self.life(self.second.cells().clone());
+        let period = Object::identify(&second, self.stability.max_period).map_or(1, |object| object.period());
+        let setups: Vec<([i64; N], u64)> = offsets.into().iter_cells().flat_map(|offset| (0..period).map(move |phase| (offset, phase))).collect();
+        let next = AtomicUsize::new(0);
+        let setups = &setups;
+        let mut rows: Vec<(usize, CollisionRow<N>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(setups.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut rows = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&(offset, phase)) = setups.get(index) else {
+                                break rows;
+                            };
+                            let outcome = self.run(offset, phase);
+                            rows.push((index, CollisionRow { offset, phase, outcome }));
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().expect("a collision thread panicked")).collect()
+        });
+        rows.sort_unstable_by_key(|(index, _)| *index);
+        CollisionTable {
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+        }
+    }
+
+    /// Get each object alone, the second one moved and advanced.
+    fn objects(&self, offset: [i64; N], phase: u64) -> (Life<N>, Life<N>) {
+        let mut second = self.life(self.second.cells().clone());
+        for _ in 0..phase {
+            second.next_generation();
+        }
+        second.translate(offset);
+        second.set_age(0);
+        (self.life(self.first.cells().clone()), second)
+    }
+
+    /// Get a game of life with the rules of the collisions.
+    fn life(&self, alive_cells: HashSet<[i64; N]>) -> Life<N> {
+        Life::new_with_alive_cells(self.birth_rules.clone(), self.survival_rules.clone(), alive_cells).expect("the rules come from a game of life")
+    }
+}
+
+/// Get the alive cells of two games of life together.
+fn union<const N: usize>(a: &Life<N>, b: &Life<N>) -> HashSet<[i64; N]> {
+    a.alive_cells().union(b.alive_cells()).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use crate::spaceship::{Chirality, Direction, Spaceship};
+
+    #[test]
+    fn test_outcome_display() {
+        let block: Object<2> = "xs4_0.0,0.1,1.0,1.1".parse().unwrap();
+        let products = CollisionOutcome::Products {
+            objects: vec![block.clone(), block],
+            unidentified: 1,
+        };
+        assert_eq!(products.to_string(), "xs4_0.0,0.1,1.0,1.1 + xs4_0.0,0.1,1.0,1.1 + 1 unidentified");
+        let nothing = CollisionOutcome::<2>::Products {
+            objects: Vec::new(),
+            unidentified: 0,
+        };
+        assert_eq!(nothing.to_string(), "nothing");
+        assert_eq!(CollisionOutcome::<2>::Unsettled.to_string(), "unsettled");
+    }
+
+    #[test]
+    fn test_catalogue() {
+        let life = conways_game_of_life();
+        // two gliders flying towards each other
+        let mut south_east = life.clone();
+        south_east.place_spaceship(Spaceship::Glider, [0, 0], Direction::SouthEast, Chirality::Standard, 0).unwrap();
+        let mut north_west = life.clone();
+        north_west.place_spaceship(Spaceship::Glider, [0, 0], Direction::NorthWest, Chirality::Standard, 0).unwrap();
+        let mut collision = Collision::new(&life, south_east.to_pattern(), north_west.to_pattern());
+        collision.set_stability(StabilityConfig {
+            max_generations: 200,
+            max_period: 4,
+        });
+        collision.set_threads(3);
+        let table = collision.catalogue([8..=8, -12..=-4]);
+        assert_eq!(table.rows().len(), 36);
+        assert_eq!(
+            table.rows()[5],
+            CollisionRow {
+                offset: [8, -11],
+                phase: 1,
+                outcome: collision.run([8, -11], 1)
+            }
+        );
+        let mut serial = collision.clone();
+        serial.set_threads(1);
+        assert_eq!(serial.catalogue([8..=8, -12..=-4]), table);
+
+        let tally = table.tally();
+        assert!(tally.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        let collisions: usize = tally.iter().map(|(_, count)| count).sum();
+        let misses = table
+            .rows()
+            .iter()
+            .filter(|row| matches!(row.outcome, CollisionOutcome::Miss | CollisionOutcome::Overlap))
+            .count();
+        assert_eq!(collisions + misses, 36);
+        // head-on gliders annihilate in many lanes, and pass each other in far ones
+        assert_eq!(tally[0].0.to_string(), "nothing");
+        assert_eq!(collision.run([8, -20], 0), CollisionOutcome::Miss);
+        let csv = table.to_csv();
+        assert!(csv.starts_with("offset,phase,outcome\n8.-12,0,"));
+        assert_eq!(csv.lines().count(), 37);
+
+        // a setup is the two objects together
+        let setup = collision.setup([8, -8], 2);
+        assert_eq!(setup.alive_cells().len(), 10);
+        assert_eq!(setup.age(), 0);
+    }
+}
@@ -0,0 +1,128 @@
+//! Byte-oriented LZ77 compression, for files of many repeated byte sequences such as saved snapshots
+//!
+//! A compressed stream is the length of the data, followed by sequences of a number of literal bytes, the literals,
+//! and a match copying earlier data: its length above the minimum plus one (0 ending the stream) and its distance back.
+//! All lengths and distances are LEB128 variable-length integers.
+
+use crate::delta::{read_unsigned, write_unsigned};
+use std::io::{self, Read};
+
+/// Minimum length of a match.
+const MIN_MATCH: usize = 4;
+/// Maximum distance back to the start of a match.
+const WINDOW: usize = 1 << 16;
+/// Number of bits of the hashes indexing the last position of every sequence of [MIN_MATCH] bytes.
+const HASH_BITS: u32 = 15;
+
+/// Compress data.
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len() / 2 + 16);
+    write_unsigned(&mut output, data.len() as u64);
+    let mut last: Vec<Option<usize>> = vec![None; 1 << HASH_BITS];
+    let (mut position, mut literals) = (0, 0);
+    while position + MIN_MATCH <= data.len() {
+        let candidate = last[hash(data, position)].replace(position);
+        let length = candidate
+            .filter(|&candidate| position - candidate <= WINDOW)
+            .map_or(0, |candidate| data[candidate..].iter().zip(&data[position..]).take_while(|(a, b)| a == b).count());
+        if length < MIN_MATCH {
+            position += 1;
+            continue;
+        }
+        let candidate = candidate.expect("a match has a candidate");
+        write_unsigned(&mut output, (position - literals) as u64);
+        output.extend_from_slice(&data[literals..position]);
+        write_unsigned(&mut output, (length - MIN_MATCH + 1) as u64);
+        write_unsigned(&mut output, (position - candidate) as u64);
+        for inside in position + 1..(position + length).min(data.len() + 1 - MIN_MATCH) {
+            last[hash(data, inside)] = Some(inside);
+        }
+        position += length;
+        literals = position;
+    }
+    write_unsigned(&mut output, (data.len() - literals) as u64);
+    output.extend_from_slice(&data[literals..]);
+    write_unsigned(&mut output, 0);
+    output
+}
+
+/// Decompress data, reading exactly the compressed stream from the input.
+pub(crate) fn decompress(input: &mut impl Read) -> io::Result<Vec<u8>> {
+    let length = read_unsigned(input)?;
+    // the length is not trusted for the allocation, as the input may be corrupted
+    let mut data = Vec::with_capacity(length.min(1 << 24) as usize);
+    loop {
+        let literals = read_unsigned(input)?;
+        if literals > length - data.len() as u64 {
+            return Err(invalid("literals beyond the length of the data"));
+        }
+        // read as they come rather than allocated up front, as the input may be corrupted
+        if input.by_ref().take(literals).read_to_end(&mut data)? as u64 != literals {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let matched = read_unsigned(input)?;
+        if matched == 0 {
+            break;
+        }
+        let distance = read_unsigned(input)?;
+        if distance == 0 || distance > data.len() as u64 {
+            return Err(invalid("match before the start of the data"));
+        }
+        let matched = matched.saturating_add(MIN_MATCH as u64 - 1);
+        if matched > length - data.len() as u64 {
+            return Err(invalid("match beyond the length of the data"));
+        }
+        // byte by byte, as a match may overlap the bytes it copies
+        let from = data.len() - distance as usize;
+        for index in from..from + matched as usize {
+            data.push(data[index]);
+        }
+    }
+    if data.len() as u64 != length {
+        return Err(invalid("data shorter than its length"));
+    }
+    Ok(data)
+}
+
+/// Get the hash of the bytes of a minimum match at a position.
+fn hash(data: &[u8], position: usize) -> usize {
+    let bytes: [u8; MIN_MATCH] = data[position..position + MIN_MATCH].try_into().expect("a match has the minimum length");
+    (u32::from_le_bytes(bytes).wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Get an error of malformed data.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = 1u64;
+        let noise: Vec<u8> = (0..5000)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 56) as u8
+            })
+            .collect();
+        let repeated: Vec<u8> = b"0123456789".iter().copied().cycle().take(100_000).collect();
+        for data in [&[][..], b"a", b"aaaa", b"abcabcabcabcabcabcx", &noise, &repeated] {
+            let compressed = compress(data);
+            assert_eq!(decompress(&mut compressed.as_slice()).unwrap(), data);
+        }
+        assert!(compress(&repeated).len() < 100);
+        assert!(compress(&noise).len() < noise.len() + 16);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let compressed = compress(b"abcabcabcabcabcabc");
+        assert_eq!(decompress(&mut &compressed[..compressed.len() - 1]).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        for bytes in [&[3, 5, b'a'][..], &[3, 1, b'a', 1, 2, 0], &[8, 1, b'a', 9, 1, 0], &[5, 1, b'a', 0]] {
+            assert_eq!(decompress(&mut &bytes[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
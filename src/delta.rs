@@ -0,0 +1,526 @@
+//! Compact binary streams of the born and died cells of every generation, for remote viewers and logs
+//!
+//! A stream starts with the magic bytes `NDLD`, a version byte (1) and the number of dimensions, followed by frames.
+//! Every frame starts with a tag byte:
+//! * `0` - A keyframe: the age and the alive cells.
+//! * `1` - A generation: the change of the age since the previous frame, then the born cells and the died cells.
+//!
+//! A list of cells is its length followed by the cells in lexicographic order, each coordinate written as its difference
+//! to the same coordinate of the previous cell (of the origin for the first cell), so clustered cells take a byte or two each.
+//! Integers are LEB128 variable-length integers, signed ones zigzag encoded first.
+
+use crate::life::Life;
+use crate::lockstep::{age_hash, cells_hash, Diff};
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of a stream.
+const MAGIC: &[u8; 4] = b"NDLD";
+/// Version of the format.
+const VERSION: u8 = 1;
+/// Tag of a keyframe.
+const KEYFRAME: u8 = 0;
+/// Tag of a generation.
+const GENERATION: u8 = 1;
+
+/// Frame of a delta stream
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DeltaFrame<const N: usize> {
+    /// The full state, from which the following generations continue.
+    Keyframe {
+        /// The age of the state.
+        age: u64,
+        /// The alive cells, in lexicographic order.
+        alive_cells: Vec<[i64; N]>,
+    },
+    /// The change of the state since the previous frame.
+    Generation {
+        /// The age after the change.
+        age: u64,
+        /// The cells which became alive, in lexicographic order.
+        born: Vec<[i64; N]>,
+        /// The cells which became dead, in lexicographic order.
+        died: Vec<[i64; N]>,
+    },
+}
+
+/// Writer of a delta stream, sending each generation as its born and died cells
+///
+/// The first frame written is always a keyframe, so the stream can be decoded from its start;
+/// more can be written at any time with [write_keyframe](Self::write_keyframe), such as a reference for viewers joining late.
+/// The encoder keeps the hash of the alive cells the stream decodes to, so a generation advanced from another state,
+/// such as after editing the game of life or skipping generations, is written as a keyframe instead of a wrong diff.
+/// # Example
+/// ```
+/// use ndlife::delta::{DeltaDecoder, DeltaEncoder};
+/// use ndlife::life::conways_game_of_life;
+///
+/// let mut life = conways_game_of_life();
+/// // a field of 100 blocks and a blinker
+/// let blocks = (0..10).flat_map(|i| (0..10).flat_map(move |j| [[0, 0], [1, 0], [0, 1], [1, 1]].map(|[x, y]| [5 * i + x, 5 * j + y])));
+/// life.extend(blocks.chain([[100, 0], [101, 0], [102, 0]]));
+///
+/// let mut encoder = DeltaEncoder::new(Vec::new()).unwrap();
+/// encoder.write_keyframe(&life).unwrap();
+/// let keyframe = encoder.bytes();
+/// for _ in 0..100 {
+///     life.next_generation();
+///     encoder.write_generation(&life).unwrap();
+/// }
+/// // a generation takes a small fraction of a full state
+/// assert!(encoder.bytes() - keyframe < 100 * keyframe / 50);
+///
+/// let bytes = encoder.into_inner();
+/// let mut decoder = DeltaDecoder::<_, 2>::new(bytes.as_slice()).unwrap();
+/// while decoder.read_frame().unwrap().is_some() {}
+/// assert_eq!(decoder.age(), 100);
+/// assert_eq!(decoder.alive_cells(), life.alive_cells());
+/// ```
+#[derive(Debug)]
+pub struct DeltaEncoder<W: Write, const N: usize> {
+    /// The output the stream is written to.
+    output: W,
+    /// The age of the last frame, or [None] before the first keyframe.
+    age: Option<u64>,
+    /// The [hash](cells_hash) of the alive cells decoded from the frames written so far.
+    cells_hash: u64,
+    /// The number of bytes written.
+    bytes: u64,
+}
+impl<W: Write, const N: usize> DeltaEncoder<W, N> {
+    /// Create an encoder, writing the header of the stream.
+    /// # Arguments
+    /// * `output` - The output to write the stream to, such as a file or a socket.
+    /// # Returns
+    /// An [io::Result] containing the encoder, or the error of the output.
+    pub fn new(output: W) -> io::Result<Self> {
+        let mut encoder = Self {
+            output,
+            age: None,
+            cells_hash: 0,
+            bytes: 0,
+        };
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        write_unsigned(&mut header, N as u64);
+        encoder.write(&header)?;
+        Ok(encoder)
+    }
+
+    /// Get the number of bytes written, the header included.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Write a keyframe of the age and alive cells of a game of life.
+    /// # Arguments
+    /// * `life` - The game of life.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn write_keyframe(&mut self, life: &Life<N>) -> io::Result<()> {
+        self.write_frame(&DeltaFrame::Keyframe {
+            age: life.age(),
+            alive_cells: life.alive_cells_sorted(),
+        })
+    }
+
+    /// Write the last generation of a game of life, from its [born](Life::born_cells) and [died](Life::died_cells) cells.
+    ///
+    /// The generation is written as a keyframe instead if no frame was written yet, or if the game of life was not
+    /// advanced from the state of the last frame, because it was edited or generations were not written.
+    /// # Arguments
+    /// * `life` - The game of life, just advanced.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn write_generation(&mut self, life: &Life<N>) -> io::Result<()> {
+        if self.age.is_none() || cells_hash(life.previous_alive_cells()) != self.cells_hash {
+            return self.write_keyframe(life);
+        }
+        self.write_frame(&DeltaFrame::Generation {
+            age: life.age(),
+            born: sorted(life.born_cells()),
+            died: sorted(life.died_cells()),
+        })
+    }
+
+    /// Write a diff of a [Lockstep](crate::lockstep::Lockstep) as a generation.
+    /// # Arguments
+    /// * `diff` - The diff, made from the alive cells of the last frame; the age may differ.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    /// # Errors
+    /// An error of kind [InvalidInput](io::ErrorKind::InvalidInput), writing nothing, if the diff was made from other alive cells.
+    pub fn write_diff(&mut self, diff: &Diff<N>) -> io::Result<()> {
+        if diff.from_hash() ^ age_hash(diff.from_age()) != self.cells_hash {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "diff not made from the state of the last frame"));
+        }
+        self.write_frame(&DeltaFrame::Generation {
+            age: diff.age(),
+            born: diff.born().to_vec(),
+            died: diff.died().to_vec(),
+        })
+    }
+
+    /// Write a frame.
+    ///
+    /// The frame is not checked to apply to the state of the last frame.
+    /// # Arguments
+    /// * `frame` - The frame, with its cells in lexicographic order.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn write_frame(&mut self, frame: &DeltaFrame<N>) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        match frame {
+            DeltaFrame::Keyframe { age, alive_cells } => {
+                bytes.push(KEYFRAME);
+                write_unsigned(&mut bytes, *age);
+                write_cells(&mut bytes, alive_cells);
+                self.age = Some(*age);
+                self.cells_hash = cells_hash(alive_cells);
+            }
+            DeltaFrame::Generation { age, born, died } => {
+                bytes.push(GENERATION);
+                write_signed(&mut bytes, age.wrapping_sub(self.age.unwrap_or(0)) as i64);
+                write_cells(&mut bytes, born);
+                write_cells(&mut bytes, died);
+                self.age = Some(*age);
+                self.cells_hash ^= cells_hash(born) ^ cells_hash(died);
+            }
+        }
+        self.write(&bytes)
+    }
+
+    /// Flush the output.
+    /// # Returns
+    /// An [io::Result] with the error of the output, if any.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    /// Get the output, consuming the encoder.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    /// Get the output.
+    pub(crate) fn get_ref(&self) -> &W {
+        &self.output
+    }
+
+    /// Create an encoder appending frames to a stream whose last frame has an age and alive cells, without writing a header.
+    pub(crate) fn resume(output: W, age: Option<u64>, alive_cells: &HashSet<[i64; N]>) -> Self {
+        Self {
+            output,
+            age,
+            cells_hash: cells_hash(alive_cells),
+            bytes: 0,
+        }
+    }
+
+    /// Write bytes to the output, counting them.
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.output.write_all(bytes)?;
+        self.bytes += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// Reader of a delta stream, rebuilding the state of the game of life frame by frame
+///
+/// The state starts empty at age 0, and every frame read is applied to it; see [DeltaEncoder] for an example.
+#[derive(Debug)]
+pub struct DeltaDecoder<R: Read, const N: usize> {
+    /// The input the stream is read from.
+    input: R,
+    /// The age of the state.
+    age: u64,
+    /// The alive cells of the state.
+    alive_cells: HashSet<[i64; N]>,
+}
+impl<R: Read, const N: usize> DeltaDecoder<R, N> {
+    /// Create a decoder, reading the header of the stream.
+    /// # Arguments
+    /// * `input` - The input to read the stream from.
+    /// # Returns
+    /// An [io::Result] containing the decoder, or an error.
+    /// # Errors
+    /// * [InvalidData](io::ErrorKind::InvalidData) - If the input is not a delta stream of version 1 in `N` dimensions.
+    /// * Any error of the input, [UnexpectedEof](io::ErrorKind::UnexpectedEof) if it ends within the header.
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let mut magic = [0; 5];
+        input.read_exact(&mut magic)?;
+        if &magic[..4] != MAGIC {
+            return Err(invalid("not a delta stream"));
+        }
+        if magic[4] != VERSION {
+            return Err(invalid(&format!("unsupported delta stream version {}", magic[4])));
+        }
+        let dimensions = read_unsigned(&mut input)?;
+        if dimensions != N as u64 {
+            return Err(invalid(&format!("expected a {}-dimensional delta stream, found {} dimensions", N, dimensions)));
+        }
+        Ok(Self {
+            input,
+            age: 0,
+            alive_cells: HashSet::new(),
+        })
+    }
+
+    /// Get the age of the state.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the alive cells of the state.
+    pub fn alive_cells(&self) -> &HashSet<[i64; N]> {
+        &self.alive_cells
+    }
+
+    /// Read the next frame and apply it to the state.
+    /// # Returns
+    /// An [io::Result] containing [Some] frame, [None] at the end of the stream, or an error.
+    /// # Errors
+    /// * [InvalidData](io::ErrorKind::InvalidData) - If the frame is malformed, or changes cells which are not in the state it applies to.
+    /// * Any error of the input, [UnexpectedEof](io::ErrorKind::UnexpectedEof) if it ends within a frame.
+    pub fn read_frame(&mut self) -> io::Result<Option<DeltaFrame<N>>> {
+        let Some(frame) = self.parse_frame()? else {
+            return Ok(None);
+        };
+        match &frame {
+            DeltaFrame::Keyframe { age, alive_cells } => {
+                let cells: HashSet<[i64; N]> = alive_cells.iter().copied().collect();
+                if cells.len() != alive_cells.len() {
+                    return Err(invalid("repeated cell in a keyframe"));
+                }
+                self.alive_cells = cells;
+                self.age = *age;
+            }
+            DeltaFrame::Generation { age, born, died } => {
+                if !died.iter().all(|cell| self.alive_cells.contains(cell)) || born.iter().any(|cell| self.alive_cells.contains(cell)) {
+                    return Err(invalid("generation does not apply to the state"));
+                }
+                for cell in died {
+                    self.alive_cells.remove(cell);
+                }
+                self.alive_cells.extend(born.iter().copied());
+                self.age = *age;
+            }
+        }
+        Ok(Some(frame))
+    }
+
+    /// Read the next frame, following its age but leaving the alive cells unchanged, such as to index a stream.
+    pub(crate) fn skip_frame(&mut self) -> io::Result<Option<DeltaFrame<N>>> {
+        let frame = self.parse_frame()?;
+        if let Some(DeltaFrame::Keyframe { age, .. } | DeltaFrame::Generation { age, .. }) = &frame {
+            self.age = *age;
+        }
+        Ok(frame)
+    }
+
+    /// Create a decoder of the frames of a stream whose header was already read, such as from a keyframe in the middle of it.
+    pub(crate) fn resume(input: R) -> Self {
+        Self {
+            input,
+            age: 0,
+            alive_cells: HashSet::new(),
+        }
+    }
+
+    /// Get the input.
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.input
+    }
+
+    /// Read the next frame, with the age of a generation relative to the age of the state.
+    fn parse_frame(&mut self) -> io::Result<Option<DeltaFrame<N>>> {
+        let mut tag = [0];
+        if self.input.read(&mut tag)? == 0 {
+            return Ok(None);
+        }
+        let frame = match tag[0] {
+            KEYFRAME => DeltaFrame::Keyframe {
+                age: read_unsigned(&mut self.input)?,
+                alive_cells: read_cells(&mut self.input)?,
+            },
+            GENERATION => DeltaFrame::Generation {
+                age: self.age.wrapping_add(read_signed(&mut self.input)? as u64),
+                born: read_cells(&mut self.input)?,
+                died: read_cells(&mut self.input)?,
+            },
+            tag => return Err(invalid(&format!("unknown frame tag {}", tag))),
+        };
+        Ok(Some(frame))
+    }
+
+    /// Get the input, consuming the decoder.
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+}
+
+/// Get cells in lexicographic order.
+fn sorted<'a, const N: usize>(cells: impl Iterator<Item = &'a [i64; N]>) -> Vec<[i64; N]> {
+    let mut cells: Vec<[i64; N]> = cells.copied().collect();
+    cells.sort_unstable();
+    cells
+}
+
+/// Get an error of malformed data.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Write an unsigned LEB128 integer.
+pub(crate) fn write_unsigned(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Write a zigzag encoded signed LEB128 integer.
+fn write_signed(bytes: &mut Vec<u8>, value: i64) {
+    write_unsigned(bytes, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// Write a list of cells, each coordinate as its difference to the previous cell.
+pub(crate) fn write_cells<const N: usize>(bytes: &mut Vec<u8>, cells: &[[i64; N]]) {
+    write_unsigned(bytes, cells.len() as u64);
+    let mut previous = [0; N];
+    for cell in cells {
+        for axis in 0..N {
+            write_signed(bytes, cell[axis].wrapping_sub(previous[axis]));
+        }
+        previous = *cell;
+    }
+}
+
+/// Read an unsigned LEB128 integer.
+pub(crate) fn read_unsigned(input: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        input.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7F) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("integer longer than 64 bits"))
+}
+
+/// Read a zigzag encoded signed LEB128 integer.
+fn read_signed(input: &mut impl Read) -> io::Result<i64> {
+    let value = read_unsigned(input)?;
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+/// Read a list of cells, each coordinate as its difference to the previous cell.
+pub(crate) fn read_cells<const N: usize>(input: &mut impl Read) -> io::Result<Vec<[i64; N]>> {
+    let count = read_unsigned(input)?;
+    // the length is not trusted for the allocation, as the stream may be corrupted
+    let mut cells = Vec::with_capacity(count.min(1 << 16) as usize);
+    let mut previous = [0i64; N];
+    for _ in 0..count {
+        for coordinate in previous.iter_mut() {
+            *coordinate = coordinate.wrapping_add(read_signed(input)?);
+        }
+        cells.push(previous);
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use crate::lockstep::Lockstep;
+
+    #[test]
+    fn test_integers() {
+        for value in [0, 1, 127, 128, 300, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_unsigned(&mut bytes, value);
+            assert_eq!(read_unsigned(&mut bytes.as_slice()).unwrap(), value);
+        }
+        for value in [0, 1, -1, 63, -64, 64, i64::MIN, i64::MAX] {
+            let mut bytes = Vec::new();
+            write_signed(&mut bytes, value);
+            assert_eq!(read_signed(&mut bytes.as_slice()).unwrap(), value);
+        }
+        let mut bytes = Vec::new();
+        write_signed(&mut bytes, -64);
+        assert_eq!(bytes, [127]);
+        assert_eq!(read_unsigned(&mut [0xFF; 10].as_slice()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut encoder = DeltaEncoder::new(Vec::new()).unwrap();
+        life.next_generation();
+        // the first generation is written as a keyframe
+        encoder.write_generation(&life).unwrap();
+        life.next_generation();
+        encoder.write_generation(&life).unwrap();
+        let mut lockstep = Lockstep::new(life.clone(), 0);
+        lockstep.edit(|life| life.set_age(10));
+        encoder.write_diff(&lockstep.step()).unwrap();
+        assert_eq!(encoder.bytes(), encoder.output.len() as u64);
+
+        let bytes = encoder.into_inner();
+        let mut decoder = DeltaDecoder::<_, 2>::new(bytes.as_slice()).unwrap();
+        assert_eq!(
+            decoder.read_frame().unwrap(),
+            Some(DeltaFrame::Keyframe {
+                age: 1,
+                alive_cells: vec![[1, -1], [1, 0], [1, 1]],
+            })
+        );
+        assert_eq!(
+            decoder.read_frame().unwrap(),
+            Some(DeltaFrame::Generation {
+                age: 2,
+                born: vec![[0, 0], [2, 0]],
+                died: vec![[1, -1], [1, 1]],
+            })
+        );
+        assert!(matches!(decoder.read_frame().unwrap(), Some(DeltaFrame::Generation { age: 11, .. })));
+        assert_eq!(decoder.read_frame().unwrap(), None);
+        assert_eq!(decoder.alive_cells(), lockstep.life().alive_cells());
+
+        // a generation not advanced from the last frame is written as a keyframe, and such a diff is refused
+        let mut encoder = DeltaEncoder::new(Vec::new()).unwrap();
+        encoder.write_keyframe(&life).unwrap();
+        life.set_cell(&[5, 5], true);
+        life.next_generation();
+        encoder.write_generation(&life).unwrap();
+        life.next_generation();
+        encoder.write_generation(&life).unwrap();
+        let mut lockstep = Lockstep::new(conways_game_of_life(), 0);
+        assert_eq!(encoder.write_diff(&lockstep.step()).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let bytes = encoder.into_inner();
+        let mut decoder = DeltaDecoder::<_, 2>::new(bytes.as_slice()).unwrap();
+        assert!(matches!(decoder.read_frame().unwrap(), Some(DeltaFrame::Keyframe { age: 2, .. })));
+        assert!(matches!(decoder.read_frame().unwrap(), Some(DeltaFrame::Keyframe { age: 3, .. })));
+        assert!(matches!(decoder.read_frame().unwrap(), Some(DeltaFrame::Generation { age: 4, .. })));
+        assert_eq!(decoder.read_frame().unwrap(), None);
+        assert_eq!(decoder.alive_cells(), life.alive_cells());
+
+        // truncated, corrupted and mismatched streams
+        let mut truncated = DeltaDecoder::<_, 2>::new(&bytes[..bytes.len() - 1]).unwrap();
+        truncated.read_frame().unwrap();
+        truncated.read_frame().unwrap();
+        assert_eq!(truncated.read_frame().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        let mut skipped = bytes[..6].to_vec();
+        skipped.extend_from_slice(&[GENERATION, 2, 0, 1, 0, 0]);
+        let mut decoder = DeltaDecoder::<_, 2>::new(skipped.as_slice()).unwrap();
+        assert_eq!(decoder.read_frame().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(DeltaDecoder::<_, 3>::new(bytes.as_slice()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(DeltaDecoder::<_, 2>::new(&b"NDLD\x02\x02"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(DeltaDecoder::<_, 2>::new(&b"GIF89a"[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
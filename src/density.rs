@@ -0,0 +1,154 @@
+//! Coarse population counts for zoomed-out rendering
+
+use crate::collections::HashMap;
+use crate::life::Life;
+use crate::region::Region;
+
+/// The highest level of coarsening, with blocks of `2^62` cells along every axis.
+const MAX_LEVEL: u32 = 62;
+
+/// Population counts of the blocks of `2^level` cells along every axis covering a region
+///
+/// Block `b` covers the cells from `b * 2^level` to `(b + 1) * 2^level - 1` along every axis, so blocks of
+/// consecutive levels nest, and level 0 has one cell per block. Only blocks containing alive cells are stored,
+/// so a grid is as large as the number of occupied blocks, however large the region.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [3, 3], [-1, -1]].into_iter().collect());
+///
+/// // blocks of 2x2 cells
+/// let grid = life.density_grid(1, [-2..=3, -2..=3]);
+/// assert_eq!(grid.block_size(), 2);
+/// assert_eq!(grid.blocks(), ndlife::region::Region::new([-1, -1], [1, 1]));
+/// assert_eq!(grid.count(&[0, 0]), 2);
+/// assert_eq!(grid.density(&[1, 1]), 0.25);
+/// assert_eq!(grid.count(&[1, 0]), 0);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DensityGrid<const N: usize> {
+    /// The level of coarsening.
+    level: u32,
+    /// The blocks covering the region.
+    blocks: Region<N>,
+    /// The number of alive cells in each occupied block.
+    counts: HashMap<[i64; N], u64>,
+}
+impl<const N: usize> DensityGrid<N> {
+    /// Get the level of coarsening.
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Get the number of cells along every axis of a block, `2^level`.
+    pub fn block_size(&self) -> i64 {
+        1 << self.level
+    }
+
+    /// Get the region of the blocks covering the region of the grid, in block coordinates.
+    pub fn blocks(&self) -> Region<N> {
+        self.blocks
+    }
+
+    /// Get the number of alive cells in a block, counting only the cells inside the region of the grid.
+    /// # Arguments
+    /// * `block` - Coordinates of the block.
+    pub fn count(&self, block: &[i64; N]) -> u64 {
+        self.counts.get(block).copied().unwrap_or(0)
+    }
+
+    /// Get the fraction of alive cells in a block, between 0 and 1.
+    /// # Arguments
+    /// * `block` - Coordinates of the block.
+    pub fn density(&self, block: &[i64; N]) -> f64 {
+        self.count(block) as f64 / (0..N).fold(1.0, |volume, _| volume * self.block_size() as f64)
+    }
+
+    /// Get the largest number of alive cells in a block, to normalize grey levels.
+    pub fn max_count(&self) -> u64 {
+        self.counts.values().copied().max().unwrap_or(0)
+    }
+
+    /// Iterate over the blocks with alive cells and their counts, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[i64; N], u64)> {
+        self.counts.iter().map(|(block, count)| (block, *count))
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Count the alive cells of a region per block of `2^level` cells along every axis.
+    ///
+    /// Zoomed-out renderers can draw one grey block per entry instead of iterating every cell. The counts are computed
+    /// on demand, scanning the alive cells or the cells of the region, whichever are fewer.
+    /// # Arguments
+    /// * `level` - The level of coarsening, clamped to 62.
+    /// * `region` - The region to summarize, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The [DensityGrid] of the blocks covering the region.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells((0..100).map(|x| [x, 0]).collect());
+    ///
+    /// let grid = life.density_grid(4, [0..=99, 0..=0]);
+    /// assert_eq!(grid.count(&[0, 0]), 16);
+    /// assert_eq!(grid.count(&[6, 0]), 4);
+    /// ```
+    pub fn density_grid(&self, level: u32, region: impl Into<Region<N>>) -> DensityGrid<N> {
+        let level = level.min(MAX_LEVEL);
+        let region = region.into();
+        let block = |cell: [i64; N]| cell.map(|coordinate| coordinate >> level);
+        let blocks = if region.is_empty() {
+            region
+        } else {
+            Region::new(block(region.min()), block(region.max()))
+        };
+        let mut counts = HashMap::new();
+        for cell in self.view(region).alive_cells() {
+            *counts.entry(block(cell)).or_insert(0) += 1;
+        }
+        DensityGrid { level, blocks, counts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_density_grid() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [0, 1], [-1, -1], [-4, 0], [100, 100]].into_iter().collect());
+
+        // level 0 has one cell per block
+        let grid = life.density_grid(0, [-4..=0, -1..=1]);
+        assert_eq!(grid.blocks(), Region::new([-4, -1], [0, 1]));
+        assert_eq!(grid.iter().count(), 4);
+        assert_eq!(grid.density(&[0, 0]), 1.0);
+
+        // negative coordinates round down
+        let grid = life.density_grid(2, [-4..=3, -4..=3]);
+        assert_eq!(grid.blocks(), Region::new([-1, -1], [0, 0]));
+        assert_eq!(grid.count(&[0, 0]), 2);
+        assert_eq!(grid.count(&[-1, -1]), 1);
+        assert_eq!(grid.count(&[-1, 0]), 1);
+        assert_eq!(grid.max_count(), 2);
+        assert_eq!(grid.density(&[0, 0]), 2.0 / 16.0);
+
+        // cells outside the region are not counted
+        let grid = life.density_grid(10, [0..=99, 0..=99]);
+        assert_eq!(grid.count(&[0, 0]), 2);
+        assert_eq!(grid.blocks(), Region::new([0, 0], [0, 0]));
+
+        let grid = life.density_grid(100, [i64::MIN..=i64::MAX, i64::MIN..=i64::MAX]);
+        assert_eq!(grid.level(), 62);
+        assert_eq!(grid.iter().map(|(_, count)| count).sum::<u64>(), 5);
+        let (start, end) = (1, 0);
+        assert_eq!(life.density_grid(1, [start..=end, 0..=0]).max_count(), 0);
+    }
+}
@@ -0,0 +1,583 @@
+//! Games of life kept in a file as chunks of cells and stepped by streaming the file, for universes larger than memory
+//!
+//! A file starts with the magic bytes `NDLU`, a version byte (1) and a header of little-endian integers:
+//! the number of dimensions (`u32`), the population and the number of chunks (`u64` each), the age (`u64`),
+//! then the birth and the survival rules, each as a count followed by the rules (`u32` each).
+//! The chunks follow in lexicographic order of their coordinates, each as its coordinates (`i64` each)
+//! and a bitmap of its cells, the first axis varying fastest.
+
+use crate::error::Error;
+use crate::life::Life;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of a file.
+const MAGIC: &[u8; 4] = b"NDLU";
+/// Version of the format.
+const VERSION: u8 = 1;
+/// Offset of the population in a file, followed by the number of chunks.
+const COUNTS_OFFSET: u64 = 9;
+
+/// Coordinates and bitmap of a chunk.
+type Chunk<const N: usize> = ([i64; N], Box<[u8]>);
+/// Chunks with the same first coordinate, by coordinates.
+type Slab<const N: usize> = HashMap<[i64; N], Box<[u8]>>;
+
+/// Streaming engine stepping a game of life stored in a file as chunks of cells
+///
+/// Only the header is held in memory. A [step](Self::step) reads the chunks in order and keeps three slabs of them at a time,
+/// the chunks sharing their first coordinate with the chunks being computed or their neighbours, so its memory is bounded
+/// by the largest slab rather than by the population. Every step rewrites the whole file: the next generation is written
+/// next to it, with the `.tmp` extension appended, made durable and renamed over it, so a process which crashes loses
+/// at most the generation it was computing and the last completed one is [opened](Self::open) again.
+///
+/// The file is not memory-mapped and does not back the cells of a [Life]: the engine has its own API,
+/// and [create](Self::create) and [to_life](Self::to_life) convert from and to a [Life] held in memory.
+///
+/// Chunks hold 64 cells, a line of 64 cells in 1 dimension, a square of 8x8 in 2 and a cube of 4x4x4 in 3,
+/// or a hypercube of 2 cells along each axis in more dimensions.
+/// # Example
+/// ```
+/// use ndlife::disk::StreamingDiskLife;
+/// use ndlife::life::conways_game_of_life;
+///
+/// let path = std::env::temp_dir().join(format!("ndlife-doc-disk-{}.ndlu", std::process::id()));
+/// let mut life = conways_game_of_life();
+/// life.randomize_region([0..=63, 0..=63], 0.5, 1);
+///
+/// let mut disk = StreamingDiskLife::create(&path, &life).unwrap();
+/// disk.advance(10).unwrap();
+/// for _ in 0..10 {
+///     life.next_generation();
+/// }
+///
+/// // reopen the file, as after a crash
+/// let disk = StreamingDiskLife::<2>::open(&path).unwrap();
+/// assert_eq!(disk.age(), 10);
+/// assert_eq!(disk.population(), life.alive_cells().len() as u64);
+/// assert_eq!(disk.to_life().unwrap().alive_cells(), life.alive_cells());
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamingDiskLife<const N: usize> {
+    /// The path of the file.
+    path: PathBuf,
+    /// The age of the life.
+    age: u64,
+    /// The rules for a dead cell to become alive.
+    birth_rules: HashSet<usize>,
+    /// The rules for alive cell to stay alive.
+    survival_rules: HashSet<usize>,
+    /// The number of alive cells.
+    population: u64,
+    /// The number of chunks with alive cells.
+    chunks: u64,
+}
+impl<const N: usize> StreamingDiskLife<N> {
+    /// Number of cells along each axis of a chunk.
+    const SIDE: i64 = match N {
+        1 => 64,
+        2 => 8,
+        3 => 4,
+        _ => 2,
+    };
+    /// Number of cells of a chunk.
+    const CELLS: usize = (Self::SIDE as usize).pow(N as u32);
+    /// Number of bytes of the bitmap of a chunk.
+    const BITMAP: usize = Self::CELLS.div_ceil(8);
+
+    /// Create a file holding the state of a game of life, replacing any file at the path.
+    /// # Arguments
+    /// * `path` - The path of the file.
+    /// * `life` - The game of life whose age, rules and alive cells are stored.
+    /// # Returns
+    /// A [Result] containing the game of life stored in the file, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be written.
+    pub fn create(path: impl AsRef<Path>, life: &Life<N>) -> Result<Self, Error> {
+        let mut chunks: BTreeMap<[i64; N], Box<[u8]>> = BTreeMap::new();
+        for cell in life.alive_cells() {
+            let (chunk, index) = Self::locate(cell);
+            let bitmap = chunks.entry(chunk).or_insert_with(|| vec![0; Self::BITMAP].into_boxed_slice());
+            bitmap[index / 8] |= 1 << (index % 8);
+        }
+        let disk = Self {
+            path: path.as_ref().to_path_buf(),
+            age: life.age(),
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+            population: life.alive_cells().len() as u64,
+            chunks: chunks.len() as u64,
+        };
+        let mut writer = disk.writer(disk.age)?;
+        for (chunk, bitmap) in &chunks {
+            write_chunk(&mut writer, chunk, bitmap)?;
+        }
+        disk.finish(writer)?;
+        Ok(disk)
+    }
+
+    /// Open a file holding the state of a game of life, reading its header and checking the order of its chunks.
+    /// # Arguments
+    /// * `path` - The path of the file.
+    /// # Returns
+    /// A [Result] containing the game of life stored in the file, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read, or is not a file of version 1 in `N` dimensions
+    ///   with its chunks in lexicographic order ([InvalidData](io::ErrorKind::InvalidData)).
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut reader = BufReader::new(File::open(path.as_ref())?);
+        let mut magic = [0; 5];
+        reader.read_exact(&mut magic)?;
+        if &magic[..4] != MAGIC || magic[4] != VERSION {
+            return Err(invalid("not a game of life file of version 1").into());
+        }
+        if read_u32(&mut reader)? as usize != N {
+            return Err(invalid(&format!("not a {}-dimensional game of life file", N)).into());
+        }
+        let (population, chunks, age) = (read_u64(&mut reader)?, read_u64(&mut reader)?, read_u64(&mut reader)?);
+        let birth_rules = read_rules(&mut reader)?;
+        let survival_rules = read_rules(&mut reader)?;
+        // validate the rules
+        Life::<N>::new(birth_rules.clone(), survival_rules.clone())?;
+        let disk = Self {
+            path: path.as_ref().to_path_buf(),
+            age,
+            birth_rules,
+            survival_rules,
+            population,
+            chunks,
+        };
+        // stepping relies on the order of the chunks
+        let mut reader = disk.reader()?;
+        let mut previous = None;
+        while let Some((chunk, _)) = reader.next_chunk()? {
+            if previous.is_some_and(|previous| previous >= chunk) {
+                return Err(invalid("chunks not in lexicographic order").into());
+            }
+            previous = Some(chunk);
+        }
+        Ok(disk)
+    }
+
+    /// Get the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the age of the life.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the birth rules.
+    pub fn birth_rules(&self) -> &HashSet<usize> {
+        &self.birth_rules
+    }
+
+    /// Get the survival rules.
+    pub fn survival_rules(&self) -> &HashSet<usize> {
+        &self.survival_rules
+    }
+
+    /// Get the number of alive cells.
+    pub fn population(&self) -> u64 {
+        self.population
+    }
+
+    /// Get the number of chunks with alive cells.
+    pub fn chunks(&self) -> u64 {
+        self.chunks
+    }
+
+    /// Advance the life by one generation, replacing the file.
+    /// # Returns
+    /// A [Result] which is an error if the file cannot be read or written; the file then keeps the current generation.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read or written.
+    pub fn step(&mut self) -> Result<(), Error> {
+        let (birth, survival) = (rule_table::<N>(&self.birth_rules), rule_table::<N>(&self.survival_rules));
+        let mut reader = self.reader()?;
+        let mut writer = self.writer(self.age + 1)?;
+        let (mut population, mut chunks) = (0, 0);
+        let mut window: BTreeMap<i64, Slab<N>> = BTreeMap::new();
+        let mut lookahead = reader.next_slab()?;
+        let mut x = match &lookahead {
+            Some((first, _)) => first - 1,
+            None => i64::MAX,
+        };
+        loop {
+            while let Some((first, _)) = &lookahead {
+                if *first > x.saturating_add(1) {
+                    break;
+                }
+                let (first, slab) = lookahead.take().expect("the lookahead was just matched");
+                window.insert(first, slab);
+                lookahead = reader.next_slab()?;
+            }
+            window = window.split_off(&x.saturating_sub(1));
+            if window.is_empty() {
+                match &lookahead {
+                    Some((first, _)) => {
+                        x = first - 1;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            let mut candidates: BTreeSet<[i64; N]> = BTreeSet::new();
+            for chunk in window.values().flat_map(|slab| slab.keys()) {
+                candidates.extend(Life::neighbours(chunk).chain([*chunk]).filter(|neighbour| neighbour[0] == x));
+            }
+            for chunk in candidates {
+                let bitmap = Self::next_chunk(&window, &chunk, &birth, &survival);
+                let alive: u32 = bitmap.iter().map(|byte| byte.count_ones()).sum();
+                if alive > 0 {
+                    write_chunk(&mut writer, &chunk, &bitmap)?;
+                    population += u64::from(alive);
+                    chunks += 1;
+                }
+            }
+            x += 1;
+        }
+        let (age, old) = (self.age, (self.population, self.chunks));
+        (self.age, self.population, self.chunks) = (self.age + 1, population, chunks);
+        if let Err(error) = self.finish(writer) {
+            (self.age, (self.population, self.chunks)) = (age, old);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Advance the life by a number of generations, replacing the file after each.
+    /// # Arguments
+    /// * `generations` - The number of generations to advance.
+    /// # Returns
+    /// A [Result] which is an error if the file cannot be read or written; the file then keeps the last completed generation.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read or written.
+    pub fn advance(&mut self, generations: u64) -> Result<(), Error> {
+        for _ in 0..generations {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Call a function on every alive cell, chunk by chunk in the order of the file, so nearby cells come together.
+    /// # Arguments
+    /// * `f` - The function to call with the coordinates of each alive cell.
+    /// # Returns
+    /// A [Result] which is an error if the file cannot be read.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read.
+    pub fn for_each_cell(&self, mut f: impl FnMut([i64; N])) -> Result<(), Error> {
+        let mut reader = self.reader()?;
+        while let Some((chunk, bitmap)) = reader.next_chunk()? {
+            for index in (0..Self::CELLS).filter(|index| bitmap[index / 8] & (1 << (index % 8)) != 0) {
+                f(Self::cell(&chunk, index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the whole game of life into memory.
+    /// # Returns
+    /// A [Result] containing the game of life, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read.
+    pub fn to_life(&self) -> Result<Life<N>, Error> {
+        let mut alive_cells = HashSet::with_capacity(self.population as usize);
+        self.for_each_cell(|cell| {
+            alive_cells.insert(cell);
+        })?;
+        Life::new_with_age(self.birth_rules.clone(), self.survival_rules.clone(), alive_cells, self.age)
+    }
+
+    /// Get the chunk of a cell and the index of the cell in it.
+    fn locate(cell: &[i64; N]) -> ([i64; N], usize) {
+        let chunk = std::array::from_fn(|axis| cell[axis].div_euclid(Self::SIDE));
+        let index = (0..N).rev().fold(0, |index, axis| index * Self::SIDE as usize + cell[axis].rem_euclid(Self::SIDE) as usize);
+        (chunk, index)
+    }
+
+    /// Get the cell at an index of a chunk.
+    fn cell(chunk: &[i64; N], mut index: usize) -> [i64; N] {
+        std::array::from_fn(|axis| {
+            let local = (index % Self::SIDE as usize) as i64;
+            index /= Self::SIDE as usize;
+            chunk[axis] * Self::SIDE + local
+        })
+    }
+
+    /// Get the bitmap of a chunk in the next generation, from the slabs around it.
+    fn next_chunk(window: &BTreeMap<i64, Slab<N>>, chunk: &[i64; N], birth: &[bool], survival: &[bool]) -> Box<[u8]> {
+        let alive = |cell: &[i64; N]| {
+            let (chunk, index) = Self::locate(cell);
+            window
+                .get(&chunk[0])
+                .and_then(|slab| slab.get(&chunk))
+                .is_some_and(|bitmap| bitmap[index / 8] & (1 << (index % 8)) != 0)
+        };
+        let mut bitmap = vec![0; Self::BITMAP].into_boxed_slice();
+        for index in 0..Self::CELLS {
+            let cell = Self::cell(chunk, index);
+            let neighbours = Life::neighbours(&cell).filter(|neighbour| alive(neighbour)).count();
+            let next = if alive(&cell) { survival[neighbours] } else { birth[neighbours] };
+            if next {
+                bitmap[index / 8] |= 1 << (index % 8);
+            }
+        }
+        bitmap
+    }
+
+    /// Open the file for reading its chunks.
+    fn reader(&self) -> Result<ChunkReader<N>, Error> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let header = 4 + 1 + 4 + 8 * 3 + 4 * 2 + 4 * (self.birth_rules.len() + self.survival_rules.len()) as u64;
+        reader.seek(SeekFrom::Start(header))?;
+        Ok(ChunkReader {
+            reader,
+            remaining: self.chunks,
+            bitmap: Self::BITMAP,
+            lookahead: None,
+        })
+    }
+
+    /// Create the temporary file of a generation and write its header, with the counts left to [finish](Self::finish).
+    fn writer(&self, age: u64) -> Result<BufWriter<File>, Error> {
+        let mut writer = BufWriter::new(File::create(self.temporary_path())?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(N as u32).to_le_bytes())?;
+        writer.write_all(&[0; 16])?;
+        writer.write_all(&age.to_le_bytes())?;
+        for rules in [&self.birth_rules, &self.survival_rules] {
+            let mut rules: Vec<usize> = rules.iter().copied().collect();
+            rules.sort_unstable();
+            writer.write_all(&(rules.len() as u32).to_le_bytes())?;
+            for rule in rules {
+                writer.write_all(&(rule as u32).to_le_bytes())?;
+            }
+        }
+        Ok(writer)
+    }
+
+    /// Write the counts to the temporary file, make it durable and replace the file with it.
+    fn finish(&self, writer: BufWriter<File>) -> Result<(), Error> {
+        let mut file = writer.into_inner().map_err(io::IntoInnerError::into_error)?;
+        file.seek(SeekFrom::Start(COUNTS_OFFSET))?;
+        file.write_all(&self.population.to_le_bytes())?;
+        file.write_all(&self.chunks.to_le_bytes())?;
+        file.sync_all()?;
+        fs::rename(self.temporary_path(), &self.path)?;
+        sync_directory(&self.path)?;
+        Ok(())
+    }
+
+    /// Get the path of the temporary file of the next generation.
+    fn temporary_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".tmp");
+        PathBuf::from(path)
+    }
+}
+
+/// Reader of the chunks of a file, in order
+struct ChunkReader<const N: usize> {
+    /// The file, positioned at the next chunk.
+    reader: BufReader<File>,
+    /// The number of chunks left to read.
+    remaining: u64,
+    /// The number of bytes of the bitmap of a chunk.
+    bitmap: usize,
+    /// The chunk read ahead of the current slab.
+    lookahead: Option<Chunk<N>>,
+}
+impl<const N: usize> ChunkReader<N> {
+    /// Read the next chunk.
+    fn next_chunk(&mut self) -> Result<Option<Chunk<N>>, Error> {
+        if let Some(chunk) = self.lookahead.take() {
+            return Ok(Some(chunk));
+        }
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let mut chunk = [0; N];
+        for coordinate in chunk.iter_mut() {
+            *coordinate = read_u64(&mut self.reader)? as i64;
+        }
+        let mut bitmap = vec![0; self.bitmap].into_boxed_slice();
+        self.reader.read_exact(&mut bitmap)?;
+        Ok(Some((chunk, bitmap)))
+    }
+
+    /// Read the chunks sharing the next first coordinate.
+    fn next_slab(&mut self) -> Result<Option<(i64, Slab<N>)>, Error> {
+        let Some((chunk, bitmap)) = self.next_chunk()? else {
+            return Ok(None);
+        };
+        let first = chunk[0];
+        let mut slab = HashMap::from([(chunk, bitmap)]);
+        while let Some((chunk, bitmap)) = self.next_chunk()? {
+            if chunk[0] != first {
+                self.lookahead = Some((chunk, bitmap));
+                break;
+            }
+            slab.insert(chunk, bitmap);
+        }
+        Ok(Some((first, slab)))
+    }
+}
+
+/// Get whether each number of neighbours is in the rules.
+fn rule_table<const N: usize>(rules: &HashSet<usize>) -> Vec<bool> {
+    (0..=Life::<N>::MAX_NEIGHBOURS).map(|neighbours| rules.contains(&neighbours)).collect()
+}
+
+/// Write a chunk.
+fn write_chunk<const N: usize>(writer: &mut impl Write, chunk: &[i64; N], bitmap: &[u8]) -> io::Result<()> {
+    for coordinate in chunk {
+        writer.write_all(&coordinate.to_le_bytes())?;
+    }
+    writer.write_all(bitmap)
+}
+
+/// Make the renaming of a file durable by syncing its directory, which only Unix-like systems allow opening.
+fn sync_directory(path: &Path) -> io::Result<()> {
+    if cfg!(unix) {
+        let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        File::open(directory)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Get an error of malformed data.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Read a little-endian `u32`.
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Read a little-endian `u64`.
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Read rules as a count followed by the rules.
+fn read_rules(reader: &mut impl Read) -> io::Result<HashSet<usize>> {
+    let count = read_u32(reader)?;
+    // the count is not trusted for the allocation, as the file may be corrupted
+    let mut rules = HashSet::with_capacity(count.min(1024) as usize);
+    for _ in 0..count {
+        rules.insert(read_u32(reader)? as usize);
+    }
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    /// Get a path in the temporary directory, unique to the process and the test.
+    fn temporary(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ndlife-disk-{}-{}.ndlu", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_chunks() {
+        for cell in [[0, 0], [7, 7], [8, 0], [-1, -1], [-9, 13]] {
+            let (chunk, index) = StreamingDiskLife::<2>::locate(&cell);
+            assert_eq!(StreamingDiskLife::<2>::cell(&chunk, index), cell);
+        }
+        assert_eq!(StreamingDiskLife::<2>::locate(&[-1, 9]), ([-1, 1], 7 + 8));
+        assert_eq!((StreamingDiskLife::<1>::CELLS, StreamingDiskLife::<3>::CELLS, StreamingDiskLife::<5>::CELLS), (64, 64, 32));
+        assert_eq!(StreamingDiskLife::<5>::BITMAP, 4);
+    }
+
+    #[test]
+    fn test_step() {
+        let path = temporary("step");
+        let mut life = conways_game_of_life();
+        // a soup around the origin, crossing the chunks of negative coordinates, and a glider far away
+        life.randomize_region([-20..=20, -20..=20], 0.4, 3);
+        life.extend([[1000, 1000], [1001, 1000], [1002, 1000], [1002, 1001], [1001, 1002]]);
+        let mut disk = StreamingDiskLife::create(&path, &life).unwrap();
+        for _ in 0..30 {
+            disk.step().unwrap();
+            life.next_generation();
+            assert_eq!(disk.population(), life.alive_cells().len() as u64);
+        }
+        assert_eq!(disk.to_life().unwrap().alive_cells(), life.alive_cells());
+        assert_eq!(StreamingDiskLife::open(&path).unwrap(), disk);
+
+        let mut cells = Vec::new();
+        disk.for_each_cell(|cell| cells.push(cell)).unwrap();
+        assert_eq!(cells.len(), life.alive_cells().len());
+        // the cells come chunk by chunk, in order of the first coordinate of the chunks
+        assert!(cells.windows(2).all(|pair| pair[0][0].div_euclid(8) <= pair[1][0].div_euclid(8)));
+
+        // a leftover temporary file of an interrupted step does not matter
+        fs::write(disk.temporary_path(), b"partial").unwrap();
+        assert_eq!(StreamingDiskLife::<2>::open(&path).unwrap().age(), 30);
+        disk.step().unwrap();
+        assert!(!disk.temporary_path().exists());
+
+        // chunks out of order are refused
+        life.set_alive_cells([[0, 0], [8, 0]].into_iter().collect());
+        let disk = StreamingDiskLife::create(&path, &life).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        let record = 2 * 8 + StreamingDiskLife::<2>::BITMAP;
+        let chunks = bytes.len() - 2 * record;
+        bytes[chunks..].rotate_left(record);
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(StreamingDiskLife::<2>::open(&path), Err(Error::IoError(error)) if error.kind() == io::ErrorKind::InvalidData));
+        bytes[chunks..].rotate_left(record);
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(StreamingDiskLife::<2>::open(&path).unwrap(), disk);
+
+        // the last generation of an empty universe
+        let mut empty = StreamingDiskLife::create(&path, &conways_game_of_life()).unwrap();
+        empty.step().unwrap();
+        assert_eq!((empty.age(), empty.population(), empty.chunks()), (1, 0, 0));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let path = temporary("dimensions");
+        let mut life = Life::<3>::empty_with_rulestring("B5/S45").unwrap();
+        life.randomize_region([-4..=4, -4..=4, -4..=4], 0.3, 5);
+        let mut disk = StreamingDiskLife::create(&path, &life).unwrap();
+        disk.advance(5).unwrap();
+        for _ in 0..5 {
+            life.next_generation();
+        }
+        assert_eq!(disk.to_life().unwrap().alive_cells(), life.alive_cells());
+        assert!(matches!(StreamingDiskLife::<2>::open(&path), Err(Error::IoError(error)) if error.kind() == io::ErrorKind::InvalidData));
+
+        let mut line = Life::<1>::empty_with_rulestring("B1/S1").unwrap();
+        line.extend([[-70], [63], [64]]);
+        let mut disk = StreamingDiskLife::create(&path, &line).unwrap();
+        disk.advance(3).unwrap();
+        line.next_generation();
+        line.next_generation();
+        line.next_generation();
+        assert_eq!(disk.to_life().unwrap().alive_cells(), line.alive_cells());
+        assert_eq!(disk.to_life().unwrap().age(), 3);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(StreamingDiskLife::<1>::open(&path), Err(Error::IoError(error)) if error.kind() == io::ErrorKind::NotFound));
+    }
+}
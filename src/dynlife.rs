@@ -0,0 +1,335 @@
+//! Game of life with the number of dimensions chosen at runtime
+
+use crate::collections::{HashMap, HashSet};
+use crate::error::Error;
+use crate::life::Life;
+use crate::rulestring;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Infinite game of life whose number of dimensions is chosen at runtime
+///
+/// This is the counterpart of [Life] for applications that learn the dimension only at runtime,
+/// such as from a configuration file. Cells are boxed slices of coordinates, so every operation taking a cell
+/// checks that it has as many coordinates as the game of life has dimensions.
+/// [Life] is faster and should be preferred when the dimension is known at compile time;
+/// the two convert into each other.
+/// # Example
+/// ```
+/// use ndlife::dynlife::DynLife;
+///
+/// let dimensions = 2; // e.g. read from a configuration file
+/// let mut life = DynLife::with_rulestring(dimensions, "B3/S23").unwrap();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]]).unwrap();
+/// life.next_generation();
+///
+/// assert_eq!(life.get_cell(&[1, 1]), Ok(true));
+/// assert_eq!(life.get_cell(&[0, 0]), Ok(false));
+/// assert_eq!(life.get_cell(&[0, 0, 0]), Err(ndlife::error::Error::DimensionMismatch(2, 3)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynLife {
+    /// The number of dimensions.
+    dimensions: usize,
+    /// The age of the life.
+    age: u64,
+    /// The rules for a dead cell to become alive.
+    birth_rules: HashSet<usize>,
+    /// The rules for alive cell to stay alive.
+    survival_rules: HashSet<usize>,
+    /// The alive cells.
+    alive_cells: HashSet<Box<[i64]>>,
+    /// The offsets of the neighbours of a cell.
+    neighbour_deltas: Vec<Box<[i64]>>,
+}
+impl DynLife {
+    /// Create a new game of life with given number of dimensions and birth and survival rules.
+    /// # Arguments
+    /// * `dimensions` - The number of dimensions.
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [max_neighbours](Self::max_neighbours).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `dimensions` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    pub fn new(dimensions: usize, birth_rules: HashSet<usize>, survival_rules: HashSet<usize>) -> Result<Self, Error> {
+        if dimensions == 0 {
+            return Err(Error::ZeroDimension);
+        }
+        if birth_rules.contains(&0) {
+            return Err(Error::ZeroNeighbourBirthRule);
+        }
+        let max_neighbours = Self::max_neighbours_in(dimensions);
+        for rule in birth_rules.iter().chain(survival_rules.iter()) {
+            if *rule > max_neighbours {
+                return Err(Error::TooHighRule(*rule, max_neighbours));
+            }
+        }
+        Ok(Self {
+            dimensions,
+            age: 0,
+            birth_rules,
+            survival_rules,
+            alive_cells: HashSet::new(),
+            neighbour_deltas: Self::neighbour_deltas(dimensions),
+        })
+    }
+
+    /// Create a new game of life without alive cells, with rules given by a rulestring in B/S notation.
+    ///
+    /// The rulestring is parsed as by [empty_with_rulestring](Life::empty_with_rulestring).
+    /// # Arguments
+    /// * `dimensions` - The number of dimensions.
+    /// * `rulestring` - The rules in B/S notation.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [InvalidRulestring](Error::InvalidRulestring) - If the rulestring is malformed.
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [max_neighbours](Self::max_neighbours).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `dimensions` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    pub fn with_rulestring(dimensions: usize, rulestring: &str) -> Result<Self, Error> {
+        let (birth_rules, survival_rules) = rulestring::parse(rulestring)?;
+        Self::new(dimensions, birth_rules, survival_rules)
+    }
+
+    /// Get the number of dimensions.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Get the maximum number of neighbours a cell can have.
+    pub fn max_neighbours(&self) -> usize {
+        Self::max_neighbours_in(self.dimensions)
+    }
+
+    /// Get the age of the game of life.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the birth rules of the game of life.
+    pub fn birth_rules(&self) -> &HashSet<usize> {
+        &self.birth_rules
+    }
+
+    /// Get the survival rules of the game of life.
+    pub fn survival_rules(&self) -> &HashSet<usize> {
+        &self.survival_rules
+    }
+
+    /// Get the alive cells in the game of life.
+    pub fn alive_cells(&self) -> &HashSet<Box<[i64]>> {
+        &self.alive_cells
+    }
+
+    /// Set the alive cells in the game of life.
+    /// # Arguments
+    /// * `alive_cells` - The coordinates of the alive cells.
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error, in which case the game of life is unchanged.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If any cell does not have [dimensions](Self::dimensions) coordinates.
+    pub fn set_alive_cells<C: AsRef<[i64]>>(&mut self, alive_cells: impl IntoIterator<Item = C>) -> Result<(), Error> {
+        let alive_cells = alive_cells
+            .into_iter()
+            .map(|cell| self.check_cell(cell.as_ref()).map(Box::from))
+            .collect::<Result<_, _>>()?;
+        self.alive_cells = alive_cells;
+        Ok(())
+    }
+
+    /// Get the state of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// A [Result] containing whether the cell is alive, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If `cell` does not have [dimensions](Self::dimensions) coordinates.
+    pub fn get_cell(&self, cell: &[i64]) -> Result<bool, Error> {
+        Ok(self.alive_cells.contains(self.check_cell(cell)?))
+    }
+
+    /// Set the state of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// * `state` - The state of the cell (true = alive, false = dead).
+    /// # Returns
+    /// A [Result] containing whether the state of the cell changed, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If `cell` does not have [dimensions](Self::dimensions) coordinates.
+    pub fn set_cell(&mut self, cell: &[i64], state: bool) -> Result<bool, Error> {
+        let cell = self.check_cell(cell)?;
+        Ok(if state {
+            self.alive_cells.insert(Box::from(cell))
+        } else {
+            self.alive_cells.remove(cell)
+        })
+    }
+
+    /// Advance the game of life by one generation.
+    pub fn next_generation(&mut self) {
+        self.age += 1;
+        let mut alive_cells = HashSet::with_capacity(self.alive_cells.len());
+        let mut dead_neighbours: HashMap<Box<[i64]>, usize> = HashMap::new();
+        let mut neighbour = vec![0; self.dimensions];
+
+        for alive_cell in self.alive_cells.iter() {
+            let mut alive_neighbours = 0;
+            for delta in self.neighbour_deltas.iter() {
+                for i in 0..self.dimensions {
+                    neighbour[i] = alive_cell[i] + delta[i];
+                }
+                if self.alive_cells.contains(neighbour.as_slice()) {
+                    alive_neighbours += 1;
+                } else if let Some(count) = dead_neighbours.get_mut(neighbour.as_slice()) {
+                    *count += 1;
+                } else {
+                    dead_neighbours.insert(Box::from(neighbour.as_slice()), 1);
+                }
+            }
+            if self.survival_rules.contains(&alive_neighbours) {
+                alive_cells.insert(alive_cell.clone());
+            }
+        }
+
+        for (cell, count) in dead_neighbours {
+            if self.birth_rules.contains(&count) {
+                alive_cells.insert(cell);
+            }
+        }
+        self.alive_cells = alive_cells;
+    }
+
+    /// Convert into a game of life with the number of dimensions fixed at compile time.
+    /// # Returns
+    /// A [Result] containing the converted game of life if successful, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If `N` is not [dimensions](Self::dimensions).
+    /// # Example
+    /// ```
+    /// use ndlife::dynlife::DynLife;
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let dynlife = DynLife::from(life.clone());
+    /// assert_eq!(dynlife.to_life::<2>().unwrap(), life);
+    /// assert!(dynlife.to_life::<3>().is_err());
+    /// ```
+    pub fn to_life<const N: usize>(&self) -> Result<Life<N>, Error> {
+        if N != self.dimensions {
+            return Err(Error::DimensionMismatch(self.dimensions, N));
+        }
+        let alive_cells = self.alive_cells.iter().map(|cell| core::array::from_fn(|i| cell[i])).collect();
+        Life::new_with_age(self.birth_rules.clone(), self.survival_rules.clone(), alive_cells, self.age)
+    }
+
+    /// Check that a cell has as many coordinates as the game of life has dimensions.
+    fn check_cell<'a>(&self, cell: &'a [i64]) -> Result<&'a [i64], Error> {
+        if cell.len() != self.dimensions {
+            return Err(Error::DimensionMismatch(self.dimensions, cell.len()));
+        }
+        Ok(cell)
+    }
+
+    /// Get the maximum number of neighbours a cell can have in a given number of dimensions.
+    fn max_neighbours_in(dimensions: usize) -> usize {
+        3usize.saturating_pow(u32::try_from(dimensions).unwrap_or(u32::MAX)) - 1
+    }
+
+    /// Get the offsets of the neighbours of a cell in a given number of dimensions.
+    fn neighbour_deltas(dimensions: usize) -> Vec<Box<[i64]>> {
+        let mut deltas = Vec::with_capacity(Self::max_neighbours_in(dimensions));
+        let mut delta = vec![-1i64; dimensions];
+        loop {
+            if delta.iter().any(|&offset| offset != 0) {
+                deltas.push(Box::from(delta.as_slice()));
+            }
+            // advance the odometer of offsets, each running from -1 to 1
+            match delta.iter().position(|&offset| offset != 1) {
+                Some(axis) => {
+                    delta[axis] += 1;
+                    delta[..axis].fill(-1);
+                }
+                None => return deltas,
+            }
+        }
+    }
+}
+impl<const N: usize> From<Life<N>> for DynLife {
+    fn from(life: Life<N>) -> Self {
+        let age = life.age();
+        let birth_rules = life.birth_rules().clone();
+        let survival_rules = life.survival_rules().clone();
+        let mut dynlife = Self::new(N, birth_rules, survival_rules).expect("the rules of a game of life are valid in its dimension");
+        dynlife.age = age;
+        dynlife.alive_cells = life.into_alive_cells().into_iter().map(|cell| Box::from(cell.as_slice())).collect();
+        dynlife
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(DynLife::new(0, HashSet::new(), HashSet::new()), Err(Error::ZeroDimension));
+        assert_eq!(DynLife::new(2, [0].into_iter().collect(), HashSet::new()), Err(Error::ZeroNeighbourBirthRule));
+        assert_eq!(DynLife::new(1, [3].into_iter().collect(), HashSet::new()), Err(Error::TooHighRule(3, 2)));
+        assert_eq!(DynLife::with_rulestring(2, "B3/X"), Err(Error::InvalidRulestring(4)));
+
+        let life = DynLife::with_rulestring(4, "B3/S23").unwrap();
+        assert_eq!(life.dimensions(), 4);
+        assert_eq!(life.max_neighbours(), 80);
+        assert_eq!(life.neighbour_deltas.len(), 80);
+        assert_eq!(life.age(), 0);
+        assert_eq!(life.birth_rules(), &[3].into_iter().collect());
+        assert_eq!(life.survival_rules(), &[2, 3].into_iter().collect());
+    }
+
+    #[test]
+    fn test_cells() {
+        let mut life = DynLife::with_rulestring(3, "B4/S4").unwrap();
+        assert_eq!(life.set_cell(&[1, 2, 3], true), Ok(true));
+        assert_eq!(life.set_cell(&[1, 2, 3], true), Ok(false));
+        assert_eq!(life.get_cell(&[1, 2, 3]), Ok(true));
+        assert_eq!(life.set_cell(&[1, 2], true), Err(Error::DimensionMismatch(3, 2)));
+        assert_eq!(life.set_cell(&[1, 2, 3], false), Ok(true));
+        assert!(life.alive_cells().is_empty());
+
+        assert_eq!(life.set_alive_cells([vec![0, 0, 0], vec![1, 1]]), Err(Error::DimensionMismatch(3, 2)));
+        assert!(life.alive_cells().is_empty());
+        life.set_alive_cells([[0, 0, 0], [1, 1, 1]]).unwrap();
+        assert_eq!(life.alive_cells().len(), 2);
+    }
+
+    #[test]
+    fn test_matches_life() {
+        let mut life = Life::<3>::new([4].into_iter().collect(), [4, 5].into_iter().collect()).unwrap();
+        life.set_alive_cells([[0, 0, 0], [1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 1, 1], [2, 1, 0]].into_iter().collect());
+        let mut dynlife = DynLife::from(life.clone());
+        for _ in 0..10 {
+            life.next_generation();
+            dynlife.next_generation();
+            assert_eq!(dynlife.to_life::<3>().unwrap().alive_cells(), life.alive_cells());
+        }
+        assert_eq!(dynlife.age(), 10);
+        assert_eq!(dynlife.to_life::<2>(), Err(Error::DimensionMismatch(3, 2)));
+
+        let mut glider = conways_game_of_life();
+        glider.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let mut dynlife = DynLife::from(glider.clone());
+        for _ in 0..4 {
+            dynlife.next_generation();
+        }
+        glider.translate([1, -1]);
+        assert_eq!(dynlife.to_life::<2>().unwrap().alive_cells(), glider.alive_cells());
+    }
+}
@@ -0,0 +1,222 @@
+//! Many games of life stepped together, for statistical studies
+
+use crate::error::Error;
+use crate::life::Life;
+use crate::region::Region;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+
+/// Aggregate statistics of the universes of an [Ensemble] in one generation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationStats {
+    /// The generation, counted from the creation of the ensemble.
+    pub generation: u64,
+    /// The mean number of alive cells of the universes.
+    pub mean_population: f64,
+    /// The fraction of the universes with alive cells.
+    pub survival_fraction: f64,
+}
+
+/// Many games of life, such as soups of different seeds or one pattern under different rules, stepped in lockstep
+///
+/// Every call to [step](Self::step) advances all the universes by the same number of generations, spread over a pool of threads,
+/// and records the [GenerationStats] of each generation, so the mean population and the survival fraction of a whole
+/// population of soups can be plotted against time without writing the orchestration. An empty ensemble has a mean
+/// population and a survival fraction of 0.
+/// # Example
+/// ```
+/// use ndlife::ensemble::Ensemble;
+/// use ndlife::life::conways_game_of_life;
+///
+/// // 20 soups of 16x16 cells at half density
+/// let mut ensemble = Ensemble::soups(&conways_game_of_life(), [0..=15, 0..=15], 0.5, 0..20);
+/// ensemble.step(100);
+///
+/// let stats = ensemble.stats();
+/// assert_eq!(stats.len(), 101);
+/// assert_eq!(stats[100].generation, 100);
+/// assert!(stats[100].mean_population < stats[0].mean_population);
+/// assert!(stats.iter().all(|stats| stats.survival_fraction <= 1.0));
+/// assert!(ensemble.universes().iter().all(|universe| universe.age() == 100));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ensemble<const N: usize> {
+    /// The games of life.
+    universes: Vec<Life<N>>,
+    /// The statistics of every generation since the creation of the ensemble.
+    stats: Vec<GenerationStats>,
+    /// The number of threads stepping universes.
+    threads: usize,
+}
+impl<const N: usize> Ensemble<N> {
+    /// Create an ensemble of games of life, stepped on as many threads as the machine runs in parallel.
+    /// # Arguments
+    /// * `universes` - The games of life, with any rules, alive cells and ages.
+    pub fn new(universes: impl IntoIterator<Item = Life<N>>) -> Self {
+        let universes: Vec<Life<N>> = universes.into_iter().collect();
+        let populations: Vec<usize> = universes.iter().map(|universe| universe.alive_cells().len()).collect();
+        Self {
+            stats: vec![stats(
+                0,
+                populations.iter().sum::<usize>() as u64,
+                populations.iter().filter(|population| **population > 0).count(),
+                universes.len(),
+            )],
+            universes,
+            threads: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Create an ensemble of random soups under the same rules, one for each seed.
+    /// # Arguments
+    /// * `life` - The game of life whose rules and alive cells the soups start from.
+    /// * `region` - The region of every soup, as in [randomize_region](Life::randomize_region).
+    /// * `density` - The probability of a cell of the region being alive.
+    /// * `seeds` - The seeds of the soups.
+    pub fn soups(life: &Life<N>, region: impl Into<Region<N>>, density: f64, seeds: impl IntoIterator<Item = u64>) -> Self {
+        let region = region.into();
+        Self::new(seeds.into_iter().map(|seed| {
+            let mut soup = life.clone();
+            soup.randomize_region(region, density, seed);
+            soup
+        }))
+    }
+
+    /// Create an ensemble of the same alive cells under different rules.
+    /// # Arguments
+    /// * `alive_cells` - The alive cells of every universe.
+    /// * `rules` - The birth and survival rules of each universe, such as a [RuleSpace](crate::sweep::RuleSpace).
+    /// # Returns
+    /// A [Result] containing the ensemble, or an error if any rule is invalid.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If any birth rules contain 0.
+    pub fn rules(alive_cells: &HashSet<[i64; N]>, rules: impl IntoIterator<Item = (HashSet<usize>, HashSet<usize>)>) -> Result<Self, Error> {
+        let universes: Result<Vec<Life<N>>, Error> = rules
+            .into_iter()
+            .map(|(birth_rules, survival_rules)| Life::new_with_alive_cells(birth_rules, survival_rules, alive_cells.clone()))
+            .collect();
+        Ok(Self::new(universes?))
+    }
+
+    /// Set the number of threads stepping universes, at least 1.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Get the games of life, in order of creation.
+    pub fn universes(&self) -> &[Life<N>] {
+        &self.universes
+    }
+
+    /// Get the games of life, consuming the ensemble.
+    pub fn into_universes(self) -> Vec<Life<N>> {
+        self.universes
+    }
+
+    /// Get the number of generations since the creation of the ensemble.
+    pub fn generation(&self) -> u64 {
+        self.stats.len() as u64 - 1
+    }
+
+    /// Get the statistics of every generation since the creation of the ensemble, starting with the initial states.
+    pub fn stats(&self) -> &[GenerationStats] {
+        &self.stats
+    }
+
+    /// Advance every universe by a number of generations, recording the statistics of each.
+    /// # Arguments
+    /// * `generations` - The number of generations to advance.
+    pub fn step(&mut self, generations: u64) {
+        let steps = usize::try_from(generations).expect("the statistics of every generation fit in memory");
+        let count = self.universes.len();
+        let queue = Mutex::new(self.universes.iter_mut());
+        let totals: Vec<(Vec<u64>, Vec<usize>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(count))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let (mut populations, mut survivors) = (vec![0; steps], vec![0; steps]);
+                        loop {
+                            let next = queue.lock().expect("an ensemble thread panicked").next();
+                            let Some(universe) = next else {
+                                break (populations, survivors);
+                            };
+                            for step in 0..steps {
+                                universe.next_generation();
+                                let population = universe.alive_cells().len();
+                                populations[step] += population as u64;
+                                survivors[step] += usize::from(population > 0);
+                            }
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join().expect("an ensemble thread panicked")).collect()
+        });
+        let start = self.generation();
+        for step in 0..steps {
+            let population = totals.iter().map(|(populations, _)| populations[step]).sum();
+            let survivors = totals.iter().map(|(_, survivors)| survivors[step]).sum();
+            self.stats.push(stats(start + step as u64 + 1, population, survivors, count));
+        }
+    }
+}
+
+/// Get the statistics of a generation from the total population and the number of universes with alive cells.
+fn stats(generation: u64, population: u64, survivors: usize, universes: usize) -> GenerationStats {
+    let universes = universes.max(1) as f64;
+    GenerationStats {
+        generation,
+        mean_population: population as f64 / universes,
+        survival_fraction: survivors as f64 / universes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_rules() {
+        let blinker: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+        let rules = |birth: &[usize], survival: &[usize]| (birth.iter().copied().collect(), survival.iter().copied().collect());
+        let mut ensemble = Ensemble::rules(&blinker, [rules(&[3], &[2, 3]), rules(&[3], &[]), rules(&[], &[1, 2]), rules(&[], &[])]).unwrap();
+        ensemble.set_threads(3);
+        ensemble.step(1);
+        ensemble.step(2);
+        assert_eq!(ensemble.generation(), 3);
+        let means: Vec<f64> = ensemble.stats().iter().map(|stats| stats.mean_population).collect();
+        // the blinker, two births before dying out, a still life and an immediate death
+        assert_eq!(means, [3.0, 2.0, 1.5, 1.5]);
+        let fractions: Vec<f64> = ensemble.stats().iter().map(|stats| stats.survival_fraction).collect();
+        assert_eq!(fractions, [1.0, 0.75, 0.5, 0.5]);
+        assert_eq!(ensemble.into_universes()[0].alive_cells(), &[[1, 1], [1, 0], [1, -1]].into_iter().collect());
+        assert_eq!(Ensemble::rules(&blinker, [rules(&[0], &[])]), Err(Error::ZeroNeighbourBirthRule));
+    }
+
+    #[test]
+    fn test_soups() {
+        let life = conways_game_of_life();
+        let mut parallel = Ensemble::soups(&life, [0..=7, 0..=7], 0.4, 0..10);
+        parallel.set_threads(4);
+        parallel.step(30);
+        let mut serial = Ensemble::soups(&life, [0..=7, 0..=7], 0.4, 0..10);
+        serial.set_threads(1);
+        serial.step(30);
+        assert_eq!(parallel, Ensemble { threads: 4, ..serial });
+
+        let mut soup = life.clone();
+        soup.randomize_region([0..=7, 0..=7], 0.4, 3);
+        for _ in 0..30 {
+            soup.next_generation();
+        }
+        assert_eq!(parallel.universes()[3], soup);
+
+        let mut empty = Ensemble::<2>::new([]);
+        empty.step(2);
+        assert_eq!(empty.stats()[2], stats(2, 0, 0, 0));
+        assert_eq!(empty.stats()[2].mean_population, 0.0);
+    }
+}
@@ -0,0 +1,199 @@
+//! Keeping one game engine entity per alive cell
+
+use crate::collections::HashMap;
+use crate::life::Life;
+use alloc::vec::Vec;
+
+/// Change of a cell between two synchronisations of [CellEntities]
+///
+/// With the `bevy` feature, it is also the Bevy message written by the `LifePlugin` for every born and died cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CellEvent<const N: usize> {
+    /// The cell became alive, so it needs an entity.
+    Born([i64; N]),
+    /// The cell died, so its entity should be removed.
+    Died([i64; N]),
+}
+
+/// Map from the alive cells of a game of life to the entities representing them in a game engine
+///
+/// Games usually spawn an entity (a sprite, a mesh, a node) per alive cell and must spawn and despawn entities
+/// as the universe evolves. [sync](Self::sync) compares the map with the game of life and calls back to spawn
+/// entities for new cells and despawn the entities of dead cells, so the map always mirrors the alive cells,
+/// whether the game of life was stepped once, many times or edited in between.
+///
+/// The entity type is anything the engine hands out, such as Bevy's `Entity`. In an ECS, the game of life and the map
+/// are resources and a system after the stepping system calls `sync` with the command buffer as the state:
+/// `cells.sync(&life, &mut commands, |commands, cell| commands.spawn(sprite(cell)).id(), |commands, _, entity| commands.entity(entity).despawn())`.
+/// # Example
+/// ```
+/// use ndlife::entities::CellEntities;
+/// use ndlife::life::conways_game_of_life;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// // the "engine" is a list of spawned entities, identified by their index
+/// let mut spawned: Vec<Option<[i64; 2]>> = Vec::new();
+/// let mut cells = CellEntities::new();
+/// let spawn = |spawned: &mut Vec<Option<[i64; 2]>>, cell| {
+///     spawned.push(Some(cell));
+///     spawned.len() - 1
+/// };
+/// let despawn = |spawned: &mut Vec<Option<[i64; 2]>>, _, entity: usize| spawned[entity] = None;
+///
+/// assert_eq!(cells.sync(&life, &mut spawned, spawn, despawn), 3);
+/// let middle = cells.get(&[1, 0]).copied();
+/// life.next_generation();
+/// // the middle cell keeps its entity, two cells are born and two die
+/// assert_eq!(cells.sync(&life, &mut spawned, spawn, despawn), 4);
+/// assert_eq!(cells.get(&[1, 0]).copied(), middle);
+/// assert_eq!(spawned.iter().flatten().count(), 3);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CellEntities<const N: usize, E> {
+    /// The entity of every cell alive at the last synchronisation.
+    entities: HashMap<[i64; N], E>,
+}
+impl<const N: usize, E> CellEntities<N, E> {
+    /// Create a map without entities.
+    pub fn new() -> Self {
+        Self { entities: HashMap::new() }
+    }
+
+    /// Get the number of entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Check whether there are no entities.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Get the entity of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// The entity, or [None] if the cell was not alive at the last synchronisation.
+    pub fn get(&self, cell: &[i64; N]) -> Option<&E> {
+        self.entities.get(cell)
+    }
+
+    /// Iterate over the cells and their entities, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[i64; N], &E)> {
+        self.entities.iter()
+    }
+
+    /// Get the changes of the cells since the last synchronisation, without applying them.
+    /// # Arguments
+    /// * `life` - The game of life to compare with.
+    /// # Returns
+    /// The events of the cells that died, followed by those of the cells that were born, each in no particular order.
+    pub fn changes(&self, life: &Life<N>) -> Vec<CellEvent<N>> {
+        let died = self.entities.keys().filter(|cell| !life.alive_cells().contains(*cell)).map(|cell| CellEvent::Died(*cell));
+        let born = life
+            .alive_cells()
+            .iter()
+            .filter(|cell| !self.entities.contains_key(*cell))
+            .map(|cell| CellEvent::Born(*cell));
+        died.chain(born).collect()
+    }
+
+    /// Spawn and despawn entities so that every alive cell has exactly one entity.
+    ///
+    /// Entities of dead cells are despawned before new ones are spawned, so engines can recycle them.
+    /// # Arguments
+    /// * `life` - The game of life to mirror.
+    /// * `state` - State passed to both callbacks, such as the command buffer of the engine.
+    /// * `spawn` - Create the entity of a born cell.
+    /// * `despawn` - Remove the entity of a dead cell.
+    /// # Returns
+    /// The number of spawned and despawned entities.
+    pub fn sync<S: ?Sized>(&mut self, life: &Life<N>, state: &mut S, mut spawn: impl FnMut(&mut S, [i64; N]) -> E, mut despawn: impl FnMut(&mut S, [i64; N], E)) -> usize {
+        let changes = self.changes(life);
+        for event in changes.iter() {
+            match *event {
+                CellEvent::Died(cell) => {
+                    let entity = self.entities.remove(&cell).expect("died cells have entities");
+                    despawn(state, cell, entity);
+                }
+                CellEvent::Born(cell) => {
+                    let entity = spawn(state, cell);
+                    self.entities.insert(cell, entity);
+                }
+            }
+        }
+        changes.len()
+    }
+
+    /// Remove all entities from the map, calling back to despawn each of them.
+    /// # Arguments
+    /// * `state` - State passed to the callback.
+    /// * `despawn` - Remove the entity of a cell.
+    pub fn clear<S: ?Sized>(&mut self, state: &mut S, mut despawn: impl FnMut(&mut S, [i64; N], E)) {
+        for (cell, entity) in self.entities.drain() {
+            despawn(state, cell, entity);
+        }
+    }
+}
+impl<const N: usize, E> Default for CellEntities<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use alloc::vec;
+
+    #[test]
+    fn test_changes() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut cells: CellEntities<2, u32> = CellEntities::new();
+        assert!(cells.is_empty());
+        let mut born = cells.changes(&life);
+        born.sort_by_key(|event| match event {
+            CellEvent::Born(cell) | CellEvent::Died(cell) => *cell,
+        });
+        assert_eq!(born, vec![CellEvent::Born([0, 0]), CellEvent::Born([1, 0]), CellEvent::Born([2, 0])]);
+
+        let mut next_id = 0;
+        let spawn = |next_id: &mut u32, _| {
+            *next_id += 1;
+            *next_id
+        };
+        cells.sync(&life, &mut next_id, spawn, |_, _, _| {});
+        assert_eq!(cells.len(), 3);
+        assert!(cells.changes(&life).is_empty());
+
+        // several generations and an edit between synchronisations
+        life.step_n(3);
+        life.set_cell(&[5, 5], true);
+        let mut despawned = Vec::new();
+        let mut despawn_state = (next_id, &mut despawned);
+        let changed = cells.sync(
+            &life,
+            &mut despawn_state,
+            |(next_id, _), _| {
+                *next_id += 1;
+                *next_id
+            },
+            |(_, despawned), cell, _| despawned.push(cell),
+        );
+        despawned.sort();
+        assert_eq!(changed, 5);
+        assert_eq!(despawned, vec![[0, 0], [2, 0]]);
+        assert_eq!(cells.iter().count(), 4);
+        assert!(cells.get(&[1, 0]).is_some_and(|id| *id <= 3));
+        assert!(cells.get(&[5, 5]).is_some_and(|id| *id > 3));
+
+        let mut cleared = 0;
+        cells.clear(&mut cleared, |cleared, _, _| *cleared += 1);
+        assert_eq!(cleared, 4);
+        assert!(cells.is_empty());
+    }
+}
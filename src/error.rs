@@ -1,11 +1,40 @@
 //! Error type for the library
 
-use std::error::Error as StdError;
-use std::fmt::Display;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use core::error::Error as StdError;
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::io;
 
 /// Error type for the library
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+///
+/// New variants may be added in future releases, so matches on it need a wildcard arm.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
+    /// Coordinates fall outside the range representable by [i64]
+    CoordinateOverflow,
+    /// Number of given coordinates does not match the number of dimensions they are for - (expected, found)
+    DimensionMismatch(usize, usize),
+    /// Two axes that must span a plane are the same axis - (axis)
+    IdenticalAxes(usize),
+    /// Pattern text contains a character that is not allowed - (character, line, column)
+    InvalidCharacter(char, usize, usize),
+    /// Axis is out of range for the dimensionality of the grid - (axis, dimensions)
+    InvalidAxis(usize, usize),
+    /// Object cannot move in the requested direction
+    InvalidDirection,
+    /// Rulestring is malformed at the given position - (column)
+    InvalidRulestring(usize),
+    /// Reading or writing failed - (the underlying error, shared so the error stays cloneable)
+    #[cfg(feature = "std")]
+    IoError(Arc<io::Error>),
+    /// State does not match the one a change applies to, as identified by the content hash - (expected, found)
+    OutOfSync(u64, u64),
+    /// Text is malformed at a location - (details)
+    ParseError(ParseError),
     /// Rule specifies more neighbours than the dimensionality of the grid allows - (neighbours, max_neighbours)
     TooHighRule(usize, usize),
     /// Life in a zero-dimensional space is not possible
@@ -14,8 +43,19 @@ pub enum Error {
     ZeroNeighbourBirthRule,
 }
 impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            Self::CoordinateOverflow => write!(f, "Coordinates overflow the range of a 64-bit integer"),
+            Self::DimensionMismatch(expected, found) => write!(f, "Expected {} coordinates, found {}", expected, found),
+            Self::IdenticalAxes(axis) => write!(f, "Axes spanning a plane must be distinct (both are {})", axis),
+            Self::InvalidAxis(axis, dimensions) => write!(f, "Axis {} is out of range for a {}-dimensional grid", axis, dimensions),
+            Self::InvalidCharacter(character, line, column) => write!(f, "Invalid character {:?} in pattern at line {}, column {}", character, line, column),
+            Self::InvalidDirection => write!(f, "The object cannot move in the requested direction"),
+            Self::InvalidRulestring(column) => write!(f, "Invalid rulestring at column {}", column),
+            #[cfg(feature = "std")]
+            Self::IoError(error) => write!(f, "I/O error: {}", error),
+            Self::OutOfSync(expected, found) => write!(f, "Expected a state with content hash {:016x}, found {:016x}", expected, found),
+            Self::ParseError(error) => write!(f, "{}", error),
             Self::TooHighRule(neighbours, max_neighbours) => write!(
                 f,
                 "A rule specifies more neighbours ({}) than the dimensionality of the grid allows (max {})",
@@ -26,4 +66,130 @@ impl Display for Error {
         }
     }
 }
-impl StdError for Error {}
+impl PartialEq for Error {
+    /// Compare errors, treating I/O errors as equal if they are of the same [kind](io::Error::kind).
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::CoordinateOverflow, Self::CoordinateOverflow)
+            | (Self::InvalidDirection, Self::InvalidDirection)
+            | (Self::ZeroDimension, Self::ZeroDimension)
+            | (Self::ZeroNeighbourBirthRule, Self::ZeroNeighbourBirthRule) => true,
+            (Self::DimensionMismatch(a, b), Self::DimensionMismatch(c, d))
+            | (Self::InvalidAxis(a, b), Self::InvalidAxis(c, d))
+            | (Self::TooHighRule(a, b), Self::TooHighRule(c, d)) => (a, b) == (c, d),
+            (Self::IdenticalAxes(a), Self::IdenticalAxes(b)) | (Self::InvalidRulestring(a), Self::InvalidRulestring(b)) => a == b,
+            (Self::InvalidCharacter(a, b, c), Self::InvalidCharacter(d, e, f)) => (a, b, c) == (d, e, f),
+            #[cfg(feature = "std")]
+            (Self::IoError(a), Self::IoError(b)) => a.kind() == b.kind(),
+            (Self::OutOfSync(a, b), Self::OutOfSync(c, d)) => (a, b) == (c, d),
+            (Self::ParseError(a), Self::ParseError(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+impl Eq for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::IoError(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::IoError(Arc::new(error))
+    }
+}
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::ParseError(error)
+    }
+}
+
+/// Malformed text, with the location of the problem
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ParseError {
+    /// The line of the problem (1-based).
+    line: usize,
+    /// The column of the problem (1-based).
+    column: usize,
+    /// The description of the problem.
+    message: String,
+}
+impl ParseError {
+    /// Create a parse error.
+    /// # Arguments
+    /// * `line` - The line of the problem (1-based).
+    /// * `column` - The column of the problem (1-based).
+    /// * `message` - The description of the problem.
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Get the line of the problem (1-based).
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Get the column of the problem (1-based).
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Get the description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+impl StdError for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Error::InvalidAxis(3, 2), Error::InvalidAxis(3, 2));
+        assert_ne!(Error::InvalidAxis(3, 2), Error::InvalidAxis(2, 2));
+        assert_ne!(Error::InvalidAxis(3, 2), Error::TooHighRule(3, 2));
+    }
+
+    #[test]
+    fn test_source() {
+        let error = Error::from(ParseError::new(2, 7, "unexpected character"));
+        assert_eq!(error.to_string(), "unexpected character at line 2, column 7");
+        assert!(error.source().is_none());
+        assert!(Error::CoordinateOverflow.source().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_io_error() {
+        assert_eq!(
+            Error::from(io::Error::from(io::ErrorKind::NotFound)),
+            Error::from(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        );
+        assert_ne!(
+            Error::from(io::Error::from(io::ErrorKind::NotFound)),
+            Error::from(io::Error::from(io::ErrorKind::InvalidData))
+        );
+
+        let error = Error::from(io::Error::new(io::ErrorKind::InvalidData, "truncated"));
+        assert_eq!(error.to_string(), "I/O error: truncated");
+        assert_eq!(error.source().unwrap().to_string(), "truncated");
+        assert!(error.clone().source().is_some());
+    }
+}
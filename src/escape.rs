@@ -0,0 +1,195 @@
+//! Detection and removal of objects escaping from the main population
+
+use crate::collections::HashSet;
+use crate::geometry;
+use crate::life::Life;
+use alloc::vec::Vec;
+
+/// An object found receding from the main population at constant velocity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escapee<const N: usize> {
+    /// The cells of the object when it was detected.
+    pub cells: HashSet<[i64; N]>,
+    /// The age of the life when the object was detected.
+    pub age: u64,
+    /// The number of generations after which the object repeats its shape.
+    pub period: u64,
+    /// The displacement of the object over one period.
+    pub displacement: [i64; N],
+}
+
+/// Detector of objects (gliders, spaceships) receding from the main population at constant velocity
+///
+/// A connected component of the alive cells is considered escaping if, evolved in isolation,
+/// it reappears translated after at most `max_period` generations, and it lies more than `margin` cells
+/// beyond the bounding box of the remaining cells along an axis in whose direction it moves.
+/// Removing escapees lets the stationary ash stabilize and keeps the memory from growing with the run.
+/// # Example
+/// ```
+/// use ndlife::escape::EscapeDetector;
+/// use ndlife::life::conways_game_of_life;
+///
+/// let mut life = conways_game_of_life();
+/// // a block and a glider flying away from it
+/// let block = [[0, 0], [1, 0], [0, 1], [1, 1]];
+/// let glider = [[20, -20], [21, -20], [22, -20], [22, -19], [21, -18]];
+/// life.set_alive_cells(block.into_iter().chain(glider).collect());
+///
+/// let mut detector = EscapeDetector::new(4, 5);
+/// assert_eq!(detector.remove_escapees(&mut life), 1);
+///
+/// assert_eq!(life.alive_cells(), &block.into_iter().collect());
+/// assert_eq!(detector.log()[0].period, 4);
+/// assert_eq!(detector.log()[0].displacement, [1, -1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapeDetector<const N: usize> {
+    /// The maximum period of the detected objects.
+    max_period: u64,
+    /// The minimum gap between an escaping object and the rest of the population.
+    margin: u64,
+    /// The objects removed so far.
+    log: Vec<Escapee<N>>,
+}
+impl<const N: usize> EscapeDetector<N> {
+    /// Create a new escape detector.
+    /// # Arguments
+    /// * `max_period` - The maximum period of the detected objects.
+    /// * `margin` - The minimum gap between an escaping object and the rest of the population.
+    pub fn new(max_period: u64, margin: u64) -> Self {
+        Self {
+            max_period,
+            margin,
+            log: Vec::new(),
+        }
+    }
+
+    /// Get the maximum period of the detected objects.
+    pub fn max_period(&self) -> u64 {
+        self.max_period
+    }
+
+    /// Get the minimum gap between an escaping object and the rest of the population.
+    pub fn margin(&self) -> u64 {
+        self.margin
+    }
+
+    /// Get the objects removed so far, in the order they were removed.
+    pub fn log(&self) -> &[Escapee<N>] {
+        &self.log
+    }
+
+    /// Find the objects currently escaping from the main population.
+    /// # Arguments
+    /// * `life` - The game of life to inspect.
+    /// # Returns
+    /// A vector of escaping objects.
+    pub fn detect(&self, life: &Life<N>) -> Vec<Escapee<N>> {
+        let components = life.connected_components();
+        let mut escapees = Vec::new();
+        for (index, component) in components.iter().enumerate() {
+            let rest = components.iter().enumerate().filter(|(other, _)| *other != index).flat_map(|(_, other)| other.iter());
+            let Some((rest_min, rest_max)) = geometry::bounding_box(rest) else {
+                continue;
+            };
+            let Some((period, displacement)) = self.motion(life, component) else {
+                continue;
+            };
+            let (min, max) = geometry::bounding_box(component).expect("components are never empty");
+            let margin = self.margin as i128;
+            let receding = (0..N)
+                .any(|i| (displacement[i] > 0 && min[i] as i128 - rest_max[i] as i128 > margin) || (displacement[i] < 0 && rest_min[i] as i128 - max[i] as i128 > margin));
+            if receding {
+                escapees.push(Escapee {
+                    cells: component.clone(),
+                    age: life.age(),
+                    period,
+                    displacement,
+                });
+            }
+        }
+        escapees
+    }
+
+    /// Remove the objects currently escaping from the main population and add them to the [log](Self::log).
+    /// # Arguments
+    /// * `life` - The game of life to clean up.
+    /// # Returns
+    /// The number of removed objects.
+    pub fn remove_escapees(&mut self, life: &mut Life<N>) -> usize {
+        let escapees = self.detect(life);
+        for escapee in escapees.iter() {
+            for cell in escapee.cells.iter() {
+                life.set_cell(cell, false);
+            }
+        }
+        let count = escapees.len();
+        self.log.extend(escapees);
+        count
+    }
+
+    /// Evolve an object in isolation and find its period and displacement if it moves at constant velocity.
+    fn motion(&self, life: &Life<N>, cells: &HashSet<[i64; N]>) -> Option<(u64, [i64; N])> {
+        let mut isolated = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), cells.clone()).ok()?;
+        let (start, _) = geometry::bounding_box(cells)?;
+        for period in 1..=self.max_period {
+            isolated.next_generation();
+            let (min, _) = isolated.bounding_box()?;
+            if isolated.alive_cells().len() != cells.len() {
+                continue;
+            }
+            let displacement: [i64; N] = core::array::from_fn(|i| min[i] - start[i]);
+            if cells.iter().all(|cell| isolated.get_cell(&core::array::from_fn(|i| cell[i] + displacement[i]))) {
+                return displacement.iter().any(|&delta| delta != 0).then_some((period, displacement));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_detect() {
+        let mut life = conways_game_of_life();
+        let block = [[0, 0], [1, 0], [0, 1], [1, 1]];
+        let receding_glider = [[20, -20], [21, -20], [22, -20], [22, -19], [21, -18]];
+        let approaching_glider = [[-20, 20], [-19, 20], [-18, 20], [-18, 21], [-19, 22]];
+        let blinker = [[-20, -20], [-20, -19], [-20, -18]];
+        life.set_alive_cells(block.into_iter().chain(receding_glider).chain(approaching_glider).chain(blinker).collect());
+
+        let detector = EscapeDetector::new(4, 5);
+        let escapees = detector.detect(&life);
+        assert_eq!(escapees.len(), 1);
+        assert_eq!(escapees[0].cells, receding_glider.into_iter().collect());
+        assert_eq!(escapees[0].age, 0);
+
+        let detector = EscapeDetector::new(3, 5);
+        assert!(detector.detect(&life).is_empty());
+        let detector = EscapeDetector::new(4, 30);
+        assert!(detector.detect(&life).is_empty());
+    }
+
+    #[test]
+    fn test_remove_escapees() {
+        let mut life = conways_game_of_life();
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        life.set_alive_cells(glider.into_iter().collect());
+
+        // a lone object has no population to escape from
+        let mut detector = EscapeDetector::new(4, 0);
+        assert_eq!(detector.remove_escapees(&mut life), 0);
+
+        life.set_cell(&[-10, 10], true);
+        life.set_cell(&[-10, 11], true);
+        life.set_cell(&[-11, 10], true);
+        life.set_cell(&[-11, 11], true);
+        assert_eq!(detector.remove_escapees(&mut life), 1);
+        assert_eq!(life.alive_cells().len(), 4);
+        assert_eq!(detector.log().len(), 1);
+        assert_eq!(detector.log()[0].displacement, [1, -1]);
+    }
+}
@@ -0,0 +1,375 @@
+//! Raster images of viewports of 2-dimensional games of life, as files or piped to encoders, for encoding videos
+
+use crate::life::Life;
+use crate::region::Region;
+use crate::view::View;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+
+/// Format of the images written by a [FrameDumper]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImageFormat {
+    /// Binary greyscale Netpbm image (`P5`), with the luma of the colours.
+    Pgm,
+    /// Binary colour Netpbm image (`P6`).
+    Ppm,
+}
+impl ImageFormat {
+    /// Get the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pgm => "pgm",
+            Self::Ppm => "ppm",
+        }
+    }
+}
+
+/// Get the width and height in pixels of an image of a region.
+pub(crate) fn image_size(region: Region<2>, cell_size: u32) -> [usize; 2] {
+    if region.is_empty() {
+        return [0, 0];
+    }
+    let (min, max) = (region.min(), region.max());
+    [0, 1].map(|axis| (max[axis].abs_diff(min[axis]) as usize + 1) * cell_size as usize)
+}
+
+/// Draw a view as raw pixels, top row first, with each cell a square of pixels.
+/// # Arguments
+/// * `view` - The view to draw.
+/// * `cell_size` - The width and height of a cell, in pixels.
+/// * `pixel` - The bytes of the pixels of a cell from its coordinates and state, such as 3 bytes of RGB.
+pub(crate) fn rasterize<const P: usize>(view: View<'_, 2>, cell_size: u32, pixel: impl Fn(&[i64; 2], bool) -> [u8; P]) -> Vec<u8> {
+    let [width, height] = image_size(view.region(), cell_size);
+    let mut pixels = Vec::with_capacity(width * height * P);
+    let mut row_pixels = Vec::with_capacity(width * P);
+    for (first, row) in view.rows() {
+        row_pixels.clear();
+        for (x, state) in (first[0]..).zip(row) {
+            let pixel = pixel(&[x, first[1]], state);
+            for _ in 0..cell_size {
+                row_pixels.extend_from_slice(&pixel);
+            }
+        }
+        for _ in 0..cell_size {
+            pixels.extend_from_slice(&row_pixels);
+        }
+    }
+    pixels
+}
+
+/// Get the luma of an RGB colour, as in the Rec. 601 conversion to greyscale.
+fn luma([red, green, blue]: [u8; 3]) -> u8 {
+    ((u32::from(red) * 299 + u32::from(green) * 587 + u32::from(blue) * 114) / 1000) as u8
+}
+
+/// Writer of one binary Netpbm image of a viewport per generation into a directory
+///
+/// Frames are named `frame_000000.ppm`, `frame_000001.ppm` and so on, numbered from 0 in the order they were dumped,
+/// so a directory of frames can be encoded with `ffmpeg -framerate 30 -i frame_%06d.ppm -pix_fmt yuv420p life.mp4`.
+/// Cells are drawn as squares of [cell_size](Self::set_cell_size) pixels, black when alive and white when dead by default.
+/// Alive cells with a [colour](Life::colour) are drawn in it instead.
+/// # Example
+/// ```
+/// use ndlife::frames::{FrameDumper, ImageFormat};
+/// use ndlife::life::conways_game_of_life;
+///
+/// let directory = std::env::temp_dir().join(format!("ndlife-doc-frames-{}", std::process::id()));
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut dumper = FrameDumper::new(&directory, [0..=2, -1..=1], ImageFormat::Pgm).unwrap();
+/// dumper.set_cell_size(4);
+/// for _ in 0..3 {
+///     dumper.dump(&life).unwrap();
+///     life.next_generation();
+/// }
+///
+/// let image = std::fs::read(directory.join("frame_000002.pgm")).unwrap();
+/// assert!(image.starts_with(b"P5\n12 12\n255\n"));
+/// # std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameDumper {
+    /// The directory the frames are written to.
+    directory: PathBuf,
+    /// The viewport of the frames.
+    region: Region<2>,
+    /// The format of the frames.
+    format: ImageFormat,
+    /// The width and height of a cell, in pixels.
+    cell_size: u32,
+    /// The colours of alive and dead cells.
+    colours: ([u8; 3], [u8; 3]),
+    /// The number of dumped frames.
+    frames: u64,
+}
+impl FrameDumper {
+    /// Create a dumper writing frames of a viewport into a directory, creating the directory if needed.
+    /// # Arguments
+    /// * `directory` - The directory to write the frames to.
+    /// * `region` - The viewport, such as the inclusive range of coordinates along each axis.
+    /// * `format` - The format of the images.
+    /// # Returns
+    /// An [io::Result] containing the dumper if successful, or the error of creating the directory.
+    pub fn new(directory: impl AsRef<Path>, region: impl Into<Region<2>>, format: ImageFormat) -> io::Result<Self> {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(Self {
+            directory: directory.as_ref().to_path_buf(),
+            region: region.into(),
+            format,
+            cell_size: 1,
+            colours: ([0, 0, 0], [255, 255, 255]),
+            frames: 0,
+        })
+    }
+
+    /// Get the viewport of the frames.
+    pub fn region(&self) -> Region<2> {
+        self.region
+    }
+
+    /// Set the viewport of the next frames; video encoders expect every frame to have the same size.
+    pub fn set_region(&mut self, region: impl Into<Region<2>>) {
+        self.region = region.into();
+    }
+
+    /// Set the width and height of a cell in pixels, at least 1.
+    pub fn set_cell_size(&mut self, cell_size: u32) {
+        self.cell_size = cell_size.max(1);
+    }
+
+    /// Set the RGB colours of alive and dead cells; greyscale images use their luma.
+    pub fn set_colours(&mut self, alive: [u8; 3], dead: [u8; 3]) {
+        self.colours = (alive, dead);
+    }
+
+    /// Get the number of dumped frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Write the image of the current generation as the next frame.
+    /// # Arguments
+    /// * `life` - The game of life to draw.
+    /// # Returns
+    /// An [io::Result] containing the path of the written frame if successful, or the error of writing it.
+    pub fn dump(&mut self, life: &Life<2>) -> io::Result<PathBuf> {
+        let [width, height] = image_size(self.region, self.cell_size);
+        let (alive, dead) = self.colours;
+        let colour = |cell: &[i64; 2], state: bool| if state { life.colour(cell).unwrap_or(alive) } else { dead };
+        let (magic, pixels) = match self.format {
+            ImageFormat::Pgm => ("P5", rasterize(life.view(self.region), self.cell_size, |cell, state| [luma(colour(cell, state))])),
+            ImageFormat::Ppm => ("P6", rasterize(life.view(self.region), self.cell_size, colour)),
+        };
+        let path = self.directory.join(format!("frame_{:06}.{}", self.frames, self.format.extension()));
+        let mut file = io::BufWriter::new(fs::File::create(&path)?);
+        write!(file, "{}\n{} {}\n255\n", magic, width, height)?;
+        file.write_all(&pixels)?;
+        file.flush()?;
+        self.frames += 1;
+        Ok(path)
+    }
+}
+
+/// Writer of one raw RGBA image of a viewport per generation to the standard input of a child process, such as a video encoder
+///
+/// Every frame is `width * height * 4` bytes, top row first, without any header, which is what `ffmpeg` reads with
+/// `-f rawvideo -pix_fmt rgba -video_size WIDTHxHEIGHT -i -`, so long evolutions are encoded without intermediate image files.
+/// The [frame_size](Self::frame_size) is fixed by the viewport and the cell size given when the pipe is spawned,
+/// as video encoders expect every frame to have the same size.
+/// # Example
+/// ```no_run
+/// use ndlife::frames::FramePipe;
+/// use ndlife::life::conways_game_of_life;
+/// use std::process::Command;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+///
+/// let (region, cell_size) = ([-40..=40, -40..=40], 8);
+/// let [width, height] = FramePipe::size_of(region.clone(), cell_size);
+/// let mut ffmpeg = Command::new("ffmpeg");
+/// ffmpeg.args(["-y", "-f", "rawvideo", "-pix_fmt", "rgba", "-framerate", "30"]);
+/// ffmpeg.args(["-video_size", &format!("{}x{}", width, height), "-i", "-", "-pix_fmt", "yuv420p", "glider.mp4"]);
+///
+/// let mut pipe = FramePipe::spawn(ffmpeg, region, cell_size).unwrap();
+/// for _ in 0..300 {
+///     pipe.write_frame(&life).unwrap();
+///     life.next_generation();
+/// }
+/// assert!(pipe.finish().unwrap().success());
+/// ```
+#[derive(Debug)]
+pub struct FramePipe {
+    /// The child process reading the frames.
+    child: Child,
+    /// The standard input of the child process.
+    stdin: io::BufWriter<ChildStdin>,
+    /// The viewport of the frames.
+    region: Region<2>,
+    /// The width and height of a cell, in pixels.
+    cell_size: u32,
+    /// The colours of alive and dead cells.
+    colours: ([u8; 4], [u8; 4]),
+    /// The number of written frames.
+    frames: u64,
+}
+impl FramePipe {
+    /// Get the width and height in pixels of the frames of a viewport, to pass to the child process.
+    /// # Arguments
+    /// * `region` - The viewport, such as the inclusive range of coordinates along each axis.
+    /// * `cell_size` - The width and height of a cell in pixels, at least 1.
+    pub fn size_of(region: impl Into<Region<2>>, cell_size: u32) -> [usize; 2] {
+        image_size(region.into(), cell_size.max(1))
+    }
+
+    /// Spawn a child process reading frames of a viewport from its standard input, black alive cells on white by default.
+    /// Alive cells with a [colour](Life::colour) are drawn in it instead, with the opacity of alive cells.
+    /// # Arguments
+    /// * `command` - The command of the child process; its standard input is replaced by the pipe.
+    /// * `region` - The viewport, such as the inclusive range of coordinates along each axis.
+    /// * `cell_size` - The width and height of a cell in pixels, at least 1.
+    /// # Returns
+    /// An [io::Result] containing the pipe if successful, or the error of spawning the process.
+    pub fn spawn(mut command: Command, region: impl Into<Region<2>>, cell_size: u32) -> io::Result<Self> {
+        let mut child = command.stdin(Stdio::piped()).spawn()?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "the child process has no standard input"))?;
+        Ok(Self {
+            child,
+            stdin: io::BufWriter::new(stdin),
+            region: region.into(),
+            cell_size: cell_size.max(1),
+            colours: ([0, 0, 0, 255], [255, 255, 255, 255]),
+            frames: 0,
+        })
+    }
+
+    /// Get the viewport of the frames.
+    pub fn region(&self) -> Region<2> {
+        self.region
+    }
+
+    /// Get the width and height of the frames, in pixels.
+    pub fn frame_size(&self) -> [usize; 2] {
+        image_size(self.region, self.cell_size)
+    }
+
+    /// Set the RGBA colours of alive and dead cells.
+    pub fn set_colours(&mut self, alive: [u8; 4], dead: [u8; 4]) {
+        self.colours = (alive, dead);
+    }
+
+    /// Get the number of written frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Write the image of the current generation as the next frame.
+    /// # Arguments
+    /// * `life` - The game of life to draw.
+    /// # Returns
+    /// An [io::Result] with the error of the pipe, if any, such as [BrokenPipe](io::ErrorKind::BrokenPipe) if the process exited.
+    pub fn write_frame(&mut self, life: &Life<2>) -> io::Result<()> {
+        let (alive, dead) = self.colours;
+        let colour = |cell: &[i64; 2], state: bool| match (state, life.colour(cell)) {
+            (true, Some([red, green, blue])) => [red, green, blue, alive[3]],
+            (true, None) => alive,
+            (false, _) => dead,
+        };
+        self.stdin.write_all(&rasterize(life.view(self.region), self.cell_size, colour))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Close the standard input of the child process, so that it finishes its output, and wait for it to exit.
+    /// # Returns
+    /// An [io::Result] containing the exit status of the process if successful, or the error of flushing the frames or waiting.
+    pub fn finish(self) -> io::Result<ExitStatus> {
+        let Self { mut child, stdin, .. } = self;
+        let flushed = stdin.into_inner().map(drop).map_err(|error| error.into_error());
+        let status = child.wait()?;
+        flushed.map(|_| status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::{conways_game_of_life, ColourInheritance};
+
+    #[test]
+    fn test_rasterize() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 1]].into_iter().collect());
+        let binary = |_: &[i64; 2], state| [u8::from(state)];
+        assert_eq!(rasterize(life.view([0..=1, 0..=1]), 1, binary), vec![1, 0, 0, 0]);
+        assert_eq!(
+            rasterize(life.view([0..=1, 1..=1]), 2, |_, state| if state { [1, 2] } else { [0, 0] }),
+            vec![1, 2, 1, 2, 0, 0, 0, 0, 1, 2, 1, 2, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            rasterize(life.view([-1..=1, 0..=1]), 1, |cell, _| [cell[0] as u8, cell[1] as u8]),
+            vec![255, 1, 0, 1, 1, 1, 255, 0, 0, 0, 1, 0]
+        );
+        let (start, end) = (1, 0);
+        assert!(rasterize(life.view([start..=end, 0..=1]), 2, binary).is_empty());
+        assert_eq!(image_size(Region::new([-1, 0], [1, 4]), 3), [9, 15]);
+        assert_eq!(luma([255, 255, 255]), 255);
+        assert_eq!(luma([255, 0, 0]), 76);
+    }
+
+    #[test]
+    fn test_dump() {
+        let directory = std::env::temp_dir().join(format!("ndlife-frames-{}", std::process::id()));
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0]].into_iter().collect());
+        let mut dumper = FrameDumper::new(&directory, [0..=1, 0..=0], ImageFormat::Ppm).unwrap();
+        dumper.set_colours([255, 0, 0], [0, 0, 255]);
+        dumper.set_cell_size(0);
+        assert_eq!(dumper.dump(&life).unwrap(), directory.join("frame_000000.ppm"));
+        assert_eq!(fs::read(directory.join("frame_000000.ppm")).unwrap(), b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff");
+
+        dumper.format = ImageFormat::Pgm;
+        dumper.set_region([0..=0, 0..=0]);
+        assert_eq!(dumper.region(), Region::new([0, 0], [0, 0]));
+        dumper.dump(&life).unwrap();
+        assert_eq!(fs::read(directory.join("frame_000001.pgm")).unwrap(), b"P5\n1 1\n255\n\x4c");
+
+        // tracked colours are drawn instead of the alive colour
+        life.track_colours(ColourInheritance::Majority, |_| [1, 2, 3]);
+        dumper.format = ImageFormat::Ppm;
+        dumper.dump(&life).unwrap();
+        assert_eq!(fs::read(directory.join("frame_000002.ppm")).unwrap(), b"P6\n1 1\n255\n\x01\x02\x03");
+        assert_eq!(dumper.frames(), 3);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_frame_pipe() {
+        let path = std::env::temp_dir().join(format!("ndlife-frame-pipe-{}", std::process::id()));
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(format!("cat > '{}'", path.display()));
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[1, 0]].into_iter().collect());
+        let mut pipe = FramePipe::spawn(command, [0..=1, 0..=0], 0).unwrap();
+        assert_eq!(pipe.frame_size(), [2, 1]);
+        assert_eq!(FramePipe::size_of([0..=1, 0..=0], 3), [6, 3]);
+        pipe.set_colours([1, 2, 3, 4], [0, 0, 0, 0]);
+        pipe.write_frame(&life).unwrap();
+        life.next_generation();
+        pipe.write_frame(&life).unwrap();
+        assert_eq!(pipe.frames(), 2);
+        assert_eq!(pipe.region(), Region::new([0, 0], [1, 0]));
+        assert!(pipe.finish().unwrap().success());
+        assert_eq!(fs::read(&path).unwrap(), [0, 0, 0, 0, 1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0]);
+        fs::remove_file(&path).unwrap();
+
+        assert!(FramePipe::spawn(Command::new("ndlife-missing-command"), [0..=1, 0..=0], 1).is_err());
+    }
+}
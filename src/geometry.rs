@@ -0,0 +1,38 @@
+//! Geometric helpers shared by the types holding sets of cells
+
+/// Get the smallest axis-aligned bounding box containing all given cells.
+/// # Returns
+/// [Some] with the minimum and maximum coordinates (inclusive) along each axis, or [None] if there are no cells.
+pub(crate) fn bounding_box<'a, const N: usize>(cells: impl IntoIterator<Item = &'a [i64; N]>) -> Option<([i64; N], [i64; N])> {
+    let mut cells = cells.into_iter();
+    let first = cells.next()?;
+    let (mut min, mut max) = (*first, *first);
+    for cell in cells {
+        for i in 0..N {
+            min[i] = min[i].min(cell[i]);
+            max[i] = max[i].max(cell[i]);
+        }
+    }
+    Some((min, max))
+}
+
+/// Iterate over all cells of the hyperrectangle between `min` and `max` (inclusive) in lexicographic order.
+///
+/// Yields nothing if `min` exceeds `max` along any axis.
+pub(crate) fn box_cells<const N: usize>(min: [i64; N], max: [i64; N]) -> impl Iterator<Item = [i64; N]> {
+    let mut next = (0..N).all(|i| min[i] <= max[i]).then_some(min);
+    core::iter::from_fn(move || {
+        let cell = next?;
+        let mut following = cell;
+        next = None;
+        for i in (0..N).rev() {
+            if following[i] < max[i] {
+                following[i] += 1;
+                next = Some(following);
+                break;
+            }
+            following[i] = min[i];
+        }
+        Some(cell)
+    })
+}
@@ -0,0 +1,399 @@
+//! Remote control of a simulation over HTTP, enabled by the `http` feature
+//!
+//! # Endpoints
+//! Requests and responses are plain text; errors respond with a `4xx` status and a one-line message.
+//! * `GET /status` - The age, population and rules, as `age 12\npopulation 5\nrule B3/S23\n`.
+//! * `PUT /pattern` - Replace the alive cells with the pattern in the body: plaintext ASCII art (bottom-left cell at the origin),
+//!   or one alive cell per line as `x y` like the output of `GET /snapshot`.
+//! * `PUT /rules` - Set the rules to the rulestring in the body, such as `B36/S23`.
+//! * `POST /step?generations=N` - Advance by `N` generations (1 if omitted), at most 100000 per request.
+//! * `GET /region?min=X,Y&max=X,Y` - The region as plaintext ASCII art, top row first.
+//! * `GET /snapshot` - The age and rules as comments, then one alive cell per line as `x y`, in lexicographic order.
+//!   `PUT /pattern` reads it back, ignoring the comments.
+//! * `GET /metrics` - The [Metrics] of the generations stepped through the server, in the Prometheus text format.
+//!
+//! `PUT` and `POST` requests respond with the status, like `GET /status`.
+
+use crate::life::Life;
+use crate::listener::Acceptor;
+use crate::metrics::Metrics;
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::rulestring;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// The largest accepted request body, in bytes.
+const MAX_BODY: usize = 16 << 20;
+
+/// The most generations a single `POST /step` request can advance by.
+const MAX_GENERATIONS: u64 = 100_000;
+
+/// How long reading a request or writing a response can block before the connection is dropped.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The most connections served at a time, each on its own thread.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Response to a request
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Response {
+    /// The HTTP status code.
+    status: u16,
+    /// The plain text body.
+    body: String,
+}
+impl Response {
+    /// Create a successful response.
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Create an error response with a one-line message.
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        let mut body = message.into();
+        body.push('\n');
+        Self { status, body }
+    }
+}
+
+/// Embedded HTTP server controlling a 2-dimensional game of life
+///
+/// Every connection is served on its own thread, so scripts in any language can load patterns,
+/// change the rules, step and query the simulation (see the [module documentation](self) for the endpoints).
+/// Requests still take turns on the game of life, but a slow client does not hold up the others.
+/// At most 64 connections are served at a time, the next ones waiting for a busy one to finish,
+/// and dropping the server stops listening.
+/// The game of life stays available locally through [life](Self::life), and the metrics through [metrics](Self::metrics).
+/// # Example
+/// ```no_run
+/// use ndlife::http::HttpServer;
+/// use ndlife::life::conways_game_of_life;
+///
+/// let server = HttpServer::bind("127.0.0.1:8080", conways_game_of_life()).unwrap();
+/// println!("try: curl -X POST http://{}/step?generations=100", server.local_addr());
+/// loop {
+///     std::thread::park();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct HttpServer {
+    /// The thread accepting the connections.
+    acceptor: Acceptor,
+    /// The controlled game of life, shared with the serving threads.
+    life: Arc<Mutex<Life<2>>>,
+    /// The metrics of the generations stepped by requests, shared with the serving threads.
+    metrics: Arc<Mutex<Metrics>>,
+}
+impl HttpServer {
+    /// Create a server listening on an address and start serving requests.
+    /// # Arguments
+    /// * `addr` - The address to listen on, port 0 picks a free port.
+    /// * `life` - The game of life to control.
+    /// # Returns
+    /// An [io::Result] containing the server if successful, or the error of binding the address.
+    pub fn bind(addr: impl ToSocketAddrs, life: Life<2>) -> io::Result<Self> {
+        let life = Arc::new(Mutex::new(life));
+        let metrics = Arc::new(Mutex::new(Metrics::new()));
+        let (served, recorded) = (Arc::clone(&life), Arc::clone(&metrics));
+        let acceptor = Acceptor::spawn(TcpListener::bind(addr)?, MAX_CONNECTIONS, move |stream| {
+            let _ = serve(stream, &served, &recorded);
+        })?;
+        Ok(Self { acceptor, life, metrics })
+    }
+
+    /// Get the address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.acceptor.local_addr()
+    }
+
+    /// Get the controlled game of life, blocking requests until the guard is dropped.
+    pub fn life(&self) -> MutexGuard<'_, Life<2>> {
+        self.life.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get the metrics served on `GET /metrics`, to [record](Metrics::record) generations stepped locally.
+    pub fn metrics(&self) -> MutexGuard<'_, Metrics> {
+        self.metrics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Read one request from a connection, answer it and close the connection.
+fn serve(stream: TcpStream, life: &Mutex<Life<2>>, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(usize::MAX);
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        _ if content_length > MAX_BODY => Response::error(413, format!("the body is larger than {} bytes", MAX_BODY)),
+        (Some(method), Some(target)) => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            match String::from_utf8(body) {
+                Ok(body) => respond(
+                    &mut life.lock().unwrap_or_else(PoisonError::into_inner),
+                    &mut metrics.lock().unwrap_or_else(PoisonError::into_inner),
+                    method,
+                    target,
+                    &body,
+                ),
+                Err(_) => Response::error(400, "the body is not UTF-8"),
+            }
+        }
+        _ => Response::error(400, "malformed request line"),
+    };
+
+    write_response(&mut reader.into_inner(), &response)
+}
+
+/// Write a response and flush the connection.
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Payload Too Large",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Answer a request on the game of life.
+fn respond(life: &mut Life<2>, metrics: &mut Metrics, method: &str, target: &str, body: &str) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let parameter = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+    match (method, path) {
+        ("GET", "/status") => Response::ok(status(life)),
+        ("PUT", "/pattern") => match parse_pattern(body) {
+            Ok(cells) => {
+                life.set_alive_cells(cells);
+                Response::ok(status(life))
+            }
+            Err(message) => Response::error(400, message),
+        },
+        ("PUT", "/rules") => match rulestring::parse(body).and_then(|(birth_rules, survival_rules)| {
+            Life::<2>::new(birth_rules.clone(), survival_rules.clone())?;
+            life.set_birth_rules(birth_rules)?;
+            life.set_survival_rules(survival_rules)
+        }) {
+            Ok(()) => Response::ok(status(life)),
+            Err(error) => Response::error(400, format!("invalid rules: {}", error)),
+        },
+        ("POST", "/step") => match parameter("generations").map_or(Ok(1), str::parse::<u64>) {
+            Ok(generations) if generations > MAX_GENERATIONS => Response::error(400, format!("generations must be at most {}", MAX_GENERATIONS)),
+            Ok(generations) => {
+                for _ in 0..generations {
+                    metrics.step(life);
+                }
+                Response::ok(status(life))
+            }
+            Err(_) => Response::error(400, "generations must be a non-negative integer"),
+        },
+        ("GET", "/region") => match (parameter("min").and_then(parse_cell), parameter("max").and_then(parse_cell)) {
+            (Some(min), Some(max)) => {
+                let region = Region::new(min, max);
+                match region.volume() {
+                    Some(volume) if volume <= MAX_BODY as u128 => {
+                        let mut art = life.view(region).to_string();
+                        if !art.is_empty() {
+                            art.push('\n');
+                        }
+                        Response::ok(art)
+                    }
+                    _ => Response::error(400, "the region is too large"),
+                }
+            }
+            _ => Response::error(400, "min and max must be cells like 0,0"),
+        },
+        ("GET", "/snapshot") => {
+            let mut snapshot = format!("!Generation: {}\n!Rule: {}\n", life.age(), rulestring::format(life.birth_rules(), life.survival_rules()));
+            for [x, y] in life.alive_cells_sorted() {
+                let _ = writeln!(snapshot, "{} {}", x, y);
+            }
+            Response::ok(snapshot)
+        }
+        ("GET", "/metrics") => Response::ok(metrics.to_prometheus()),
+        (_, "/status" | "/pattern" | "/rules" | "/step" | "/region" | "/snapshot" | "/metrics") => Response::error(405, format!("{} is not allowed on {}", method, path)),
+        _ => Response::error(404, format!("no endpoint {}", path)),
+    }
+}
+
+/// Get the status of the game of life.
+fn status(life: &Life<2>) -> String {
+    format!(
+        "age {}\npopulation {}\nrule {}\n",
+        life.age(),
+        life.alive_cells().len(),
+        rulestring::format(life.birth_rules(), life.survival_rules())
+    )
+}
+
+/// Parse the body of `PUT /pattern`, plaintext ASCII art or a list of cells as written by `GET /snapshot`.
+fn parse_pattern(body: &str) -> Result<HashSet<[i64; 2]>, String> {
+    let rows = || body.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('!'));
+    // ASCII art has no digits, so any row with a number is a cell list
+    if !rows().any(|row| row.contains(|character: char| character.is_ascii_digit())) {
+        return body.parse::<Pattern<2>>().map(Pattern::into_cells).map_err(|error| format!("invalid pattern: {}", error));
+    }
+    rows()
+        .map(|row| {
+            let mut coordinates = row.split_whitespace().map(str::parse::<i64>);
+            match (coordinates.next(), coordinates.next(), coordinates.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => Ok([x, y]),
+                _ => Err(format!("invalid cell: {}", row)),
+            }
+        })
+        .collect()
+}
+
+/// Parse a cell written as `x,y`.
+fn parse_cell(text: &str) -> Option<[i64; 2]> {
+    let (x, y) = text.split_once(',')?;
+    Some([x.parse().ok()?, y.parse().ok()?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_respond() {
+        let mut life = conways_game_of_life();
+        let mut metrics = Metrics::new();
+        assert_eq!(
+            respond(&mut life, &mut metrics, "PUT", "/pattern", "!Name: Blinker\nOOO\n"),
+            Response::ok(String::from("age 0\npopulation 3\nrule B3/S23\n"))
+        );
+        assert_eq!(respond(&mut life, &mut metrics, "POST", "/step", "").body, "age 1\npopulation 3\nrule B3/S23\n");
+        assert_eq!(
+            respond(&mut life, &mut metrics, "POST", "/step?generations=3", "").body,
+            "age 4\npopulation 3\nrule B3/S23\n"
+        );
+        assert_eq!(respond(&mut life, &mut metrics, "GET", "/region?min=0,-1&max=2,1", "").body, "...\nOOO\n...\n");
+        assert_eq!(
+            respond(&mut life, &mut metrics, "GET", "/snapshot", "").body,
+            "!Generation: 4\n!Rule: B3/S23\n0 0\n1 0\n2 0\n"
+        );
+        // the snapshot loads back into the same cells
+        let snapshot = respond(&mut life, &mut metrics, "GET", "/snapshot", "").body;
+        let mut copy = conways_game_of_life();
+        assert_eq!(respond(&mut copy, &mut metrics, "PUT", "/pattern", &snapshot).status, 200);
+        assert_eq!(copy.alive_cells(), life.alive_cells());
+        respond(&mut copy, &mut metrics, "PUT", "/pattern", "-5 7\n\n12 -3\n");
+        assert_eq!(copy.alive_cells(), &[[-5, 7], [12, -3]].into_iter().collect());
+        let exposition = respond(&mut life, &mut metrics, "GET", "/metrics", "").body;
+        assert!(exposition.contains("\nndlife_generations_total 4\n"));
+        assert!(exposition.contains("\nndlife_births_total 8\n"));
+
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/rules", "B36/S23").body, "age 4\npopulation 3\nrule B36/S23\n");
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/rules", "B0/S23").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/rules", "B3/S9").status, 400);
+        assert_eq!(life.birth_rules(), &[3, 6].into_iter().collect());
+    }
+
+    #[test]
+    fn test_respond_errors() {
+        let mut life = conways_game_of_life();
+        let mut metrics = Metrics::new();
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/pattern", "OX").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "POST", "/step?generations=-1", "").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "POST", "/step?generations=100001", "").status, 400);
+        assert_eq!(life.age(), 0);
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/pattern", "1 2 3").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "PUT", "/pattern", "1 O").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "GET", "/region?min=0,0", "").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "GET", "/region?min=0,0&max=100000,100000", "").status, 400);
+        assert_eq!(respond(&mut life, &mut metrics, "GET", "/region?min=1,1&max=0,0", "").body, "");
+        assert_eq!(respond(&mut life, &mut metrics, "DELETE", "/status", "").status, 405);
+        assert_eq!(respond(&mut life, &mut metrics, "GET", "/", "").status, 404);
+    }
+
+    #[test]
+    fn test_server() {
+        let server = HttpServer::bind("127.0.0.1:0", conways_game_of_life()).unwrap();
+        let request = |request: &str| {
+            let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = request("PUT /pattern HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\nOOO\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nage 0\npopulation 3\nrule B3/S23\n"));
+        let response = request("POST /step?generations=1 HTTP/1.1\r\n\r\n");
+        assert!(response.ends_with("age 1\npopulation 3\nrule B3/S23\n"));
+        assert!(request("GET /nowhere HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(request("PUT /pattern HTTP/1.1\r\nContent-Length: 99999999999\r\n\r\n").starts_with("HTTP/1.1 413"));
+
+        // a client which never sends its request does not hold up the others
+        let stalled = TcpStream::connect(server.local_addr()).unwrap();
+        assert!(request("GET /status HTTP/1.1\r\n\r\n").ends_with("age 1\npopulation 3\nrule B3/S23\n"));
+
+        // the game of life is shared with the server
+        assert_eq!(server.life().alive_cells(), &[[1, -1], [1, 0], [1, 1]].into_iter().collect());
+        assert_eq!(server.metrics().generations(), 1);
+
+        // connections beyond the limit wait for a busy one to finish
+        let mut stalled = vec![stalled];
+        stalled.extend((1..MAX_CONNECTIONS).map(|_| TcpStream::connect(server.local_addr()).unwrap()));
+        let mut waiting = TcpStream::connect(server.local_addr()).unwrap();
+        waiting.write_all(b"GET /status HTTP/1.1\r\n\r\n").unwrap();
+        waiting.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+        let mut response = String::new();
+        assert!(waiting.read_to_string(&mut response).is_err());
+        stalled.pop();
+        waiting.set_read_timeout(None).unwrap();
+        waiting.read_to_string(&mut response).unwrap();
+        assert!(response.ends_with("age 1\npopulation 3\nrule B3/S23\n"));
+
+        // dropping the server stops listening, even with connections still being served
+        let addr = server.local_addr();
+        drop(server);
+        let mut attempts = 0;
+        while TcpStream::connect(addr).is_ok() {
+            attempts += 1;
+            assert!(attempts < 1000, "the server still listens");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
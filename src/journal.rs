@@ -0,0 +1,354 @@
+//! Append-only journals of long runs, as a delta stream with periodic keyframes in a single file
+
+use crate::delta::{DeltaDecoder, DeltaEncoder, DeltaFrame};
+use crate::error::Error;
+use crate::life::Life;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Writer appending the generations of a run to a journal file
+///
+/// The file is a [delta stream](crate::delta): every recorded generation is written as its born and died cells, and every
+/// `keyframe_interval` generations as a full keyframe instead, from which [Journal::seek] decodes. Generations must be
+/// recorded in order, one after the other.
+/// # Example
+/// ```
+/// use ndlife::journal::{Journal, JournalWriter};
+/// use ndlife::life::conways_game_of_life;
+///
+/// let path = std::env::temp_dir().join(format!("ndlife-doc-journal-{}.ndld", std::process::id()));
+/// let mut life = conways_game_of_life();
+/// life.randomize_region([0..=15, 0..=15], 0.5, 2);
+///
+/// let mut writer = JournalWriter::create(&path, &life, 16).unwrap();
+/// for _ in 0..100 {
+///     life.next_generation();
+///     writer.record(&life).unwrap();
+/// }
+/// writer.flush().unwrap();
+///
+/// let journal = Journal::<2>::open(&path).unwrap();
+/// assert_eq!((journal.first_age(), journal.last_age()), (0, 100));
+/// assert_eq!(journal.keyframes().len(), 7);
+///
+/// // go back to generation 40
+/// let mut past = conways_game_of_life();
+/// assert!(journal.restore(&mut past, 40).unwrap());
+/// assert_eq!(past.age(), 40);
+/// for _ in 40..100 {
+///     past.next_generation();
+/// }
+/// assert_eq!(past.alive_cells(), life.alive_cells());
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct JournalWriter<const N: usize> {
+    /// The encoder of the file.
+    encoder: DeltaEncoder<BufWriter<File>, N>,
+    /// The number of generations between keyframes.
+    keyframe_interval: u64,
+    /// The age of the last keyframe.
+    last_keyframe: u64,
+}
+impl<const N: usize> JournalWriter<N> {
+    /// Create a journal, replacing any file at the path, and write a keyframe of the initial state.
+    /// # Arguments
+    /// * `path` - The path of the file.
+    /// * `life` - The game of life whose state starts the journal.
+    /// * `keyframe_interval` - The number of generations between keyframes, at least 1.
+    /// # Returns
+    /// A [Result] containing the writer, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be written.
+    pub fn create(path: impl AsRef<Path>, life: &Life<N>, keyframe_interval: u64) -> Result<Self, Error> {
+        let mut encoder = DeltaEncoder::new(BufWriter::new(File::create(path)?))?;
+        encoder.write_keyframe(life)?;
+        Ok(Self {
+            encoder,
+            keyframe_interval: keyframe_interval.max(1),
+            last_keyframe: life.age(),
+        })
+    }
+
+    /// Reopen a journal to append to it, such as after the process stopped, dropping a frame left incomplete by a crash.
+    /// # Arguments
+    /// * `path` - The path of the file.
+    /// * `keyframe_interval` - The number of generations between keyframes, at least 1.
+    /// # Returns
+    /// A [Result] containing the writer, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read or written, or is not a journal in `N` dimensions.
+    pub fn append(path: impl AsRef<Path>, keyframe_interval: u64) -> Result<Self, Error> {
+        let journal = Journal::<N>::open(path.as_ref())?;
+        // the generations recorded next are diffs from the last recorded state
+        let Some(last) = journal.seek(journal.last_age)? else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "journal without its last generation").into());
+        };
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(journal.length)?;
+        let mut writer = BufWriter::new(file);
+        writer.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            encoder: DeltaEncoder::resume(writer, Some(journal.last_age), last.alive_cells()),
+            keyframe_interval: keyframe_interval.max(1),
+            last_keyframe: journal.keyframes.last().map_or(journal.first_age, |(age, _)| *age),
+        })
+    }
+
+    /// Record the last generation of a game of life, as a keyframe if the interval has passed since the last one.
+    /// # Arguments
+    /// * `life` - The game of life, just advanced from the last recorded generation.
+    /// # Returns
+    /// A [Result] which is an error if the file cannot be written.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be written.
+    pub fn record(&mut self, life: &Life<N>) -> Result<(), Error> {
+        if life.age().saturating_sub(self.last_keyframe) >= self.keyframe_interval {
+            self.last_keyframe = life.age();
+            self.encoder.write_keyframe(life)?;
+        } else {
+            self.encoder.write_generation(life)?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered frames to the file and wait until they are on the disk.
+    /// # Returns
+    /// A [Result] which is an error if the file cannot be written.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be written.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.encoder.flush()?;
+        self.encoder.get_ref().get_ref().sync_all()?;
+        Ok(())
+    }
+}
+
+/// Index of the keyframes of a journal file, to decode any recorded generation
+///
+/// Opening a journal reads it once to find its keyframes; a frame left incomplete at the end by a crash is ignored.
+/// See [JournalWriter] for an example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal<const N: usize> {
+    /// The path of the file.
+    path: PathBuf,
+    /// The age and the offset in the file of each keyframe.
+    keyframes: Vec<(u64, u64)>,
+    /// The age of the first frame.
+    first_age: u64,
+    /// The age of the last frame.
+    last_age: u64,
+    /// The length of the complete frames of the file, the header included.
+    length: u64,
+}
+impl<const N: usize> Journal<N> {
+    /// Open a journal and index its keyframes.
+    /// # Arguments
+    /// * `path` - The path of the file.
+    /// # Returns
+    /// A [Result] containing the journal, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read, or is not a journal in `N` dimensions starting with a keyframe.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut decoder = DeltaDecoder::<_, N>::new(Counting {
+            input: BufReader::new(File::open(path.as_ref())?),
+            count: 0,
+        })?;
+        let (mut keyframes, mut first_age) = (Vec::new(), None);
+        let mut length = decoder.get_ref().count;
+        loop {
+            let offset = decoder.get_ref().count;
+            let frame = match decoder.skip_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break,
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error.into()),
+            };
+            if let DeltaFrame::Keyframe { age, .. } = frame {
+                keyframes.push((age, offset));
+            } else if keyframes.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "journal does not start with a keyframe").into());
+            }
+            first_age.get_or_insert(decoder.age());
+            length = decoder.get_ref().count;
+        }
+        let Some(first_age) = first_age else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "journal without frames").into());
+        };
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            keyframes,
+            first_age,
+            last_age: decoder.age(),
+            length,
+        })
+    }
+
+    /// Get the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the age and the offset in the file of each keyframe, in order.
+    pub fn keyframes(&self) -> &[(u64, u64)] {
+        &self.keyframes
+    }
+
+    /// Get the age of the first recorded generation.
+    pub fn first_age(&self) -> u64 {
+        self.first_age
+    }
+
+    /// Get the age of the last recorded generation.
+    pub fn last_age(&self) -> u64 {
+        self.last_age
+    }
+
+    /// Decode the journal from the last keyframe up to a recorded generation.
+    /// # Arguments
+    /// * `age` - The age of the generation.
+    /// # Returns
+    /// A [Result] containing [Some] decoder whose state is the generation, to continue reading the following frames from,
+    /// [None] if the generation is not recorded, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read or is malformed.
+    pub fn seek(&self, age: u64) -> Result<Option<DeltaDecoder<impl Read, N>>, Error> {
+        let index = self.keyframes.partition_point(|(keyframe, _)| *keyframe <= age);
+        let Some(&(_, offset)) = index.checked_sub(1).and_then(|index| self.keyframes.get(index)) else {
+            return Ok(None);
+        };
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut decoder = DeltaDecoder::resume(BufReader::new(file).take(self.length - offset));
+        while decoder.read_frame()?.is_some() {
+            match decoder.age().cmp(&age) {
+                std::cmp::Ordering::Less => continue,
+                std::cmp::Ordering::Equal => return Ok(Some(decoder)),
+                std::cmp::Ordering::Greater => break,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Set the alive cells and the age of a game of life to a recorded generation, keeping its rules.
+    /// # Arguments
+    /// * `life` - The game of life to restore.
+    /// * `age` - The age of the generation.
+    /// # Returns
+    /// A [Result] containing whether the generation is recorded, leaving the game of life unchanged if not, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the file cannot be read or is malformed.
+    pub fn restore(&self, life: &mut Life<N>, age: u64) -> Result<bool, Error> {
+        let Some(decoder) = self.seek(age)? else {
+            return Ok(false);
+        };
+        life.reset_to(decoder.alive_cells().clone());
+        life.set_age(age);
+        Ok(true)
+    }
+}
+
+/// Reader counting the bytes read through it
+#[derive(Debug)]
+struct Counting<R: Read> {
+    /// The input.
+    input: R,
+    /// The number of bytes read.
+    count: u64,
+}
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.input.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use std::fs;
+
+    /// Get a path in the temporary directory, unique to the process and the test.
+    fn temporary(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ndlife-journal-{}-{}.ndld", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_seek() {
+        let path = temporary("seek");
+        let mut life = conways_game_of_life();
+        life.randomize_region([0..=15, 0..=15], 0.4, 9);
+        life.set_age(1000);
+        let mut states = vec![life.alive_cells().clone()];
+        let mut writer = JournalWriter::create(&path, &life, 8).unwrap();
+        for _ in 0..30 {
+            life.next_generation();
+            writer.record(&life).unwrap();
+            states.push(life.alive_cells().clone());
+        }
+        drop(writer);
+
+        let journal = Journal::<2>::open(&path).unwrap();
+        let ages: Vec<u64> = journal.keyframes().iter().map(|(age, _)| *age).collect();
+        assert_eq!(ages, [1000, 1008, 1016, 1024]);
+        for age in [1000, 1001, 1008, 1015, 1030] {
+            let decoder = journal.seek(age).unwrap().unwrap();
+            assert_eq!((decoder.age(), decoder.alive_cells()), (age, &states[(age - 1000) as usize]));
+        }
+        // a decoder continues to the following generations
+        let mut decoder = journal.seek(1020).unwrap().unwrap();
+        decoder.read_frame().unwrap();
+        assert_eq!(decoder.alive_cells(), &states[21]);
+        assert!(journal.seek(999).unwrap().is_none());
+        assert!(journal.seek(1031).unwrap().is_none());
+        let mut other = conways_game_of_life();
+        assert!(!journal.restore(&mut other, 5).unwrap());
+        assert!(other.alive_cells().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_append() {
+        let path = temporary("append");
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let mut writer = JournalWriter::create(&path, &life, 4).unwrap();
+        for _ in 0..10 {
+            life.next_generation();
+            writer.record(&life).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        // a crash in the middle of a frame
+        let length = fs::metadata(&path).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(length - 2).unwrap();
+        drop(file);
+        assert_eq!(Journal::<2>::open(&path).unwrap().last_age(), 9);
+
+        let mut resumed = conways_game_of_life();
+        Journal::open(&path).unwrap().restore(&mut resumed, 9).unwrap();
+        let mut writer = JournalWriter::append(&path, 4).unwrap();
+        for _ in 0..11 {
+            resumed.next_generation();
+            writer.record(&resumed).unwrap();
+        }
+        drop(writer);
+        let journal = Journal::<2>::open(&path).unwrap();
+        assert_eq!(journal.last_age(), 20);
+        assert_eq!(journal.keyframes().iter().map(|(age, _)| *age).collect::<Vec<_>>(), [0, 4, 8, 12, 16, 20]);
+        let mut restored = conways_game_of_life();
+        assert!(journal.restore(&mut restored, 20).unwrap());
+        assert_eq!(restored.alive_cells(), resumed.alive_cells());
+
+        // not a journal, or of another dimension
+        assert!(matches!(Journal::<3>::open(&path), Err(Error::IoError(_))));
+        fs::write(&path, b"NDLD\x01\x02\x01\x00\x00\x00").unwrap();
+        assert!(matches!(Journal::<2>::open(&path), Err(Error::IoError(error)) if error.kind() == io::ErrorKind::InvalidData));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -51,12 +51,255 @@
 //! // assert that is indeed what happened
 //! assert_eq!(life.alive_cells(), &expected_alive_cells);
 //! ```
+//!
+//! # Features
+//! * `alloc` - The `alloc`-only configuration without `std`, storing cells in [hashbrown](https://docs.rs/hashbrown) sets
+//!   (build with `--no-default-features --features alloc`).
+//! * `arbitrary` - [Arbitrary](https://docs.rs/arbitrary) games of life for structure-aware fuzzing, decoded
+//!   like [from_fuzz_input](Life::from_fuzz_input).
+//! * `async` - Asynchronous stepping with [into_stream](Life::into_stream), a `futures` [Stream](futures_core::Stream) of generations,
+//!   and [advance_async](Life::advance_async).
+//! * `bevy` - A [Bevy plugin](plugin::LifePlugin) stepping a game of life resource on the fixed timestep
+//!   and sending the born and died cells as messages.
+//! * `cli` - The `ndlife` binary, running plaintext patterns from the command line (`ndlife --help`).
+//! * `compression` - Saving [snapshots](life::Snapshot::save_compressed) with a built-in LZ77 compressor, which shrinks
+//!   the coordinates of sparse patterns and patterns of repeated objects many times over.
+//! * `egui` - An [egui](https://docs.rs/egui) [widget](widget::LifeView::widget) painting a game of life,
+//!   panning when dragged, zooming when scrolled and toggling clicked cells.
+//! * `http` - An [HTTP server](http::HttpServer) to load patterns, set rules, step and query a simulation remotely,
+//!   including its [metrics](metrics::Metrics) for Prometheus.
+//! * `sat` - An embedded [SAT solver](sat::Solver) and [predecessor searches](sat::PredecessorSearch) built on it,
+//!   to find the states which evolve into a pattern inside a box.
+//! * `std` (default) - The parts of the crate needing an operating system or floating-point functions: reading and writing
+//!   files and streams (including saving and loading [snapshots](life::Snapshot)), threads, clocks, the servers and the renderers.
+//!   Without it the crate is `no_std`, and with the `alloc` feature the engine still runs on embedded targets
+//!   and in constrained wasm environments.
+//! * `tui` - The `ndlife-tui` binary, an interactive terminal viewer of 2-dimensional patterns with play/pause,
+//!   stepping, speed control, panning and cell toggling (`ndlife-tui --help`). It relies on `stty`, so it runs on Unix-like systems.
+//! * `viewer3d` - The `ndlife-viewer3d` binary, a windowed viewer of 3-dimensional games of life drawn as instanced cubes
+//!   with [three-d](https://docs.rs/three-d), with an [orbiting camera](orbit::OrbitCamera), play/pause and stepping
+//!   (`ndlife-viewer3d --help`).
+//! * `websocket` - A [WebSocket server](websocket::WebSocketServer) streaming the born and died cells of every generation to browsers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("ndlife needs either the `std` or the `alloc` feature");
 
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+#[cfg(feature = "std")]
+pub mod animation;
+#[cfg(feature = "std")]
+pub mod camera;
+#[cfg(feature = "std")]
+pub mod catalyst;
+mod collections;
+#[cfg(feature = "std")]
+pub mod collision;
+pub mod compact;
+#[cfg(feature = "compression")]
+mod compress;
+#[cfg(feature = "std")]
+pub mod delta;
+pub mod density;
+#[cfg(feature = "std")]
+pub mod disk;
+pub mod dynlife;
+#[cfg(feature = "std")]
+pub mod ensemble;
+pub mod entities;
 pub mod error;
+pub mod escape;
+#[cfg(feature = "std")]
+pub mod frames;
+mod geometry;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "std")]
+pub mod journal;
 pub mod life;
+#[cfg(any(feature = "http", feature = "websocket"))]
+mod listener;
+pub mod lockstep;
+pub mod metadata;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+mod notebook;
+#[cfg(feature = "std")]
+pub mod orbit;
+pub mod pattern;
+#[cfg(feature = "bevy")]
+pub mod plugin;
+mod random;
+pub mod region;
+pub mod replay;
+mod rulestring;
+#[cfg(feature = "sat")]
+pub mod sat;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod shared;
+pub mod spaceship;
+pub mod spatial;
+#[cfg(feature = "std")]
+pub mod store;
+#[cfg(feature = "std")]
+pub mod sweep;
+pub mod symmetry;
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod ticker;
+pub mod transform;
+pub mod trie;
+pub mod universe;
+pub mod view;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+#[cfg(feature = "std")]
+pub mod widget;
 
 #[doc(inline)]
 pub use life::*;
 
+#[doc(inline)]
+pub use lockstep::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use animation::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use camera::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use catalyst::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use collision::*;
+
+#[doc(inline)]
+pub use compact::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use delta::*;
+
+#[doc(inline)]
+pub use density::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use disk::*;
+
+#[doc(inline)]
+pub use dynlife::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use ensemble::*;
+
+#[doc(inline)]
+pub use entities::*;
+
 #[doc(inline)]
 pub use error::*;
+
+#[doc(inline)]
+pub use escape::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use frames::*;
+
+#[cfg(feature = "http")]
+#[doc(inline)]
+pub use http::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use journal::*;
+
+#[doc(inline)]
+pub use metadata::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use metrics::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use orbit::*;
+
+#[doc(inline)]
+pub use pattern::*;
+
+#[cfg(feature = "bevy")]
+#[doc(inline)]
+pub use plugin::*;
+
+#[doc(inline)]
+pub use region::*;
+
+#[doc(inline)]
+pub use replay::*;
+
+#[cfg(feature = "sat")]
+#[doc(inline)]
+pub use sat::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use search::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use shared::*;
+
+#[doc(inline)]
+pub use spaceship::*;
+
+#[doc(inline)]
+pub use spatial::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use store::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use sweep::*;
+
+#[doc(inline)]
+pub use symmetry::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use ticker::*;
+
+#[doc(inline)]
+pub use transform::*;
+
+#[doc(inline)]
+pub use trie::*;
+
+#[doc(inline)]
+pub use universe::*;
+
+#[doc(inline)]
+pub use view::*;
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use widget::*;
+
+#[cfg(feature = "websocket")]
+#[doc(inline)]
+pub use websocket::*;
@@ -1,7 +1,42 @@
 //! Implementation of infinite N-dimensional game of life
 
+use crate::collections::{HashMap, HashSet};
 use crate::error::Error;
-use std::collections::{HashMap, HashSet};
+use crate::geometry;
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::rulestring;
+use crate::view::View;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Index;
+
+mod analysis;
+mod ancestry;
+mod colours;
+mod editing;
+mod fuzz;
+mod history;
+mod morphology;
+mod observers;
+mod running;
+mod snapshot;
+#[cfg(feature = "async")]
+mod stream;
+mod transformations;
+
+use ancestry::Ancestry;
+pub use colours::ColourInheritance;
+use colours::Colours;
+use history::History;
+use observers::Observers;
+#[cfg(feature = "std")]
+pub use running::Progress;
+pub use running::{Generation, Generations, Stability, StabilityConfig};
+pub use snapshot::Snapshot;
+#[cfg(feature = "async")]
+pub use stream::GenerationStream;
+pub use transformations::Centre;
 
 /// Infinite N-dimensional game of life
 /// # Example
@@ -33,12 +68,22 @@ pub struct Life<const N: usize> {
     birth_rules: HashSet<usize>,
     /// The rules for alive cell to stay alive.
     survival_rules: HashSet<usize>,
-    /// The alive cells.
-    alive_cells: HashSet<[i64; N]>,
-    /// The alive cells in the previous generation.
-    prev_alive: HashSet<[i64; N]>,
+    /// The alive cells, shared with forks until either modifies them.
+    alive_cells: Arc<HashSet<[i64; N]>>,
+    /// The alive cells in the previous generation, shared with forks until either modifies them.
+    prev_alive: Arc<HashSet<[i64; N]>>,
+    /// Whether the last generation changed the alive cells; cleared when they are replaced.
+    changed: bool,
     /// The number of alive neighbours for each dead cell, used in the [next_generation] method.
     dead_neighbours: HashMap<[i64; N], usize>,
+    /// The causal ancestry of the alive cells, if tracking is enabled.
+    ancestry: Option<Arc<Ancestry<N>>>,
+    /// The colours of the alive cells, if colouring is enabled.
+    colours: Option<Arc<Colours<N>>>,
+    /// The retained past generations, if history is enabled.
+    history: Option<History<N>>,
+    /// The callbacks notified of the changes made by each generation.
+    observers: Observers<N>,
 }
 impl<const N: usize> Life<N> {
     /// Maximum number of neighbours a cell can have with given dimension `N`.
@@ -100,6 +145,178 @@ impl<const N: usize> Life<N> {
     /// assert_eq!(life.alive_cells(), &alive_cells);
     /// ```
     pub fn new_with_alive_cells(birth_rules: HashSet<usize>, survival_rules: HashSet<usize>, alive_cells: HashSet<[i64; N]>) -> Result<Self, Error> {
+        Self::new_with_age(birth_rules, survival_rules, alive_cells, 0)
+    }
+
+    /// Create a new game of life with given birth and survival rules, alive cells and age.
+    ///
+    /// This allows a game of life reconstructed from a saved state to continue counting generations from where it stopped.
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// * `alive_cells` - A set of coordinates of alive cells.
+    /// * `age` - The age of the game of life.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+    /// let mut life = Life::new_with_age([3].into_iter().collect(), [2, 3].into_iter().collect(), alive_cells, 1000).unwrap();
+    ///
+    /// life.next_generation();
+    /// assert_eq!(life.age(), 1001);
+    /// ```
+    pub fn new_with_age(birth_rules: HashSet<usize>, survival_rules: HashSet<usize>, alive_cells: HashSet<[i64; N]>, age: u64) -> Result<Self, Error> {
+        Self::validate_rules(&birth_rules, &survival_rules)?;
+        Ok(Self {
+            age,
+            birth_rules,
+            survival_rules,
+            alive_cells: Arc::new(alive_cells),
+            prev_alive: Arc::default(),
+            changed: false,
+            dead_neighbours: HashMap::new(),
+            ancestry: None,
+            colours: None,
+            history: None,
+            observers: Observers::default(),
+        })
+    }
+
+    /// Create a new game of life with given birth and survival rules, with room for an expected number of alive cells.
+    ///
+    /// Pre-sizing avoids repeatedly growing the cell sets while a large pattern is loaded or evolves.
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// * `capacity` - The expected number of alive cells.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    ///
+    /// let mut life = Life::<2>::with_capacity([3].into_iter().collect(), [2, 3].into_iter().collect(), 10_000).unwrap();
+    /// assert!(life.alive_cells().capacity() >= 10_000);
+    /// ```
+    pub fn with_capacity(birth_rules: HashSet<usize>, survival_rules: HashSet<usize>, capacity: usize) -> Result<Self, Error> {
+        let mut life = Self::new(birth_rules, survival_rules)?;
+        life.reserve(capacity);
+        Ok(life)
+    }
+
+    /// Reserve room for at least `additional` more alive cells.
+    ///
+    /// The sets of alive cells of the current and previous generation and the scratch buffer of
+    /// [next_generation](Self::next_generation) all grow, so the next generations do not need to rehash.
+    /// # Arguments
+    /// * `additional` - The number of additional alive cells to make room for.
+    pub fn reserve(&mut self, additional: usize) {
+        Arc::make_mut(&mut self.alive_cells).reserve(additional);
+        // the sets are swapped by the next generation, so the previous one must hold the current cells and the additional ones
+        let prev_additional = (self.alive_cells.len() + additional).saturating_sub(self.prev_alive.len());
+        Arc::make_mut(&mut self.prev_alive).reserve(prev_additional);
+        self.dead_neighbours.reserve(additional);
+    }
+
+    /// Get an estimate of the heap memory used by the cells, in bytes.
+    ///
+    /// The estimate counts the allocated capacity of the alive cells, of the previous generation and of the neighbour counts
+    /// of [next_generation](Self::next_generation); ancestry, colours, history and observers are not included.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// let empty = life.memory_estimate();
+    /// life.reserve(1000);
+    /// assert!(life.memory_estimate() > empty + 1000 * 16);
+    /// ```
+    pub fn memory_estimate(&self) -> usize {
+        // every slot of a hash table also has a control byte
+        let cell = size_of::<[i64; N]>() + 1;
+        let count = size_of::<([i64; N], usize)>() + 1;
+        (self.alive_cells.capacity() + self.prev_alive.capacity()) * cell + self.dead_neighbours.capacity() * count
+    }
+
+    /// Create a new game of life without alive cells, with rules given by a rulestring in B/S notation.
+    ///
+    /// Birth and survival counts follow `B` and `S` in either order, separated by `/`, such as `B3/S23`.
+    /// Counts are single digits, or comma-separated numbers and inclusive ranges to allow counts above 9, such as `B5-7/S6,10`.
+    /// # Arguments
+    /// * `rulestring` - The rules in B/S notation.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [InvalidRulestring](Error::InvalidRulestring) - If the rulestring is malformed.
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::{conways_game_of_life, Life};
+    /// use ndlife::error::Error;
+    ///
+    /// assert_eq!(Life::<2>::empty_with_rulestring("B3/S23"), Ok(conways_game_of_life()));
+    ///
+    /// let life = Life::<3>::empty_with_rulestring("B5-7/S5,6,10").unwrap();
+    /// assert_eq!(life.survival_rules(), &[5, 6, 10].into_iter().collect());
+    ///
+    /// assert_eq!(Life::<2>::empty_with_rulestring("B3/S2a"), Err(Error::InvalidRulestring(6)));
+    /// ```
+    pub fn empty_with_rulestring(rulestring: &str) -> Result<Self, Error> {
+        let (birth_rules, survival_rules) = rulestring::parse(rulestring)?;
+        Self::new(birth_rules, survival_rules)
+    }
+
+    /// Create a new game of life with given birth and survival rules, and alive cells taken from an iterator.
+    ///
+    /// The cells are inserted directly, without collecting them into an intermediate set first.
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// * `cells` - The coordinates of alive cells, possibly repeated.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    ///
+    /// let life = Life::from_cells([3].into_iter().collect(), [2, 3].into_iter().collect(), (0..100).map(|x| [x, 0])).unwrap();
+    /// assert_eq!(life.alive_cells().len(), 100);
+    /// ```
+    pub fn from_cells(birth_rules: HashSet<usize>, survival_rules: HashSet<usize>, cells: impl IntoIterator<Item = [i64; N]>) -> Result<Self, Error> {
+        let mut life = Self::new(birth_rules, survival_rules)?;
+        life.extend(cells);
+        Ok(life)
+    }
+
+    /// Check that rules are valid for a game of life in `N` dimensions, as shared by the games of life of every cell storage.
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// # Returns
+    /// A [Result] containing `()` if the rules are valid, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    pub(crate) fn validate_rules(birth_rules: &HashSet<usize>, survival_rules: &HashSet<usize>) -> Result<(), Error> {
         if N == 0 {
             return Err(Error::ZeroDimension);
         }
@@ -111,14 +328,7 @@ impl<const N: usize> Life<N> {
                 return Err(Error::TooHighRule(*rule, Self::MAX_NEIGHBOURS));
             }
         }
-        Ok(Self {
-            age: 0,
-            birth_rules,
-            survival_rules,
-            alive_cells,
-            prev_alive: HashSet::new(),
-            dead_neighbours: HashMap::new(),
-        })
+        Ok(())
     }
 
     /// Get the age of the game of life.
@@ -126,6 +336,13 @@ impl<const N: usize> Life<N> {
         self.age
     }
 
+    /// Set the age of the game of life, for example to continue the generation count of a saved state.
+    /// # Arguments
+    /// * `age` - The new age.
+    pub fn set_age(&mut self, age: u64) {
+        self.age = age;
+    }
+
     /// Get the birth rules of the game of life.
     pub fn birth_rules(&self) -> &HashSet<usize> {
         &self.birth_rules
@@ -158,14 +375,7 @@ impl<const N: usize> Life<N> {
     /// assert_eq!(life.set_birth_rules(birth_rules), Err(Error::TooHighRule(9, 8)));
     /// ```
     pub fn set_birth_rules(&mut self, birth_rules: HashSet<usize>) -> Result<(), Error> {
-        if birth_rules.contains(&0) {
-            return Err(Error::ZeroNeighbourBirthRule);
-        }
-        for rule in birth_rules.iter() {
-            if *rule > Self::MAX_NEIGHBOURS {
-                return Err(Error::TooHighRule(*rule, Self::MAX_NEIGHBOURS));
-            }
-        }
+        Self::validate_rules(&birth_rules, &HashSet::new())?;
         self.birth_rules = birth_rules;
         Ok(())
     }
@@ -198,11 +408,7 @@ impl<const N: usize> Life<N> {
     /// assert_eq!(life.set_survival_rules(survival_rules), Err(Error::TooHighRule(9, 8)));
     /// ```
     pub fn set_survival_rules(&mut self, survival_rules: HashSet<usize>) -> Result<(), Error> {
-        for rule in survival_rules.iter() {
-            if *rule > Self::MAX_NEIGHBOURS {
-                return Err(Error::TooHighRule(*rule, Self::MAX_NEIGHBOURS));
-            }
-        }
+        Self::validate_rules(&HashSet::new(), &survival_rules)?;
         self.survival_rules = survival_rules;
         Ok(())
     }
@@ -212,6 +418,114 @@ impl<const N: usize> Life<N> {
         &self.alive_cells
     }
 
+    /// Get the alive cells as the shared set, which the game of life copies before modifying while it is shared.
+    #[cfg(feature = "std")]
+    pub(crate) fn shared_alive_cells(&self) -> Arc<HashSet<[i64; N]>> {
+        Arc::clone(&self.alive_cells)
+    }
+
+    /// Get the cells that were alive before the last generation.
+    ///
+    /// Older generations are available through [past_alive_cells](Self::past_alive_cells) once history is enabled.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// assert_eq!(life.previous_alive_cells(), &[[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// ```
+    pub fn previous_alive_cells(&self) -> &HashSet<[i64; N]> {
+        &self.prev_alive
+    }
+
+    /// Get the alive cells in lexicographic order.
+    ///
+    /// Unlike iterating over [alive_cells](Self::alive_cells), the order is the same in every run,
+    /// so it suits exported files, diffs and golden tests.
+    /// # Returns
+    /// The coordinates of the alive cells, sorted lexicographically.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[1, 2], [0, 0], [2, 1], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// assert_eq!(life.alive_cells_sorted(), vec![[0, 0], [1, 0], [1, 2], [2, 0], [2, 1]]);
+    /// ```
+    pub fn alive_cells_sorted(&self) -> Vec<[i64; N]> {
+        let mut alive_cells: Vec<[i64; N]> = self.alive_cells.iter().copied().collect();
+        alive_cells.sort_unstable();
+        alive_cells
+    }
+
+    /// Iterate over the alive cells inside a region, in no particular order.
+    ///
+    /// Either the cells of the region or the alive cells are scanned, whichever are fewer,
+    /// so small viewports of large universes are cheap to query.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// An iterator over the coordinates of the alive cells in the region.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    /// life.fill_region([0..=999, 0..=999]);
+    ///
+    /// assert_eq!(life.alive_cells_in([-5..=4, -5..=4]).count(), 25);
+    /// ```
+    pub fn alive_cells_in(&self, region: impl Into<Region<N>>) -> impl Iterator<Item = [i64; N]> + '_ {
+        self.view(region).alive_cells()
+    }
+
+    /// Get a view of the alive cells restricted to a region, borrowing instead of copying them.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The view of the region.
+    pub fn view(&self, region: impl Into<Region<N>>) -> View<'_, N> {
+        View::new(&self.alive_cells, region.into())
+    }
+
+    /// Consume the game of life and get its alive cells without copying them.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = life.into_alive_cells();
+    /// assert_eq!(alive_cells, [[1, -1], [1, 0], [1, 1]].into_iter().collect());
+    /// ```
+    pub fn into_alive_cells(self) -> HashSet<[i64; N]> {
+        Arc::unwrap_or_clone(self.alive_cells)
+    }
+
+    /// Move the alive cells out of the game of life without copying them, leaving no alive cells behind.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0]].into_iter().collect());
+    ///
+    /// let alive_cells = life.take_alive_cells();
+    /// assert_eq!(alive_cells.len(), 2);
+    /// assert!(life.alive_cells().is_empty());
+    /// ```
+    pub fn take_alive_cells(&mut self) -> HashSet<[i64; N]> {
+        Arc::unwrap_or_clone(core::mem::take(&mut self.alive_cells))
+    }
+
     /// Set the alive cells for the game of life.
     /// # Arguments
     /// * `alive_cells` - A set of coordinates of alive cells.
@@ -228,7 +542,79 @@ impl<const N: usize> Life<N> {
     /// assert_eq!(life.alive_cells(), &alive_cells);
     /// ```
     pub fn set_alive_cells(&mut self, alive_cells: HashSet<[i64; N]>) {
-        self.alive_cells = alive_cells;
+        self.alive_cells = Arc::new(alive_cells);
+        self.changed = false;
+    }
+
+    /// Reset the game of life to age 0 with no alive cells, keeping its rules.
+    ///
+    /// The previous generation and internal buffers are cleared, the retained history is discarded
+    /// and tracking of the causal ancestry and colours stops. Observers stay registered.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// life.reset();
+    /// assert_eq!(life, conways_game_of_life());
+    /// ```
+    pub fn reset(&mut self) {
+        self.age = 0;
+        emptied(&mut self.alive_cells);
+        emptied(&mut self.prev_alive);
+        self.changed = false;
+        self.dead_neighbours.clear();
+        self.ancestry = None;
+        self.colours = None;
+        if let Some(history) = self.history.as_mut() {
+            history.clear();
+        }
+    }
+
+    /// Reset the game of life to age 0 with given alive cells, keeping its rules.
+    ///
+    /// This is the same as [reset](Self::reset) followed by [set_alive_cells](Self::set_alive_cells).
+    /// # Arguments
+    /// * `alive_cells` - A set of coordinates of alive cells.
+    pub fn reset_to(&mut self, alive_cells: HashSet<[i64; N]>) {
+        self.reset();
+        self.alive_cells = Arc::new(alive_cells);
+    }
+
+    /// Get a pattern of the alive cells, for example to paste them into another game of life.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::pattern::Pattern;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 2]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells.clone()).unwrap();
+    ///
+    /// assert_eq!(life.to_pattern(), Pattern::new(alive_cells));
+    /// ```
+    pub fn to_pattern(&self) -> Pattern<N> {
+        Pattern::new(HashSet::clone(&self.alive_cells))
+    }
+
+    /// Get the smallest axis-aligned bounding box containing all alive cells.
+    /// # Returns
+    /// [Some] with the minimum and maximum coordinates (inclusive) along each axis, or [None] if there are no alive cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[1, -2], [3, 4], [-1, 0]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// assert_eq!(life.bounding_box(), Some(([-1, -2], [3, 4])));
+    /// ```
+    pub fn bounding_box(&self) -> Option<([i64; N], [i64; N])> {
+        geometry::bounding_box(self.alive_cells.iter())
     }
 
     /// Get whether a cell is alive.
@@ -272,12 +658,50 @@ impl<const N: usize> Life<N> {
     /// ```
     pub fn set_cell(&mut self, cell: &[i64; N], state: bool) -> bool {
         if state {
-            self.alive_cells.insert(*cell)
+            Arc::make_mut(&mut self.alive_cells).insert(*cell)
         } else {
-            self.alive_cells.remove(cell)
+            Arc::make_mut(&mut self.alive_cells).remove(cell)
         }
     }
 
+    /// Make cells dead.
+    /// # Arguments
+    /// * `cells` - Coordinates of the cells to make dead.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    /// life.extend((0..10).map(|x| [x, 0]));
+    ///
+    /// life.remove_cells((0..10).filter(|x| x % 2 == 0).map(|x| [x, 0]));
+    /// assert_eq!(life.alive_cells(), &[[1, 0], [3, 0], [5, 0], [7, 0], [9, 0]].into_iter().collect());
+    /// ```
+    pub fn remove_cells(&mut self, cells: impl IntoIterator<Item = [i64; N]>) {
+        for cell in cells {
+            Arc::make_mut(&mut self.alive_cells).remove(&cell);
+        }
+    }
+
+    /// Keep only the alive cells satisfying a predicate, making the others dead in place.
+    /// # Arguments
+    /// * `predicate` - The condition for a cell to stay alive, given its coordinates.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[-5, 0], [0, 0], [5, 0]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.retain_cells(|cell| cell[0] >= 0);
+    /// assert_eq!(life.alive_cells(), &[[0, 0], [5, 0]].into_iter().collect());
+    /// ```
+    pub fn retain_cells(&mut self, mut predicate: impl FnMut(&[i64; N]) -> bool) {
+        Arc::make_mut(&mut self.alive_cells).retain(|cell| predicate(cell));
+    }
+
     /// Toggle a cell between alive and dead.
     /// # Arguments
     /// * `cell` - Coordinates of the cell.
@@ -297,42 +721,25 @@ impl<const N: usize> Life<N> {
     /// assert_eq!(life.alive_cells(), &expected_alive_cells);
     /// ```
     pub fn toggle_cell(&mut self, cell: &[i64; N]) {
-        if !self.alive_cells.remove(cell) {
-            self.alive_cells.insert(*cell);
+        if !Arc::make_mut(&mut self.alive_cells).remove(cell) {
+            Arc::make_mut(&mut self.alive_cells).insert(*cell);
         }
     }
 
     /// Advance the game of life to the next generation.
     pub fn next_generation(&mut self) {
-        let deltas = || {
-            let mut ptr = 0;
-            let mut deltas = [-1i64; N];
-            deltas[ptr] = -2;
-            std::iter::from_fn(move || {
-                while ptr < N {
-                    if deltas[ptr] == 1 {
-                        ptr += 1;
-                    } else {
-                        deltas[ptr] += 1;
-                        deltas[0..ptr].fill(-1);
-                        ptr = 0;
-                        return Some(deltas);
-                    }
-                }
-                None
-            })
-            .filter(|deltas| deltas.iter().any(|&delta| delta != 0))
-        };
-
+        if let Some(history) = self.history.as_mut() {
+            history.record(&self.prev_alive, &self.alive_cells);
+        }
         self.age += 1;
-        std::mem::swap(&mut self.alive_cells, &mut self.prev_alive);
-        self.alive_cells.clear();
+        core::mem::swap(&mut self.alive_cells, &mut self.prev_alive);
+        let alive_cells = emptied(&mut self.alive_cells);
         self.dead_neighbours.clear();
+        let mut changed = false;
 
         self.prev_alive.iter().for_each(|alive_cell| {
             let mut alive_neighbours = 0;
-            for delta in deltas() {
-                let neighbour = std::array::from_fn(|i| alive_cell[i] + delta[i]);
+            for neighbour in Self::neighbours(alive_cell) {
                 if self.prev_alive.contains(&neighbour) {
                     alive_neighbours += 1;
                 } else {
@@ -340,15 +747,115 @@ impl<const N: usize> Life<N> {
                 }
             }
             if self.survival_rules.contains(&alive_neighbours) {
-                self.alive_cells.insert(*alive_cell);
+                alive_cells.insert(*alive_cell);
+            } else {
+                changed = true;
             }
         });
 
         for (key, value) in self.dead_neighbours.iter() {
             if self.birth_rules.contains(value) {
-                self.alive_cells.insert(*key);
+                alive_cells.insert(*key);
+                changed = true;
             }
         }
+        self.changed = changed;
+
+        if let Some(ancestry) = self.ancestry.as_mut() {
+            Arc::make_mut(ancestry).advance(&self.prev_alive, &self.alive_cells);
+        }
+        if let Some(colours) = self.colours.as_mut() {
+            Arc::make_mut(colours).advance(&self.prev_alive, &self.alive_cells);
+        }
+        self.notify_observers();
+    }
+
+    /// Get the next generation without modifying the game of life.
+    ///
+    /// The successor is evolved from a [fork](Self::fork), so it carries no history and observers are not notified.
+    /// # Returns
+    /// A new game of life in the next generation.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// // a blinker has period 2
+    /// assert_eq!(life.next().next().alive_cells(), life.alive_cells());
+    /// assert_ne!(life.next().alive_cells(), life.alive_cells());
+    /// assert_eq!(life.age(), 0);
+    /// ```
+    pub fn next(&self) -> Self {
+        let mut next = self.fork();
+        next.next_generation();
+        next
+    }
+
+    /// Get the number of alive neighbours of a cell, as counted by the rules in the next generation.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell, which may be alive or dead.
+    /// # Returns
+    /// The number of alive cells in the Moore neighbourhood of the cell, excluding the cell itself.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// // the dead cell above the centre is about to be born
+    /// assert_eq!(life.alive_neighbour_count(&[1, 1]), 3);
+    /// assert_eq!(life.alive_neighbour_count(&[1, 0]), 2);
+    /// assert_eq!(life.alive_neighbour_count(&[5, 5]), 0);
+    /// ```
+    pub fn alive_neighbour_count(&self, cell: &[i64; N]) -> usize {
+        Self::neighbours(cell).filter(|neighbour| self.alive_cells.contains(neighbour)).count()
+    }
+
+    /// Iterate over the [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS) neighbours of a cell in the Moore neighbourhood,
+    /// in the same order as they are visited by [next_generation](Self::next_generation).
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// An iterator over the coordinates of the neighbours, excluding the cell itself.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let neighbours: HashSet<[i64; 2]> = Life::<2>::neighbours(&[5, 5]).collect();
+    /// assert_eq!(neighbours.len(), 8);
+    /// assert!(neighbours.contains(&[4, 6]));
+    /// assert!(!neighbours.contains(&[5, 5]));
+    ///
+    /// assert_eq!(Life::<3>::neighbours(&[0, 0, 0]).count(), Life::<3>::MAX_NEIGHBOURS);
+    /// ```
+    pub fn neighbours(cell: &[i64; N]) -> impl Iterator<Item = [i64; N]> {
+        let cell = *cell;
+        Self::neighbour_deltas().map(move |delta| core::array::from_fn(|i| cell[i] + delta[i]))
+    }
+
+    /// Iterate over the offsets from a cell to each of its [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS) neighbours.
+    fn neighbour_deltas() -> impl Iterator<Item = [i64; N]> {
+        let mut ptr = 0;
+        let mut deltas = [-1i64; N];
+        deltas[ptr] = -2;
+        core::iter::from_fn(move || {
+            while ptr < N {
+                if deltas[ptr] == 1 {
+                    ptr += 1;
+                } else {
+                    deltas[ptr] += 1;
+                    deltas[0..ptr].fill(-1);
+                    ptr = 0;
+                    return Some(deltas);
+                }
+            }
+            None
+        })
+        .filter(|deltas| deltas.iter().any(|&delta| delta != 0))
     }
 
     /// Get the cells that have changed between the previous and current generation.
@@ -369,6 +876,167 @@ impl<const N: usize> Life<N> {
     pub fn changed_cells(&self) -> impl Iterator<Item = &[i64; N]> {
         self.prev_alive.symmetric_difference(&self.alive_cells)
     }
+
+    /// Get whether there are no alive cells.
+    pub fn is_extinct(&self) -> bool {
+        self.alive_cells.is_empty()
+    }
+
+    /// Get whether the last generation changed the alive cells.
+    ///
+    /// Edits made since the last generation do not count, and replacing the alive cells with
+    /// [set_alive_cells](Self::set_alive_cells), [reset](Self::reset) or [restore](Self::restore) clears it.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+    ///
+    /// life.next_generation();
+    /// assert!(life.changed_last_generation());
+    /// life.next_generation();
+    /// assert!(!life.changed_last_generation());
+    ///
+    /// // editing the block does not make its generation a change
+    /// life.set_cell(&[5, 5], true);
+    /// assert!(!life.changed_last_generation());
+    /// ```
+    pub fn changed_last_generation(&self) -> bool {
+        self.changed
+    }
+
+    /// Get whether the next generation would leave the alive cells unchanged, without advancing.
+    ///
+    /// A game of life without alive cells never changes, so it counts as a still life as well as [extinct](Self::is_extinct).
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect());
+    /// assert!(life.is_still_life());
+    ///
+    /// life.set_cell(&[2, 0], true);
+    /// assert!(!life.is_still_life());
+    /// assert_eq!(life.age(), 0);
+    /// ```
+    pub fn is_still_life(&self) -> bool {
+        let mut dead_neighbours: HashMap<[i64; N], usize> = HashMap::new();
+        for cell in self.alive_cells.iter() {
+            let mut alive_neighbours = 0;
+            for neighbour in Self::neighbours(cell) {
+                if self.alive_cells.contains(&neighbour) {
+                    alive_neighbours += 1;
+                } else {
+                    *dead_neighbours.entry(neighbour).or_insert(0) += 1;
+                }
+            }
+            if !self.survival_rules.contains(&alive_neighbours) {
+                return false;
+            }
+        }
+        dead_neighbours.values().all(|count| !self.birth_rules.contains(count))
+    }
+
+    /// Get the cells that are alive in the current generation but were dead in the previous one.
+    /// # Returns
+    /// An iterator over the coordinates of born cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// let mut born_cells: Vec<[i64; 2]> = life.born_cells().copied().collect();
+    /// born_cells.sort();
+    /// assert_eq!(born_cells, vec![[1, -1], [1, 1]]);
+    /// ```
+    pub fn born_cells(&self) -> impl Iterator<Item = &[i64; N]> {
+        self.alive_cells.difference(&self.prev_alive)
+    }
+
+    /// Get the cells that were alive in the previous generation but are dead in the current one.
+    /// # Returns
+    /// An iterator over the coordinates of died cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// let mut died_cells: Vec<[i64; 2]> = life.died_cells().copied().collect();
+    /// died_cells.sort();
+    /// assert_eq!(died_cells, vec![[0, 0], [2, 0]]);
+    /// ```
+    pub fn died_cells(&self) -> impl Iterator<Item = &[i64; N]> {
+        self.prev_alive.difference(&self.alive_cells)
+    }
+}
+
+impl Default for Life<2> {
+    /// Create an empty 2-dimensional game of life with Conway's rules, like [conways_game_of_life].
+    fn default() -> Self {
+        conways_game_of_life()
+    }
+}
+impl<const N: usize> Extend<[i64; N]> for Life<N> {
+    fn extend<T: IntoIterator<Item = [i64; N]>>(&mut self, cells: T) {
+        Arc::make_mut(&mut self.alive_cells).extend(cells);
+    }
+}
+impl<'a, const N: usize> Extend<&'a [i64; N]> for Life<N> {
+    fn extend<T: IntoIterator<Item = &'a [i64; N]>>(&mut self, cells: T) {
+        Arc::make_mut(&mut self.alive_cells).extend(cells);
+    }
+}
+impl<'a, const N: usize> IntoIterator for &'a Life<N> {
+    type Item = &'a [i64; N];
+    type IntoIter = crate::collections::hash_set::Iter<'a, [i64; N]>;
+
+    /// Iterate over the alive cells, in no particular order.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let mut x_sum = 0;
+    /// for cell in &life {
+    ///     x_sum += cell[0];
+    /// }
+    /// assert_eq!(x_sum, 3);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.alive_cells.iter()
+    }
+}
+impl<const N: usize> Index<[i64; N]> for Life<N> {
+    type Output = bool;
+
+    /// Get the state of a cell, like [get_cell](Life::get_cell).
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_cell(&[3, -2], true);
+    ///
+    /// assert!(life[[3, -2]]);
+    /// assert!(!life[[0, 0]]);
+    /// ```
+    fn index(&self, cell: [i64; N]) -> &bool {
+        if self.get_cell(&cell) {
+            &true
+        } else {
+            &false
+        }
+    }
 }
 
 /// Create new game of life with Conway's rules
@@ -387,9 +1055,20 @@ pub fn conways_game_of_life() -> Life<2> {
     Life::new([3].into_iter().collect(), [2, 3].into_iter().collect()).unwrap()
 }
 
+/// Get a set of cells emptied for reuse, replaced by a new set if it is shared with a fork.
+fn emptied<const N: usize>(cells: &mut Arc<HashSet<[i64; N]>>) -> &mut HashSet<[i64; N]> {
+    if Arc::get_mut(cells).is_none() {
+        *cells = Arc::new(HashSet::with_capacity(cells.len()));
+    }
+    let cells = Arc::get_mut(cells).expect("a new set is not shared");
+    cells.clear();
+    cells
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_max_neighbours() {
@@ -427,6 +1106,55 @@ mod tests {
         assert_eq!(life.age(), 100);
     }
 
+    #[test]
+    fn test_new_with_age() {
+        let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+        let mut life = Life::new_with_age([3].into_iter().collect(), [2, 3].into_iter().collect(), alive_cells.clone(), 41).unwrap();
+        assert_eq!(life.age(), 41);
+        life.next_generation();
+        assert_eq!(life.age(), 42);
+        assert_eq!(
+            Life::<2>::new_with_age([0].into_iter().collect(), HashSet::new(), alive_cells, 5),
+            Err(Error::ZeroNeighbourBirthRule)
+        );
+        assert_eq!(Life::<0>::new_with_age(HashSet::new(), HashSet::new(), HashSet::new(), 5), Err(Error::ZeroDimension));
+    }
+
+    #[test]
+    fn test_empty_with_rulestring() {
+        let life = Life::<2>::empty_with_rulestring("B36/S23").unwrap();
+        assert_eq!(life.birth_rules(), &[3, 6].into_iter().collect());
+        assert_eq!(life.survival_rules(), &[2, 3].into_iter().collect());
+        assert!(life.alive_cells().is_empty());
+        assert_eq!(Life::<2>::empty_with_rulestring("B0/S"), Err(Error::ZeroNeighbourBirthRule));
+        assert_eq!(Life::<1>::empty_with_rulestring("B1/S3"), Err(Error::TooHighRule(3, 2)));
+        assert_eq!(Life::<2>::empty_with_rulestring("S23"), Err(Error::InvalidRulestring(4)));
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(Life::<2>::default(), conways_game_of_life());
+    }
+
+    #[test]
+    fn test_from_cells() {
+        let life = Life::from_cells([3].into_iter().collect(), [2, 3].into_iter().collect(), [[0, 0], [1, 2], [0, 0]]).unwrap();
+        assert_eq!(
+            life,
+            Life::new_with_alive_cells([3].into_iter().collect(), [2, 3].into_iter().collect(), [[0, 0], [1, 2]].into_iter().collect()).unwrap()
+        );
+        assert_eq!(Life::<1>::from_cells(HashSet::new(), [3].into_iter().collect(), [[0]]), Err(Error::TooHighRule(3, 2)));
+    }
+
+    #[test]
+    fn test_set_age() {
+        let mut life = conways_game_of_life();
+        life.set_age(u64::MAX - 1);
+        assert_eq!(life.age(), u64::MAX - 1);
+        life.next_generation();
+        assert_eq!(life.age(), u64::MAX);
+    }
+
     #[test]
     fn test_birth_rules() {
         let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
@@ -460,6 +1188,156 @@ mod tests {
         assert_eq!(life.alive_cells(), &alive_cells);
     }
 
+    #[test]
+    fn test_reset() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        life.enable_history(3);
+        life.track_ancestry();
+        life.next_generation();
+        life.next_generation();
+
+        let glider: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+        life.reset_to(glider.clone());
+        assert_eq!(life.age(), 0);
+        assert_eq!(life.alive_cells(), &glider);
+        assert_eq!(life.changed_cells().count(), 5);
+        assert_eq!(life.history_len(), 0);
+        assert!(!life.is_tracking_ancestry());
+        let mut expected = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), glider).unwrap();
+        expected.enable_history(3);
+        assert_eq!(life, expected);
+
+        life.reset();
+        assert!(life.alive_cells().is_empty());
+        assert_eq!(life.changed_cells().count(), 0);
+    }
+
+    #[test]
+    fn test_extend_and_remove_cells() {
+        let mut life = conways_game_of_life();
+        life.extend([[0, 0], [1, 0]]);
+        life.extend(&[[2, 0], [0, 0]]);
+        assert_eq!(life.alive_cells(), &[[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        life.remove_cells([[0, 0], [5, 5]]);
+        assert_eq!(life.alive_cells(), &[[1, 0], [2, 0]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut life = conways_game_of_life();
+        life.extend([[0, 0], [1, 5], [-2, 3]]);
+        let mut cells: Vec<[i64; 2]> = (&life).into_iter().copied().collect();
+        cells.sort();
+        assert_eq!(cells, life.alive_cells_sorted());
+        assert_eq!((&life).into_iter().filter(|cell| cell[0] >= 0).count(), 2);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut life = Life::<2>::with_capacity([3].into_iter().collect(), [2, 3].into_iter().collect(), 1000).unwrap();
+        assert!(life.alive_cells().capacity() >= 1000);
+        assert!(life.prev_alive.capacity() >= 1000);
+        assert!(life.dead_neighbours.capacity() >= 1000);
+        assert_eq!(life, conways_game_of_life());
+        assert_eq!(Life::<2>::with_capacity([0].into_iter().collect(), HashSet::new(), 10), Err(Error::ZeroNeighbourBirthRule));
+
+        life.extend((0..500).map(|x| [x, 0]));
+        life.reserve(2000);
+        assert!(life.alive_cells().capacity() >= 2500);
+        assert!(life.prev_alive.capacity() >= 2500);
+
+        let mut life = conways_game_of_life();
+        life.extend((0..1000).map(|x| [x, 0]));
+        life.reserve(1000);
+        assert!(life.alive_cells().capacity() >= 2000);
+        assert!(life.prev_alive.capacity() >= 2000);
+    }
+
+    #[test]
+    fn test_next() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let next = life.next();
+        assert_eq!(life.age(), 0);
+        assert_eq!(next.age(), 1);
+        let mut stepped = life.clone();
+        stepped.next_generation();
+        stepped.next_generation();
+        assert_eq!(life.next().next().alive_cells(), stepped.alive_cells());
+        assert_eq!(next.next().changed_cells().count(), stepped.changed_cells().count());
+    }
+
+    #[test]
+    fn test_index() {
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.extend([[1, 2, 3]]);
+        assert!(life[[1, 2, 3]]);
+        assert!(!life[[3, 2, 1]]);
+        assert_eq!(life[[1, 2, 3]], life.get_cell(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_retain_cells() {
+        let mut life = conways_game_of_life();
+        life.extend((-5..=5).flat_map(|x| (-5..=5).map(move |y| [x, y])));
+        life.retain_cells(|[x, y]| x * x + y * y <= 4);
+        assert_eq!(life.alive_cells().len(), 13);
+        life.retain_cells(|_| false);
+        assert!(life.alive_cells().is_empty());
+    }
+
+    #[test]
+    fn test_alive_cells_sorted() {
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert!(life.alive_cells_sorted().is_empty());
+        life.extend([[0, 0, 1], [-1, 5, 5], [0, -1, 9], [0, 0, 0]]);
+        assert_eq!(life.alive_cells_sorted(), vec![[-1, 5, 5], [0, -1, 9], [0, 0, 0], [0, 0, 1]]);
+        assert_eq!(life.alive_cells_sorted(), life.clone().alive_cells_sorted());
+    }
+
+    #[test]
+    fn test_alive_cells_in() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.extend([[0, 0], [3, 3], [-2, 7], [10, 10]]);
+        let sorted = |mut cells: Vec<[i64; 2]>| {
+            cells.sort();
+            cells
+        };
+        // a small region is scanned cell by cell, a large one through the population
+        assert_eq!(sorted(life.alive_cells_in([0..=1, 0..=0]).collect()), vec![[0, 0]]);
+        assert_eq!(sorted(life.alive_cells_in([-5..=5, -5..=8]).collect()), vec![[-2, 7], [0, 0], [3, 3]]);
+        assert_eq!(life.alive_cells_in(Region::new([i64::MIN; 2], [i64::MAX; 2])).count(), 4);
+        let (start, end) = (5, 4);
+        assert_eq!(life.alive_cells_in([start..=end, 0..=10]).count(), 0);
+    }
+
+    #[test]
+    fn test_into_and_take_alive_cells() {
+        let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 2]].into_iter().collect();
+        let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells.clone()).unwrap();
+        assert_eq!(life.take_alive_cells(), alive_cells);
+        assert!(life.take_alive_cells().is_empty());
+        life.set_alive_cells(alive_cells.clone());
+        assert_eq!(life.into_alive_cells(), alive_cells);
+    }
+
+    #[test]
+    fn test_to_pattern() {
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert!(life.to_pattern().is_empty());
+        life.set_cell(&[1, 2, 3], true);
+        assert_eq!(life.to_pattern(), [[1, 2, 3]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert_eq!(life.bounding_box(), None);
+        life.set_alive_cells([[1, -2], [3, 4], [-1, 0]].into_iter().collect());
+        assert_eq!(life.bounding_box(), Some(([-1, -2], [3, 4])));
+    }
+
     #[test]
     fn test_get_cell() {
         let alive_cells: HashSet<[i64; 2]> = [[1, 1]].into_iter().collect();
@@ -503,6 +1381,70 @@ mod tests {
         assert_eq!(life.alive_cells(), &expected_alive_cells);
     }
 
+    #[test]
+    fn test_neighbours() {
+        let neighbours: Vec<[i64; 1]> = Life::<1>::neighbours(&[7]).collect();
+        assert_eq!(neighbours, vec![[6], [8]]);
+        let neighbours: HashSet<[i64; 2]> = Life::<2>::neighbours(&[0, 0]).collect();
+        let expected: HashSet<[i64; 2]> = [[-1, -1], [0, -1], [1, -1], [-1, 0], [1, 0], [-1, 1], [0, 1], [1, 1]].into_iter().collect();
+        assert_eq!(neighbours, expected);
+        assert_eq!(Life::<4>::neighbours(&[0; 4]).collect::<HashSet<_>>().len(), 80);
+    }
+
+    #[test]
+    fn test_alive_neighbour_count() {
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.fill_region([-1..=1, -1..=1, -1..=1]);
+        assert_eq!(life.alive_neighbour_count(&[0, 0, 0]), 26);
+        assert_eq!(life.alive_neighbour_count(&[1, 1, 1]), 7);
+        assert_eq!(life.alive_neighbour_count(&[2, 0, 0]), 9);
+        assert_eq!(life.alive_neighbour_count(&[3, 0, 0]), 0);
+    }
+
+    #[test]
+    fn test_state_queries() {
+        let mut life = conways_game_of_life();
+        assert!(life.is_extinct());
+        assert!(life.is_still_life());
+        assert!(!life.changed_last_generation());
+
+        // a beehive is still, a blinker is not
+        life.set_alive_cells([[1, 0], [2, 0], [0, 1], [3, 1], [1, 2], [2, 2]].into_iter().collect());
+        assert!(!life.is_extinct());
+        assert!(life.is_still_life());
+        assert!(!life.changed_last_generation());
+        life.next_generation();
+        assert!(!life.changed_last_generation());
+        // edits after a generation do not change what it did
+        life.set_cell(&[10, 10], true);
+        life.toggle_cell(&[1, 0]);
+        assert!(!life.changed_last_generation());
+
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        assert!(!life.is_still_life());
+        life.next_generation();
+        assert!(life.changed_last_generation());
+        life.set_alive_cells(life.previous_alive_cells().clone());
+        assert!(!life.changed_last_generation());
+
+        // stepping back restores the change of the generation before
+        life.enable_history(4);
+        life.next_generation();
+        life.next_generation();
+        life.set_cell(&[10, 10], true);
+        life.next_generation();
+        assert!(life.changed_last_generation());
+        assert!(life.step_back());
+        assert!(life.changed_last_generation());
+        life.reset();
+        assert!(!life.changed_last_generation());
+
+        // survival alone is not enough if a neighbour would be born
+        let mut life = Life::<2>::new([1].into_iter().collect(), [0].into_iter().collect()).unwrap();
+        life.set_cell(&[0, 0], true);
+        assert!(!life.is_still_life());
+    }
+
     #[test]
     fn test_changed_cells() {
         let mut alive_cells = HashSet::new();
@@ -511,4 +1453,19 @@ mod tests {
         life.next_generation();
         assert_eq!(vec![[1, 1]], life.changed_cells().copied().collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_born_and_died_cells() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+        life.next_generation();
+        assert_eq!(life.born_cells().copied().collect::<Vec<_>>(), vec![[1, 1]]);
+        assert_eq!(life.died_cells().count(), 0);
+
+        life.set_alive_cells([[0, 0]].into_iter().collect());
+        life.next_generation();
+        assert_eq!(life.born_cells().count(), 0);
+        assert_eq!(life.died_cells().copied().collect::<Vec<_>>(), vec![[0, 0]]);
+        assert_eq!(life.born_cells().count() + life.died_cells().count(), life.changed_cells().count());
+    }
 }
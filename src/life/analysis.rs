@@ -0,0 +1,246 @@
+//! Measurements and observables computed from the alive cells
+
+use super::Life;
+use crate::collections::{HashMap, HashSet};
+use crate::error::Error;
+use alloc::vec;
+use alloc::vec::Vec;
+
+impl<const N: usize> Life<N> {
+    /// Estimate the fractal dimension of the alive cells using box-counting.
+    ///
+    /// The bounding box of the alive cells is covered with boxes of side `2^k` for `k = 0, 1, ..., K`,
+    /// where `2^K` is the smallest power of two that covers the whole bounding box (with `K >= 1`).
+    /// The estimate is the negated slope of the least-squares fit of `log(boxes)` against `log(side)`.
+    /// # Returns
+    /// [Some] with the estimated dimension, or [None] if there are no alive cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// // a filled 8x8 square is two-dimensional
+    /// let alive_cells: HashSet<[i64; 2]> = (0..8).flat_map(|x| (0..8).map(move |y| [x, y])).collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    /// assert!((life.box_counting_dimension().unwrap() - 2.0).abs() < 1e-9);
+    ///
+    /// // a line of 8 cells is one-dimensional
+    /// let alive_cells: HashSet<[i64; 2]> = (0..8).map(|x| [x, 0]).collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    /// assert!((life.box_counting_dimension().unwrap() - 1.0).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn box_counting_dimension(&self) -> Option<f64> {
+        let (min, max) = self.bounding_box()?;
+        let extent = (0..N).map(|i| max[i].abs_diff(min[i]) + 1).max().unwrap_or(1);
+        let max_level = (u64::BITS - (extent - 1).leading_zeros()).max(1);
+
+        let mut boxes = HashSet::with_capacity(self.alive_cells.len());
+        let points: Vec<(f64, f64)> = (0..=max_level)
+            .map(|level| {
+                boxes.clear();
+                boxes.extend(self.alive_cells.iter().map(|cell| {
+                    let key: [u64; N] = core::array::from_fn(|i| cell[i].abs_diff(min[i]) >> level);
+                    key
+                }));
+                (level as f64, (boxes.len() as f64).log2())
+            })
+            .collect();
+
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        Some(-covariance / variance)
+    }
+
+    /// Split the alive cells into connected components.
+    ///
+    /// Two alive cells belong to the same component if they are neighbours,
+    /// or if they are connected through a chain of neighbouring alive cells.
+    /// # Returns
+    /// A vector of components, each a set of coordinates of alive cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 1], [5, 5]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// let mut sizes: Vec<usize> = life.connected_components().iter().map(|component| component.len()).collect();
+    /// sizes.sort();
+    /// assert_eq!(sizes, vec![1, 2]);
+    /// ```
+    pub fn connected_components(&self) -> Vec<HashSet<[i64; N]>> {
+        let mut visited = HashSet::with_capacity(self.alive_cells.len());
+        let mut components = Vec::new();
+        for start in self.alive_cells.iter() {
+            if !visited.insert(*start) {
+                continue;
+            }
+            let mut component = HashSet::new();
+            let mut stack = vec![*start];
+            while let Some(cell) = stack.pop() {
+                component.insert(cell);
+                for neighbour in Self::neighbours(&cell) {
+                    if self.alive_cells.contains(&neighbour) && visited.insert(neighbour) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Project the alive cells along an axis onto an (N-1)-dimensional grid.
+    ///
+    /// `M` must be equal to `N - 1`, which is checked at compile time.
+    /// # Arguments
+    /// * `axis` - The axis along which to project.
+    /// # Returns
+    /// A [Result] containing the number of alive cells projected onto each coordinate, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis` is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::{HashMap, HashSet};
+    ///
+    /// let alive_cells: HashSet<[i64; 3]> = [[0, 0, 0], [0, 1, 0], [0, 1, 5], [2, 2, 2]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// let projection: HashMap<[i64; 2], usize> = life.projection(2).unwrap();
+    /// assert_eq!(projection, [([0, 0], 1), ([0, 1], 2), ([2, 2], 1)].into_iter().collect());
+    /// ```
+    pub fn projection<const M: usize>(&self, axis: usize) -> Result<HashMap<[i64; M], usize>, Error> {
+        const { assert!(M + 1 == N, "the projection must have one dimension less than the life") };
+        if axis >= N {
+            return Err(Error::InvalidAxis(axis, N));
+        }
+        let mut projection = HashMap::new();
+        for cell in self.alive_cells.iter() {
+            let projected: [i64; M] = core::array::from_fn(|i| if i < axis { cell[i] } else { cell[i + 1] });
+            *projection.entry(projected).or_insert(0) += 1;
+        }
+        Ok(projection)
+    }
+
+    /// Extract the alive cells lying in an axis-aligned hyperplane of lower dimension.
+    ///
+    /// The remaining free axes, in increasing order, become the axes of the slice.
+    /// `M` must not be greater than `N`, which is checked at compile time.
+    /// # Arguments
+    /// * `fixed_axes` - The `N - M` pairs of an axis and the coordinate it is fixed at.
+    /// # Returns
+    /// A [Result] containing the coordinates of the alive cells in the slice, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the length of `fixed_axes` is not `N - M`.
+    /// * [IdenticalAxes](Error::IdenticalAxes) - If an axis is fixed more than once.
+    /// * [InvalidAxis](Error::InvalidAxis) - If any fixed axis is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 3]> = [[0, 0, 0], [1, 5, 0], [1, 5, 1], [2, 2, 2]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// let slice: HashSet<[i64; 2]> = life.slice(&[(2, 0)]).unwrap();
+    /// assert_eq!(slice, [[0, 0], [1, 5]].into_iter().collect());
+    ///
+    /// let slice: HashSet<[i64; 1]> = life.slice(&[(0, 1), (1, 5)]).unwrap();
+    /// assert_eq!(slice, [[0], [1]].into_iter().collect());
+    /// ```
+    pub fn slice<const M: usize>(&self, fixed_axes: &[(usize, i64)]) -> Result<HashSet<[i64; M]>, Error> {
+        const { assert!(M <= N, "the slice cannot have more dimensions than the life") };
+        if fixed_axes.len() != N - M {
+            return Err(Error::DimensionMismatch(N - M, fixed_axes.len()));
+        }
+        let mut fixed = [None; N];
+        for &(axis, coordinate) in fixed_axes {
+            if axis >= N {
+                return Err(Error::InvalidAxis(axis, N));
+            }
+            if fixed[axis].replace(coordinate).is_some() {
+                return Err(Error::IdenticalAxes(axis));
+            }
+        }
+        let free_axes: Vec<usize> = (0..N).filter(|&axis| fixed[axis].is_none()).collect();
+        Ok(self
+            .alive_cells
+            .iter()
+            .filter(|cell| (0..N).all(|axis| fixed[axis].is_none_or(|coordinate| cell[axis] == coordinate)))
+            .map(|cell| core::array::from_fn(|i| cell[free_axes[i]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_box_counting_dimension() {
+        let life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert_eq!(life.box_counting_dimension(), None);
+
+        let alive_cells: HashSet<[i64; 2]> = [[5, -3]].into_iter().collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        assert_eq!(life.box_counting_dimension(), Some(0.0));
+
+        let alive_cells: HashSet<[i64; 2]> = (-8..8).flat_map(|x| (-8..8).map(move |y| [x, y])).collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        assert!((life.box_counting_dimension().unwrap() - 2.0).abs() < 1e-9);
+
+        let alive_cells: HashSet<[i64; 3]> = (0..16).map(|x| [x, x, x]).collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        assert!((life.box_counting_dimension().unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert!(life.connected_components().is_empty());
+
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        let block = [[10, 10], [11, 10], [10, 11], [11, 11]];
+        let alive_cells: HashSet<[i64; 2]> = glider.into_iter().chain(block).collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        let mut components = life.connected_components();
+        components.sort_by_key(|component| component.len());
+        assert_eq!(components, vec![block.into_iter().collect(), glider.into_iter().collect()]);
+    }
+
+    #[test]
+    fn test_projection() {
+        let alive_cells: HashSet<[i64; 3]> = [[0, 0, 0], [0, 1, 0], [0, 1, 5], [2, 2, 2]].into_iter().collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        assert_eq!(life.projection(0).unwrap(), [([0, 0], 1), ([1, 0], 1), ([1, 5], 1), ([2, 2], 1)].into_iter().collect());
+        assert_eq!(life.projection(1).unwrap(), [([0, 0], 2), ([0, 5], 1), ([2, 2], 1)].into_iter().collect());
+        assert_eq!(life.projection(2).unwrap(), [([0, 0], 1), ([0, 1], 2), ([2, 2], 1)].into_iter().collect());
+        assert_eq!(life.projection::<2>(3), Err(Error::InvalidAxis(3, 3)));
+
+        let alive_cells: HashSet<[i64; 1]> = [[4], [-2]].into_iter().collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+        assert_eq!(life.projection(0).unwrap(), [([], 2)].into_iter().collect());
+    }
+
+    #[test]
+    fn test_slice() {
+        let alive_cells: HashSet<[i64; 3]> = [[0, 0, 0], [1, 5, 0], [1, 5, 1], [2, 2, 2]].into_iter().collect();
+        let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells.clone()).unwrap();
+        assert_eq!(life.slice(&[(0, 1)]), Ok([[5, 0], [5, 1]].into_iter().collect()));
+        assert_eq!(life.slice(&[(1, 2)]), Ok([[2, 2]].into_iter().collect()));
+        assert_eq!(life.slice(&[(1, 3)]), Ok(HashSet::<[i64; 2]>::new()));
+        assert_eq!(life.slice(&[(2, 1), (0, 1)]), Ok([[5]].into_iter().collect()));
+        assert_eq!(life.slice(&[(2, 2), (1, 2), (0, 2)]), Ok([[]].into_iter().collect()));
+        assert_eq!(life.slice(&[]), Ok(alive_cells));
+        assert_eq!(life.slice::<2>(&[(0, 1), (1, 1)]), Err(Error::DimensionMismatch(1, 2)));
+        assert_eq!(life.slice::<1>(&[(0, 1), (0, 2)]), Err(Error::IdenticalAxes(0)));
+        assert_eq!(life.slice::<2>(&[(3, 1)]), Err(Error::InvalidAxis(3, 3)));
+    }
+}
@@ -0,0 +1,149 @@
+//! Tracking of the causal past of alive cells
+
+use super::Life;
+use crate::collections::{HashMap, HashSet};
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Causal ancestry of the alive cells.
+///
+/// Every cell that was alive when tracking started is a seed with an ID (its index in `seeds`).
+/// Each alive cell stores the IDs of the seeds in its causal past.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Ancestry<const N: usize> {
+    /// The coordinates of the seed cells, indexed by ID.
+    seeds: Vec<[i64; N]>,
+    /// The IDs of the seeds in the causal past of each alive cell.
+    ancestors: HashMap<[i64; N], BTreeSet<usize>>,
+}
+impl<const N: usize> Ancestry<N> {
+    /// Start tracking with the given cells as seeds.
+    fn new(alive_cells: &HashSet<[i64; N]>) -> Self {
+        let mut seeds: Vec<[i64; N]> = alive_cells.iter().copied().collect();
+        seeds.sort_unstable();
+        let ancestors = seeds.iter().enumerate().map(|(id, seed)| (*seed, BTreeSet::from([id]))).collect();
+        Self { seeds, ancestors }
+    }
+
+    /// Propagate the ancestry from the previous generation to the current one.
+    ///
+    /// The causal past of a cell is the union of the causal pasts of the cells
+    /// alive in its neighbourhood (including the cell itself) in the previous generation.
+    pub(super) fn advance(&mut self, prev_alive: &HashSet<[i64; N]>, alive_cells: &HashSet<[i64; N]>) {
+        let mut ancestors = HashMap::with_capacity(alive_cells.len());
+        for cell in alive_cells {
+            let mut cell_ancestors = BTreeSet::new();
+            for neighbour in core::iter::once(*cell).chain(Life::<N>::neighbours(cell)) {
+                if prev_alive.contains(&neighbour) {
+                    if let Some(neighbour_ancestors) = self.ancestors.get(&neighbour) {
+                        cell_ancestors.extend(neighbour_ancestors);
+                    }
+                }
+            }
+            ancestors.insert(*cell, cell_ancestors);
+        }
+        self.ancestors = ancestors;
+    }
+
+    /// Move the seeds and the tracked cells to new coordinates.
+    pub(super) fn map_cells(&mut self, f: impl Fn(&[i64; N]) -> [i64; N]) {
+        self.seeds.iter_mut().for_each(|seed| *seed = f(seed));
+        self.ancestors = self.ancestors.drain().map(|(cell, ids)| (f(&cell), ids)).collect();
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Start tracking the causal ancestry of the alive cells.
+    ///
+    /// The currently alive cells become the seeds. From now on, every generation propagates
+    /// to each alive cell the set of seeds in its causal past. Cells made alive manually
+    /// after tracking started have no ancestors of their own.
+    /// Calling this while already tracking restarts tracking with the current cells as seeds.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // a blinker and a distant block
+    /// let blinker = [[0, 0], [1, 0], [2, 0]];
+    /// let block = [[20, 0], [21, 0], [20, 1], [21, 1]];
+    /// life.set_alive_cells(blinker.into_iter().chain(block).collect());
+    ///
+    /// life.track_ancestry();
+    /// life.next_generation();
+    ///
+    /// let blinker_seeds: HashSet<[i64; 2]> = blinker.into_iter().collect();
+    /// assert_eq!(life.ancestors(&[1, 1]), Some(blinker_seeds));
+    /// assert_eq!(life.ancestors(&[5, 5]), None);
+    /// ```
+    pub fn track_ancestry(&mut self) {
+        self.ancestry = Some(Arc::new(Ancestry::new(&self.alive_cells)));
+    }
+
+    /// Stop tracking the causal ancestry and discard the tracked data.
+    pub fn stop_tracking_ancestry(&mut self) {
+        self.ancestry = None;
+    }
+
+    /// Get whether the causal ancestry is being tracked.
+    pub fn is_tracking_ancestry(&self) -> bool {
+        self.ancestry.is_some()
+    }
+
+    /// Get the seed cells in the causal past of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// [Some] with the coordinates of the seeds that influenced the cell,
+    /// or [None] if the cell is dead or ancestry is not being tracked.
+    pub fn ancestors(&self, cell: &[i64; N]) -> Option<HashSet<[i64; N]>> {
+        if !self.alive_cells.contains(cell) {
+            return None;
+        }
+        let ancestry = self.ancestry.as_ref()?;
+        Some(match ancestry.ancestors.get(cell) {
+            Some(ids) => ids.iter().map(|&id| ancestry.seeds[id]).collect(),
+            None => HashSet::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_ancestry() {
+        let mut life = conways_game_of_life();
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        let blinker = [[-20, 0], [-20, 1], [-20, 2]];
+        life.set_alive_cells(glider.into_iter().chain(blinker).collect());
+        assert!(!life.is_tracking_ancestry());
+        assert_eq!(life.ancestors(&[0, 0]), None);
+
+        life.track_ancestry();
+        assert!(life.is_tracking_ancestry());
+        assert_eq!(life.ancestors(&[0, 0]), Some([[0, 0]].into_iter().collect()));
+
+        for _ in 0..8 {
+            life.next_generation();
+        }
+        let glider_seeds: HashSet<[i64; 2]> = glider.into_iter().collect();
+        let blinker_seeds: HashSet<[i64; 2]> = blinker.into_iter().collect();
+        assert_eq!(life.ancestors(&[2, -2]), Some(glider_seeds));
+        assert_eq!(life.ancestors(&[-20, 1]), Some(blinker_seeds));
+
+        life.set_cell(&[100, 100], true);
+        life.next_generation();
+        assert_eq!(life.ancestors(&[100, 100]), None);
+        life.set_cell(&[100, 100], true);
+        assert_eq!(life.ancestors(&[100, 100]), Some(HashSet::new()));
+
+        life.stop_tracking_ancestry();
+        assert!(!life.is_tracking_ancestry());
+        assert_eq!(life.ancestors(&[-20, 1]), None);
+    }
+}
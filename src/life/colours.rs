@@ -0,0 +1,185 @@
+//! Colours of alive cells inherited from their parents, for renderers to show where cells came from
+
+use super::Life;
+use crate::collections::{HashMap, HashSet};
+use crate::metadata::majority;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Way a born cell gets its colour from the coloured cells alive in its neighbourhood in the previous generation
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColourInheritance {
+    /// The most common colour of the parents, the first parent in the order of [Life::neighbours] winning ties.
+    Majority,
+    /// The average of the colours of the parents, channel by channel.
+    Blend,
+}
+
+/// Colours of the alive cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Colours<const N: usize> {
+    /// The way born cells get their colour.
+    inheritance: ColourInheritance,
+    /// The RGB colour of each coloured alive cell.
+    colours: HashMap<[i64; N], [u8; 3]>,
+}
+impl<const N: usize> Colours<N> {
+    /// Propagate the colours from the previous generation to the current one.
+    ///
+    /// Surviving cells keep their colour and born cells inherit the colours of their parents,
+    /// staying uncoloured if they have no coloured parents.
+    pub(super) fn advance(&mut self, prev_alive: &HashSet<[i64; N]>, alive_cells: &HashSet<[i64; N]>) {
+        let mut born = Vec::new();
+        for cell in alive_cells.difference(prev_alive) {
+            let parents: Vec<(&[i64; N], &[u8; 3])> = Life::<N>::neighbours(cell)
+                .filter(|neighbour| prev_alive.contains(neighbour))
+                .filter_map(|neighbour| self.colours.get_key_value(&neighbour))
+                .collect();
+            let colour = match self.inheritance {
+                ColourInheritance::Majority => majority(&parents).copied(),
+                ColourInheritance::Blend => blend(parents.iter().map(|(_, colour)| **colour)),
+            };
+            if let Some(colour) = colour {
+                born.push((*cell, colour));
+            }
+        }
+        self.colours.retain(|cell, _| alive_cells.contains(cell));
+        self.colours.extend(born);
+    }
+
+    /// Move the coloured cells to new coordinates.
+    pub(super) fn map_cells(&mut self, f: impl Fn(&[i64; N]) -> [i64; N]) {
+        self.colours = self.colours.drain().map(|(cell, colour)| (f(&cell), colour)).collect();
+    }
+}
+
+/// Get the average of colours, rounded to the nearest, or [None] if there are no colours.
+fn blend(colours: impl Iterator<Item = [u8; 3]>) -> Option<[u8; 3]> {
+    let (mut sum, mut count) = ([0u32; 3], 0);
+    for colour in colours {
+        (0..3).for_each(|channel| sum[channel] += u32::from(colour[channel]));
+        count += 1;
+    }
+    (count > 0).then(|| sum.map(|sum| ((sum + count / 2) / count) as u8))
+}
+
+impl<const N: usize> Life<N> {
+    /// Start colouring the alive cells, for renderers such as [FrameDumper](crate::frames::FrameDumper) to draw.
+    ///
+    /// The currently alive cells get their colour from a callback. From now on, every generation keeps the colours
+    /// of surviving cells and gives born cells the colour inherited from their parents, the coloured cells alive
+    /// in their neighbourhood in the previous generation. Cells made alive manually are uncoloured until
+    /// [set_colour](Self::set_colour) is called on them.
+    /// Calling this while already colouring restarts with the colours of the callback.
+    /// # Arguments
+    /// * `inheritance` - The way born cells get their colour.
+    /// * `colour` - The callback, receiving an alive cell and returning its RGB colour.
+    /// # Example
+    /// ```
+    /// use ndlife::life::{conways_game_of_life, ColourInheritance};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // a blinker, red on the left and blue on the right
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// life.track_colours(ColourInheritance::Blend, |cell| if cell[0] < 2 { [255, 0, 0] } else { [0, 0, 255] });
+    ///
+    /// life.next_generation();
+    /// assert_eq!(life.colour(&[1, 0]), Some([255, 0, 0]));
+    /// assert_eq!(life.colour(&[1, 1]), Some([170, 0, 85]));
+    /// assert_eq!(life.colour(&[0, 0]), None);
+    /// ```
+    pub fn track_colours(&mut self, inheritance: ColourInheritance, mut colour: impl FnMut(&[i64; N]) -> [u8; 3]) {
+        let colours = self.alive_cells.iter().map(|cell| (*cell, colour(cell))).collect();
+        self.colours = Some(Arc::new(Colours { inheritance, colours }));
+    }
+
+    /// Stop colouring the alive cells and discard their colours.
+    pub fn stop_tracking_colours(&mut self) {
+        self.colours = None;
+    }
+
+    /// Get whether the alive cells are being coloured.
+    pub fn is_tracking_colours(&self) -> bool {
+        self.colours.is_some()
+    }
+
+    /// Get the colour of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// [Some] with the RGB colour of the cell,
+    /// or [None] if the cell is dead, uncoloured or the cells are not being coloured.
+    pub fn colour(&self, cell: &[i64; N]) -> Option<[u8; 3]> {
+        if !self.alive_cells.contains(cell) {
+            return None;
+        }
+        self.colours.as_ref()?.colours.get(cell).copied()
+    }
+
+    /// Set the colour of an alive cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// * `colour` - The RGB colour.
+    /// # Returns
+    /// Whether the colour was set, which requires the cell to be alive and the cells to be coloured.
+    pub fn set_colour(&mut self, cell: &[i64; N], colour: [u8; 3]) -> bool {
+        match self.colours.as_mut() {
+            Some(colours) if self.alive_cells.contains(cell) => {
+                Arc::make_mut(colours).colours.insert(*cell, colour);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_colours() {
+        let mut life = conways_game_of_life();
+        // a glider and a distant blinker
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        let blinker = [[-20, 0], [-20, 1], [-20, 2]];
+        life.set_alive_cells(glider.into_iter().chain(blinker).collect());
+        assert!(!life.is_tracking_colours());
+        assert!(!life.set_colour(&[0, 0], [1, 1, 1]));
+        assert_eq!(life.colour(&[0, 0]), None);
+
+        life.track_colours(ColourInheritance::Majority, |cell| if cell[0] < 0 { [0, 0, 255] } else { [255, 0, 0] });
+        assert!(life.is_tracking_colours());
+        for _ in 0..8 {
+            life.next_generation();
+        }
+        assert!(life
+            .alive_cells()
+            .iter()
+            .all(|cell| life.colour(cell) == Some(if cell[0] < 0 { [0, 0, 255] } else { [255, 0, 0] })));
+
+        // manually added cells are uncoloured until coloured
+        life.set_cell(&[100, 100], true);
+        assert_eq!(life.colour(&[100, 100]), None);
+        assert!(life.set_colour(&[100, 100], [0, 255, 0]));
+        assert!(!life.set_colour(&[100, 101], [0, 255, 0]));
+        assert_eq!(life.colour(&[100, 100]), Some([0, 255, 0]));
+        life.next_generation();
+        assert_eq!(life.colour(&[100, 100]), None);
+
+        life.translate([1, 0]);
+        assert_eq!(life.colour(&[-19, 1]), Some([0, 0, 255]));
+        life.stop_tracking_colours();
+        assert!(!life.is_tracking_colours());
+        assert_eq!(life.colour(&[-19, 1]), None);
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(blend([[255, 0, 0], [0, 0, 255], [0, 0, 255]].into_iter()), Some([85, 0, 170]));
+        assert_eq!(blend([[1, 2, 3]].into_iter()), Some([1, 2, 3]));
+        assert_eq!(blend([[0, 0, 0], [1, 1, 255]].into_iter()), Some([1, 1, 128]));
+        assert_eq!(blend(std::iter::empty()), None);
+    }
+}
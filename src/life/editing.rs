@@ -0,0 +1,467 @@
+//! Bulk editing of the alive cells
+
+use super::Life;
+use crate::collections::HashSet;
+use crate::pattern::{PasteMode, Pattern};
+use crate::random::SplitMix64;
+use crate::region::Region;
+use alloc::sync::Arc;
+
+impl<const N: usize> Life<N> {
+    /// Paste a pattern into the game of life.
+    /// # Arguments
+    /// * `pattern` - The pattern to paste.
+    /// * `offset` - The offset to add to the coordinates of every cell of the pattern.
+    /// * `mode` - How the pattern is combined with the cells already present.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::pattern::{PasteMode, Pattern};
+    /// use std::collections::HashSet;
+    ///
+    /// let blinker: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    ///
+    /// life.paste(&blinker, [0, 0], PasteMode::Or);
+    /// life.paste(&blinker, [1, 0], PasteMode::Xor);
+    /// assert_eq!(life.alive_cells(), &[[0, 0], [3, 0]].into_iter().collect());
+    ///
+    /// // the empty cells of the pattern's bounding box are cleared as well
+    /// let center: Pattern<2> = [[0, 0], [2, 0], [1, 1]].into_iter().collect();
+    /// life.paste(&center, [0, -1], PasteMode::Copy);
+    /// assert_eq!(life.alive_cells(), &[[0, -1], [2, -1], [1, 0], [3, 0]].into_iter().collect());
+    /// ```
+    pub fn paste(&mut self, pattern: &Pattern<N>, offset: [i64; N], mode: PasteMode) {
+        let shift = |cell: &[i64; N]| -> [i64; N] { core::array::from_fn(|i| cell[i] + offset[i]) };
+        match mode {
+            PasteMode::Or => Arc::make_mut(&mut self.alive_cells).extend(pattern.cells().iter().map(shift)),
+            PasteMode::Xor => pattern.cells().iter().map(shift).for_each(|cell| self.toggle_cell(&cell)),
+            PasteMode::And | PasteMode::Copy => {
+                let Some((min, max)) = pattern.bounding_box() else {
+                    return;
+                };
+                let region = Region::new(shift(&min), shift(&max));
+                let outside = |cell: &[i64; N]| !region.contains(cell);
+                if mode == PasteMode::And {
+                    Arc::make_mut(&mut self.alive_cells).retain(|cell| outside(cell) || pattern.contains(&core::array::from_fn(|i| cell[i] - offset[i])));
+                } else {
+                    Arc::make_mut(&mut self.alive_cells).retain(outside);
+                    Arc::make_mut(&mut self.alive_cells).extend(pattern.cells().iter().map(shift));
+                }
+            }
+        }
+    }
+
+    /// Paste copies of a pattern at the points of a regular N-dimensional lattice.
+    ///
+    /// The copies are combined with the cells already present using [Or](PasteMode::Or).
+    /// # Arguments
+    /// * `pattern` - The pattern to paste.
+    /// * `counts` - The number of copies along each axis.
+    /// * `spacing` - The distance between neighbouring copies along each axis.
+    /// * `origin` - The offset of the first copy.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let block: Pattern<2> = [[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect();
+    /// let mut life = conways_game_of_life();
+    ///
+    /// life.stamp_grid(&block, [10, 5], [4, 4], [-20, 0]);
+    /// assert_eq!(life.alive_cells().len(), 200);
+    /// assert!(life.get_cell(&[-20, 0]));
+    /// assert!(life.get_cell(&[17, 17]));
+    /// ```
+    pub fn stamp_grid(&mut self, pattern: &Pattern<N>, counts: [usize; N], spacing: [i64; N], origin: [i64; N]) {
+        if counts.contains(&0) {
+            return;
+        }
+        for index in Region::new([0; N], core::array::from_fn(|i| counts[i] as i64 - 1)).iter_cells() {
+            self.paste(pattern, core::array::from_fn(|i| origin[i] + index[i] * spacing[i]), PasteMode::Or);
+        }
+    }
+
+    /// Tile a region with copies of a pattern, reflecting every other copy along each axis (kaleidoscope-style).
+    ///
+    /// The tile is the bounding box of the pattern and the first copy is placed unreflected at the minimum corner of the region.
+    /// Along each axis, every odd-numbered copy is mirrored, so neighbouring copies are reflections of each other
+    /// and the result is symmetric about every boundary between copies.
+    /// Copies are clipped to the region and combined with the cells already present using [Or](PasteMode::Or).
+    /// # Arguments
+    /// * `pattern` - The pattern to tile.
+    /// * `region` - The region to tile, such as the inclusive range of coordinates along each axis.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::pattern::Pattern;
+    /// use std::collections::HashSet;
+    ///
+    /// let tile: Pattern<2> = [[0, 0], [1, 0], [0, 1]].into_iter().collect();
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    ///
+    /// life.mirror_tile(&tile, [0..=3, 0..=1]);
+    /// let expected_alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [0, 1], [2, 0], [3, 0], [3, 1]].into_iter().collect();
+    /// assert_eq!(life.alive_cells(), &expected_alive_cells);
+    /// ```
+    pub fn mirror_tile(&mut self, pattern: &Pattern<N>, region: impl Into<Region<N>>) {
+        let region = region.into();
+        let Some((tile_min, tile_max)) = pattern.bounding_box() else {
+            return;
+        };
+        if region.is_empty() {
+            return;
+        }
+        let (min, max) = (region.min(), region.max());
+        let size: [i64; N] = core::array::from_fn(|i| tile_max[i] - tile_min[i] + 1);
+        let last_tile = core::array::from_fn(|i| (max[i] - min[i]).div_euclid(size[i]));
+        for tile in Region::new([0; N], last_tile).iter_cells() {
+            for cell in pattern.cells() {
+                let image: [i64; N] = core::array::from_fn(|i| {
+                    let local = cell[i] - tile_min[i];
+                    let local = if tile[i] % 2 == 1 { size[i] - 1 - local } else { local };
+                    min[i] + tile[i] * size[i] + local
+                });
+                if (0..N).all(|i| image[i] <= max[i]) {
+                    Arc::make_mut(&mut self.alive_cells).insert(image);
+                }
+            }
+        }
+    }
+
+    /// Make all cells in a hyperrectangular region alive.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+    ///
+    /// life.fill_region([0..=9, -4..=5, 1..=10]);
+    /// assert_eq!(life.alive_cells().len(), 1000);
+    /// ```
+    pub fn fill_region(&mut self, region: impl Into<Region<N>>) {
+        Arc::make_mut(&mut self.alive_cells).extend(region.into().iter_cells());
+    }
+
+    /// Make all cells in a hyperrectangular region dead.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [5, 5], [10, 10]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.clear_region([1..=10, 1..=9]);
+    /// assert_eq!(life.alive_cells(), &[[0, 0], [10, 10]].into_iter().collect());
+    /// ```
+    pub fn clear_region(&mut self, region: impl Into<Region<N>>) {
+        let region = region.into();
+        Arc::make_mut(&mut self.alive_cells).retain(|cell| !region.contains(cell));
+    }
+
+    /// Make all cells outside a hyperrectangular region dead.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [5, 5], [10, 10]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.crop([1..=10, 1..=9]);
+    /// assert_eq!(life.alive_cells(), &[[5, 5]].into_iter().collect());
+    /// ```
+    pub fn crop(&mut self, region: impl Into<Region<N>>) {
+        let region = region.into();
+        Arc::make_mut(&mut self.alive_cells).retain(|cell| region.contains(cell));
+    }
+
+    /// Remove debris far from the main object.
+    ///
+    /// Crops to the bounding box of the largest [connected component](Self::connected_components),
+    /// extended by `margin` cells on every side.
+    /// # Arguments
+    /// * `margin` - The number of cells to keep around the bounding box of the largest component.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let block = [[0, 0], [1, 0], [0, 1], [1, 1]];
+    /// let debris = [[4, 4], [50, -50]];
+    /// let alive_cells: HashSet<[i64; 2]> = block.into_iter().chain(debris).collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.trim(3);
+    /// assert_eq!(life.alive_cells(), &block.into_iter().chain([[4, 4]]).collect());
+    /// ```
+    pub fn trim(&mut self, margin: u64) {
+        let Some(largest) = self.connected_components().into_iter().max_by_key(|component| component.len()) else {
+            return;
+        };
+        self.crop(Region::bounding(&largest).expect("components are never empty").inflate(margin));
+    }
+
+    /// Fill a hyperrectangular region with a random soup.
+    ///
+    /// Every cell in the region is made alive with probability `density` and dead otherwise.
+    /// The soup depends only on the region, density and seed, so it is reproducible across runs and platforms.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// * `density` - The probability of a cell being alive, clamped to `0.0..=1.0`.
+    /// * `seed` - The seed of the pseudo-random number generator.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.randomize_region([0..=15, 0..=15], 0.5, 42);
+    /// assert!(life.alive_cells().iter().all(|cell| (0..=15).contains(&cell[0]) && (0..=15).contains(&cell[1])));
+    ///
+    /// let mut same_life = conways_game_of_life();
+    /// same_life.randomize_region([0..=15, 0..=15], 0.5, 42);
+    /// assert_eq!(life, same_life);
+    /// ```
+    pub fn randomize_region(&mut self, region: impl Into<Region<N>>, density: f64, seed: u64) {
+        let density = density.clamp(0.0, 1.0);
+        let mut rng = SplitMix64::new(seed);
+        for cell in region.into().iter_cells() {
+            self.set_cell(&cell, rng.next_f64() < density);
+        }
+    }
+
+    /// Get the union of the alive cells of two games of life.
+    /// # Arguments
+    /// * `other` - The other game of life.
+    /// # Returns
+    /// A new game of life with the rules of `self`, whose alive cells are alive in `self` or `other`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut a = conways_game_of_life();
+    /// a.set_alive_cells([[0, 0], [1, 0]].into_iter().collect());
+    /// let mut b = conways_game_of_life();
+    /// b.set_alive_cells([[1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// assert_eq!(a.union(&b).alive_cells(), &[[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// assert_eq!(a.intersection(&b).alive_cells(), &[[1, 0]].into_iter().collect());
+    /// assert_eq!(a.difference(&b).alive_cells(), &[[0, 0]].into_iter().collect());
+    /// assert_eq!(a.symmetric_difference(&b).alive_cells(), &[[0, 0], [2, 0]].into_iter().collect());
+    /// ```
+    pub fn union(&self, other: &Life<N>) -> Self {
+        self.with_alive_cells(self.alive_cells.union(&other.alive_cells).copied().collect())
+    }
+
+    /// Get the intersection of the alive cells of two games of life.
+    /// # Arguments
+    /// * `other` - The other game of life.
+    /// # Returns
+    /// A new game of life with the rules of `self`, whose alive cells are alive in both `self` and `other`.
+    pub fn intersection(&self, other: &Life<N>) -> Self {
+        self.with_alive_cells(self.alive_cells.intersection(&other.alive_cells).copied().collect())
+    }
+
+    /// Get the difference of the alive cells of two games of life.
+    /// # Arguments
+    /// * `other` - The other game of life.
+    /// # Returns
+    /// A new game of life with the rules of `self`, whose alive cells are alive in `self` but not in `other`.
+    pub fn difference(&self, other: &Life<N>) -> Self {
+        self.with_alive_cells(self.alive_cells.difference(&other.alive_cells).copied().collect())
+    }
+
+    /// Get the symmetric difference of the alive cells of two games of life.
+    /// # Arguments
+    /// * `other` - The other game of life.
+    /// # Returns
+    /// A new game of life with the rules of `self`, whose alive cells are alive in exactly one of `self` and `other`.
+    pub fn symmetric_difference(&self, other: &Life<N>) -> Self {
+        self.with_alive_cells(self.alive_cells.symmetric_difference(&other.alive_cells).copied().collect())
+    }
+
+    /// Create a new game of life with the rules of `self` and given alive cells.
+    fn with_alive_cells(&self, alive_cells: HashSet<[i64; N]>) -> Self {
+        Self::new_with_alive_cells(self.birth_rules.clone(), self.survival_rules.clone(), alive_cells).expect("the rules of an existing life are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_paste() {
+        let square: Pattern<2> = [[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect();
+        let diagonal: Pattern<2> = [[0, 0], [1, 1]].into_iter().collect();
+        let background: HashSet<[i64; 2]> = [[0, 0], [1, 0], [5, 5]].into_iter().collect();
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+
+        life.set_alive_cells(background.clone());
+        life.paste(&square, [0, 0], PasteMode::Or);
+        assert_eq!(life.alive_cells(), &[[0, 0], [1, 0], [0, 1], [1, 1], [5, 5]].into_iter().collect());
+
+        life.set_alive_cells(background.clone());
+        life.paste(&square, [0, 0], PasteMode::Xor);
+        assert_eq!(life.alive_cells(), &[[0, 1], [1, 1], [5, 5]].into_iter().collect());
+
+        life.set_alive_cells(background.clone());
+        life.paste(&diagonal, [0, 0], PasteMode::And);
+        assert_eq!(life.alive_cells(), &[[0, 0], [5, 5]].into_iter().collect());
+
+        life.set_alive_cells(background.clone());
+        life.paste(&diagonal, [5, 4], PasteMode::Copy);
+        assert_eq!(life.alive_cells(), &[[0, 0], [1, 0], [5, 4], [6, 5]].into_iter().collect());
+
+        life.set_alive_cells(background.clone());
+        for mode in [PasteMode::Or, PasteMode::Xor, PasteMode::And, PasteMode::Copy] {
+            life.paste(&Pattern::default(), [0, 0], mode);
+            assert_eq!(life.alive_cells(), &background);
+        }
+    }
+
+    #[test]
+    fn test_mirror_tile() {
+        let tile: Pattern<2> = [[10, 10], [11, 10], [12, 10], [10, 11]].into_iter().collect();
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.mirror_tile(&tile, [-3..=4, 0..=3]);
+        let bottom = [[-3, 0], [-2, 0], [-1, 0], [-3, 1], [0, 0], [1, 0], [2, 0], [2, 1], [3, 0], [4, 0], [3, 1]];
+        let expected_alive_cells: HashSet<[i64; 2]> = bottom.into_iter().flat_map(|[x, y]| [[x, y], [x, 3 - y]]).collect();
+        assert_eq!(life.alive_cells(), &expected_alive_cells);
+
+        // the copies are symmetric about the boundaries between them
+        let mut mirrored = life.clone();
+        mirrored.reflect_about(1, 0).unwrap();
+        mirrored.translate([0, 3]);
+        assert_eq!(mirrored.alive_cells(), life.alive_cells());
+
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.mirror_tile(&Pattern::default(), [0..=5, 0..=5]);
+        let (start, end) = (5, 4);
+        life.mirror_tile(&tile, [0..=5, start..=end]);
+        assert!(life.alive_cells().is_empty());
+    }
+
+    #[test]
+    fn test_fill_region() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.fill_region([-1..=1, 3..=4]);
+        assert_eq!(life.alive_cells(), &[[-1, 3], [0, 3], [1, 3], [-1, 4], [0, 4], [1, 4]].into_iter().collect());
+        let (start, end) = (5, 4);
+        life.fill_region([start..=end, 0..=10]);
+        assert_eq!(life.alive_cells().len(), 6);
+    }
+
+    #[test]
+    fn test_clear_region() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.fill_region([0..=4, 0..=4]);
+        life.clear_region([1..=3, 1..=3]);
+        assert_eq!(life.alive_cells().len(), 16);
+        assert!(!life.get_cell(&[2, 2]));
+        assert!(life.get_cell(&[0, 2]));
+        let (start, end) = (3, 1);
+        life.clear_region([start..=end, 0..=4]);
+        assert_eq!(life.alive_cells().len(), 16);
+    }
+
+    #[test]
+    fn test_randomize_region() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.set_cell(&[100, 100], true);
+        life.fill_region([0..=99, 0..=99]);
+        life.randomize_region([0..=99, 0..=99], 0.3, 7);
+        let population = life.alive_cells().len() - 1;
+        assert!((2500..3500).contains(&population));
+        assert!(life.get_cell(&[100, 100]));
+
+        let mut other = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        other.randomize_region([0..=99, 0..=99], 0.3, 7);
+        other.set_cell(&[100, 100], true);
+        assert_eq!(life.alive_cells(), other.alive_cells());
+        other.randomize_region([0..=99, 0..=99], 0.3, 8);
+        other.set_cell(&[100, 100], true);
+        assert_ne!(life.alive_cells(), other.alive_cells());
+
+        life.randomize_region([0..=99, 0..=99], 0.0, 7);
+        assert_eq!(life.alive_cells().len(), 1);
+        life.randomize_region([0..=99, 0..=99], 2.0, 7);
+        assert_eq!(life.alive_cells().len(), 10001);
+    }
+
+    #[test]
+    fn test_crop() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.fill_region([0..=4, 0..=4]);
+        life.crop([1..=3, 1..=3]);
+        assert_eq!(life.alive_cells().len(), 9);
+        assert!(life.get_cell(&[2, 2]));
+        assert!(!life.get_cell(&[0, 2]));
+        let (start, end) = (3, 1);
+        life.crop([start..=end, 0..=4]);
+        assert!(life.alive_cells().is_empty());
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.trim(0);
+        assert!(life.alive_cells().is_empty());
+
+        life.fill_region([0..=2, 0..=2]);
+        life.set_cell(&[3, 3], true);
+        life.set_cell(&[5, 0], true);
+        life.set_cell(&[-9, 0], true);
+        life.trim(1);
+        assert_eq!(life.alive_cells().len(), 10);
+        assert!(!life.get_cell(&[5, 0]));
+        assert!(life.get_cell(&[3, 3]));
+        life.trim(u64::MAX);
+        assert_eq!(life.alive_cells().len(), 10);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut a = conways_game_of_life();
+        a.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        a.next_generation();
+        let b = Life::<2>::new_with_alive_cells(HashSet::new(), [8].into_iter().collect(), [[1, 0], [5, 5]].into_iter().collect()).unwrap();
+
+        let union = a.union(&b);
+        assert_eq!(union.alive_cells(), &[[1, -1], [1, 0], [1, 1], [5, 5]].into_iter().collect());
+        assert_eq!(union.birth_rules(), a.birth_rules());
+        assert_eq!(union.survival_rules(), a.survival_rules());
+        assert_eq!(union.age(), 0);
+        assert_eq!(a.intersection(&b).alive_cells(), &[[1, 0]].into_iter().collect());
+        assert_eq!(a.difference(&b).alive_cells(), &[[1, -1], [1, 1]].into_iter().collect());
+        assert_eq!(b.difference(&a).alive_cells(), &[[5, 5]].into_iter().collect());
+        assert_eq!(b.difference(&a).survival_rules(), &[8].into_iter().collect());
+        assert_eq!(a.symmetric_difference(&b).alive_cells(), &[[1, -1], [1, 1], [5, 5]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_stamp_grid() {
+        let blinker: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+        let mut life = conways_game_of_life();
+        life.stamp_grid(&blinker, [0, 3], [5, 5], [0, 0]);
+        assert!(life.alive_cells().is_empty());
+        life.stamp_grid(&blinker, [3, 2], [5, -5], [1, 1]);
+        assert_eq!(life.alive_cells().len(), 18);
+        assert!(life.get_cell(&[11, 1]));
+        assert!(life.get_cell(&[13, -4]));
+        for _ in 0..2 {
+            life.next_generation();
+        }
+        assert_eq!(life.alive_cells().len(), 18);
+        assert!(life.get_cell(&[13, -4]));
+    }
+}
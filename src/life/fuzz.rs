@@ -0,0 +1,135 @@
+//! Construction of games of life from unstructured fuzzer input
+
+use super::Life;
+use crate::collections::HashSet;
+use crate::error::Error;
+
+/// The highest neighbour count the rules built from fuzzer input can contain.
+const MAX_FUZZ_RULE: usize = 63;
+/// The number of bytes holding the rule masks at the start of fuzzer input.
+const RULE_BYTES: usize = 16;
+
+impl<const N: usize> Life<N> {
+    /// Build a game of life from arbitrary bytes, such as the input of a fuzz target.
+    ///
+    /// Every input gives a valid game of life, so fuzzers can explore universes directly instead of
+    /// failing on invalid rules. The first 8 bytes are a bit mask of the birth rules and the next 8 bytes
+    /// a bit mask of the survival rules (bit `i` of the little-endian mask enables `i` neighbours; counts above
+    /// [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS) and births with 0 neighbours are dropped).
+    /// The remaining bytes are taken `N` at a time as the coordinates of alive cells, each a signed byte,
+    /// so coordinates stay within `-128..=127`. Missing bytes count as zero.
+    /// # Arguments
+    /// * `data` - The bytes to build the game of life from.
+    /// # Returns
+    /// A [Result] containing the game of life if successful, or an error.
+    /// # Errors
+    /// * [ZeroDimension](Error::ZeroDimension) - If `N` is 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    ///
+    /// // B3/S23 followed by the cells [0, 0] and [-1, 2]
+    /// let mut data = Vec::new();
+    /// data.extend((1u64 << 3).to_le_bytes());
+    /// data.extend((1u64 << 2 | 1 << 3).to_le_bytes());
+    /// data.extend([0, 0, 255, 2]);
+    ///
+    /// let life = Life::<2>::from_fuzz_input(&data).unwrap();
+    /// assert_eq!(life, Life::from_cells([3].into_iter().collect(), [2, 3].into_iter().collect(), [[0, 0], [-1, 2]]).unwrap());
+    /// ```
+    pub fn from_fuzz_input(data: &[u8]) -> Result<Self, Error> {
+        let mask = |offset: usize| {
+            let mut bytes = [0; 8];
+            for (byte, value) in bytes.iter_mut().zip(data.iter().skip(offset)) {
+                *byte = *value;
+            }
+            u64::from_le_bytes(bytes)
+        };
+        let rules = |mask: u64, min: usize| -> HashSet<usize> { (min..=Self::MAX_NEIGHBOURS.min(MAX_FUZZ_RULE)).filter(|rule| mask & (1 << rule) != 0).collect() };
+        let birth_rules = rules(mask(0), 1);
+        let survival_rules = rules(mask(8), 0);
+        let cells = data.get(RULE_BYTES..).unwrap_or_default().chunks(N.max(1)).map(|chunk| {
+            let mut cell = [0; N];
+            for (coordinate, byte) in cell.iter_mut().zip(chunk) {
+                *coordinate = *byte as i8 as i64;
+            }
+            cell
+        });
+        Self::from_cells(birth_rules, survival_rules, cells)
+    }
+}
+
+/// Games of life built by fuzzers with structure-aware input, decoded like [from_fuzz_input](Life::from_fuzz_input)
+///
+/// The rule masks are followed by a number of cells chosen by the [Unstructured](arbitrary::Unstructured) data,
+/// so the game of life can be a field of a larger arbitrary input. A 0-dimensional game of life cannot be built.
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for Life<N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut data = u.bytes(RULE_BYTES.min(u.len()))?.to_vec();
+        data.resize(RULE_BYTES, 0);
+        let cells = u.arbitrary_len::<[u8; N]>()?;
+        data.extend(u.bytes(cells * N)?);
+        Self::from_fuzz_input(&data).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::from_fuzz_input(u.take_rest()).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_from_fuzz_input() {
+        let empty = Life::<2>::from_fuzz_input(&[]).unwrap();
+        assert!(empty.birth_rules().is_empty());
+        assert!(empty.survival_rules().is_empty());
+        assert!(empty.alive_cells().is_empty());
+
+        // every bit set: rules are clipped to the dimension, and a partial chunk is padded with zeros
+        let data = [255; 19];
+        let mut life = Life::<2>::from_fuzz_input(&data).unwrap();
+        assert_eq!(life.birth_rules(), &(1..=8).collect());
+        assert_eq!(life.survival_rules(), &(0..=8).collect());
+        assert_eq!(life.alive_cells(), &[[-1, -1], [-1, 0]].into_iter().collect());
+        life.next_generation();
+
+        let life = Life::<1>::from_fuzz_input(&[0; 17]).unwrap();
+        assert_eq!(life.alive_cells(), &[[0]].into_iter().collect());
+        assert_eq!(Life::<0>::from_fuzz_input(&[]), Err(Error::ZeroDimension));
+
+        // arbitrary inputs never fail or panic when stepped
+        for seed in 0..64u8 {
+            let data: Vec<u8> = (0..40).map(|i: u8| i.wrapping_mul(seed).wrapping_add(seed)).collect();
+            let mut life = Life::<3>::from_fuzz_input(&data).unwrap();
+            life.next_generation();
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut data = Vec::new();
+        data.extend((1u64 << 3).to_le_bytes());
+        data.extend((1u64 << 2 | 1 << 3).to_le_bytes());
+        data.extend([0, 0, 255, 2]);
+        let expected = Life::<2>::from_fuzz_input(&data).unwrap();
+        assert_eq!(Life::<2>::arbitrary_take_rest(Unstructured::new(&data)), Ok(expected.clone()));
+
+        // as a field, the number of cells is taken from the data, so some cells are left out
+        let life = Life::<2>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert_eq!(life.birth_rules(), expected.birth_rules());
+        assert_eq!(life.survival_rules(), expected.survival_rules());
+        assert!(life.alive_cells().is_subset(expected.alive_cells()));
+        assert!(life.alive_cells().len() < 2);
+
+        assert_eq!(Life::<2>::arbitrary(&mut Unstructured::new(&[])).map(|life| life.alive_cells().len()), Ok(0));
+        assert!(Life::<0>::arbitrary_take_rest(Unstructured::new(&[])).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Bounded history of past generations for stepping back
+
+use super::Life;
+use crate::collections::HashSet;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Change between the states before two consecutive generations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Diff<const N: usize> {
+    /// The cells alive before the later generation but not before the earlier one.
+    added: Vec<[i64; N]>,
+    /// The cells alive before the earlier generation but not before the later one.
+    removed: Vec<[i64; N]>,
+}
+
+/// The most recent generations, stored as diffs.
+///
+/// The state before the last generation is kept in `prev_alive`, so each diff
+/// restores the state before the generation preceding it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct History<const N: usize> {
+    /// The maximum number of diffs kept.
+    capacity: usize,
+    /// The diffs, the most recent last.
+    diffs: VecDeque<Diff<N>>,
+}
+impl<const N: usize> History<N> {
+    /// Record the change from the state before the previous generation to the state before the next one.
+    pub(super) fn record(&mut self, prev_alive: &HashSet<[i64; N]>, alive_cells: &HashSet<[i64; N]>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.diffs.len() == self.capacity {
+            self.diffs.pop_front();
+        }
+        self.diffs.push_back(Diff {
+            added: alive_cells.difference(prev_alive).copied().collect(),
+            removed: prev_alive.difference(alive_cells).copied().collect(),
+        });
+    }
+
+    /// Discard all recorded diffs.
+    pub(super) fn clear(&mut self) {
+        self.diffs.clear();
+    }
+
+    /// Move the recorded cells to new coordinates.
+    pub(super) fn map_cells(&mut self, f: impl Fn(&[i64; N]) -> [i64; N]) {
+        for diff in self.diffs.iter_mut() {
+            diff.added.iter_mut().for_each(|cell| *cell = f(cell));
+            diff.removed.iter_mut().for_each(|cell| *cell = f(cell));
+        }
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Start retaining the last `capacity` generations, so they can be returned to with [step_back](Self::step_back).
+    ///
+    /// Generations are stored as the cells that changed, keeping memory proportional to the activity.
+    /// Calling this while already retaining generations discards them.
+    /// # Arguments
+    /// * `capacity` - The maximum number of generations to step back by.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+    /// life.set_alive_cells(glider.into_iter().collect());
+    ///
+    /// life.enable_history(10);
+    /// for _ in 0..8 {
+    ///     life.next_generation();
+    /// }
+    /// assert_eq!(life.history_len(), 8);
+    ///
+    /// assert_eq!(life.rewind(20), 8);
+    /// assert_eq!(life.age(), 0);
+    /// assert_eq!(life.alive_cells(), &glider.into_iter().collect());
+    /// ```
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(History {
+            capacity,
+            diffs: VecDeque::with_capacity(capacity),
+        });
+    }
+
+    /// Stop retaining past generations and discard the retained ones.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Get the number of generations that can currently be stepped back by.
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, |history| history.diffs.len())
+    }
+
+    /// Iterate over the retained past generations, the most recent first.
+    ///
+    /// The first item is the state before the last generation (as in [previous_alive_cells](Self::previous_alive_cells)),
+    /// and each further item is one generation older, so the items are the states [step_back](Self::step_back) returns to.
+    /// The states are rebuilt from the stored diffs while iterating, one set of cells per item.
+    /// # Returns
+    /// An iterator over the alive cells of the [history_len](Self::history_len) past generations.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    /// life.enable_history(3);
+    /// for _ in 0..5 {
+    ///     life.next_generation();
+    /// }
+    ///
+    /// // the population of the glider over the last 3 generations
+    /// let populations: Vec<usize> = life.past_alive_cells().map(|cells| cells.len()).collect();
+    /// assert_eq!(populations, vec![5, 5, 5]);
+    /// ```
+    pub fn past_alive_cells(&self) -> impl Iterator<Item = HashSet<[i64; N]>> + '_ {
+        let diffs = self.history.as_ref().map(|history| &history.diffs);
+        let mut undone = diffs.into_iter().flat_map(|diffs| diffs.iter().rev());
+        let mut state: Option<HashSet<[i64; N]>> = None;
+        (0..self.history_len()).map(move |_| {
+            let next = match state.take() {
+                None => HashSet::clone(&self.prev_alive),
+                Some(mut cells) => {
+                    let diff = undone.next().expect("one diff per retained generation");
+                    for cell in diff.added.iter() {
+                        cells.remove(cell);
+                    }
+                    cells.extend(diff.removed.iter().copied());
+                    cells
+                }
+            };
+            state = Some(next.clone());
+            next
+        })
+    }
+
+    /// Return to the state before the last generation.
+    ///
+    /// Edits made since the last generation are discarded, and the age is decreased by one.
+    /// The causal ancestry and colours cannot be stepped back, so tracking them stops.
+    /// # Returns
+    /// Whether a retained generation was available to step back to.
+    pub fn step_back(&mut self) -> bool {
+        let Some(diff) = self.history.as_mut().and_then(|history| history.diffs.pop_back()) else {
+            return false;
+        };
+        // the undone diff is the change made by the generation before the last
+        self.changed = !diff.added.is_empty() || !diff.removed.is_empty();
+        let mut prev_alive = HashSet::clone(&self.prev_alive);
+        for cell in diff.added.iter() {
+            prev_alive.remove(cell);
+        }
+        prev_alive.extend(diff.removed);
+        self.alive_cells = core::mem::replace(&mut self.prev_alive, Arc::new(prev_alive));
+        self.age = self.age.saturating_sub(1);
+        self.ancestry = None;
+        self.colours = None;
+        true
+    }
+
+    /// Step back by up to `n` generations.
+    /// # Arguments
+    /// * `n` - The maximum number of generations to step back by.
+    /// # Returns
+    /// The number of generations actually stepped back by.
+    pub fn rewind(&mut self, n: u64) -> u64 {
+        (0..n).take_while(|_| self.step_back()).count() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::life::conways_game_of_life;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_step_back() {
+        let mut life = conways_game_of_life();
+        assert!(!life.step_back());
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        life.enable_history(3);
+
+        let mut states = vec![life.alive_cells().clone()];
+        for generation in 1..=5 {
+            if generation == 3 {
+                life.set_cell(&[20, 20], true);
+                states[2] = life.alive_cells().clone();
+            }
+            life.next_generation();
+            states.push(life.alive_cells().clone());
+        }
+        assert_eq!(life.history_len(), 3);
+        let mut changed_cells: Vec<[i64; 2]> = life.changed_cells().copied().collect();
+
+        // edits made since the last generation are undone too
+        life.set_cell(&[-20, -20], true);
+        assert!(life.step_back());
+        assert_eq!(life.age(), 4);
+        assert_eq!(life.alive_cells(), &states[4]);
+        life.next_generation();
+        assert_eq!(life.alive_cells(), &states[5]);
+        let mut replayed: Vec<[i64; 2]> = life.changed_cells().copied().collect();
+        changed_cells.sort();
+        replayed.sort();
+        assert_eq!(replayed, changed_cells);
+
+        assert_eq!(life.rewind(10), 3);
+        assert_eq!(life.age(), 2);
+        assert_eq!(life.alive_cells(), &states[2]);
+        assert!(!life.step_back());
+
+        life.track_ancestry();
+        life.next_generation();
+        assert!(life.step_back());
+        assert!(!life.is_tracking_ancestry());
+
+        life.disable_history();
+        life.next_generation();
+        assert_eq!(life.history_len(), 0);
+        assert!(!life.step_back());
+    }
+
+    #[test]
+    fn test_past_alive_cells() {
+        let mut life = conways_game_of_life();
+        assert_eq!(life.past_alive_cells().count(), 0);
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        life.enable_history(4);
+        let mut states = vec![life.alive_cells().clone()];
+        for _ in 0..6 {
+            life.next_generation();
+            states.push(life.alive_cells().clone());
+        }
+        assert_eq!(life.previous_alive_cells(), &states[5]);
+        let past: Vec<_> = life.past_alive_cells().collect();
+        assert_eq!(past, vec![states[5].clone(), states[4].clone(), states[3].clone(), states[2].clone()]);
+
+        // the states match stepping back
+        for state in past {
+            assert!(life.step_back());
+            assert_eq!(life.alive_cells(), &state);
+        }
+    }
+
+    #[test]
+    fn test_history_transformed() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        life.enable_history(5);
+        life.next_generation();
+        life.next_generation();
+        life.translate([10, 10]);
+        assert_eq!(life.rewind(2), 2);
+        assert_eq!(life.alive_cells(), &[[10, 10], [11, 10], [12, 10]].into_iter().collect());
+    }
+}
@@ -0,0 +1,96 @@
+//! Morphological operations using the neighbourhood as the structuring element
+
+use super::Life;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+impl<const N: usize> Life<N> {
+    /// Dilate the alive cells, repeatedly making every neighbour of an alive cell alive.
+    /// # Arguments
+    /// * `k` - The number of times to dilate.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.dilate(2);
+    /// assert_eq!(life.alive_cells().len(), 25);
+    /// ```
+    pub fn dilate(&mut self, k: usize) {
+        for _ in 0..k {
+            let neighbours: Vec<[i64; N]> = self.alive_cells.iter().flat_map(Self::neighbours).collect();
+            Arc::make_mut(&mut self.alive_cells).extend(neighbours);
+        }
+    }
+
+    /// Erode the alive cells, repeatedly making every alive cell with a dead neighbour dead.
+    /// # Arguments
+    /// * `k` - The number of times to erode.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    /// life.fill_region([0..=4, 0..=4]);
+    ///
+    /// life.erode(2);
+    /// assert_eq!(life.alive_cells(), &[[2, 2]].into_iter().collect());
+    /// ```
+    pub fn erode(&mut self, k: usize) {
+        for _ in 0..k {
+            if self.alive_cells.is_empty() {
+                break;
+            }
+            let eroded: Vec<[i64; N]> = self
+                .alive_cells
+                .iter()
+                .filter(|cell| Self::neighbours(cell).any(|neighbour| !self.alive_cells.contains(&neighbour)))
+                .copied()
+                .collect();
+            for cell in eroded {
+                Arc::make_mut(&mut self.alive_cells).remove(&cell);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::HashSet;
+
+    #[test]
+    fn test_dilate() {
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.dilate(3);
+        assert!(life.alive_cells().is_empty());
+        life.set_cell(&[0, 0, 0], true);
+        life.dilate(0);
+        assert_eq!(life.alive_cells().len(), 1);
+        life.dilate(1);
+        assert_eq!(life.alive_cells().len(), 27);
+        life.dilate(1);
+        assert_eq!(life.alive_cells().len(), 125);
+    }
+
+    #[test]
+    fn test_erode() {
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.fill_region([0..=5, 0..=3]);
+        life.set_cell(&[10, 10], true);
+        life.erode(1);
+        assert_eq!(life.alive_cells(), &[[1, 1], [2, 1], [3, 1], [4, 1], [1, 2], [2, 2], [3, 2], [4, 2]].into_iter().collect());
+        life.erode(5);
+        assert!(life.alive_cells().is_empty());
+
+        // erosion undoes dilation of a convex set
+        life.fill_region([0..=2, 0..=2]);
+        life.dilate(3);
+        life.erode(3);
+        assert_eq!(life.alive_cells().len(), 9);
+    }
+}
@@ -0,0 +1,181 @@
+//! Callbacks notified of the changes made by each generation
+
+use super::Life;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Callback receiving the coordinates of a cell.
+type CellObserver<const N: usize> = Box<dyn FnMut(&[i64; N]) + Send + Sync>;
+/// Callback receiving the game of life after a generation.
+type GenerationObserver<const N: usize> = Box<dyn FnMut(&Life<N>) + Send + Sync>;
+
+/// Registered observers of a game of life.
+///
+/// Observers are closures, so they can be neither cloned nor compared.
+/// A cloned game of life starts without observers, and observers are ignored when comparing.
+#[derive(Default)]
+pub(super) struct Observers<const N: usize> {
+    /// Called with every cell that became alive.
+    birth: Vec<CellObserver<N>>,
+    /// Called with every cell that died.
+    death: Vec<CellObserver<N>>,
+    /// Called after every generation.
+    generation: Vec<GenerationObserver<N>>,
+}
+impl<const N: usize> Observers<N> {
+    /// Get whether no observers are registered.
+    fn is_empty(&self) -> bool {
+        self.birth.is_empty() && self.death.is_empty() && self.generation.is_empty()
+    }
+}
+impl<const N: usize> fmt::Debug for Observers<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("birth", &self.birth.len())
+            .field("death", &self.death.len())
+            .field("generation", &self.generation.len())
+            .finish()
+    }
+}
+impl<const N: usize> Clone for Observers<N> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+impl<const N: usize> PartialEq for Observers<N> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+impl<const N: usize> Eq for Observers<N> {}
+
+impl<const N: usize> Life<N> {
+    /// Register a callback invoked with every cell that becomes alive in [next_generation](Self::next_generation).
+    ///
+    /// Observers are not cloned with the game of life and do not affect comparisons.
+    /// # Arguments
+    /// * `observer` - The callback, receiving the coordinates of the born cell.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let born = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&born);
+    /// life.on_birth(move |cell| sink.lock().unwrap().push(*cell));
+    /// life.next_generation();
+    ///
+    /// let mut born = born.lock().unwrap().clone();
+    /// born.sort();
+    /// assert_eq!(born, vec![[1, -1], [1, 1]]);
+    /// ```
+    pub fn on_birth(&mut self, observer: impl FnMut(&[i64; N]) + Send + Sync + 'static) {
+        self.observers.birth.push(Box::new(observer));
+    }
+
+    /// Register a callback invoked with every cell that dies in [next_generation](Self::next_generation).
+    ///
+    /// Observers are not cloned with the game of life and do not affect comparisons.
+    /// # Arguments
+    /// * `observer` - The callback, receiving the coordinates of the dead cell.
+    pub fn on_death(&mut self, observer: impl FnMut(&[i64; N]) + Send + Sync + 'static) {
+        self.observers.death.push(Box::new(observer));
+    }
+
+    /// Register a callback invoked at the end of every [next_generation](Self::next_generation),
+    /// after the birth and death observers.
+    ///
+    /// Observers are not cloned with the game of life and do not affect comparisons.
+    /// # Arguments
+    /// * `observer` - The callback, receiving the game of life in its new generation.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&log);
+    /// life.on_generation(move |life| sink.lock().unwrap().push((life.age(), life.alive_cells().len())));
+    /// life.next_generation();
+    /// life.next_generation();
+    ///
+    /// assert_eq!(*log.lock().unwrap(), vec![(1, 5), (2, 5)]);
+    /// ```
+    pub fn on_generation(&mut self, observer: impl FnMut(&Life<N>) + Send + Sync + 'static) {
+        self.observers.generation.push(Box::new(observer));
+    }
+
+    /// Remove all registered observers.
+    pub fn clear_observers(&mut self) {
+        self.observers = Observers::default();
+    }
+
+    /// Notify the observers of the changes made by the last generation.
+    pub(super) fn notify_observers(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut observers = core::mem::take(&mut self.observers);
+        if !observers.birth.is_empty() {
+            for cell in self.born_cells() {
+                observers.birth.iter_mut().for_each(|observer| observer(cell));
+            }
+        }
+        if !observers.death.is_empty() {
+            for cell in self.died_cells() {
+                observers.death.iter_mut().for_each(|observer| observer(cell));
+            }
+        }
+        for observer in observers.generation.iter_mut() {
+            observer(self);
+        }
+        self.observers = observers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::HashSet;
+    use crate::life::conways_game_of_life;
+    use alloc::vec::Vec;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_observers() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let births = Arc::clone(&events);
+        life.on_birth(move |cell| births.lock().unwrap().push(("birth", *cell)));
+        let deaths = Arc::clone(&events);
+        life.on_death(move |cell| deaths.lock().unwrap().push(("death", *cell)));
+        let generations = Arc::clone(&events);
+        life.on_generation(move |life| generations.lock().unwrap().push(("generation", [life.age() as i64, 0])));
+
+        life.next_generation();
+        let recorded: HashSet<(&str, [i64; 2])> = events.lock().unwrap().iter().copied().collect();
+        let expected: HashSet<(&str, [i64; 2])> = [("birth", [1, -1]), ("birth", [1, 1]), ("death", [0, 0]), ("death", [2, 0]), ("generation", [1, 0])]
+            .into_iter()
+            .collect();
+        assert_eq!(recorded, expected);
+        assert_eq!(events.lock().unwrap().last(), Some(&("generation", [1, 0])));
+
+        // clones start without observers but compare equal
+        let mut clone = life.clone();
+        assert_eq!(clone, life);
+        clone.next_generation();
+        assert_eq!(events.lock().unwrap().len(), 5);
+
+        life.clear_observers();
+        life.next_generation();
+        assert_eq!(events.lock().unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,423 @@
+//! Driving the evolution over many generations
+
+use super::Life;
+use crate::collections::HashSet;
+use crate::lockstep::cells_hash;
+use alloc::collections::VecDeque;
+use core::ops::ControlFlow;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Statistics of a generation, yielded by [Generations]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Generation {
+    /// The age of the game of life in this generation.
+    pub age: u64,
+    /// The number of alive cells.
+    pub population: usize,
+    /// The number of cells that became alive in this generation.
+    pub births: usize,
+    /// The number of cells that died in this generation.
+    pub deaths: usize,
+}
+
+/// Endless iterator advancing a game of life by one generation per item, created by [generations](Life::generations)
+#[derive(Debug)]
+pub struct Generations<'a, const N: usize> {
+    /// The game of life being advanced.
+    life: &'a mut Life<N>,
+}
+impl<const N: usize> Iterator for Generations<'_, N> {
+    type Item = Generation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.life.step_generation())
+    }
+}
+
+/// Progress of a long advancement, reported by [step_n_with_progress](Life::step_n_with_progress)
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Progress {
+    /// The number of generations advanced so far.
+    pub generations: u64,
+    /// The age of the game of life.
+    pub age: u64,
+    /// The number of alive cells.
+    pub population: usize,
+    /// The time elapsed since the advancement started.
+    pub elapsed: Duration,
+}
+
+/// Limits on the search for a stable state in [run_until_stable](Life::run_until_stable)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StabilityConfig {
+    /// The maximum number of generations to advance.
+    pub max_generations: u64,
+    /// The maximum period of oscillation to detect.
+    pub max_period: u64,
+}
+impl Default for StabilityConfig {
+    fn default() -> Self {
+        Self {
+            max_generations: 1000,
+            max_period: 30,
+        }
+    }
+}
+
+/// Stable state reached by [run_until_stable](Life::run_until_stable)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Stability {
+    /// There are no alive cells since the given age.
+    Extinct {
+        /// The age at which the game of life was first found extinct.
+        age: u64,
+    },
+    /// The alive cells stopped changing.
+    StillLife {
+        /// The age at which the alive cells were first found unchanged.
+        age: u64,
+    },
+    /// The alive cells repeat with a period greater than 1.
+    Oscillator {
+        /// The age at which the repetition was first found.
+        age: u64,
+        /// The smallest number of generations after which the alive cells repeat.
+        period: u64,
+    },
+    /// No stable state was found within the maximum number of generations.
+    Unsettled,
+}
+
+impl<const N: usize> Life<N> {
+    /// Get an endless iterator that advances the game of life by one generation per item.
+    ///
+    /// Each item holds the statistics of the generation just reached, so the evolution composes with iterator adaptors.
+    /// # Returns
+    /// An iterator over the statistics of the following generations.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // the R-pentomino
+    /// life.set_alive_cells([[1, 0], [0, 1], [1, 1], [1, 2], [2, 2]].into_iter().collect());
+    ///
+    /// let peak = life.generations().take(100).map(|generation| generation.population).max();
+    /// assert_eq!(peak, Some(121));
+    /// assert_eq!(life.age(), 100);
+    /// ```
+    pub fn generations(&mut self) -> Generations<'_, N> {
+        Generations { life: self }
+    }
+
+    /// Advance the game of life by one generation and get the statistics of the generation reached.
+    pub(super) fn step_generation(&mut self) -> Generation {
+        self.next_generation();
+        let births = self.born_cells().count();
+        Generation {
+            age: self.age,
+            population: self.alive_cells.len(),
+            births,
+            deaths: self.prev_alive.len() + births - self.alive_cells.len(),
+        }
+    }
+
+    /// Advance the game of life by up to `n` generations, stopping early once it is extinct or stops changing.
+    /// # Arguments
+    /// * `n` - The maximum number of generations to advance.
+    /// # Returns
+    /// The number of generations actually advanced.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // a glider keeps changing
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    /// assert_eq!(life.step_n(100), 100);
+    ///
+    /// // a pair of cells dies out after one generation
+    /// life.set_alive_cells([[0, 0], [1, 0]].into_iter().collect());
+    /// assert_eq!(life.step_n(100), 1);
+    /// assert!(life.alive_cells().is_empty());
+    /// ```
+    pub fn step_n(&mut self, n: u64) -> u64 {
+        self.step_n_with(n, |_, _| {})
+    }
+
+    /// Advance the game of life like [step_n](Self::step_n), reporting progress periodically.
+    /// # Arguments
+    /// * `n` - The maximum number of generations to advance.
+    /// * `every` - The number of generations between reports (treated as 1 if 0).
+    /// * `progress` - The callback, invoked after every `every` generations advanced.
+    /// # Returns
+    /// The number of generations actually advanced.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// let mut reports = Vec::new();
+    /// assert_eq!(life.step_n_with_progress(100, 25, |progress| reports.push(progress.generations)), 100);
+    /// assert_eq!(reports, vec![25, 50, 75, 100]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn step_n_with_progress(&mut self, n: u64, every: u64, mut progress: impl FnMut(Progress)) -> u64 {
+        let every = every.max(1);
+        let start = Instant::now();
+        self.step_n_with(n, |life, generations| {
+            if generations % every == 0 {
+                progress(Progress {
+                    generations,
+                    age: life.age,
+                    population: life.alive_cells.len(),
+                    elapsed: start.elapsed(),
+                });
+            }
+        })
+    }
+
+    /// Advance the game of life like [step_n](Self::step_n), calling a callback after every generation advanced.
+    /// # Arguments
+    /// * `n` - The maximum number of generations to advance.
+    /// * `after` - The callback, receiving the game of life and the number of generations advanced so far.
+    /// # Returns
+    /// The number of generations actually advanced.
+    fn step_n_with(&mut self, n: u64, mut after: impl FnMut(&Self, u64)) -> u64 {
+        for generation in 0..n {
+            if self.is_extinct() {
+                return generation;
+            }
+            self.next_generation();
+            after(self, generation + 1);
+            if !self.changed_last_generation() {
+                return generation + 1;
+            }
+        }
+        n
+    }
+
+    /// Advance the game of life until a predicate holds, checking it before every generation and after the last one.
+    /// # Arguments
+    /// * `max_generations` - The maximum number of generations to advance.
+    /// * `predicate` - The condition to stop at, given the game of life in its current generation.
+    /// # Returns
+    /// [Break](ControlFlow::Break) with the number of generations advanced when the predicate first held,
+    /// or [Continue](ControlFlow::Continue) if it did not hold within `max_generations` generations.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// // the glider reaches the cell after 6 generations
+    /// assert_eq!(life.run_until(100, |life| life.get_cell(&[3, -2])), ControlFlow::Break(6));
+    /// assert_eq!(life.run_until(10, |life| life.alive_cells().len() > 5), ControlFlow::Continue(()));
+    /// assert_eq!(life.age(), 16);
+    /// ```
+    pub fn run_until(&mut self, max_generations: u64, mut predicate: impl FnMut(&Self) -> bool) -> ControlFlow<u64> {
+        for generation in 0..max_generations {
+            if predicate(self) {
+                return ControlFlow::Break(generation);
+            }
+            self.next_generation();
+        }
+        if predicate(self) {
+            ControlFlow::Break(max_generations)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    /// Advance the game of life until it dies out, becomes a still life or starts oscillating.
+    ///
+    /// The last [max_period](StabilityConfig::max_period) generations are remembered by their hashes,
+    /// and a generation repeating one of them ends the run. Moving patterns such as spaceships never repeat
+    /// in place, so they run until [max_generations](StabilityConfig::max_generations).
+    /// # Arguments
+    /// * `config` - The limits on the number of generations and on the detected period.
+    /// # Returns
+    /// The stable state reached, or [Unsettled](Stability::Unsettled) if none was found.
+    /// # Example
+    /// ```
+    /// use ndlife::life::{conways_game_of_life, Stability, StabilityConfig};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // the R-pentomino takes 1103 generations to stabilize
+    /// life.set_alive_cells([[1, 0], [0, 1], [1, 1], [1, 2], [2, 2]].into_iter().collect());
+    /// assert_eq!(life.run_until_stable(StabilityConfig::default()), Stability::Unsettled);
+    ///
+    /// // a blinker oscillates with period 2
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// let age = life.age();
+    /// assert_eq!(life.run_until_stable(StabilityConfig::default()), Stability::Oscillator { age: age + 2, period: 2 });
+    /// ```
+    pub fn run_until_stable(&mut self, config: StabilityConfig) -> Stability {
+        let mut history: VecDeque<(u64, HashSet<[i64; N]>)> = VecDeque::new();
+        for generation in 0..=config.max_generations {
+            if self.is_extinct() {
+                return Stability::Extinct { age: self.age };
+            }
+            let hash = cells_hash(self.alive_cells.iter());
+            let repeated = history
+                .iter()
+                .rev()
+                .position(|(previous_hash, cells)| *previous_hash == hash && *cells == *self.alive_cells);
+            match repeated {
+                Some(0) => return Stability::StillLife { age: self.age },
+                Some(index) => {
+                    return Stability::Oscillator {
+                        age: self.age,
+                        period: index as u64 + 1,
+                    }
+                }
+                None => {}
+            }
+            if config.max_period > 0 {
+                if history.len() as u64 >= config.max_period {
+                    history.pop_front();
+                }
+                history.push_back((hash, HashSet::clone(&self.alive_cells)));
+            }
+            if generation < config.max_generations {
+                self.next_generation();
+            }
+        }
+        Stability::Unsettled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_step_n() {
+        let mut life = conways_game_of_life();
+        assert_eq!(life.step_n(10), 0);
+        assert_eq!(life.age(), 0);
+
+        // a blinker never stops changing
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        assert_eq!(life.step_n(7), 7);
+        assert_eq!(life.age(), 7);
+        assert_eq!(life.step_n(0), 0);
+
+        // a pre-block becomes a block after one generation, which is then detected as unchanged
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+        assert_eq!(life.step_n(10), 2);
+        assert_eq!(life.alive_cells(), &[[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect());
+        assert_eq!(life.step_n(10), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_step_n_with_progress() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut reports = Vec::new();
+        assert_eq!(life.step_n_with_progress(10, 4, |progress| reports.push(progress)), 10);
+        let reported: Vec<(u64, u64, usize)> = reports.iter().map(|progress| (progress.generations, progress.age, progress.population)).collect();
+        assert_eq!(reported, vec![(4, 4, 3), (8, 8, 3)]);
+        assert!(reports[0].elapsed <= reports[1].elapsed);
+
+        // every generation, stopping early once the pre-block settles
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+        let mut count = 0;
+        assert_eq!(life.step_n_with_progress(10, 0, |_| count += 1), 2);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        assert_eq!(life.run_until(5, |_| true), ControlFlow::Break(0));
+        assert_eq!(life.age(), 0);
+        assert_eq!(life.run_until(5, |life| life.age() == 5), ControlFlow::Break(5));
+        assert_eq!(life.run_until(3, |life| life.get_cell(&[5, 5])), ControlFlow::Continue(()));
+        assert_eq!(life.age(), 8);
+
+        let mut calls = 0;
+        assert_eq!(
+            life.run_until(4, |_| {
+                calls += 1;
+                false
+            }),
+            ControlFlow::Continue(())
+        );
+        assert_eq!(calls, 5);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        let config = StabilityConfig::default();
+        let mut life = conways_game_of_life();
+        assert_eq!(life.run_until_stable(config), Stability::Extinct { age: 0 });
+
+        life.set_alive_cells([[0, 0], [1, 0]].into_iter().collect());
+        assert_eq!(life.run_until_stable(config), Stability::Extinct { age: 1 });
+
+        // a pre-block becomes a block in generation 2, which repeats in generation 3
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+        assert_eq!(life.run_until_stable(config), Stability::StillLife { age: 3 });
+
+        // the pentadecathlon has period 15
+        let pentadecathlon = [[0, 0], [0, 1], [-1, 2], [1, 2], [0, 3], [0, 4], [0, 5], [0, 6], [-1, 7], [1, 7], [0, 8], [0, 9]];
+        life.set_alive_cells(pentadecathlon.into_iter().collect());
+        let age = life.age();
+        assert_eq!(life.run_until_stable(config), Stability::Oscillator { age: age + 15, period: 15 });
+        life.set_alive_cells(pentadecathlon.into_iter().collect());
+        let age = life.age();
+        let short = StabilityConfig {
+            max_generations: 100,
+            max_period: 14,
+        };
+        assert_eq!(life.run_until_stable(short), Stability::Unsettled);
+        assert_eq!(life.age(), age + 100);
+
+        // a glider never repeats in place
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let age = life.age();
+        assert_eq!(
+            life.run_until_stable(StabilityConfig {
+                max_generations: 50,
+                max_period: 10
+            }),
+            Stability::Unsettled
+        );
+        assert_eq!(life.age(), age + 50);
+    }
+
+    #[test]
+    fn test_generations() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let generations: Vec<Generation> = life.generations().take(3).collect();
+        assert_eq!(
+            generations,
+            (1..=3)
+                .map(|age| Generation {
+                    age,
+                    population: 3,
+                    births: 2,
+                    deaths: 2
+                })
+                .collect::<Vec<_>>()
+        );
+
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1]].into_iter().collect());
+        let generation = life.generations().next().unwrap();
+        assert_eq!((generation.population, generation.births, generation.deaths), (4, 1, 0));
+        let generation = life.generations().next().unwrap();
+        assert_eq!((generation.population, generation.births, generation.deaths), (4, 0, 0));
+    }
+}
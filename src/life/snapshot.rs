@@ -0,0 +1,577 @@
+//! Compact checkpoints of the state of a game of life
+//!
+//! A saved snapshot starts with the magic bytes `NDLS` and a version byte, followed by the state in the layout of that version:
+//! * `1` - Little-endian integers: the number of dimensions (u32), the age (u64), the birth and the survival rules
+//!   (each a u32 count, then the rules as u32) and the alive cells (a u64 count, then the coordinates as i64).
+//! * `2` - The same fields as LEB128 variable-length integers, with the alive cells written as in a [delta stream](crate::delta),
+//!   each coordinate as its difference to the previous cell.
+//!
+//! Snapshots are saved in the latest version, and loading a snapshot of an older version migrates it to the latest one.
+//! Saving and loading needs the `std` feature.
+//! With the `compression` feature, snapshots can also be saved with the state [compressed](Snapshot::save_compressed),
+//! marked by the highest bit of the version byte.
+
+use super::{Life, Observers};
+use crate::collections::{HashMap, HashSet};
+#[cfg(feature = "compression")]
+use crate::compress::{compress, decompress};
+#[cfg(feature = "std")]
+use crate::delta::{read_cells, read_unsigned, write_cells, write_unsigned};
+use crate::error::Error;
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::format;
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of a saved snapshot.
+#[cfg(feature = "std")]
+const MAGIC: &[u8; 4] = b"NDLS";
+/// Flag of the version byte of a snapshot with the state compressed.
+#[cfg(feature = "std")]
+const COMPRESSED: u8 = 0x80;
+
+/// The age, birth rules, survival rules and alive cells read from a saved snapshot.
+#[cfg(feature = "std")]
+type Parts<const N: usize> = (u64, Vec<usize>, Vec<usize>, Vec<[i64; N]>);
+
+/// Owned checkpoint of the age, rules and alive cells of a game of life
+///
+/// The rules and cells are stored as sorted slices, so a snapshot is smaller than the game of life it was taken from
+/// and snapshots of equal states compare and hash equal, which makes them usable as keys of visited states in searches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot<const N: usize> {
+    /// The age of the life.
+    age: u64,
+    /// The sorted rules for a dead cell to become alive.
+    birth_rules: Box<[usize]>,
+    /// The sorted rules for alive cell to stay alive.
+    survival_rules: Box<[usize]>,
+    /// The sorted alive cells.
+    alive_cells: Box<[[i64; N]]>,
+}
+impl<const N: usize> Snapshot<N> {
+    /// Get the age of the game of life when the snapshot was taken.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the birth rules, sorted in ascending order.
+    pub fn birth_rules(&self) -> &[usize] {
+        &self.birth_rules
+    }
+
+    /// Get the survival rules, sorted in ascending order.
+    pub fn survival_rules(&self) -> &[usize] {
+        &self.survival_rules
+    }
+
+    /// Get the alive cells, sorted in lexicographic order.
+    pub fn alive_cells(&self) -> &[[i64; N]] {
+        &self.alive_cells
+    }
+
+    /// Version of the format that snapshots are saved in.
+    pub const VERSION: u8 = 2;
+
+    /// Save the snapshot in the latest version of the format.
+    /// # Arguments
+    /// * `output` - The output to write to, such as a buffered file.
+    /// # Returns
+    /// An [io::Result] which is an error if the output fails.
+    /// # Example
+    /// ```
+    /// use ndlife::life::{conways_game_of_life, Snapshot};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    /// life.next_generation();
+    ///
+    /// let mut bytes = Vec::new();
+    /// life.checkpoint().save(&mut bytes).unwrap();
+    /// assert_eq!(bytes[4], Snapshot::<2>::VERSION);
+    ///
+    /// let mut other = conways_game_of_life();
+    /// other.restore(&Snapshot::load(bytes.as_slice()).unwrap());
+    /// assert_eq!(other.age(), 1);
+    /// assert_eq!(other.alive_cells(), life.alive_cells());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn save(&self, mut output: impl Write) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(Self::VERSION);
+        bytes.extend(self.state_bytes());
+        output.write_all(&bytes)
+    }
+
+    /// Save the snapshot in the latest version of the format, with the state compressed.
+    ///
+    /// Sparse patterns and patterns of repeated objects compress best, such as a field of still lifes.
+    /// # Arguments
+    /// * `output` - The output to write to, such as a buffered file.
+    /// # Returns
+    /// An [io::Result] which is an error if the output fails.
+    /// # Example
+    /// ```
+    /// use ndlife::life::{conways_game_of_life, Snapshot};
+    ///
+    /// // a row of 1000 blocks
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells((0..1000).flat_map(|block| [[3 * block, 0], [3 * block + 1, 0], [3 * block, 1], [3 * block + 1, 1]]).collect());
+    /// let snapshot = life.checkpoint();
+    ///
+    /// let (mut bytes, mut compressed) = (Vec::new(), Vec::new());
+    /// snapshot.save(&mut bytes).unwrap();
+    /// snapshot.save_compressed(&mut compressed).unwrap();
+    /// assert!(compressed.len() * 50 < bytes.len());
+    /// assert_eq!(Snapshot::load(compressed.as_slice()).unwrap(), snapshot);
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn save_compressed(&self, mut output: impl Write) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(Self::VERSION | COMPRESSED);
+        bytes.extend(compress(&self.state_bytes()));
+        output.write_all(&bytes)
+    }
+
+    /// Load a snapshot saved in any version of the format, migrating an older version to the latest one.
+    ///
+    /// Compressed snapshots are decompressed, which needs the `compression` feature.
+    /// # Arguments
+    /// * `input` - The input to read from, such as a buffered file.
+    /// # Returns
+    /// A [Result] containing the snapshot, or an error.
+    /// # Errors
+    /// * [IoError](Error::IoError) - If the input fails, or is not a snapshot in `N` dimensions of a version up to [VERSION](Self::VERSION),
+    ///   or is compressed without the `compression` feature.
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    #[cfg(feature = "std")]
+    pub fn load(mut input: impl Read) -> Result<Self, Error> {
+        let mut magic = [0; 5];
+        input.read_exact(&mut magic)?;
+        if &magic[..4] != MAGIC {
+            return Err(invalid("not a snapshot").into());
+        }
+        let (age, birth_rules, survival_rules, alive_cells) = if magic[4] & COMPRESSED == 0 {
+            read_state(magic[4], &mut input)?
+        } else {
+            #[cfg(feature = "compression")]
+            {
+                read_state(magic[4] & !COMPRESSED, &mut decompress(&mut input)?.as_slice())?
+            }
+            #[cfg(not(feature = "compression"))]
+            return Err(invalid("compressed snapshot, which needs the `compression` feature").into());
+        };
+        // the rules are validated and the cells sorted as if the state was taken from a game of life
+        let life = Life::new_with_age(
+            birth_rules.into_iter().collect(),
+            survival_rules.into_iter().collect(),
+            alive_cells.into_iter().collect(),
+            age,
+        )?;
+        Ok(life.checkpoint())
+    }
+
+    /// Get the state as saved after the version byte, in the latest version of the format.
+    #[cfg(feature = "std")]
+    fn state_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_unsigned(&mut bytes, N as u64);
+        write_unsigned(&mut bytes, self.age);
+        for rules in [&self.birth_rules, &self.survival_rules] {
+            write_unsigned(&mut bytes, rules.len() as u64);
+            for rule in rules.iter() {
+                write_unsigned(&mut bytes, *rule as u64);
+            }
+        }
+        write_cells(&mut bytes, &self.alive_cells);
+        bytes
+    }
+}
+
+/// Get an error of malformed data.
+#[cfg(feature = "std")]
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Check the number of dimensions of a saved snapshot.
+#[cfg(feature = "std")]
+fn check_dimensions<const N: usize>(dimensions: u64) -> io::Result<()> {
+    if dimensions != N as u64 {
+        return Err(invalid(&format!("expected a {}-dimensional snapshot, found {} dimensions", N, dimensions)));
+    }
+    Ok(())
+}
+
+/// Read a fixed number of bytes.
+#[cfg(feature = "std")]
+fn read_bytes<const B: usize>(input: &mut impl Read) -> io::Result<[u8; B]> {
+    let mut bytes = [0; B];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Read the state of a snapshot of a version.
+#[cfg(feature = "std")]
+fn read_state<const N: usize>(version: u8, input: &mut impl Read) -> io::Result<Parts<N>> {
+    match version {
+        1 => read_version_1(input),
+        2 => read_version_2(input),
+        version => Err(invalid(&format!("unsupported snapshot version {}", version))),
+    }
+}
+
+/// Read the state of a snapshot of version 1, of fixed-width integers.
+#[cfg(feature = "std")]
+fn read_version_1<const N: usize>(input: &mut impl Read) -> io::Result<Parts<N>> {
+    check_dimensions::<N>(u32::from_le_bytes(read_bytes(input)?).into())?;
+    let age = u64::from_le_bytes(read_bytes(input)?);
+    let mut rules = [Vec::new(), Vec::new()];
+    for rules in rules.iter_mut() {
+        for _ in 0..u32::from_le_bytes(read_bytes(input)?) {
+            rules.push(u32::from_le_bytes(read_bytes(input)?) as usize);
+        }
+    }
+    let count = u64::from_le_bytes(read_bytes(input)?);
+    // the length is not trusted for the allocation, as the input may be corrupted
+    let mut alive_cells = Vec::with_capacity(count.min(1 << 16) as usize);
+    for _ in 0..count {
+        let mut cell = [0; N];
+        for coordinate in cell.iter_mut() {
+            *coordinate = i64::from_le_bytes(read_bytes(input)?);
+        }
+        alive_cells.push(cell);
+    }
+    let [birth_rules, survival_rules] = rules;
+    Ok((age, birth_rules, survival_rules, alive_cells))
+}
+
+/// Read the state of a snapshot of version 2, of variable-length integers.
+#[cfg(feature = "std")]
+fn read_version_2<const N: usize>(input: &mut impl Read) -> io::Result<Parts<N>> {
+    check_dimensions::<N>(read_unsigned(input)?)?;
+    let age = read_unsigned(input)?;
+    let mut rules = [Vec::new(), Vec::new()];
+    for rules in rules.iter_mut() {
+        for _ in 0..read_unsigned(input)? {
+            rules.push(usize::try_from(read_unsigned(input)?).map_err(|_| invalid("rule out of range"))?);
+        }
+    }
+    let alive_cells = read_cells(input)?;
+    let [birth_rules, survival_rules] = rules;
+    Ok((age, birth_rules, survival_rules, alive_cells))
+}
+
+impl<const N: usize> Life<N> {
+    /// Capture the age, rules and alive cells in a snapshot that can be restored later.
+    /// # Returns
+    /// The snapshot of the current state.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// let snapshot = life.checkpoint();
+    /// for _ in 0..10 {
+    ///     life.next_generation();
+    /// }
+    /// life.restore(&snapshot);
+    ///
+    /// assert_eq!(life.age(), 0);
+    /// assert_eq!(life.checkpoint(), snapshot);
+    /// ```
+    pub fn checkpoint(&self) -> Snapshot<N> {
+        let sorted = |rules: &HashSet<usize>| {
+            let mut rules: Box<[usize]> = rules.iter().copied().collect();
+            rules.sort_unstable();
+            rules
+        };
+        Snapshot {
+            age: self.age,
+            birth_rules: sorted(&self.birth_rules),
+            survival_rules: sorted(&self.survival_rules),
+            alive_cells: self.alive_cells_sorted().into_boxed_slice(),
+        }
+    }
+
+    /// Return to the age, rules and alive cells captured in a snapshot.
+    ///
+    /// The previous generation is forgotten, so no cells count as changed afterwards.
+    /// The retained history is discarded and tracking of the causal ancestry and colours stops, since neither applies to the restored state.
+    /// Observers stay registered.
+    /// # Arguments
+    /// * `snapshot` - The snapshot to restore, taken from any game of life of the same dimension.
+    pub fn restore(&mut self, snapshot: &Snapshot<N>) {
+        self.age = snapshot.age;
+        self.birth_rules = snapshot.birth_rules.iter().copied().collect();
+        self.survival_rules = snapshot.survival_rules.iter().copied().collect();
+        self.alive_cells = Arc::new(snapshot.alive_cells.iter().copied().collect());
+        self.prev_alive = self.alive_cells.clone();
+        self.changed = false;
+        self.ancestry = None;
+        self.colours = None;
+        if let Some(history) = self.history.as_mut() {
+            history.clear();
+        }
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Create an independent copy of the game of life to evolve separately, for example under other rules.
+    ///
+    /// Unlike [clone](Clone::clone), the fork holds only the state needed to continue the evolution:
+    /// the age, rules, alive cells, previous generation, causal ancestry and colours.
+    /// The scratch buffers of [next_generation](Self::next_generation) and the retained history are left behind,
+    /// and observers are not carried over.
+    /// Forking takes constant time: the fork shares the cell sets, ancestry and colours with the game of life,
+    /// and whichever of the two modifies them first copies them, so forks that are never edited or
+    /// evolved do not take memory for their cells.
+    /// # Returns
+    /// The forked game of life.
+    pub fn fork(&self) -> Self {
+        Self {
+            age: self.age,
+            birth_rules: self.birth_rules.clone(),
+            survival_rules: self.survival_rules.clone(),
+            alive_cells: Arc::clone(&self.alive_cells),
+            prev_alive: Arc::clone(&self.prev_alive),
+            changed: self.changed,
+            dead_neighbours: HashMap::new(),
+            ancestry: self.ancestry.clone(),
+            colours: self.colours.clone(),
+            history: None,
+            observers: Observers::default(),
+        }
+    }
+
+    /// Create an independent copy of the game of life to evolve under other rules, like [fork](Self::fork).
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// # Returns
+    /// A [Result] containing the forked game of life if successful, or an error.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Self::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// // B36/S23 (HighLife) and B3/S (no survival)
+    /// let mut highlife = life.fork_with_rules([3, 6].into_iter().collect(), [2, 3].into_iter().collect()).unwrap();
+    /// let mut no_survival = life.fork_with_rules([3].into_iter().collect(), [].into_iter().collect()).unwrap();
+    /// life.next_generation();
+    /// highlife.next_generation();
+    /// no_survival.next_generation();
+    ///
+    /// assert_eq!(highlife.alive_cells(), life.alive_cells());
+    /// assert_eq!(no_survival.alive_cells(), &[[1, -1], [1, 1]].into_iter().collect());
+    /// ```
+    pub fn fork_with_rules(&self, birth_rules: HashSet<usize>, survival_rules: HashSet<usize>) -> Result<Self, Error> {
+        let mut fork = self.fork();
+        fork.set_birth_rules(birth_rules)?;
+        fork.set_survival_rules(survival_rules)?;
+        Ok(fork)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_checkpoint() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[2, 0], [0, 0], [1, 0]].into_iter().collect());
+        life.next_generation();
+        let snapshot = life.checkpoint();
+        assert_eq!(snapshot.age(), 1);
+        assert_eq!(snapshot.birth_rules(), &[3]);
+        assert_eq!(snapshot.survival_rules(), &[2, 3]);
+        assert_eq!(snapshot.alive_cells(), &[[1, -1], [1, 0], [1, 1]]);
+        life.next_generation();
+        life.next_generation();
+        // the same cells in a different generation
+        assert_eq!(life.checkpoint().alive_cells(), snapshot.alive_cells());
+        assert_ne!(life.checkpoint(), snapshot);
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let snapshot = life.checkpoint();
+
+        let mut other = Life::<2>::new([1].into_iter().collect(), HashSet::new()).unwrap();
+        other.enable_history(5);
+        other.track_ancestry();
+        other.next_generation();
+        other.restore(&snapshot);
+        assert_eq!(other.age(), 0);
+        assert_eq!(other.birth_rules(), life.birth_rules());
+        assert_eq!(other.survival_rules(), life.survival_rules());
+        assert_eq!(other.alive_cells(), life.alive_cells());
+        assert_eq!(other.changed_cells().count(), 0);
+        assert_eq!(other.history_len(), 0);
+        assert!(!other.is_tracking_ancestry());
+
+        for _ in 0..4 {
+            life.next_generation();
+            other.next_generation();
+        }
+        assert_eq!(other.alive_cells(), life.alive_cells());
+    }
+
+    /// Save a snapshot in version 1 of the format.
+    #[cfg(feature = "std")]
+    fn save_version_1<const N: usize>(snapshot: &Snapshot<N>) -> Vec<u8> {
+        let mut bytes = b"NDLS\x01".to_vec();
+        bytes.extend((N as u32).to_le_bytes());
+        bytes.extend(snapshot.age().to_le_bytes());
+        for rules in [snapshot.birth_rules(), snapshot.survival_rules()] {
+            bytes.extend((rules.len() as u32).to_le_bytes());
+            for rule in rules {
+                bytes.extend((*rule as u32).to_le_bytes());
+            }
+        }
+        bytes.extend((snapshot.alive_cells().len() as u64).to_le_bytes());
+        for coordinate in snapshot.alive_cells().iter().flatten() {
+            bytes.extend(coordinate.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_save() {
+        let mut life = Life::<3>::new([5].into_iter().collect(), [4, 5].into_iter().collect()).unwrap();
+        life.randomize_region([-5..=5, -5..=5, -5..=5], 0.3, 7);
+        life.set_age(u64::MAX - 1);
+        let snapshot = life.checkpoint();
+        let mut bytes = Vec::new();
+        snapshot.save(&mut bytes).unwrap();
+        assert_eq!(Snapshot::load(bytes.as_slice()).unwrap(), snapshot);
+
+        // an older version is migrated, and saved again in the latest one
+        let old = save_version_1(&snapshot);
+        assert!(old.len() > bytes.len());
+        let migrated = Snapshot::<3>::load(old.as_slice()).unwrap();
+        assert_eq!(migrated, snapshot);
+        let mut resaved = Vec::new();
+        migrated.save(&mut resaved).unwrap();
+        assert_eq!(resaved, bytes);
+
+        let empty = Life::<1>::new(HashSet::new(), HashSet::new()).unwrap().checkpoint();
+        for bytes in [save_version_1(&empty), {
+            let mut bytes = Vec::new();
+            empty.save(&mut bytes).unwrap();
+            bytes
+        }] {
+            assert_eq!(Snapshot::load(bytes.as_slice()).unwrap(), empty);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_save_compressed() {
+        let mut life = Life::<3>::new([5].into_iter().collect(), [4, 5].into_iter().collect()).unwrap();
+        life.randomize_region([-5..=5, -5..=5, -5..=5], 0.3, 7);
+        let snapshot = life.checkpoint();
+        let mut bytes = Vec::new();
+        #[cfg(feature = "compression")]
+        {
+            snapshot.save_compressed(&mut bytes).unwrap();
+            assert_eq!(bytes[4], Snapshot::<3>::VERSION | COMPRESSED);
+            assert_eq!(Snapshot::load(bytes.as_slice()).unwrap(), snapshot);
+            // a compressed older version, and a compressed stream ending early
+            let old = save_version_1(&snapshot);
+            let mut compressed = b"NDLS\x81".to_vec();
+            compressed.extend(compress(&old[5..]));
+            assert_eq!(Snapshot::load(compressed.as_slice()).unwrap(), snapshot);
+            assert!(matches!(Snapshot::<3>::load(&bytes[..bytes.len() - 1]), Err(Error::IoError(_))));
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            snapshot.save(&mut bytes).unwrap();
+            bytes[4] |= COMPRESSED;
+            assert!(matches!(Snapshot::<3>::load(bytes.as_slice()), Err(Error::IoError(error)) if error.kind() == io::ErrorKind::InvalidData));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_load_invalid() {
+        let kind = |bytes: &[u8]| match Snapshot::<2>::load(bytes) {
+            Err(Error::IoError(error)) => Some(error.kind()),
+            _ => None,
+        };
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut bytes = Vec::new();
+        life.checkpoint().save(&mut bytes).unwrap();
+
+        assert_eq!(kind(b"NDLD\x02"), Some(io::ErrorKind::InvalidData));
+        assert_eq!(kind(b"NDLS\x03\x02"), Some(io::ErrorKind::InvalidData));
+        assert_eq!(kind(&bytes[..bytes.len() - 1]), Some(io::ErrorKind::UnexpectedEof));
+        assert!(matches!(Snapshot::<3>::load(bytes.as_slice()), Err(Error::IoError(_))));
+
+        // the rules are checked, and the cells sorted and deduplicated
+        let mut old = save_version_1(&life.checkpoint());
+        old[21] = 0;
+        assert_eq!(Snapshot::<2>::load(old.as_slice()), Err(Error::ZeroNeighbourBirthRule));
+        old[21] = 9;
+        assert_eq!(Snapshot::<2>::load(old.as_slice()), Err(Error::TooHighRule(9, 8)));
+        let mut old = save_version_1(&life.checkpoint());
+        let cells = old.len() - 48;
+        old[cells - 8] = 4;
+        old.extend_from_within(cells..cells + 16);
+        let loaded = Snapshot::<2>::load(old.as_slice()).unwrap();
+        assert_eq!(loaded.alive_cells(), &[[0, 0], [1, 0], [2, 0]]);
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        life.enable_history(5);
+        life.track_ancestry();
+        life.next_generation();
+
+        let mut fork = life.fork();
+        assert!(Arc::ptr_eq(&fork.alive_cells, &life.alive_cells) && Arc::ptr_eq(&fork.prev_alive, &life.prev_alive));
+        assert!(Arc::ptr_eq(fork.ancestry.as_ref().unwrap(), life.ancestry.as_ref().unwrap()));
+        assert_eq!(fork.age(), life.age());
+        assert_eq!(fork.alive_cells(), life.alive_cells());
+        assert_eq!(fork.changed_cells().count(), life.changed_cells().count());
+        assert_eq!(fork.history_len(), 0);
+        assert_eq!(fork.ancestors(&[1, 0]), life.ancestors(&[1, 0]));
+
+        fork.next_generation();
+        assert_ne!(fork.alive_cells(), life.alive_cells());
+        // the generation of the game of life is the previous generation of the fork, without a copy
+        assert!(Arc::ptr_eq(&fork.prev_alive, &life.alive_cells));
+        assert_eq!(life.changed_cells().count(), 4);
+
+        // editing a fork copies the cells it modifies without changing the game of life
+        let mut edited = life.fork();
+        edited.set_cell(&[100, 100], true);
+        assert!(!Arc::ptr_eq(&edited.alive_cells, &life.alive_cells));
+        assert!(!life.get_cell(&[100, 100]));
+        life.next_generation();
+        assert_eq!(fork.alive_cells(), life.alive_cells());
+
+        assert_eq!(life.fork_with_rules([0].into_iter().collect(), HashSet::new()), Err(Error::ZeroNeighbourBirthRule));
+        assert_eq!(life.fork_with_rules(HashSet::new(), [9].into_iter().collect()), Err(Error::TooHighRule(9, 8)));
+    }
+}
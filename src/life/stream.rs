@@ -0,0 +1,230 @@
+//! Asynchronous driving of the evolution, enabled by the `async` feature
+
+use super::{Generation, Life};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Endless asynchronous stream advancing an owned game of life by one generation per item, created by [into_stream](Life::into_stream)
+///
+/// The stream implements [Stream](futures_core::Stream), so the combinators of the `futures` crate and
+/// the executors built on it consume it directly, and [next](GenerationStream::next) awaits an item without them.
+/// Every poll advances one generation and then asks to be polled again, so a task consuming the stream
+/// in a loop gives other tasks a chance to run after every generation.
+#[derive(Debug)]
+pub struct GenerationStream<const N: usize> {
+    /// The game of life being advanced.
+    life: Life<N>,
+    /// Whether the stream has already yielded to the executor before the next generation.
+    yielded: bool,
+}
+impl<const N: usize> futures_core::Stream for GenerationStream<N> {
+    type Item = Generation;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Generation>> {
+        GenerationStream::poll_next(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+impl<const N: usize> GenerationStream<N> {
+    /// Attempt to advance the game of life by one generation.
+    /// # Arguments
+    /// * `cx` - The context of the task polling the stream.
+    /// # Returns
+    /// [Ready](Poll::Ready) with the statistics of the generation reached, or [Pending](Poll::Pending)
+    /// after waking the task, to let other tasks run between generations. The stream never ends.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Generation>> {
+        let this = self.get_mut();
+        if !core::mem::replace(&mut this.yielded, true) {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.yielded = false;
+        Poll::Ready(Some(this.life.step_generation()))
+    }
+
+    /// Wait for the next generation.
+    /// # Returns
+    /// The statistics of the generation reached, which is always [Some] as the stream never ends.
+    pub async fn next(&mut self) -> Option<Generation> {
+        core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Get the game of life being advanced.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Stop the stream and get back the game of life.
+    pub fn into_inner(self) -> Life<N> {
+        self.life
+    }
+}
+
+/// Future completing on its second poll, letting the executor run other tasks in between
+struct YieldNow {
+    /// Whether the future has already been polled once.
+    yielded: bool,
+}
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if core::mem::replace(&mut self.yielded, true) {
+            return Poll::Ready(());
+        }
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Turn the game of life into an endless asynchronous stream of generations.
+    ///
+    /// Requires the `async` feature.
+    /// # Returns
+    /// A stream advancing the game of life by one generation per item.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::task::{Context, Poll, Waker};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// let mut stream = life.into_stream();
+    /// // a minimal executor: poll until ready
+    /// let mut next = pin!(stream.next());
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let generation = loop {
+    ///     if let Poll::Ready(generation) = next.as_mut().poll(&mut cx) {
+    ///         break generation.unwrap();
+    ///     }
+    /// };
+    /// assert_eq!(generation.age, 1);
+    /// assert_eq!(generation.population, 5);
+    /// ```
+    pub fn into_stream(self) -> GenerationStream<N> {
+        GenerationStream { life: self, yielded: false }
+    }
+
+    /// Asynchronously advance the game of life by a number of generations, yielding to the executor periodically.
+    ///
+    /// Requires the `async` feature. Unlike advancing in a loop inside an async task,
+    /// this lets other tasks on the same executor run while a long simulation progresses.
+    /// # Arguments
+    /// * `generations` - The number of generations to advance.
+    /// * `yield_every` - The number of generations advanced between yields (treated as 1 if 0).
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use std::future::Future;
+    /// use std::pin::pin;
+    /// use std::task::{Context, Waker};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+    ///
+    /// // a minimal executor: poll until ready, counting the polls
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut polls = 1;
+    /// {
+    ///     let mut advance = pin!(life.advance_async(12, 5));
+    ///     while advance.as_mut().poll(&mut cx).is_pending() {
+    ///         polls += 1;
+    ///     }
+    /// }
+    /// // yielded after generations 5 and 10
+    /// assert_eq!(polls, 3);
+    /// assert_eq!(life.age(), 12);
+    /// ```
+    pub async fn advance_async(&mut self, generations: u64, yield_every: u64) {
+        let yield_every = yield_every.max(1);
+        for generation in 1..=generations {
+            self.next_generation();
+            if generation % yield_every == 0 && generation < generations {
+                YieldNow { yielded: false }.await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use std::task::Waker;
+
+    /// Poll a future to completion on the current thread, counting the polls.
+    fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return (output, polls);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let mut expected = life.clone();
+        let expected_generations: Vec<Generation> = expected.generations().take(8).collect();
+
+        let mut stream = life.into_stream();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+        assert_eq!(stream.life().age(), 0);
+        let mut generations = Vec::new();
+        for _ in 0..8 {
+            let (generation, polls) = block_on(stream.next());
+            generations.push(generation.unwrap());
+            assert!(polls <= 2);
+        }
+        assert_eq!(generations, expected_generations);
+        assert_eq!(stream.into_inner().alive_cells(), expected.alive_cells());
+    }
+
+    #[test]
+    fn test_futures_stream() {
+        /// Get the next item of any stream.
+        fn next<S: futures_core::Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+            block_on(std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx))).0
+        }
+
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut stream = life.into_stream();
+        assert_eq!(futures_core::Stream::size_hint(&stream), (usize::MAX, None));
+        assert_eq!(next(&mut stream).map(|generation| (generation.age, generation.population)), Some((1, 3)));
+        assert_eq!(next(&mut stream).map(|generation| generation.age), Some(2));
+    }
+
+    #[test]
+    fn test_advance_async() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let mut expected = life.clone();
+        for _ in 0..10 {
+            expected.next_generation();
+        }
+
+        let ((), polls) = block_on(life.advance_async(10, 5));
+        assert_eq!(polls, 2);
+        assert_eq!(life.alive_cells(), expected.alive_cells());
+        let ((), polls) = block_on(life.advance_async(3, 0));
+        assert_eq!(polls, 3);
+        assert_eq!(life.age(), 13);
+        let ((), polls) = block_on(life.advance_async(0, 1));
+        assert_eq!(polls, 1);
+    }
+}
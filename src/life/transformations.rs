@@ -0,0 +1,553 @@
+//! Exact geometric transformations of the whole universe
+
+use super::Life;
+use crate::error::Error;
+use crate::pattern::Pattern;
+use crate::symmetry::Symmetry;
+use crate::transform::Transform;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+/// Point of the alive cells moved to the origin when recentering
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Centre {
+    /// The centre of the bounding box, rounded down.
+    #[default]
+    BoundingBox,
+    /// The centre of mass, rounded to the nearest cell.
+    Mass,
+    /// The minimum corner of the bounding box.
+    MinCorner,
+}
+
+impl<const N: usize> Life<N> {
+    /// Shift all alive cells by an offset.
+    /// # Arguments
+    /// * `offset` - The offset to add to the coordinates of every cell.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 2]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.translate([3, -1]);
+    ///
+    /// let expected_alive_cells: HashSet<[i64; 2]> = [[3, -1], [4, 1]].into_iter().collect();
+    /// assert_eq!(life.alive_cells(), &expected_alive_cells);
+    /// ```
+    pub fn translate(&mut self, offset: [i64; N]) {
+        self.transform(&Transform::translation(offset));
+    }
+
+    /// Get a copy of the game of life with all alive cells shifted by an offset.
+    /// # Arguments
+    /// * `offset` - The offset to add to the coordinates of every cell.
+    /// # Returns
+    /// The translated game of life.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 2]].into_iter().collect();
+    /// let life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// let translated = life.translated([3, -1]);
+    ///
+    /// let expected_alive_cells: HashSet<[i64; 2]> = [[3, -1], [4, 1]].into_iter().collect();
+    /// assert_eq!(translated.alive_cells(), &expected_alive_cells);
+    /// assert_eq!(life.alive_cells().len(), 2);
+    /// ```
+    pub fn translated(&self, offset: [i64; N]) -> Self {
+        let mut life = self.clone();
+        life.translate(offset);
+        life
+    }
+
+    /// Translate the alive cells so that their centre is at the origin.
+    /// # Arguments
+    /// * `centre` - Which point of the alive cells to move to the origin.
+    /// # Returns
+    /// The offset the cells were translated by (all zeros if there are no alive cells).
+    /// # Example
+    /// ```
+    /// use ndlife::life::{Centre, Life};
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[10, 10], [12, 10], [13, 11]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// assert_eq!(life.recenter(Centre::MinCorner), [-10, -10]);
+    /// assert_eq!(life.alive_cells(), &[[0, 0], [2, 0], [3, 1]].into_iter().collect());
+    ///
+    /// assert_eq!(life.recenter(Centre::BoundingBox), [-1, 0]);
+    /// assert_eq!(life.recenter(Centre::Mass), [-1, 0]);
+    /// ```
+    pub fn recenter(&mut self, centre: Centre) -> [i64; N] {
+        let Some((min, max)) = self.bounding_box() else {
+            return [0; N];
+        };
+        let offset = match centre {
+            Centre::BoundingBox => core::array::from_fn(|i| -((min[i] as i128 + max[i] as i128).div_euclid(2) as i64)),
+            Centre::Mass => {
+                let count = self.alive_cells.len() as i128;
+                let mut sums = [0i128; N];
+                for cell in self.alive_cells.iter() {
+                    for i in 0..N {
+                        sums[i] += cell[i] as i128;
+                    }
+                }
+                core::array::from_fn(|i| -((2 * sums[i] + count).div_euclid(2 * count) as i64))
+            }
+            Centre::MinCorner => core::array::from_fn(|i| -min[i]),
+        };
+        self.translate(offset);
+        offset
+    }
+
+    /// Rotate all alive cells by a multiple of 90° about the origin, in the plane spanned by two axes.
+    ///
+    /// A positive quarter turn rotates `axis_a` onto `axis_b`, so in 2D `rotate(0, 1, 1)`
+    /// is a counter-clockwise rotation mapping `[x, y]` to `[-y, x]`.
+    /// # Arguments
+    /// * `axis_a` - The first axis spanning the plane of rotation.
+    /// * `axis_b` - The second axis spanning the plane of rotation.
+    /// * `quarter_turns` - The number of quarter turns to rotate by (negative values rotate the other way).
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error.
+    /// # Errors
+    /// * [IdenticalAxes](Error::IdenticalAxes) - If `axis_a` and `axis_b` are the same.
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis_a` or `axis_b` is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 3]> = [[1, 2, 3]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.rotate(0, 2, 1).unwrap();
+    /// assert_eq!(life.alive_cells(), &[[-3, 2, 1]].into_iter().collect());
+    ///
+    /// life.rotate(0, 2, -1).unwrap();
+    /// assert_eq!(life.alive_cells(), &[[1, 2, 3]].into_iter().collect());
+    /// ```
+    pub fn rotate(&mut self, axis_a: usize, axis_b: usize, quarter_turns: i64) -> Result<(), Error> {
+        self.transform(&Transform::rotation(axis_a, axis_b, quarter_turns)?);
+        Ok(())
+    }
+
+    /// Mirror all alive cells across the hyperplane perpendicular to an axis through the centre of their bounding box.
+    ///
+    /// The alive cells keep occupying the same bounding box.
+    /// # Arguments
+    /// * `axis` - The axis perpendicular to the mirror.
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis` is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [3, 1]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.reflect(0).unwrap();
+    /// assert_eq!(life.alive_cells(), &[[3, 0], [2, 0], [0, 1]].into_iter().collect());
+    /// ```
+    pub fn reflect(&mut self, axis: usize) -> Result<(), Error> {
+        let reflection = Transform::reflection(axis)?;
+        if let Some((min, max)) = self.bounding_box() {
+            let mut offset = [0; N];
+            offset[axis] = min[axis].wrapping_add(max[axis]);
+            self.transform(&reflection.then(&Transform::translation(offset)));
+        }
+        Ok(())
+    }
+
+    /// Mirror all alive cells across the hyperplane perpendicular to an axis through a given coordinate.
+    ///
+    /// Cells lying on the mirror stay in place, and the coordinate `x` along the axis becomes `2 * coordinate - x`,
+    /// wrapping around if it does not fit in [i64].
+    /// # Arguments
+    /// * `axis` - The axis perpendicular to the mirror.
+    /// * `coordinate` - The coordinate of the mirror along the axis.
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis` is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 5]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.reflect_about(1, 1).unwrap();
+    /// assert_eq!(life.alive_cells(), &[[0, 2], [1, -3]].into_iter().collect());
+    /// ```
+    pub fn reflect_about(&mut self, axis: usize, coordinate: i64) -> Result<(), Error> {
+        let reflection = Transform::reflection(axis)?;
+        let mut offset = [0; N];
+        offset[axis] = coordinate.wrapping_mul(2);
+        self.transform(&reflection.then(&Transform::translation(offset)));
+        Ok(())
+    }
+
+    /// Make the alive cells symmetric by adding their images under every element of a symmetry group.
+    ///
+    /// The symmetries fix the origin, so translate the cells first to choose the centre of symmetry.
+    /// # Arguments
+    /// * `symmetry` - The symmetry group.
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If the group acts in a plane and `N` is less than 2.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::symmetry::Symmetry;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[1, 2]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// life.symmetrize(Symmetry::C4).unwrap();
+    /// assert_eq!(life.alive_cells(), &[[1, 2], [-2, 1], [-1, -2], [2, -1]].into_iter().collect());
+    /// ```
+    pub fn symmetrize(&mut self, symmetry: Symmetry) -> Result<(), Error> {
+        let elements = symmetry.elements::<N>()?;
+        let images: Vec<[i64; N]> = self.alive_cells.iter().flat_map(|cell| elements.iter().map(|element| element.apply(cell))).collect();
+        Arc::make_mut(&mut self.alive_cells).extend(images);
+        Ok(())
+    }
+
+    /// Apply a lattice transformation to all alive cells.
+    ///
+    /// Lattice transformations preserve neighbourhoods, so the transformed cells evolve
+    /// exactly like the transformed evolution of the original cells.
+    /// # Arguments
+    /// * `transform` - The transformation to apply.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::transform::Transform;
+    /// use std::collections::HashSet;
+    ///
+    /// let alive_cells: HashSet<[i64; 2]> = [[0, 0], [1, 2]].into_iter().collect();
+    /// let mut life = Life::new_with_alive_cells(HashSet::new(), HashSet::new(), alive_cells).unwrap();
+    ///
+    /// // swap the axes, then move up by one cell
+    /// let transform = Transform::signed_permutation([1, 0], [false, false]).unwrap().then(&Transform::translation([0, 1]));
+    /// life.transform(&transform);
+    /// assert_eq!(life.alive_cells(), &[[0, 1], [2, 2]].into_iter().collect());
+    /// ```
+    pub fn transform(&mut self, transform: &Transform<N>) {
+        self.map_cells(|cell| transform.apply(cell));
+    }
+
+    /// Get a copy of the game of life with a lattice transformation applied to all alive cells.
+    /// # Arguments
+    /// * `transform` - The transformation to apply.
+    /// # Returns
+    /// The transformed game of life.
+    pub fn transformed(&self, transform: &Transform<N>) -> Self {
+        let mut life = self.clone();
+        life.transform(transform);
+        life
+    }
+
+    /// Embed the game of life into a higher-dimensional space by fixing the extra coordinates of its alive cells.
+    ///
+    /// The cells are embedded like a [Pattern::embed], and the new game of life has the same age and rules,
+    /// which are valid in every higher dimension, counting the neighbours out of the larger neighbourhood.
+    /// `M` must be greater than `N`, which is checked at compile time.
+    /// # Arguments
+    /// * `fixed` - The values of the `M - N` extra coordinates.
+    /// # Returns
+    /// A [Result] containing the embedded game of life, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the length of `fixed` is not `M - N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let embedded = life.embed::<3>(&[7]).unwrap();
+    /// assert_eq!(embedded.alive_cells(), &[[0, 0, 7], [1, 0, 7], [2, 0, 7]].into_iter().collect());
+    /// assert_eq!(embedded.birth_rules(), life.birth_rules());
+    /// ```
+    pub fn embed<const M: usize>(&self, fixed: &[i64]) -> Result<Life<M>, Error> {
+        self.with_cells(self.pattern().embed(fixed)?)
+    }
+
+    /// Extrude the game of life into a higher-dimensional space across ranges of the extra coordinates of its alive cells.
+    ///
+    /// The cells are extruded like a [Pattern::extrude], and the new game of life has the same age and rules,
+    /// which are valid in every higher dimension, counting the neighbours out of the larger neighbourhood.
+    /// `M` must be greater than `N`, which is checked at compile time.
+    /// # Arguments
+    /// * `ranges` - The inclusive ranges of the `M - N` extra coordinates.
+    /// # Returns
+    /// A [Result] containing the extruded game of life, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the length of `ranges` is not `M - N`.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let extruded = life.extrude::<3>(&[-1..=1]).unwrap();
+    /// assert_eq!(extruded.alive_cells().len(), 9);
+    /// assert!(life.extrude::<4>(&[0..=1]).is_err());
+    /// ```
+    pub fn extrude<const M: usize>(&self, ranges: &[RangeInclusive<i64>]) -> Result<Life<M>, Error> {
+        self.with_cells(self.pattern().extrude(ranges)?)
+    }
+
+    /// Get the alive cells as a pattern.
+    fn pattern(&self) -> Pattern<N> {
+        self.alive_cells.iter().copied().collect()
+    }
+
+    /// Create a game of life of another dimension with the age and rules of this one and given alive cells.
+    fn with_cells<const M: usize>(&self, cells: Pattern<M>) -> Result<Life<M>, Error> {
+        let mut life = Life::from_cells(self.birth_rules().clone(), self.survival_rules().clone(), cells)?;
+        life.set_age(self.age());
+        Ok(life)
+    }
+
+    /// Move every cell of the universe (current, previous, tracked and retained cells) to new coordinates.
+    ///
+    /// The mapping must be injective, otherwise cells are merged.
+    fn map_cells(&mut self, f: impl Fn(&[i64; N]) -> [i64; N]) {
+        self.alive_cells = Arc::new(self.alive_cells.iter().map(&f).collect());
+        self.prev_alive = Arc::new(self.prev_alive.iter().map(&f).collect());
+        if let Some(ancestry) = self.ancestry.as_mut() {
+            Arc::make_mut(ancestry).map_cells(&f);
+        }
+        if let Some(colours) = self.colours.as_mut() {
+            Arc::make_mut(colours).map_cells(&f);
+        }
+        if let Some(history) = self.history.as_mut() {
+            history.map_cells(&f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::HashSet;
+    use crate::life::conways_game_of_life;
+    use alloc::vec;
+
+    #[test]
+    fn test_translate() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [0, 1], [0, 2]].into_iter().collect());
+        life.next_generation();
+        life.translate([10, -10]);
+        let expected_alive_cells: HashSet<[i64; 2]> = [[9, -9], [10, -9], [11, -9]].into_iter().collect();
+        assert_eq!(life.alive_cells(), &expected_alive_cells);
+        let mut changed_cells: Vec<[i64; 2]> = life.changed_cells().copied().collect();
+        changed_cells.sort();
+        assert_eq!(changed_cells, vec![[9, -9], [10, -10], [10, -8], [11, -9]]);
+        life.next_generation();
+        let expected_alive_cells: HashSet<[i64; 2]> = [[10, -10], [10, -9], [10, -8]].into_iter().collect();
+        assert_eq!(life.alive_cells(), &expected_alive_cells);
+    }
+
+    #[test]
+    fn test_translated() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 2]].into_iter().collect());
+        life.track_ancestry();
+        let translated = life.translated([-5, 5]);
+        assert_eq!(translated.alive_cells(), &[[-5, 5], [-4, 7]].into_iter().collect());
+        assert_eq!(translated.ancestors(&[-4, 7]), Some([[-4, 7]].into_iter().collect()));
+        assert_eq!(life.alive_cells(), &[[0, 0], [1, 2]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut life = conways_game_of_life();
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        life.set_alive_cells(glider.into_iter().collect());
+
+        life.rotate(0, 1, 1).unwrap();
+        assert_eq!(life.alive_cells(), &[[0, 0], [0, 1], [0, 2], [-1, 2], [-2, 1]].into_iter().collect());
+        life.rotate(1, 0, 1).unwrap();
+        assert_eq!(life.alive_cells(), &glider.into_iter().collect());
+        life.rotate(0, 1, 2).unwrap();
+        assert_eq!(life.alive_cells(), &[[0, 0], [-1, 0], [-2, 0], [-2, -1], [-1, -2]].into_iter().collect());
+        life.rotate(0, 1, 6).unwrap();
+        assert_eq!(life.alive_cells(), &glider.into_iter().collect());
+        life.rotate(0, 1, -1).unwrap();
+        assert_eq!(life.alive_cells(), &[[0, 0], [0, -1], [0, -2], [1, -2], [2, -1]].into_iter().collect());
+
+        // the rotated glider moves in the rotated direction
+        for _ in 0..4 {
+            life.next_generation();
+        }
+        assert_eq!(life.alive_cells(), &[[-1, -1], [-1, -2], [-1, -3], [0, -3], [1, -2]].into_iter().collect());
+
+        assert_eq!(life.rotate(0, 2, 1), Err(Error::InvalidAxis(2, 2)));
+        assert_eq!(life.rotate(1, 1, 1), Err(Error::IdenticalAxes(1)));
+    }
+
+    #[test]
+    fn test_reflect() {
+        let mut life = conways_game_of_life();
+        assert_eq!(life.reflect(1), Ok(()));
+        assert!(life.alive_cells().is_empty());
+
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        life.set_alive_cells(glider.into_iter().collect());
+        life.reflect(0).unwrap();
+        assert_eq!(life.alive_cells(), &[[2, 0], [1, 0], [0, 0], [0, 1], [1, 2]].into_iter().collect());
+        life.reflect(0).unwrap();
+        assert_eq!(life.alive_cells(), &glider.into_iter().collect());
+        assert_eq!(life.reflect(2), Err(Error::InvalidAxis(2, 2)));
+
+        // cells at the edges of the coordinates are reflected even though the sum of the edges overflows
+        life.set_alive_cells([[i64::MAX, 0], [i64::MAX - 2, 1]].into_iter().collect());
+        life.reflect(0).unwrap();
+        assert_eq!(life.alive_cells(), &[[i64::MAX - 2, 0], [i64::MAX, 1]].into_iter().collect());
+        life.set_alive_cells([[i64::MIN, 0], [i64::MAX, 1]].into_iter().collect());
+        life.reflect(0).unwrap();
+        assert_eq!(life.alive_cells(), &[[i64::MAX, 0], [i64::MIN, 1]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_reflect_about() {
+        let mut life = conways_game_of_life();
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        life.set_alive_cells(glider.into_iter().collect());
+        life.reflect_about(1, -1).unwrap();
+        assert_eq!(life.alive_cells(), &[[0, -2], [1, -2], [2, -2], [2, -3], [1, -4]].into_iter().collect());
+
+        // the mirrored glider moves in the mirrored direction
+        for _ in 0..4 {
+            life.next_generation();
+        }
+        assert_eq!(life.alive_cells(), &[[1, -1], [2, -1], [3, -1], [3, -2], [2, -3]].into_iter().collect());
+        assert_eq!(life.reflect_about(5, 0), Err(Error::InvalidAxis(5, 2)));
+
+        // a mirror beyond half the coordinates reflects the cells whose images fit exactly
+        let mirror = i64::MAX / 2 + 1;
+        life.set_alive_cells([[0, i64::MAX], [0, mirror]].into_iter().collect());
+        life.reflect_about(1, mirror).unwrap();
+        assert_eq!(life.alive_cells(), &[[0, 1], [0, mirror]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[1, 2], [0, 0]].into_iter().collect());
+        life.symmetrize(Symmetry::D8).unwrap();
+        assert_eq!(life.alive_cells().len(), 9);
+        life.symmetrize(Symmetry::D8).unwrap();
+        assert_eq!(life.alive_cells().len(), 9);
+
+        life.set_alive_cells([[1, 2]].into_iter().collect());
+        life.symmetrize(Symmetry::D2).unwrap();
+        assert_eq!(life.alive_cells(), &[[1, 2], [-1, 2]].into_iter().collect());
+
+        let mut life = Life::<3>::new(HashSet::new(), HashSet::new()).unwrap();
+        life.set_cell(&[1, 2, 3], true);
+        life.symmetrize(Symmetry::SignedPermutations).unwrap();
+        assert_eq!(life.alive_cells().len(), 48);
+
+        let mut life = Life::<1>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert_eq!(life.symmetrize(Symmetry::C2), Err(Error::InvalidAxis(1, 1)));
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut life = conways_game_of_life();
+        let glider = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]];
+        life.set_alive_cells(glider.into_iter().collect());
+        life.track_ancestry();
+        let transform = Transform::rotation(0, 1, 1)
+            .unwrap()
+            .then(&Transform::reflection(1).unwrap())
+            .then(&Transform::translation([5, 5]));
+
+        // transforming commutes with evolution
+        let mut transformed = life.transformed(&transform);
+        assert_eq!(transformed.ancestors(&[5, 5]), Some([[5, 5]].into_iter().collect()));
+        for _ in 0..4 {
+            life.next_generation();
+            transformed.next_generation();
+        }
+        life.transform(&transform);
+        assert_eq!(life.alive_cells(), transformed.alive_cells());
+        assert_eq!(life.ancestors(&[4, 4]), transformed.ancestors(&[4, 4]));
+
+        life.transform(&transform.inverse());
+        assert_eq!(life.alive_cells(), &[[1, -1], [2, -1], [3, -1], [3, 0], [2, 1]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_recenter() {
+        let mut life = conways_game_of_life();
+        assert_eq!(life.recenter(Centre::Mass), [0, 0]);
+
+        life.set_alive_cells([[-3, 5], [0, 5], [1, 8]].into_iter().collect());
+        assert_eq!(life.recenter(Centre::BoundingBox), [1, -6]);
+        assert_eq!(life.bounding_box(), Some(([-2, -1], [2, 2])));
+        assert_eq!(life.recenter(Centre::Mass), [0, 0]);
+        assert_eq!(life.recenter(Centre::MinCorner), [2, 1]);
+        assert_eq!(life.bounding_box(), Some(([0, 0], [4, 3])));
+        assert_eq!(life.recenter(Centre::Mass), [-2, -1]);
+        assert_eq!(life.alive_cells(), &[[-2, -1], [1, -1], [2, 2]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_embed_and_extrude() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        life.next_generation();
+
+        let embedded = life.embed::<4>(&[3, -3]).unwrap();
+        assert_eq!(embedded.alive_cells(), &[[1, -1, 3, -3], [1, 0, 3, -3], [1, 1, 3, -3]].into_iter().collect());
+        assert_eq!(
+            (embedded.age(), embedded.birth_rules(), embedded.survival_rules()),
+            (1, life.birth_rules(), life.survival_rules())
+        );
+        assert_eq!(life.embed::<3>(&[]).unwrap_err(), Error::DimensionMismatch(1, 0));
+
+        // an extruded blinker is a slab whose cells count their neighbours in three dimensions
+        let mut extruded = life.extrude::<3>(&[0..=1]).unwrap();
+        assert_eq!(extruded.age(), 1);
+        assert_eq!(extruded.alive_cells().len(), 6);
+        assert_eq!(extruded.alive_neighbour_count(&[1, 0, 0]), 5);
+        extruded.next_generation();
+        // the middle of the slab has too many neighbours to survive, and cells are born above and below it instead of beside it
+        let expected = [
+            [0, 0, -1],
+            [0, 0, 2],
+            [1, -1, 0],
+            [1, -1, 1],
+            [1, 0, -1],
+            [1, 0, 2],
+            [1, 1, 0],
+            [1, 1, 1],
+            [2, 0, -1],
+            [2, 0, 2],
+        ];
+        assert_eq!(extruded.alive_cells(), &expected.into_iter().collect());
+        assert_eq!(life.extrude::<3>(&[0..=1, 0..=1]).unwrap_err(), Error::DimensionMismatch(1, 2));
+    }
+}
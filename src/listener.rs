@@ -0,0 +1,167 @@
+//! Accepting the connections of the servers on a background thread
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Thread accepting the connections of a listener and serving each on its own thread, a limited number at a time
+///
+/// When the limit is reached, the acceptor waits for a connection to finish before accepting the next,
+/// so further clients queue in the backlog of the listener instead of getting threads.
+/// Dropping the acceptor stops the accepting thread, which closes the listener, without waiting for the connections being served.
+#[derive(Debug)]
+pub(crate) struct Acceptor {
+    /// The address the listener listens on.
+    local_addr: SocketAddr,
+    /// The connections being served, shared with the accepting and the serving threads.
+    slots: Arc<Slots>,
+}
+impl Acceptor {
+    /// Start accepting the connections of a listener.
+    /// # Arguments
+    /// * `listener` - The listener.
+    /// * `max_connections` - The most connections served at a time.
+    /// * `serve` - The function serving a connection, called on a thread of its own.
+    /// # Returns
+    /// An [io::Result] containing the acceptor, or the error of getting the address of the listener.
+    pub(crate) fn spawn(listener: TcpListener, max_connections: usize, serve: impl Fn(TcpStream) + Send + Sync + 'static) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let slots = Arc::new(Slots {
+            state: Mutex::new(State { active: 0, stopped: false }),
+            freed: Condvar::new(),
+            max_connections,
+        });
+        let accepting = Arc::clone(&slots);
+        let serve = Arc::new(serve);
+        thread::spawn(move || {
+            while accepting.wait_for_slot() {
+                let Ok((stream, _)) = listener.accept() else {
+                    continue;
+                };
+                let mut state = accepting.lock();
+                if state.stopped {
+                    break;
+                }
+                state.active += 1;
+                drop(state);
+                let (slots, serve) = (Arc::clone(&accepting), Arc::clone(&serve));
+                thread::spawn(move || {
+                    serve(stream);
+                    slots.lock().active -= 1;
+                    slots.freed.notify_one();
+                });
+            }
+        });
+        Ok(Self { local_addr, slots })
+    }
+
+    /// Get the address the listener listens on.
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+impl Drop for Acceptor {
+    fn drop(&mut self) {
+        self.slots.lock().stopped = true;
+        self.slots.freed.notify_one();
+        // the accepting thread may instead be blocked on the listener until a connection arrives
+        let mut addr = self.local_addr;
+        if addr.ip().is_unspecified() {
+            addr.set_ip(if addr.is_ipv4() { Ipv4Addr::LOCALHOST.into() } else { Ipv6Addr::LOCALHOST.into() });
+        }
+        let _ = TcpStream::connect_timeout(&addr, Duration::from_secs(1));
+    }
+}
+
+/// Count of the connections being served, with a condition signalled when one finishes
+#[derive(Debug)]
+struct Slots {
+    /// The number of connections being served and whether the acceptor was dropped.
+    state: Mutex<State>,
+    /// Signalled when a connection finishes or the acceptor is dropped.
+    freed: Condvar,
+    /// The most connections served at a time.
+    max_connections: usize,
+}
+impl Slots {
+    /// Lock the state, which is consistent even if a thread panicked while holding it.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wait until fewer connections than the limit are being served.
+    /// # Returns
+    /// Whether to accept another connection, false once the acceptor was dropped.
+    fn wait_for_slot(&self) -> bool {
+        let state = self
+            .freed
+            .wait_while(self.lock(), |state| !state.stopped && state.active >= self.max_connections)
+            .unwrap_or_else(PoisonError::into_inner);
+        !state.stopped
+    }
+}
+
+/// State of the connections of an acceptor
+#[derive(Debug)]
+struct State {
+    /// The number of connections being served.
+    active: usize,
+    /// Whether the acceptor was dropped.
+    stopped: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_acceptor() {
+        // connections are served until told to finish, so the third one waits
+        let (finish, finished) = mpsc::channel::<()>();
+        let finished = Mutex::new(finished);
+        let acceptor = Acceptor::spawn(TcpListener::bind("127.0.0.1:0").unwrap(), 2, move |mut stream| {
+            stream.write_all(b"served").unwrap();
+            let _ = finished.lock().unwrap().recv();
+        })
+        .unwrap();
+        let read = |stream: &mut TcpStream| {
+            let mut bytes = [0; 6];
+            stream.read_exact(&mut bytes).map(|_| bytes)
+        };
+        let mut first = TcpStream::connect(acceptor.local_addr()).unwrap();
+        let mut second = TcpStream::connect(acceptor.local_addr()).unwrap();
+        assert_eq!((read(&mut first).unwrap(), read(&mut second).unwrap()), (*b"served", *b"served"));
+        let mut third = TcpStream::connect(acceptor.local_addr()).unwrap();
+        third.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+        assert!(read(&mut third).is_err());
+
+        // a finished connection lets the next one in
+        finish.send(()).unwrap();
+        third.set_read_timeout(None).unwrap();
+        assert_eq!(read(&mut third).unwrap(), *b"served");
+
+        // dropping the acceptor closes the listener, while it waits for a place and while it waits for a connection
+        let addr = acceptor.local_addr();
+        drop(acceptor);
+        let mut attempts = 0;
+        while TcpStream::connect(addr).is_ok() {
+            attempts += 1;
+            assert!(attempts < 1000, "the listener was not closed");
+            thread::sleep(Duration::from_millis(1));
+        }
+        drop(finish);
+        let idle = Acceptor::spawn(TcpListener::bind("127.0.0.1:0").unwrap(), 1, |_| {}).unwrap();
+        let addr = idle.local_addr();
+        drop(idle);
+        let mut attempts = 0;
+        while TcpStream::connect(addr).is_ok() {
+            attempts += 1;
+            assert!(attempts < 1000, "the listener was not closed");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
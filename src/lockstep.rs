@@ -0,0 +1,420 @@
+//! State identity and per-generation diffs for keeping copies of a game of life in sync, as in lockstep networking
+
+use crate::error::Error;
+use crate::life::Life;
+use crate::random::SplitMix64;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Seed of the hash of the coordinates of a cell.
+const CELL_SEED: u64 = 0x6E64_6C69_6665_2D63;
+/// Seed of the hash of the age.
+const AGE_SEED: u64 = 0x6E64_6C69_6665_2D61;
+
+/// Change of the alive cells and the age of a game of life, from one state to another
+///
+/// Both states are identified by their [content hash](Life::content_hash), so a diff can only be
+/// [applied](Lockstep::apply) to the state it was made from, and the result is checked to be the state it leads to.
+/// The cells are sorted in lexicographic order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diff<const N: usize> {
+    /// The age before the change.
+    from_age: u64,
+    /// The age after the change.
+    age: u64,
+    /// The content hash before the change.
+    from_hash: u64,
+    /// The content hash after the change.
+    hash: u64,
+    /// The cells which became alive.
+    born: Box<[[i64; N]]>,
+    /// The cells which became dead.
+    died: Box<[[i64; N]]>,
+}
+impl<const N: usize> Diff<N> {
+    /// Get the age before the change.
+    pub fn from_age(&self) -> u64 {
+        self.from_age
+    }
+
+    /// Get the age after the change.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the content hash of the state before the change.
+    pub fn from_hash(&self) -> u64 {
+        self.from_hash
+    }
+
+    /// Get the content hash of the state after the change.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Get the cells which became alive, sorted in lexicographic order.
+    pub fn born(&self) -> &[[i64; N]] {
+        &self.born
+    }
+
+    /// Get the cells which became dead, sorted in lexicographic order.
+    pub fn died(&self) -> &[[i64; N]] {
+        &self.died
+    }
+
+    /// Get whether no cell changed, such as in a generation of a still life.
+    pub fn is_empty(&self) -> bool {
+        self.born.is_empty() && self.died.is_empty()
+    }
+
+    /// Get the diff undoing this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            from_age: self.age,
+            age: self.from_age,
+            from_hash: self.hash,
+            hash: self.from_hash,
+            born: self.died.clone(),
+            died: self.born.clone(),
+        }
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Get the content hash of the age and alive cells, which identifies the state when comparing copies of a game of life.
+    ///
+    /// The hash is the same in every version of the library and on every platform. It combines one hash for each alive cell
+    /// with exclusive or, so the [Lockstep] holding a game of life updates it from the changed cells only; this method
+    /// computes it from scratch. The rules are not part of the hash.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// let empty = life.content_hash();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    /// let blinker = life.content_hash();
+    /// assert_ne!(blinker, empty);
+    ///
+    /// // the same cells at another age are another state
+    /// life.next_generation();
+    /// life.next_generation();
+    /// assert_ne!(life.content_hash(), blinker);
+    /// life.set_age(0);
+    /// assert_eq!(life.content_hash(), blinker);
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        cells_hash(self.alive_cells()) ^ age_hash(self.age())
+    }
+}
+
+/// Game of life with an incrementally maintained content hash and a bounded log of diffs, for lockstep and rollback networking
+///
+/// Every peer of a multiplayer sandbox holds a lockstep of the same game of life and advances it with [step](Self::step),
+/// which updates the [content hash](Life::content_hash) from the changed cells and returns the [Diff] of the generation.
+/// Peers compare hashes to verify they are in sync. A peer which fell behind or diverged is resynchronized with diffs
+/// rather than full states: it [rolls back](Self::rollback_to) to a state both peers logged, receives the
+/// [diffs since](Self::diffs_since) that state and [applies](Self::apply) them.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::lockstep::Lockstep;
+///
+/// let mut life = conways_game_of_life();
+/// life.randomize_region([0..=15, 0..=15], 0.5, 7);
+/// let mut host = Lockstep::new(life.clone(), 16);
+/// let mut guest = Lockstep::new(life, 16);
+///
+/// for _ in 0..5 {
+///     let diff = host.step();
+///     guest.apply(&diff).unwrap();
+/// }
+/// assert_eq!(guest.hash(), host.hash());
+///
+/// // the guest diverges, then rolls back and catches up with the host
+/// let common = guest.hash();
+/// guest.edit(|life| life.extend([[40, 40], [41, 40], [40, 41], [41, 41]]));
+/// for _ in 0..3 {
+///     host.step();
+///     guest.step();
+/// }
+/// assert_ne!(guest.hash(), host.hash());
+/// assert!(guest.rollback_to(common));
+/// for diff in host.diffs_since(common).unwrap() {
+///     guest.apply(&diff).unwrap();
+/// }
+/// assert_eq!(guest.hash(), host.hash());
+/// assert_eq!(guest.life().alive_cells(), host.life().alive_cells());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lockstep<const N: usize> {
+    /// The game of life.
+    life: Life<N>,
+    /// The hash of the alive cells, without the age.
+    cells_hash: u64,
+    /// The most recent diffs, oldest first.
+    diffs: VecDeque<Diff<N>>,
+    /// The maximum number of diffs kept for rolling back.
+    capacity: usize,
+}
+impl<const N: usize> Lockstep<N> {
+    /// Create a lockstep of a game of life.
+    /// # Arguments
+    /// * `life` - The game of life.
+    /// * `capacity` - The maximum number of diffs kept for rolling back; older ones are forgotten.
+    pub fn new(life: Life<N>, capacity: usize) -> Self {
+        Self {
+            cells_hash: cells_hash(life.alive_cells()),
+            life,
+            diffs: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Get the game of life.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Get the game of life, consuming the lockstep.
+    pub fn into_life(self) -> Life<N> {
+        self.life
+    }
+
+    /// Get the content hash of the current state, as [content_hash](Life::content_hash) without computing it from scratch.
+    pub fn hash(&self) -> u64 {
+        self.cells_hash ^ age_hash(self.life.age())
+    }
+
+    /// Get the logged diffs, oldest first.
+    pub fn diffs(&self) -> impl Iterator<Item = &Diff<N>> {
+        self.diffs.iter()
+    }
+
+    /// Advance the game of life by one generation.
+    /// # Returns
+    /// The diff of the generation, also logged for rolling back.
+    pub fn step(&mut self) -> Diff<N> {
+        let (from_age, from_hash) = (self.life.age(), self.hash());
+        self.life.next_generation();
+        let diff = self.diff(from_age, from_hash, self.life.born_cells().copied().collect(), self.life.died_cells().copied().collect());
+        self.log(diff.clone());
+        diff
+    }
+
+    /// Change the game of life in any way, such as editing cells or setting the age, and log the change as a diff.
+    ///
+    /// The change is found by comparing all the alive cells before and after, so editing is slower than stepping.
+    /// Changes of the rules are not part of the diff.
+    /// # Arguments
+    /// * `edit` - The change to make.
+    /// # Returns
+    /// The diff of the change.
+    pub fn edit(&mut self, edit: impl FnOnce(&mut Life<N>)) -> Diff<N> {
+        let (from_age, from_hash) = (self.life.age(), self.hash());
+        let before = self.life.alive_cells().clone();
+        edit(&mut self.life);
+        let after = self.life.alive_cells();
+        let diff = self.diff(
+            from_age,
+            from_hash,
+            after.difference(&before).copied().collect(),
+            before.difference(after).copied().collect(),
+        );
+        self.log(diff.clone());
+        diff
+    }
+
+    /// Apply a diff made by another lockstep, such as one received from a peer, and log it.
+    /// # Arguments
+    /// * `diff` - The diff, made from the current state.
+    /// # Returns
+    /// A [Result] which is an error if the diff does not apply, leaving the lockstep unchanged.
+    /// # Errors
+    /// * [OutOfSync](Error::OutOfSync) - If the current state is not the one the diff was made from,
+    ///   or the diff is corrupted and does not lead to the state it was made to.
+    pub fn apply(&mut self, diff: &Diff<N>) -> Result<(), Error> {
+        let hash = self.hash();
+        if diff.from_hash != hash {
+            return Err(Error::OutOfSync(diff.from_hash, hash));
+        }
+        let cells_hash = self.cells_hash ^ cells_hash(diff.born.iter().chain(diff.died.iter()));
+        let hash = cells_hash ^ age_hash(diff.age);
+        if diff.hash != hash {
+            return Err(Error::OutOfSync(diff.hash, hash));
+        }
+        self.change(diff);
+        self.log(diff.clone());
+        Ok(())
+    }
+
+    /// Undo the most recent logged diffs.
+    /// # Arguments
+    /// * `count` - The number of diffs to undo.
+    /// # Returns
+    /// The number of diffs undone, fewer than `count` if the log runs out.
+    pub fn rollback(&mut self, count: usize) -> usize {
+        let mut undone = 0;
+        while undone < count {
+            let Some(diff) = self.diffs.pop_back() else {
+                break;
+            };
+            self.change(&diff.inverse());
+            undone += 1;
+        }
+        undone
+    }
+
+    /// Undo the logged diffs back to a state, such as the last state known to be shared with a peer.
+    /// # Arguments
+    /// * `hash` - The content hash of the state.
+    /// # Returns
+    /// Whether the state is now the one of the hash; if the state is not in the log, nothing is undone.
+    pub fn rollback_to(&mut self, hash: u64) -> bool {
+        match self.position(hash) {
+            Some(position) => {
+                self.rollback(self.diffs.len() - position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the logged diffs leading from a state to the current one, such as to resynchronize a peer.
+    /// # Arguments
+    /// * `hash` - The content hash of the state.
+    /// # Returns
+    /// [Some] diffs, oldest first and none if the state is the current one, or [None] if the state is not in the log.
+    pub fn diffs_since(&self, hash: u64) -> Option<Vec<Diff<N>>> {
+        self.position(hash).map(|position| self.diffs.range(position..).cloned().collect())
+    }
+
+    /// Get the index of the first logged diff after the most recent state with a hash, the length of the log for the current state.
+    fn position(&self, hash: u64) -> Option<usize> {
+        if self.hash() == hash {
+            return Some(self.diffs.len());
+        }
+        self.diffs.iter().rposition(|diff| diff.from_hash == hash)
+    }
+
+    /// Make a diff from the current state, updating the hash of the alive cells.
+    fn diff(&mut self, from_age: u64, from_hash: u64, born: Vec<[i64; N]>, died: Vec<[i64; N]>) -> Diff<N> {
+        self.cells_hash ^= cells_hash(born.iter().chain(died.iter()));
+        let (mut born, mut died) = (born.into_boxed_slice(), died.into_boxed_slice());
+        born.sort_unstable();
+        died.sort_unstable();
+        Diff {
+            from_age,
+            age: self.life.age(),
+            from_hash,
+            hash: self.hash(),
+            born,
+            died,
+        }
+    }
+
+    /// Change the game of life by a diff known to apply, updating the hash of the alive cells.
+    fn change(&mut self, diff: &Diff<N>) {
+        self.cells_hash ^= cells_hash(diff.born.iter().chain(diff.died.iter()));
+        self.life.remove_cells(diff.died.iter().copied());
+        self.life.extend(diff.born.iter());
+        self.life.set_age(diff.age);
+    }
+
+    /// Log a diff, forgetting the oldest one if the log is full.
+    fn log(&mut self, diff: Diff<N>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.diffs.len() == self.capacity {
+            self.diffs.pop_front();
+        }
+        self.diffs.push_back(diff);
+    }
+}
+
+/// Get the combined hash of cells, each of which changes it the same way when added or removed.
+pub(crate) fn cells_hash<'a, const N: usize>(cells: impl IntoIterator<Item = &'a [i64; N]>) -> u64 {
+    cells.into_iter().fold(0, |hash, cell| {
+        hash ^ cell.iter().fold(CELL_SEED, |hash, coordinate| SplitMix64::new(hash ^ *coordinate as u64).next_u64())
+    })
+}
+
+/// Get the hash of an age.
+pub(crate) fn age_hash(age: u64) -> u64 {
+    SplitMix64::new(age ^ AGE_SEED).next_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_hash() {
+        let mut life = conways_game_of_life();
+        life.randomize_region([0..=31, 0..=31], 0.4, 1);
+        let mut lockstep = Lockstep::new(life.clone(), 4);
+        for _ in 0..20 {
+            let diff = lockstep.step();
+            life.next_generation();
+            assert_eq!(diff.hash(), life.content_hash());
+            assert_eq!(diff.age(), diff.from_age() + 1);
+        }
+        assert_eq!(lockstep.hash(), lockstep.life().content_hash());
+        assert_eq!(lockstep.diffs().count(), 4);
+        // hashes do not depend on the order of the cells, and mirrored states differ
+        let cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 1]].into_iter().collect();
+        assert_eq!(cells_hash(&cells), cells_hash(&[[2, 1], [0, 0], [1, 0]]));
+        assert_ne!(cells_hash(&cells), cells_hash(&[[0, 0], [0, 1], [1, 2]]));
+        assert_eq!(cells_hash::<2>(&[]), 0);
+    }
+
+    #[test]
+    fn test_apply_and_rollback() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+        let start = life.clone();
+        let mut host = Lockstep::new(life.clone(), 8);
+        let mut guest = Lockstep::new(life, 8);
+        let diffs: Vec<Diff<2>> = (0..4).map(|_| host.step()).collect();
+
+        // out of order, then in order
+        assert_eq!(guest.apply(&diffs[1]), Err(Error::OutOfSync(diffs[1].from_hash(), guest.hash())));
+        for diff in &diffs {
+            guest.apply(diff).unwrap();
+        }
+        assert_eq!(guest.life().alive_cells(), host.life().alive_cells());
+        assert_eq!(guest.life().age(), 4);
+
+        // a corrupted diff is rejected without changes
+        let mut corrupted = host.step();
+        corrupted.born[0][0] += 1;
+        let before = guest.clone();
+        assert!(matches!(guest.apply(&corrupted), Err(Error::OutOfSync(..))));
+        assert_eq!(guest, before);
+
+        // an edit is logged and undone like a generation
+        let edit = guest.edit(|life| {
+            life.set_cell(&[10, 10], true);
+            life.set_cell(&[2, 1], false);
+        });
+        assert_eq!((edit.born(), edit.died(), edit.age()), (&[[10, 10]][..], &[[2, 1]][..], 4));
+        assert_eq!(guest.rollback(1), 1);
+        assert_eq!(guest, before);
+        assert_eq!(guest.rollback(10), 4);
+        assert_eq!(guest.life().alive_cells(), start.alive_cells());
+        assert_eq!(guest.hash(), start.content_hash());
+
+        assert_eq!(host.diffs_since(host.hash()), Some(Vec::new()));
+        assert_eq!(host.diffs_since(start.content_hash()).unwrap().len(), 5);
+        assert_eq!(host.diffs_since(0), None);
+        assert!(!guest.rollback_to(0));
+        let mut forgetful = Lockstep::new(start, 0);
+        forgetful.step();
+        assert_eq!(forgetful.diffs().count(), 0);
+        assert_eq!(forgetful.rollback(1), 0);
+    }
+}
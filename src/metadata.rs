@@ -0,0 +1,294 @@
+//! Attaching user data to the alive cells of a game of life
+
+use crate::collections::HashMap;
+use crate::life::Life;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Callback computing the data of a born cell from its cell and its parents with their data.
+type InheritanceFn<const N: usize, T> = Box<dyn FnMut(&[i64; N], &[(&[i64; N], &T)]) -> Option<T> + Send>;
+
+/// Policy giving data to the cells born in a generation of a [TaggedLife]
+///
+/// The parents of a born cell are the cells alive in its neighbourhood in the previous generation which had data.
+pub enum Inheritance<const N: usize, T> {
+    /// Born cells have no data.
+    None,
+    /// Born cells copy the most common data of their parents, the first parent in the order of [Life::neighbours] winning ties.
+    /// Born cells without parents have no data.
+    Majority,
+    /// Born cells get the data computed by a callback from the cell and its parents (possibly none), or no data if it returns [None].
+    With(InheritanceFn<N, T>),
+}
+impl<const N: usize, T> Inheritance<N, T> {
+    /// Create a policy computing the data of born cells with a callback.
+    /// # Arguments
+    /// * `f` - The callback, receiving the born cell and its parents with their data, in the order of [Life::neighbours].
+    pub fn with(f: impl FnMut(&[i64; N], &[(&[i64; N], &T)]) -> Option<T> + Send + 'static) -> Self {
+        Self::With(Box::new(f))
+    }
+}
+impl<const N: usize, T> fmt::Debug for Inheritance<N, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Majority => write!(f, "Majority"),
+            Self::With(_) => write!(f, "With(..)"),
+        }
+    }
+}
+
+/// Game of life with user data attached to alive cells, such as the player owning them or their energy
+///
+/// The game of life is owned and only modified through [next_generation](Self::next_generation),
+/// [step](Self::step) and [update](Self::update), so the data always belongs to alive cells:
+/// the data of dying cells is dropped, surviving cells keep theirs and born cells get data by the [Inheritance] policy.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::metadata::{Inheritance, TaggedLife};
+///
+/// let mut life = conways_game_of_life();
+/// // a blinker
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut tagged = TaggedLife::new(life, Inheritance::Majority);
+/// tagged.set(&[0, 0], "red");
+/// tagged.set(&[1, 0], "red");
+/// tagged.set(&[2, 0], "blue");
+///
+/// tagged.next_generation();
+/// // the middle cell survives, the born cells have two red parents and one blue
+/// assert_eq!(tagged.get(&[1, 0]), Some(&"red"));
+/// assert_eq!(tagged.get(&[1, 1]), Some(&"red"));
+/// assert_eq!(tagged.get(&[0, 0]), None);
+/// assert_eq!(tagged.len(), 3);
+/// ```
+#[derive(Debug)]
+pub struct TaggedLife<const N: usize, T> {
+    /// The game of life.
+    life: Life<N>,
+    /// The data of the alive cells which have any.
+    data: HashMap<[i64; N], T>,
+    /// The policy giving data to born cells.
+    inheritance: Inheritance<N, T>,
+}
+impl<const N: usize, T: Clone + PartialEq> TaggedLife<N, T> {
+    /// Start attaching data to the cells of a game of life, none of which has data yet.
+    /// # Arguments
+    /// * `life` - The game of life.
+    /// * `inheritance` - The policy giving data to born cells.
+    pub fn new(life: Life<N>, inheritance: Inheritance<N, T>) -> Self {
+        Self {
+            life,
+            data: HashMap::new(),
+            inheritance,
+        }
+    }
+
+    /// Get the game of life.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Get the policy giving data to born cells.
+    pub fn inheritance(&self) -> &Inheritance<N, T> {
+        &self.inheritance
+    }
+
+    /// Set the policy giving data to born cells.
+    pub fn set_inheritance(&mut self, inheritance: Inheritance<N, T>) {
+        self.inheritance = inheritance;
+    }
+
+    /// Get the number of cells with data.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Check whether no cell has data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the data of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// The data, or [None] if the cell is dead or has no data.
+    pub fn get(&self, cell: &[i64; N]) -> Option<&T> {
+        self.data.get(cell)
+    }
+
+    /// Get the data of a cell to modify it.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// The data, or [None] if the cell is dead or has no data.
+    pub fn get_mut(&mut self, cell: &[i64; N]) -> Option<&mut T> {
+        self.data.get_mut(cell)
+    }
+
+    /// Set the data of a cell, making it alive.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// * `value` - The data.
+    /// # Returns
+    /// The previous data of the cell, if it had any.
+    pub fn set(&mut self, cell: &[i64; N], value: T) -> Option<T> {
+        self.life.set_cell(cell, true);
+        self.data.insert(*cell, value)
+    }
+
+    /// Remove the data of a cell, leaving it alive.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// The data of the cell, if it had any.
+    pub fn remove(&mut self, cell: &[i64; N]) -> Option<T> {
+        self.data.remove(cell)
+    }
+
+    /// Iterate over the cells with data and their data, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[i64; N], &T)> {
+        self.data.iter()
+    }
+
+    /// Compute the next generation, dropping the data of dying cells and giving data to born cells.
+    pub fn next_generation(&mut self) {
+        self.life.next_generation();
+        let previous = self.life.previous_alive_cells();
+        // the parents are read before the data of the dying cells is dropped
+        let mut born = Vec::new();
+        let inherits = !matches!(self.inheritance, Inheritance::None);
+        for cell in self.life.born_cells().filter(|_| inherits) {
+            let parents: Vec<(&[i64; N], &T)> = Life::<N>::neighbours(cell)
+                .filter(|neighbour| previous.contains(neighbour))
+                .filter_map(|neighbour| self.data.get_key_value(&neighbour))
+                .collect();
+            let value = match &mut self.inheritance {
+                Inheritance::None => None,
+                Inheritance::Majority => majority(&parents).cloned(),
+                Inheritance::With(f) => f(cell, &parents),
+            };
+            if let Some(value) = value {
+                born.push((*cell, value));
+            }
+        }
+        for cell in self.life.died_cells() {
+            self.data.remove(cell);
+        }
+        self.data.extend(born);
+    }
+
+    /// Advance a number of generations.
+    /// # Arguments
+    /// * `generations` - The number of generations.
+    pub fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.next_generation();
+        }
+    }
+
+    /// Modify the game of life (e.g. edit cells or change rules), then drop the data of the cells it killed.
+    /// Cells it made alive have no data.
+    /// # Arguments
+    /// * `f` - The modification.
+    /// # Returns
+    /// The value returned by `f`.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut Life<N>) -> R) -> R {
+        let result = f(&mut self.life);
+        let alive_cells = self.life.alive_cells();
+        self.data.retain(|cell, _| alive_cells.contains(cell));
+        result
+    }
+
+    /// Stop attaching data and get back the game of life and the data of its cells.
+    pub fn into_parts(self) -> (Life<N>, HashMap<[i64; N], T>) {
+        (self.life, self.data)
+    }
+}
+
+/// Get the most common data of parents, the first parent winning ties.
+pub(crate) fn majority<'a, const N: usize, T: PartialEq>(parents: &[(&[i64; N], &'a T)]) -> Option<&'a T> {
+    let count = |value: &T| parents.iter().filter(|(_, other)| *other == value).count();
+    let mut best: Option<(&T, usize)> = None;
+    for (_, value) in parents {
+        let votes = count(value);
+        if best.is_none_or(|(_, best)| votes > best) {
+            best = Some((value, votes));
+        }
+    }
+    best.map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use alloc::format;
+
+    #[test]
+    fn test_inheritance() {
+        let mut life = conways_game_of_life();
+        // a glider
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+
+        let mut tagged = TaggedLife::new(life.clone(), Inheritance::None);
+        for cell in life.alive_cells() {
+            tagged.set(cell, 1u32);
+        }
+        tagged.step(4);
+        // after a period, only the cells that survived all along keep their data
+        assert!(tagged.len() < 5);
+        assert!(tagged.iter().all(|(cell, _)| tagged.life().get_cell(cell)));
+
+        // energy is the sum of the parents' energy
+        let mut tagged = TaggedLife::new(
+            life.clone(),
+            Inheritance::with(|_, parents: &[(&[i64; 2], &u32)]| Some(parents.iter().map(|(_, energy)| **energy).sum())),
+        );
+        for cell in life.alive_cells() {
+            tagged.set(cell, 1);
+        }
+        tagged.next_generation();
+        let born: Vec<[i64; 2]> = tagged.life().born_cells().copied().collect();
+        assert!(!born.is_empty());
+        assert!(born.iter().all(|cell| tagged.get(cell) == Some(&3)));
+        assert_eq!(tagged.len(), 5);
+
+        *tagged.get_mut(&born[0]).unwrap() = 10;
+        assert_eq!(tagged.remove(&born[0]), Some(10));
+        assert_eq!(tagged.remove(&born[0]), None);
+        assert!(tagged.life().get_cell(&born[0]));
+        assert!(matches!(tagged.inheritance(), Inheritance::With(_)));
+        tagged.set_inheritance(Inheritance::Majority);
+        assert_eq!(format!("{:?}", tagged.inheritance()), "Majority");
+    }
+
+    #[test]
+    fn test_update() {
+        let mut tagged = TaggedLife::new(conways_game_of_life(), Inheritance::Majority);
+        assert!(tagged.is_empty());
+        assert_eq!(tagged.set(&[0, 0], 'a'), None);
+        assert_eq!(tagged.set(&[0, 0], 'b'), Some('a'));
+        tagged.set(&[5, 5], 'c');
+        assert!(tagged.update(|life| life.set_cell(&[0, 0], false)));
+        assert_eq!(tagged.get(&[0, 0]), None);
+        assert_eq!(tagged.get(&[5, 5]), Some(&'c'));
+
+        // born cells without tagged parents stay untagged
+        tagged.update(|life| life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect()));
+        assert!(tagged.is_empty());
+        tagged.next_generation();
+        assert!(tagged.is_empty());
+        let (life, data) = tagged.into_parts();
+        assert_eq!(life.alive_cells().len(), 3);
+        assert!(data.is_empty());
+
+        assert_eq!(majority::<2, char>(&[(&[0, 0], &'a'), (&[1, 0], &'b'), (&[2, 0], &'b')]), Some(&'b'));
+        assert_eq!(majority::<2, char>(&[(&[0, 0], &'a'), (&[1, 0], &'b')]), Some(&'a'));
+        assert_eq!(majority::<2, char>(&[]), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Monitoring of long-running simulations
+
+use crate::life::Life;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Running totals and latest values describing a simulation, for monitoring
+///
+/// Metrics are recorded after every generation, either by stepping through [step](Self::step), which also measures
+/// the latency, or with [record](Self::record) for generations computed elsewhere.
+/// [to_prometheus](Self::to_prometheus) renders them in the Prometheus text format, as served by the `http` feature on `GET /metrics`.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::metrics::Metrics;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut metrics = Metrics::new();
+/// for _ in 0..10 {
+///     metrics.step(&mut life);
+/// }
+/// assert_eq!(metrics.generations(), 10);
+/// assert_eq!(metrics.population(), 3);
+/// assert_eq!((metrics.births(), metrics.deaths()), (20, 20));
+/// assert!(metrics.to_prometheus().contains("\nndlife_births_total 20\n"));
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Metrics {
+    /// The number of recorded generations.
+    generations: u64,
+    /// The population after the last recorded generation.
+    population: usize,
+    /// The number of cells born in the recorded generations.
+    births: u64,
+    /// The number of cells died in the recorded generations.
+    deaths: u64,
+    /// The latency of the last recorded generation.
+    last_step: Duration,
+    /// The total latency of the recorded generations.
+    total_step: Duration,
+    /// The memory estimate after the last recorded generation, in bytes.
+    memory: usize,
+}
+impl Metrics {
+    /// Create metrics without any recorded generation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance the game of life by one generation, measuring it and recording the metrics.
+    /// # Arguments
+    /// * `life` - The game of life to advance.
+    pub fn step<const N: usize>(&mut self, life: &mut Life<N>) {
+        let start = Instant::now();
+        life.next_generation();
+        self.record(life, start.elapsed());
+    }
+
+    /// Record a generation that was just computed.
+    /// # Arguments
+    /// * `life` - The game of life, right after the generation.
+    /// * `latency` - How long computing the generation took.
+    pub fn record<const N: usize>(&mut self, life: &Life<N>, latency: Duration) {
+        self.generations += 1;
+        self.population = life.alive_cells().len();
+        self.births += life.born_cells().count() as u64;
+        self.deaths += life.died_cells().count() as u64;
+        self.last_step = latency;
+        self.total_step += latency;
+        self.memory = life.memory_estimate();
+    }
+
+    /// Get the number of recorded generations.
+    pub fn generations(&self) -> u64 {
+        self.generations
+    }
+
+    /// Get the population after the last recorded generation.
+    pub fn population(&self) -> usize {
+        self.population
+    }
+
+    /// Get the number of cells born in the recorded generations.
+    pub fn births(&self) -> u64 {
+        self.births
+    }
+
+    /// Get the number of cells died in the recorded generations.
+    pub fn deaths(&self) -> u64 {
+        self.deaths
+    }
+
+    /// Get the latency of the last recorded generation.
+    pub fn last_step_latency(&self) -> Duration {
+        self.last_step
+    }
+
+    /// Get the mean latency of the recorded generations, or zero if none were recorded.
+    pub fn mean_step_latency(&self) -> Duration {
+        match self.generations {
+            0 => Duration::ZERO,
+            generations => Duration::from_secs_f64(self.total_step.as_secs_f64() / generations as f64),
+        }
+    }
+
+    /// Get the [memory estimate](Life::memory_estimate) after the last recorded generation, in bytes.
+    pub fn memory_estimate(&self) -> usize {
+        self.memory
+    }
+
+    /// Render the metrics in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let metrics: [(&str, &str, &str, String); 7] = [
+            ("ndlife_generations_total", "counter", "Recorded generations.", self.generations.to_string()),
+            ("ndlife_population", "gauge", "Alive cells after the last generation.", self.population.to_string()),
+            ("ndlife_births_total", "counter", "Cells born in the recorded generations.", self.births.to_string()),
+            ("ndlife_deaths_total", "counter", "Cells died in the recorded generations.", self.deaths.to_string()),
+            (
+                "ndlife_step_seconds_total",
+                "counter",
+                "Time spent computing the recorded generations.",
+                self.total_step.as_secs_f64().to_string(),
+            ),
+            (
+                "ndlife_last_step_seconds",
+                "gauge",
+                "Time spent computing the last generation.",
+                self.last_step.as_secs_f64().to_string(),
+            ),
+            ("ndlife_memory_bytes", "gauge", "Estimated heap memory of the cells.", self.memory.to_string()),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = write!(text, "# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name, value);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_record() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut metrics = Metrics::new();
+        assert_eq!(metrics.mean_step_latency(), Duration::ZERO);
+
+        life.next_generation();
+        metrics.record(&life, Duration::from_millis(3));
+        life.next_generation();
+        metrics.record(&life, Duration::from_millis(1));
+        assert_eq!(metrics.generations(), 2);
+        assert_eq!(metrics.population(), 3);
+        assert_eq!((metrics.births(), metrics.deaths()), (4, 4));
+        assert_eq!(metrics.last_step_latency(), Duration::from_millis(1));
+        assert_eq!(metrics.mean_step_latency(), Duration::from_millis(2));
+        assert_eq!(metrics.memory_estimate(), life.memory_estimate());
+        assert!(metrics.memory_estimate() > 0);
+    }
+
+    #[test]
+    fn test_to_prometheus() {
+        let metrics = Metrics {
+            generations: 2,
+            population: 3,
+            births: 4,
+            deaths: 5,
+            last_step: Duration::from_millis(250),
+            total_step: Duration::from_millis(1500),
+            memory: 1024,
+        };
+        let text = metrics.to_prometheus();
+        assert!(text.starts_with("# HELP ndlife_generations_total Recorded generations.\n# TYPE ndlife_generations_total counter\nndlife_generations_total 2\n"));
+        for line in [
+            "ndlife_population 3",
+            "ndlife_deaths_total 5",
+            "ndlife_step_seconds_total 1.5",
+            "ndlife_last_step_seconds 0.25",
+            "ndlife_memory_bytes 1024",
+        ] {
+            assert!(text.lines().any(|text_line| text_line == line), "missing {}", line);
+        }
+        assert_eq!(text.lines().count(), 21);
+    }
+}
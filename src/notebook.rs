@@ -0,0 +1,129 @@
+//! Rich display of 2-dimensional games of life in Jupyter notebooks running the evcxr kernel
+
+use crate::life::Life;
+use crate::region::Region;
+use crate::view::View;
+use std::fmt::Write;
+
+/// The largest width or height of a rendered game of life, in pixels.
+const MAX_IMAGE_SIZE: i64 = 600;
+
+/// The largest size of a rendered cell, in pixels.
+const MAX_CELL_SIZE: i64 = 12;
+
+impl View<'_, 2> {
+    /// Render the view as an SVG image, with black alive cells on a white background and the top row first.
+    /// # Arguments
+    /// * `cell_size` - The width and height of a cell, in pixels.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0]].into_iter().collect());
+    ///
+    /// let svg = life.view([0..=1, 0..=0]).to_svg(10);
+    /// assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10""#));
+    /// assert!(svg.contains(r#"<rect x="0" y="0" width="1" height="1"/>"#));
+    /// ```
+    pub fn to_svg(&self, cell_size: u32) -> String {
+        let region = self.region();
+        let (min, max) = (region.min(), region.max());
+        let [width, height] = if region.is_empty() { [0, 0] } else { [max[0] - min[0] + 1, max[1] - min[1] + 1] };
+        let cell_size = i128::from(cell_size);
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+            i128::from(width) * cell_size,
+            i128::from(height) * cell_size,
+            width,
+            height
+        );
+        let _ = write!(svg, r#"<rect width="{}" height="{}" fill="white"/><g fill="black">"#, width, height);
+        let mut cells: Vec<[i64; 2]> = self.alive_cells().collect();
+        cells.sort_unstable_by_key(|[x, y]| (-y, *x));
+        for [x, y] in cells {
+            let _ = write!(svg, r#"<rect x="{}" y="{}" width="1" height="1"/>"#, x - min[0], max[1] - y);
+        }
+        svg.push_str("</g></svg>");
+        svg
+    }
+}
+
+impl Life<2> {
+    /// Render the game of life as HTML: an SVG image of the alive cells with a margin of one cell,
+    /// and a caption with the age, population and bounding box.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// let html = life.to_html();
+    /// assert!(html.contains("<svg"));
+    /// assert!(html.contains("generation 0, population 3, cells [0, 0] to [2, 0]"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        let (region, caption) = match Region::bounding(self.alive_cells()) {
+            Some(bounds) => (
+                bounds.inflate(1),
+                format!(
+                    "generation {}, population {}, cells {:?} to {:?}",
+                    self.age(),
+                    self.alive_cells().len(),
+                    bounds.min(),
+                    bounds.max()
+                ),
+            ),
+            None => (Region::new([0, 0], [0, 0]), format!("generation {}, no alive cells", self.age())),
+        };
+        let (min, max) = (region.min(), region.max());
+        let extent = (max[0].saturating_sub(min[0]).saturating_add(1)).max(max[1].saturating_sub(min[1]).saturating_add(1));
+        let cell_size = (MAX_IMAGE_SIZE / extent).clamp(1, MAX_CELL_SIZE);
+        format!(
+            r#"<div style="display:inline-block">{}<div style="font-family:monospace;font-size:small">{}</div></div>"#,
+            self.view(region).to_svg(cell_size as u32),
+            caption
+        )
+    }
+
+    /// Display the game of life inline in a Jupyter notebook running the evcxr kernel.
+    ///
+    /// evcxr calls this method when a game of life is the value of a cell, and renders the [HTML](Self::to_html)
+    /// it prints instead of the [Debug](std::fmt::Debug) output. Outside of a notebook it just prints the HTML.
+    pub fn evcxr_display(&self) {
+        println!("EVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT", self.to_html());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_to_svg() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 1], [5, 5]].into_iter().collect());
+        assert_eq!(
+            life.view([0..=1, 0..=1]).to_svg(3),
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="6" height="6" viewBox="0 0 2 2" shape-rendering="crispEdges">"#,
+                r#"<rect width="2" height="2" fill="white"/><g fill="black">"#,
+                r#"<rect x="1" y="0" width="1" height="1"/><rect x="0" y="1" width="1" height="1"/></g></svg>"#
+            )
+        );
+        let (start, end) = (1, 0);
+        assert!(life.view([start..=end, 0..=1]).to_svg(3).contains(r#"width="0" height="0""#));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let mut life = conways_game_of_life();
+        assert!(life.to_html().contains("generation 0, no alive cells"));
+        life.set_alive_cells([[0, 0], [1000, 0]].into_iter().collect());
+        let html = life.to_html();
+        // 1003 cells wide, scaled down to the smallest cell size
+        assert!(html.contains(r#"width="1003" height="3" viewBox="0 0 1003 3""#));
+        assert!(html.contains("population 2, cells [0, 0] to [1000, 0]"));
+    }
+}
@@ -0,0 +1,261 @@
+//! Orbiting camera projecting 3-dimensional games of life, the maths of a 3D viewer without the drawing
+
+use crate::life::Life;
+use crate::region::Region;
+use std::f64::consts::FRAC_PI_2;
+
+/// The largest pitch, just short of looking straight up or down.
+const MAX_PITCH: f64 = FRAC_PI_2 - 1e-3;
+
+/// Alive cell of a 3-dimensional game of life projected on the screen by an [OrbitCamera]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ProjectedCube {
+    /// The coordinates of the cell.
+    pub cell: [i64; 3],
+    /// The screen point of the centre of the cube, in pixels from the top-left corner with `y` down.
+    pub centre: [f32; 2],
+    /// The apparent width of the cube at its centre, in pixels.
+    pub size: f32,
+    /// The distance from the camera to the centre of the cube.
+    pub depth: f32,
+}
+
+/// Camera orbiting a target point, with perspective projection of the alive cells of a [Life<3>]
+///
+/// The camera turns around the target with [orbit](Self::orbit), moves closer or farther with [zoom](Self::zoom)
+/// and frames the alive cells with [fit](Self::fit), the usual orbit controls of 3D viewers; stepping stays up to the application.
+/// [cubes](Self::cubes) projects the cells, ordered from the farthest to the nearest, so a painter's algorithm draws
+/// them correctly with any 2D or 3D toolkit, for instance as instanced cubes scaled by their apparent size.
+/// The camera does not draw anything, open a window or read input: the application renders the cubes and maps
+/// its mouse and keys to the camera, as the `ndlife-viewer3d` binary of the `viewer3d` feature does.
+/// # Example
+/// ```
+/// use ndlife::life::Life;
+/// use ndlife::orbit::OrbitCamera;
+///
+/// let mut life = Life::<3>::empty_with_rulestring("B5/S4-6").unwrap();
+/// life.set_alive_cells([[0, 0, 0], [1, 0, 0], [0, 1, 0], [0, 0, 5]].into_iter().collect());
+///
+/// let mut camera = OrbitCamera::new();
+/// camera.fit(&life);
+/// camera.orbit(0.3, 0.2);
+/// let cubes = camera.cubes(&life, [640.0, 480.0]);
+/// assert_eq!(cubes.len(), 4);
+/// assert!(cubes.windows(2).all(|pair| pair[0].depth >= pair[1].depth));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrbitCamera {
+    /// The point the camera looks at and orbits around.
+    target: [f64; 3],
+    /// The angle around the `z` axis, in radians.
+    yaw: f64,
+    /// The angle above the `xy` plane, in radians.
+    pitch: f64,
+    /// The distance from the target.
+    distance: f64,
+    /// The vertical field of view, in radians.
+    field_of_view: f64,
+}
+impl OrbitCamera {
+    /// Create a camera 20 cells away from the origin, looking at it slightly from above, with a 60° field of view.
+    pub fn new() -> Self {
+        Self {
+            target: [0.5; 3],
+            yaw: -FRAC_PI_2,
+            pitch: 0.5,
+            distance: 20.0,
+            field_of_view: 60f64.to_radians(),
+        }
+    }
+
+    /// Get the point the camera looks at.
+    pub fn target(&self) -> [f64; 3] {
+        self.target
+    }
+
+    /// Set the point the camera looks at; the centre of cell `c` is at `c + 0.5` along every axis.
+    pub fn set_target(&mut self, target: [f64; 3]) {
+        self.target = target;
+    }
+
+    /// Get the distance from the target.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    /// Get the vertical field of view, in radians.
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    /// Get the position of the camera.
+    pub fn position(&self) -> [f64; 3] {
+        let horizontal = self.distance * self.pitch.cos();
+        [
+            self.target[0] + horizontal * self.yaw.cos(),
+            self.target[1] + horizontal * self.yaw.sin(),
+            self.target[2] + self.distance * self.pitch.sin(),
+        ]
+    }
+
+    /// Turn the camera around the target.
+    /// # Arguments
+    /// * `yaw` - The angle to turn around the vertical `z` axis, in radians.
+    /// * `pitch` - The angle to turn upwards, in radians; the camera stops just short of the poles.
+    pub fn orbit(&mut self, yaw: f64, pitch: f64) {
+        self.yaw = (self.yaw + yaw).rem_euclid(std::f64::consts::TAU);
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Move the camera towards the target, dividing the distance by a factor.
+    /// # Arguments
+    /// * `factor` - Greater than 1 to move closer, between 0 and 1 to move away.
+    pub fn zoom(&mut self, factor: f64) {
+        if factor > 0.0 {
+            self.distance = (self.distance / factor).max(1e-3);
+        }
+    }
+
+    /// Look at the centre of the alive cells from far enough to see all of them, keeping the direction of the camera.
+    /// # Arguments
+    /// * `life` - The game of life to frame; an empty one resets the target to the origin.
+    pub fn fit(&mut self, life: &Life<3>) {
+        let Some(bounds) = Region::bounding(life.alive_cells()) else {
+            self.target = [0.5; 3];
+            return;
+        };
+        let (min, max) = (bounds.min(), bounds.max());
+        self.target = std::array::from_fn(|axis| (min[axis] as f64 + max[axis] as f64 + 1.0) / 2.0);
+        let radius = (0..3).map(|axis| (max[axis] as f64 - min[axis] as f64 + 1.0).powi(2)).sum::<f64>().sqrt() / 2.0;
+        self.distance = radius / (self.field_of_view / 2.0).sin() + 1.0;
+    }
+
+    /// Project a point on the screen.
+    /// # Arguments
+    /// * `point` - The point in space.
+    /// * `size` - The width and height of the screen, in pixels.
+    /// # Returns
+    /// The screen point with `y` down and the depth, or [None] if the point is behind the camera.
+    pub fn project(&self, point: [f64; 3], size: [f32; 2]) -> Option<([f32; 2], f32)> {
+        let (forward, right, up) = self.axes();
+        let position = self.position();
+        let relative: [f64; 3] = std::array::from_fn(|axis| point[axis] - position[axis]);
+        let depth = dot(relative, forward);
+        if depth <= 1e-6 {
+            return None;
+        }
+        let scale = f64::from(size[1]) / 2.0 / (self.field_of_view / 2.0).tan() / depth;
+        Some((
+            [
+                (f64::from(size[0]) / 2.0 + dot(relative, right) * scale) as f32,
+                (f64::from(size[1]) / 2.0 - dot(relative, up) * scale) as f32,
+            ],
+            depth as f32,
+        ))
+    }
+
+    /// Project the alive cells in front of the camera, ordered from the farthest to the nearest.
+    /// # Arguments
+    /// * `life` - The game of life to project.
+    /// * `size` - The width and height of the screen, in pixels.
+    pub fn cubes(&self, life: &Life<3>, size: [f32; 2]) -> Vec<ProjectedCube> {
+        let focal = f64::from(size[1]) / 2.0 / (self.field_of_view / 2.0).tan();
+        let mut cubes: Vec<ProjectedCube> = life
+            .alive_cells()
+            .iter()
+            .filter_map(|cell| {
+                let (centre, depth) = self.project(cell.map(|coordinate| coordinate as f64 + 0.5), size)?;
+                Some(ProjectedCube {
+                    cell: *cell,
+                    centre,
+                    size: (focal / f64::from(depth)) as f32,
+                    depth,
+                })
+            })
+            .collect();
+        cubes.sort_by(|a, b| b.depth.total_cmp(&a.depth).then(a.cell.cmp(&b.cell)));
+        cubes
+    }
+
+    /// Get the forward, right and up unit vectors of the camera.
+    fn axes(&self) -> ([f64; 3], [f64; 3], [f64; 3]) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let forward = [-cos_pitch * cos_yaw, -cos_pitch * sin_yaw, -sin_pitch];
+        let right = [-sin_yaw, cos_yaw, 0.0];
+        let up = [-sin_pitch * cos_yaw, -sin_pitch * sin_yaw, cos_pitch];
+        (forward, right, up)
+    }
+}
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the dot product of two vectors.
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: [f32; 2] = [200.0, 100.0];
+
+    fn life(cells: impl IntoIterator<Item = [i64; 3]>) -> Life<3> {
+        let mut life = Life::<3>::empty_with_rulestring("B5/S4-6").unwrap();
+        life.set_alive_cells(cells.into_iter().collect());
+        life
+    }
+
+    #[test]
+    fn test_project() {
+        let mut camera = OrbitCamera::new();
+        camera.orbit(0.0, -0.5);
+        // level with the target, looking along +y
+        let position = camera.position();
+        assert!((position[0] - 0.5).abs() < 1e-9 && (position[1] + 19.5).abs() < 1e-9 && (position[2] - 0.5).abs() < 1e-9);
+        let (centre, depth) = camera.project(camera.target(), SIZE).unwrap();
+        assert_eq!(centre, [100.0, 50.0]);
+        assert!((depth - 20.0).abs() < 1e-4);
+
+        // +x is to the right and +z is up on the screen
+        let (right, _) = camera.project([1.5, 0.5, 0.5], SIZE).unwrap();
+        let (up, _) = camera.project([0.5, 0.5, 1.5], SIZE).unwrap();
+        assert!(right[0] > 100.0 && (right[1] - 50.0).abs() < 1e-3);
+        assert!(up[1] < 50.0 && (up[0] - 100.0).abs() < 1e-3);
+        assert_eq!(camera.project([0.5, -30.0, 0.5], SIZE), None);
+    }
+
+    #[test]
+    fn test_controls() {
+        let mut camera = OrbitCamera::new();
+        camera.orbit(0.0, 10.0);
+        assert_eq!(camera.pitch, MAX_PITCH);
+        camera.zoom(2.0);
+        assert_eq!(camera.distance(), 10.0);
+        camera.zoom(0.0);
+        assert_eq!(camera.distance(), 10.0);
+        assert_eq!(camera.field_of_view(), 60f64.to_radians());
+
+        camera.fit(&life([[0, 0, 0], [3, 4, 0]]));
+        assert_eq!(camera.target(), [2.0, 2.5, 0.5]);
+        camera.fit(&life([]));
+        assert_eq!(camera.target(), [0.5; 3]);
+    }
+
+    #[test]
+    fn test_cubes() {
+        let mut camera = OrbitCamera::new();
+        camera.orbit(0.0, -0.5);
+        let life = life([[0, 0, 0], [0, 5, 0], [0, -5, 0], [0, -40, 0]]);
+        let cubes = camera.cubes(&life, SIZE);
+        // the cell behind the camera is dropped, the others are drawn from the farthest
+        assert_eq!(cubes.iter().map(|cube| cube.cell).collect::<Vec<_>>(), vec![[0, 5, 0], [0, 0, 0], [0, -5, 0]]);
+        assert!(cubes[0].size < cubes[1].size && cubes[1].size < cubes[2].size);
+        camera.fit(&life);
+        assert_eq!(camera.cubes(&life, SIZE).len(), 4);
+    }
+}
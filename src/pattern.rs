@@ -0,0 +1,685 @@
+//! Finite patterns of cells that can be placed into a game of life
+
+use crate::collections::HashSet;
+use crate::error::Error;
+use crate::geometry;
+use crate::life::Life;
+use crate::region::Region;
+use crate::symmetry::Symmetry;
+use crate::transform::Transform;
+use crate::view::View;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
+
+/// Finite set of alive cells, independent of any rules
+/// # Example
+/// ```
+/// use ndlife::pattern::Pattern;
+///
+/// let glider: Pattern<2> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+///
+/// assert_eq!(glider.len(), 5);
+/// assert!(glider.contains(&[2, 1]));
+/// assert_eq!(glider.bounding_box(), Some(([0, 0], [2, 2])));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pattern<const N: usize> {
+    /// The alive cells of the pattern.
+    cells: HashSet<[i64; N]>,
+}
+impl<const N: usize> Pattern<N> {
+    /// Create a new pattern with given alive cells.
+    /// # Arguments
+    /// * `cells` - A set of coordinates of alive cells.
+    pub fn new(cells: HashSet<[i64; N]>) -> Self {
+        Self { cells }
+    }
+
+    /// Get the alive cells of the pattern.
+    pub fn cells(&self) -> &HashSet<[i64; N]> {
+        &self.cells
+    }
+
+    /// Convert the pattern into its set of alive cells.
+    pub fn into_cells(self) -> HashSet<[i64; N]> {
+        self.cells
+    }
+
+    /// Get the number of alive cells in the pattern.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Get whether the pattern has no alive cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Get whether a cell of the pattern is alive.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    pub fn contains(&self, cell: &[i64; N]) -> bool {
+        self.cells.contains(cell)
+    }
+
+    /// Get the smallest axis-aligned bounding box containing all alive cells.
+    /// # Returns
+    /// [Some] with the minimum and maximum coordinates (inclusive) along each axis, or [None] if the pattern is empty.
+    pub fn bounding_box(&self) -> Option<([i64; N], [i64; N])> {
+        geometry::bounding_box(&self.cells)
+    }
+
+    /// Embed the pattern into a higher-dimensional space by fixing the extra coordinates.
+    ///
+    /// The coordinates of the pattern become the first `N` coordinates of the embedded cells.
+    /// `M` must be greater than `N`, which is checked at compile time.
+    /// # Arguments
+    /// * `fixed` - The values of the `M - N` extra coordinates.
+    /// # Returns
+    /// A [Result] containing the embedded pattern, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the length of `fixed` is not `M - N`.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let blinker: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+    ///
+    /// let embedded: Pattern<4> = blinker.embed(&[5, -1]).unwrap();
+    /// assert_eq!(embedded, [[0, 0, 5, -1], [1, 0, 5, -1], [2, 0, 5, -1]].into_iter().collect());
+    /// ```
+    pub fn embed<const M: usize>(&self, fixed: &[i64]) -> Result<Pattern<M>, Error> {
+        const { assert!(M > N, "the pattern can only be embedded into a space of higher dimension") };
+        if fixed.len() != M - N {
+            return Err(Error::DimensionMismatch(M - N, fixed.len()));
+        }
+        Ok(self.cells.iter().map(|cell| core::array::from_fn(|i| if i < N { cell[i] } else { fixed[i - N] })).collect())
+    }
+
+    /// Extrude the pattern into a higher-dimensional space across ranges of the extra coordinates.
+    ///
+    /// The coordinates of the pattern become the first `N` coordinates of the extruded cells,
+    /// and every cell is repeated for each combination of the extra coordinates.
+    /// `M` must be greater than `N`, which is checked at compile time.
+    /// # Arguments
+    /// * `ranges` - The inclusive ranges of the `M - N` extra coordinates.
+    /// # Returns
+    /// A [Result] containing the extruded pattern, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the length of `ranges` is not `M - N`.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let blinker: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+    ///
+    /// let extruded: Pattern<3> = blinker.extrude(&[0..=1]).unwrap();
+    /// assert_eq!(extruded, [[0, 0, 0], [1, 0, 0], [2, 0, 0], [0, 0, 1], [1, 0, 1], [2, 0, 1]].into_iter().collect());
+    /// ```
+    pub fn extrude<const M: usize>(&self, ranges: &[RangeInclusive<i64>]) -> Result<Pattern<M>, Error> {
+        const { assert!(M > N, "the pattern can only be extruded into a space of higher dimension") };
+        if ranges.len() != M - N {
+            return Err(Error::DimensionMismatch(M - N, ranges.len()));
+        }
+        let min: [i64; M] = core::array::from_fn(|i| if i < N { 0 } else { *ranges[i - N].start() });
+        let max: [i64; M] = core::array::from_fn(|i| if i < N { 0 } else { *ranges[i - N].end() });
+        let layers: Vec<[i64; M]> = geometry::box_cells(min, max).collect();
+        Ok(self
+            .cells
+            .iter()
+            .flat_map(|cell| layers.iter().map(move |layer| core::array::from_fn(|i| if i < N { cell[i] } else { layer[i] })))
+            .collect())
+    }
+
+    /// Get the union of the pattern with its images under every element of a symmetry group.
+    ///
+    /// The symmetries fix the origin.
+    /// # Arguments
+    /// * `symmetry` - The symmetry group.
+    /// # Returns
+    /// A [Result] containing the symmetric pattern, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If the group acts in a plane and `N` is less than 2.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    /// use ndlife::symmetry::Symmetry;
+    ///
+    /// let pattern: Pattern<2> = [[1, 2]].into_iter().collect();
+    ///
+    /// let symmetric = pattern.symmetrize(Symmetry::D4).unwrap();
+    /// assert_eq!(symmetric, [[1, 2], [-1, 2], [1, -2], [-1, -2]].into_iter().collect());
+    /// ```
+    pub fn symmetrize(&self, symmetry: Symmetry) -> Result<Self, Error> {
+        let elements = symmetry.elements::<N>()?;
+        Ok(self.cells.iter().flat_map(|cell| elements.iter().map(|element| element.apply(cell))).collect())
+    }
+
+    /// Get the canonical form of the pattern, the same for every pattern congruent to it.
+    ///
+    /// Two patterns are congruent if a [signed permutation](Symmetry::SignedPermutations) of the axes followed by a translation
+    /// maps one onto the other. The canonical form is the image of the pattern under the symmetry whose sorted cells,
+    /// translated so that the bounding box starts at the origin, come first in lexicographic order.
+    /// # Returns
+    /// The canonical form, with its bounding box starting at the origin.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let glider: Pattern<2> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+    /// let flipped: Pattern<2> = [[10, 10], [9, 10], [8, 10], [8, 11], [9, 12]].into_iter().collect();
+    ///
+    /// assert_eq!(glider.canonical_form(), flipped.canonical_form());
+    /// assert!(glider.is_congruent(&flipped));
+    /// assert!(!glider.is_congruent(&[[0, 0]].into_iter().collect()));
+    /// ```
+    pub fn canonical_form(&self) -> Self {
+        self.canonical_cells().into_iter().collect()
+    }
+
+    /// Get whether the pattern is congruent to another pattern, as defined by [canonical_form](Self::canonical_form).
+    pub fn is_congruent(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.canonical_cells() == other.canonical_cells()
+    }
+
+    /// Get the sorted cells of the canonical form.
+    pub(crate) fn canonical_cells(&self) -> Vec<[i64; N]> {
+        let elements = Symmetry::SignedPermutations.elements::<N>().expect("signed permutations exist in every dimension");
+        elements
+            .iter()
+            .map(|element| normalized(self.cells.iter().map(|cell| element.apply(cell))))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Get a view of the cells of the pattern restricted to a region, borrowing instead of copying them.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The view of the region.
+    pub fn view(&self, region: impl Into<Region<N>>) -> View<'_, N> {
+        View::new(&self.cells, region.into())
+    }
+
+    /// Get the pattern with a lattice transformation applied to every cell.
+    /// # Arguments
+    /// * `transform` - The transformation to apply.
+    /// # Returns
+    /// The transformed pattern.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    /// use ndlife::transform::Transform;
+    ///
+    /// let pattern: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+    /// let transform = Transform::rotation(0, 1, 1).unwrap().then(&Transform::translation([1, -1]));
+    /// assert_eq!(pattern.transformed(&transform), [[1, -1], [1, 0], [1, 1]].into_iter().collect());
+    /// ```
+    pub fn transformed(&self, transform: &Transform<N>) -> Self {
+        self.cells.iter().map(|cell| transform.apply(cell)).collect()
+    }
+
+    /// Get the union of two patterns.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let a: Pattern<1> = [[0], [1]].into_iter().collect();
+    /// let b: Pattern<1> = [[1], [2]].into_iter().collect();
+    ///
+    /// assert_eq!(a.union(&b), [[0], [1], [2]].into_iter().collect());
+    /// assert_eq!(a.intersection(&b), [[1]].into_iter().collect());
+    /// assert_eq!(a.difference(&b), [[0]].into_iter().collect());
+    /// assert_eq!(a.symmetric_difference(&b), [[0], [2]].into_iter().collect());
+    /// ```
+    pub fn union(&self, other: &Self) -> Self {
+        self.cells.union(&other.cells).copied().collect()
+    }
+
+    /// Get the intersection of two patterns.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.cells.intersection(&other.cells).copied().collect()
+    }
+
+    /// Get the cells of this pattern that are not in the other pattern.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.cells.difference(&other.cells).copied().collect()
+    }
+
+    /// Get the cells that are in exactly one of the two patterns.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.cells.symmetric_difference(&other.cells).copied().collect()
+    }
+}
+/// Region without cells, rendered when there are no alive cells.
+const EMPTY_REGION: Region<2> = Region::new([0, 0], [-1, -1]);
+
+impl FromStr for Pattern<2> {
+    type Err = Error;
+
+    /// Parse a 2-dimensional pattern from plaintext ASCII art.
+    ///
+    /// The format is the same as for the [pattern!](crate::pattern!) macro: `O` and `*` are alive cells, `.` are dead cells,
+    /// each line is a row with the bottom-left cell at the origin, and whitespace and blank lines are ignored.
+    /// In addition, lines starting with `!` are comments, as in the plaintext (`.cells`) file format.
+    /// # Errors
+    /// * [InvalidCharacter](Error::InvalidCharacter) - If the text contains any other character.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern;
+    /// use ndlife::pattern::Pattern;
+    ///
+    /// let glider: Pattern<2> = "!Name: Glider\n.O.\n..O\nOOO".parse().unwrap();
+    /// assert_eq!(glider, pattern!(".O.\n..O\nOOO"));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rows = Vec::new();
+        for (line_index, line) in s.lines().enumerate() {
+            if line.trim_start().starts_with('!') {
+                continue;
+            }
+            let mut row = Vec::new();
+            for (column_index, character) in line.chars().enumerate() {
+                match character {
+                    'O' | '*' => row.push(true),
+                    '.' => row.push(false),
+                    character if character.is_whitespace() => {}
+                    character => return Err(Error::InvalidCharacter(character, line_index + 1, column_index + 1)),
+                }
+            }
+            if !row.is_empty() {
+                rows.push(row);
+            }
+        }
+        let height = rows.len() as i64;
+        Ok(rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, alive)| **alive).map(move |(x, _)| [x as i64, height - 1 - y as i64]))
+            .collect())
+    }
+}
+
+impl fmt::Display for Pattern<2> {
+    /// Render the bounding box of the pattern as plaintext ASCII art, which parses back into the pattern moved to the origin.
+    ///
+    /// Alive cells are `O` and dead cells are `.`, with the row of the largest `y` first.
+    /// An empty pattern renders as an empty string.
+    /// # Example
+    /// ```
+    /// use ndlife::pattern;
+    ///
+    /// let glider = pattern!(".O.\n..O\nOOO");
+    /// assert_eq!(glider.to_string(), ".O.\n..O\nOOO");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.view(Region::bounding(&self.cells).unwrap_or(EMPTY_REGION)).fmt(f)
+    }
+}
+
+impl Life<2> {
+    /// Render a viewport of the game of life as plaintext ASCII art, in the same format as its [Display](fmt::Display) implementation.
+    /// # Arguments
+    /// * `region` - The viewport, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The [View] of the viewport, which renders it when displayed.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+    ///
+    /// assert_eq!(life.to_string(), "OOO");
+    /// assert_eq!(life.display_region([-1..=1, -1..=1]).to_string(), "...\n.OO\n...");
+    /// ```
+    pub fn display_region(&self, region: impl Into<Region<2>>) -> View<'_, 2> {
+        self.view(region)
+    }
+
+    /// Create a new 2-dimensional game of life with given rules and alive cells parsed from plaintext ASCII art.
+    ///
+    /// The art is parsed the same way as a [Pattern] is parsed with [FromStr].
+    /// # Arguments
+    /// * `birth_rules` - A set of number of neighbours required for a dead cell to become alive.
+    /// * `survival_rules` - A set of number of neighbours required for a live cell to stay alive.
+    /// * `art` - The ASCII art of the alive cells.
+    /// # Returns
+    /// A [Result] containing a new game of life if successful, or an error.
+    /// # Errors
+    /// * [InvalidCharacter](Error::InvalidCharacter) - If the art contains an invalid character.
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If birth_rules contains 0.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    ///
+    /// let life = Life::from_plaintext([3].into_iter().collect(), [2, 3].into_iter().collect(), "OO\nOO").unwrap();
+    /// assert_eq!(life.alive_cells(), &[[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect());
+    /// ```
+    pub fn from_plaintext(birth_rules: HashSet<usize>, survival_rules: HashSet<usize>, art: &str) -> Result<Self, Error> {
+        let pattern: Pattern<2> = art.parse()?;
+        Self::new_with_alive_cells(birth_rules, survival_rules, pattern.into_cells())
+    }
+}
+
+impl fmt::Display for Life<2> {
+    /// Render the bounding box of the alive cells as plaintext ASCII art, like a [Pattern].
+    ///
+    /// Use [display_region](Life::display_region) to render a fixed viewport instead.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.view(Region::bounding(self.alive_cells()).unwrap_or(EMPTY_REGION)).fmt(f)
+    }
+}
+
+impl<const N: usize> From<HashSet<[i64; N]>> for Pattern<N> {
+    fn from(cells: HashSet<[i64; N]>) -> Self {
+        Self::new(cells)
+    }
+}
+impl<const N: usize> FromIterator<[i64; N]> for Pattern<N> {
+    fn from_iter<I: IntoIterator<Item = [i64; N]>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+impl<const N: usize> IntoIterator for Pattern<N> {
+    type Item = [i64; N];
+    type IntoIter = crate::collections::hash_set::IntoIter<[i64; N]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+/// Create a 2-dimensional [Pattern] from ASCII art at compile time.
+///
+/// `O` and `*` are alive cells and `.` are dead cells. Each line of the art is a row,
+/// with the bottom-left cell at the origin, `x` increasing to the right and `y` increasing upwards.
+/// Whitespace and blank lines are ignored, and any other character fails the compilation.
+/// # Example
+/// ```
+/// use ndlife::pattern;
+/// use ndlife::pattern::Pattern;
+///
+/// let glider = pattern!(
+///     "
+///     .O.
+///     ..O
+///     OOO
+///     "
+/// );
+///
+/// let expected: Pattern<2> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+/// assert_eq!(glider, expected);
+/// ```
+///
+/// Invalid characters are rejected during compilation.
+/// ```compile_fail
+/// let invalid = ndlife::pattern!("O#O");
+/// ```
+#[macro_export]
+macro_rules! pattern {
+    ($art:expr) => {{
+        const CELLS: [[i64; 2]; $crate::pattern::__count_cells($art)] = $crate::pattern::__parse_cells($art);
+        CELLS.into_iter().collect::<$crate::pattern::Pattern<2>>()
+    }};
+}
+
+/// Count the alive cells of ASCII art, used by the [pattern!](crate::pattern!) macro.
+#[doc(hidden)]
+pub const fn __count_cells(art: &str) -> usize {
+    let bytes = art.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'O' | b'*' => count += 1,
+            b'.' | b' ' | b'\t' | b'\r' | b'\n' => {}
+            _ => panic!("invalid character in pattern, expected 'O', '*' or '.'"),
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Parse the alive cells of ASCII art, used by the [pattern!](crate::pattern!) macro.
+#[doc(hidden)]
+pub const fn __parse_cells<const L: usize>(art: &str) -> [[i64; 2]; L] {
+    let bytes = art.as_bytes();
+
+    // count the non-blank rows, to put the bottom row at y = 0
+    let mut rows = 0;
+    let mut blank = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                if !blank {
+                    rows += 1;
+                }
+                blank = true;
+            }
+            b'O' | b'*' | b'.' => blank = false,
+            _ => {}
+        }
+        i += 1;
+    }
+    if !blank {
+        rows += 1;
+    }
+
+    let mut cells = [[0; 2]; L];
+    let mut count = 0;
+    let (mut x, mut y) = (0, rows - 1);
+    let mut blank = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                if !blank {
+                    y -= 1;
+                }
+                x = 0;
+                blank = true;
+            }
+            b'O' | b'*' => {
+                cells[count] = [x, y];
+                count += 1;
+                x += 1;
+                blank = false;
+            }
+            b'.' => {
+                x += 1;
+                blank = false;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    cells
+}
+
+/// Way of combining a pasted pattern with the cells already present
+///
+/// The modes mirror the paste modes of Golly. [And](PasteMode::And) and [Copy](PasteMode::Copy)
+/// act on the whole bounding box of the pasted pattern, the other modes only on its alive cells.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum PasteMode {
+    /// Cells alive in the pattern become alive, other cells are unchanged.
+    #[default]
+    Or,
+    /// Cells alive in the pattern are toggled, other cells are unchanged.
+    Xor,
+    /// Within the bounding box of the pattern, cells stay alive only if they are also alive in the pattern.
+    And,
+    /// The bounding box of the pattern is cleared, then the cells alive in the pattern become alive.
+    Copy,
+}
+
+/// Get the sorted cells translated so that their bounding box starts at the origin.
+pub(crate) fn normalized<const N: usize>(cells: impl IntoIterator<Item = [i64; N]>) -> Vec<[i64; N]> {
+    let mut cells: Vec<[i64; N]> = cells.into_iter().collect();
+    let min: [i64; N] = core::array::from_fn(|axis| cells.iter().map(|cell| cell[axis]).min().unwrap_or(0));
+    for cell in cells.iter_mut() {
+        for axis in 0..N {
+            cell[axis] = cell[axis].wrapping_sub(min[axis]);
+        }
+    }
+    cells.sort_unstable();
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::<2>::default();
+        assert!(pattern.is_empty());
+        assert_eq!(pattern.bounding_box(), None);
+
+        let cells: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, -1]].into_iter().collect();
+        let pattern = Pattern::from(cells.clone());
+        assert_eq!(pattern.len(), 3);
+        assert!(pattern.contains(&[2, -1]));
+        assert!(!pattern.contains(&[2, 1]));
+        assert_eq!(pattern.cells(), &cells);
+        assert_eq!(pattern.bounding_box(), Some(([0, -1], [2, 0])));
+        assert_eq!(pattern.clone().into_iter().collect::<Pattern<2>>(), pattern);
+        assert_eq!(pattern.into_cells(), cells);
+    }
+
+    #[test]
+    fn test_pattern_macro() {
+        assert!(pattern!("").is_empty());
+        assert!(pattern!("...\n...").is_empty());
+        assert_eq!(pattern!("O"), [[0, 0]].into_iter().collect());
+        assert_eq!(pattern!("*O\n\n.O\nO."), [[0, 2], [1, 2], [1, 1], [0, 0]].into_iter().collect());
+        let beehive = pattern!(
+            "
+            .OO.
+            O..O
+            .OO.
+            "
+        );
+        assert_eq!(beehive, [[1, 0], [2, 0], [0, 1], [3, 1], [1, 2], [2, 2]].into_iter().collect());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("".parse(), Ok(Pattern::<2>::default()));
+        assert_eq!("! only a comment\n\n".parse(), Ok(Pattern::<2>::default()));
+        assert_eq!("*O\r\n\r\n.O\r\nO.".parse(), Ok(pattern!("*O\n.O\nO.")));
+        let beehive = "
+            ! beehive
+            .OO.
+            O..O
+            .OO.
+        ";
+        assert_eq!(beehive.parse::<Pattern<2>>(), Ok([[1, 0], [2, 0], [0, 1], [3, 1], [1, 2], [2, 2]].into_iter().collect()));
+        assert_eq!("OO\nO#".parse::<Pattern<2>>(), Err(Error::InvalidCharacter('#', 2, 2)));
+        assert_eq!("  x".parse::<Pattern<2>>(), Err(Error::InvalidCharacter('x', 1, 3)));
+    }
+
+    #[test]
+    fn test_from_plaintext() {
+        let life = Life::from_plaintext([3].into_iter().collect(), [2, 3].into_iter().collect(), ".O.\n..O\nOOO").unwrap();
+        assert_eq!(life.to_pattern(), pattern!(".O.\n..O\nOOO"));
+        assert_eq!(Life::from_plaintext(HashSet::new(), HashSet::new(), "O-O"), Err(Error::InvalidCharacter('-', 1, 2)));
+        assert_eq!(Life::from_plaintext([0].into_iter().collect(), HashSet::new(), "O"), Err(Error::ZeroNeighbourBirthRule));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Pattern::<2>::default().to_string(), "");
+        let pattern: Pattern<2> = [[-3, 5], [-1, 4]].into_iter().collect();
+        assert_eq!(pattern.to_string(), "O..\n..O");
+        assert_eq!(pattern.to_string().parse::<Pattern<2>>(), Ok([[0, 1], [2, 0]].into_iter().collect()));
+
+        let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert_eq!(format!("{life}"), "");
+        life.set_alive_cells(pattern.into_cells());
+        assert_eq!(format!("{life}"), "O..\n..O");
+        assert_eq!(life.display_region([-3..=-3, 3..=5]).to_string(), "O\n.\n.");
+        let (start, end) = (5, 4);
+        assert_eq!(life.display_region([start..=end, 0..=1]).to_string(), "");
+    }
+
+    #[test]
+    fn test_transformed() {
+        let pattern: Pattern<3> = [[1, 2, 3], [0, 0, 0]].into_iter().collect();
+        let transform = Transform::signed_permutation([2, 1, 0], [true, false, false])
+            .unwrap()
+            .then(&Transform::translation([0, 0, 1]));
+        let transformed = pattern.transformed(&transform);
+        assert_eq!(transformed, [[-3, 2, 2], [0, 0, 1]].into_iter().collect());
+        assert_eq!(transformed.transformed(&transform.inverse()), pattern);
+        assert_eq!(pattern.transformed(&Transform::identity()), pattern);
+    }
+
+    #[test]
+    fn test_symmetrize() {
+        let pattern: Pattern<2> = [[1, 2]].into_iter().collect();
+        assert_eq!(pattern.symmetrize(Symmetry::C2), Ok([[1, 2], [-1, -2]].into_iter().collect()));
+        assert_eq!(pattern.symmetrize(Symmetry::D2Diagonal), Ok([[1, 2], [2, 1]].into_iter().collect()));
+        assert_eq!(pattern.symmetrize(Symmetry::D8).unwrap().len(), 8);
+        let pattern: Pattern<1> = [[1]].into_iter().collect();
+        assert_eq!(pattern.symmetrize(Symmetry::Reflections), Ok([[1], [-1]].into_iter().collect()));
+        assert_eq!(pattern.symmetrize(Symmetry::D4), Err(Error::InvalidAxis(1, 1)));
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        assert!(Pattern::<2>::default().canonical_form().is_empty());
+        let l_tromino: Pattern<2> = [[0, 0], [1, 0], [0, 1]].into_iter().collect();
+        let canonical = l_tromino.canonical_form();
+        for element in Symmetry::D8.elements::<2>().unwrap() {
+            let image = l_tromino.transformed(&element.then(&Transform::translation([-7, 3])));
+            assert_eq!(image.canonical_form(), canonical);
+        }
+        assert_eq!(canonical.bounding_box(), Some(([0, 0], [1, 1])));
+        assert!(!l_tromino.is_congruent(&[[0, 0], [1, 0], [2, 0]].into_iter().collect()));
+
+        // mirror images are congruent in 3 dimensions too
+        let chiral: Pattern<3> = [[0, 0, 0], [1, 0, 0], [0, 1, 0], [0, 0, 1], [2, 0, 0]].into_iter().collect();
+        let mirrored = chiral.transformed(&Transform::reflection(2).unwrap());
+        assert!(chiral.is_congruent(&mirrored));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: Pattern<2> = [[0, 0], [1, 1], [2, 2]].into_iter().collect();
+        let b: Pattern<2> = [[1, 1], [3, 3]].into_iter().collect();
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.intersection(&b), [[1, 1]].into_iter().collect());
+        assert_eq!(a.difference(&b), [[0, 0], [2, 2]].into_iter().collect());
+        assert_eq!(b.difference(&a), [[3, 3]].into_iter().collect());
+        assert_eq!(a.symmetric_difference(&b), [[0, 0], [2, 2], [3, 3]].into_iter().collect());
+        assert_eq!(a.union(&Pattern::default()), a);
+        assert!(a.intersection(&Pattern::default()).is_empty());
+    }
+
+    #[test]
+    fn test_embed() {
+        let pattern: Pattern<1> = [[0], [3]].into_iter().collect();
+        assert_eq!(pattern.embed::<2>(&[7]), Ok([[0, 7], [3, 7]].into_iter().collect()));
+        assert_eq!(pattern.embed::<3>(&[1, 2]), Ok([[0, 1, 2], [3, 1, 2]].into_iter().collect()));
+        assert_eq!(pattern.embed::<3>(&[1]), Err(Error::DimensionMismatch(2, 1)));
+    }
+
+    #[test]
+    fn test_extrude() {
+        let pattern: Pattern<1> = [[0], [3]].into_iter().collect();
+        assert_eq!(pattern.extrude::<2>(&[-1..=1]).unwrap().len(), 6);
+        let extruded: Pattern<3> = pattern.extrude(&[0..=1, 5..=5]).unwrap();
+        assert_eq!(extruded, [[0, 0, 5], [0, 1, 5], [3, 0, 5], [3, 1, 5]].into_iter().collect());
+        let (start, end) = (1, 0);
+        assert!(pattern.extrude::<2>(&[start..=end]).unwrap().is_empty());
+        assert_eq!(pattern.extrude::<2>(&[]), Err(Error::DimensionMismatch(1, 0)));
+    }
+}
@@ -0,0 +1,160 @@
+//! Bevy plugin stepping a game of life on a fixed timestep
+
+use crate::entities::CellEvent;
+use crate::life::Life;
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::ecs::message::{Message, MessageWriter};
+use bevy::ecs::resource::Resource;
+use bevy::ecs::schedule::common_conditions::resource_exists;
+use bevy::ecs::schedule::IntoScheduleConfigs;
+use bevy::ecs::system::ResMut;
+use bevy::time::{Fixed, Time};
+use std::time::Duration;
+
+/// Game of life simulated by a [LifePlugin], as a Bevy resource
+///
+/// Systems read the game of life with `Res<LifeResource<N>>` and edit it or pause it with `ResMut<LifeResource<N>>`.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct LifeResource<const N: usize> {
+    /// The game of life.
+    life: Life<N>,
+    /// Whether the stepping is paused.
+    paused: bool,
+}
+impl<const N: usize> LifeResource<N> {
+    /// Create a running resource holding a game of life.
+    /// # Arguments
+    /// * `life` - The game of life.
+    pub fn new(life: Life<N>) -> Self {
+        Self { life, paused: false }
+    }
+
+    /// Get the game of life.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Get the game of life to modify it.
+    pub fn life_mut(&mut self) -> &mut Life<N> {
+        &mut self.life
+    }
+
+    /// Check whether the stepping is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume the stepping.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+}
+
+impl<const N: usize> Message for CellEvent<N> {}
+
+/// Bevy plugin inserting a [LifeResource] and advancing it by a generation every fixed timestep
+///
+/// The plugin adds [step_life] to the `FixedUpdate` schedule, which writes a [CellEvent] message for every cell born
+/// or died in the generation, so systems can read them with `MessageReader<CellEvent<N>>` to spawn and despawn
+/// entities. The timestep is the app's `Time<Fixed>`, which the plugin sets if given one.
+/// The game of life is cloned into the resource, so it starts without observers.
+/// # Example
+/// ```
+/// use bevy::app::{App, FixedUpdate};
+/// use ndlife::entities::CellEvent;
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::plugin::{LifePlugin, LifeResource};
+/// use std::time::Duration;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut app = App::new();
+/// app.add_plugins(LifePlugin::new(life).with_timestep(Duration::from_millis(100)));
+/// app.world_mut().run_schedule(FixedUpdate);
+///
+/// assert_eq!(app.world().resource::<LifeResource<2>>().life().age(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LifePlugin<const N: usize> {
+    /// The initial game of life.
+    life: Life<N>,
+    /// The timestep to set, or [None] to keep the app's.
+    timestep: Option<Duration>,
+}
+impl<const N: usize> LifePlugin<N> {
+    /// Create a plugin simulating a game of life on the app's fixed timestep.
+    /// # Arguments
+    /// * `life` - The initial game of life.
+    pub fn new(life: Life<N>) -> Self {
+        Self { life, timestep: None }
+    }
+
+    /// Set the fixed timestep of the app, the time between generations.
+    /// # Arguments
+    /// * `timestep` - The timestep.
+    pub fn with_timestep(mut self, timestep: Duration) -> Self {
+        self.timestep = Some(timestep);
+        self
+    }
+}
+impl<const N: usize> Plugin for LifePlugin<N> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LifeResource::new(self.life.clone()))
+            .add_message::<CellEvent<N>>()
+            .add_systems(FixedUpdate, step_life::<N>.run_if(resource_exists::<LifeResource<N>>));
+        if let Some(timestep) = self.timestep {
+            app.insert_resource(Time::<Fixed>::from_duration(timestep));
+        }
+    }
+}
+
+/// System advancing the [LifeResource] by a generation unless it is paused, writing the born and died cells as [CellEvent]s.
+pub fn step_life<const N: usize>(mut resource: ResMut<LifeResource<N>>, mut events: MessageWriter<CellEvent<N>>) {
+    if resource.paused {
+        return;
+    }
+    let life = &mut resource.life;
+    life.next_generation();
+    events.write_batch(life.born_cells().map(|cell| CellEvent::Born(*cell)));
+    events.write_batch(life.died_cells().map(|cell| CellEvent::Died(*cell)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use bevy::ecs::message::Messages;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_plugin() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut app = App::new();
+        app.add_plugins(LifePlugin::new(life.clone()).with_timestep(Duration::from_millis(50)));
+        assert_eq!(app.world().resource::<Time<Fixed>>().timestep(), Duration::from_millis(50));
+
+        app.world_mut().run_schedule(FixedUpdate);
+        life.next_generation();
+        assert_eq!(app.world().resource::<LifeResource<2>>().life(), &life);
+        let events: HashSet<CellEvent<2>> = app.world().resource::<Messages<CellEvent<2>>>().iter_current_update_messages().copied().collect();
+        let expected = [CellEvent::Born([1, -1]), CellEvent::Born([1, 1]), CellEvent::Died([0, 0]), CellEvent::Died([2, 0])];
+        assert_eq!(events, expected.into_iter().collect());
+
+        // a paused game of life neither steps nor writes events
+        app.world_mut().resource_mut::<LifeResource<2>>().set_paused(true);
+        app.world_mut().resource_mut::<Messages<CellEvent<2>>>().update();
+        app.world_mut().run_schedule(FixedUpdate);
+        let resource = app.world().resource::<LifeResource<2>>();
+        assert!(resource.is_paused());
+        assert_eq!(resource.life().age(), 1);
+        assert_eq!(app.world().resource::<Messages<CellEvent<2>>>().iter_current_update_messages().count(), 0);
+
+        // edits through the resource are stepped from
+        app.world_mut().resource_mut::<LifeResource<2>>().life_mut().set_cell(&[1, 0], false);
+        app.world_mut().resource_mut::<LifeResource<2>>().set_paused(false);
+        app.world_mut().run_schedule(FixedUpdate);
+        assert!(app.world().resource::<LifeResource<2>>().life().alive_cells().is_empty());
+    }
+}
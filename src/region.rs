@@ -0,0 +1,200 @@
+//! Axis-aligned hyperrectangular regions of the lattice
+
+use crate::geometry;
+use core::ops::RangeInclusive;
+
+/// Axis-aligned hyperrectangle of cells, including its boundary
+///
+/// A region is empty if its minimum corner exceeds its maximum corner along any axis.
+/// Regions are used by every API that acts on a part of the universe, and can be created
+/// from per-axis inclusive ranges or from the corners returned by a bounding box.
+/// # Example
+/// ```
+/// use ndlife::region::Region;
+///
+/// let region = Region::from([0..=9, -4..=5]);
+/// assert!(region.contains(&[9, -4]));
+/// assert!(!region.contains(&[10, 0]));
+/// assert_eq!(region.iter_cells().count(), 100);
+///
+/// let inner = region.deflate(2);
+/// assert_eq!(inner, Region::new([2, -2], [7, 3]));
+/// assert_eq!(inner.inflate(2), region);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Region<const N: usize> {
+    /// The minimum coordinates along each axis (inclusive).
+    min: [i64; N],
+    /// The maximum coordinates along each axis (inclusive).
+    max: [i64; N],
+}
+impl<const N: usize> Region<N> {
+    /// Create a region from its corners.
+    /// # Arguments
+    /// * `min` - The minimum coordinates along each axis (inclusive).
+    /// * `max` - The maximum coordinates along each axis (inclusive).
+    pub const fn new(min: [i64; N], max: [i64; N]) -> Self {
+        Self { min, max }
+    }
+
+    /// Get the smallest region containing all given cells.
+    /// # Arguments
+    /// * `cells` - The cells the region must contain.
+    /// # Returns
+    /// [Some] with the bounding region, or [None] if there are no cells.
+    pub fn bounding<'a>(cells: impl IntoIterator<Item = &'a [i64; N]>) -> Option<Self> {
+        geometry::bounding_box(cells).map(Self::from)
+    }
+
+    /// Get the minimum coordinates along each axis (inclusive).
+    pub fn min(&self) -> [i64; N] {
+        self.min
+    }
+
+    /// Get the maximum coordinates along each axis (inclusive).
+    pub fn max(&self) -> [i64; N] {
+        self.max
+    }
+
+    /// Get whether the region contains no cells.
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|i| self.min[i] > self.max[i])
+    }
+
+    /// Get the number of cells in the region.
+    /// # Returns
+    /// [Some] with the number of cells, or [None] if it does not fit in a [u128].
+    pub fn volume(&self) -> Option<u128> {
+        if self.is_empty() {
+            return Some(0);
+        }
+        (0..N).try_fold(1u128, |volume, i| volume.checked_mul((self.max[i] as i128 - self.min[i] as i128) as u128 + 1))
+    }
+
+    /// Check whether a cell lies in the region.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// Whether the cell lies in the region.
+    pub fn contains(&self, cell: &[i64; N]) -> bool {
+        (0..N).all(|i| self.min[i] <= cell[i] && cell[i] <= self.max[i])
+    }
+
+    /// Iterate over all cells of the region in lexicographic order.
+    pub fn iter_cells(&self) -> impl Iterator<Item = [i64; N]> {
+        geometry::box_cells(self.min, self.max)
+    }
+
+    /// Get the cells lying in both regions.
+    /// # Arguments
+    /// * `other` - The other region.
+    /// # Returns
+    /// The intersection of the regions, which is empty if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            min: core::array::from_fn(|i| self.min[i].max(other.min[i])),
+            max: core::array::from_fn(|i| self.max[i].min(other.max[i])),
+        }
+    }
+
+    /// Grow the region by a margin on every side.
+    ///
+    /// The coordinates saturate at the limits of [i64], and an empty region stays empty.
+    /// # Arguments
+    /// * `margin` - The number of cells to add on every side.
+    /// # Returns
+    /// The inflated region.
+    pub fn inflate(&self, margin: u64) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let margin = i64::try_from(margin).unwrap_or(i64::MAX);
+        Self {
+            min: core::array::from_fn(|i| self.min[i].saturating_sub(margin)),
+            max: core::array::from_fn(|i| self.max[i].saturating_add(margin)),
+        }
+    }
+
+    /// Shrink the region by a margin on every side.
+    ///
+    /// The region becomes empty if it is too small to shrink by the margin.
+    /// # Arguments
+    /// * `margin` - The number of cells to remove on every side.
+    /// # Returns
+    /// The deflated region.
+    pub fn deflate(&self, margin: u64) -> Self {
+        let margin = i64::try_from(margin).unwrap_or(i64::MAX);
+        Self {
+            min: core::array::from_fn(|i| self.min[i].saturating_add(margin)),
+            max: core::array::from_fn(|i| self.max[i].saturating_sub(margin)),
+        }
+    }
+}
+impl<const N: usize> From<[RangeInclusive<i64>; N]> for Region<N> {
+    fn from(ranges: [RangeInclusive<i64>; N]) -> Self {
+        Self::new(core::array::from_fn(|i| *ranges[i].start()), core::array::from_fn(|i| *ranges[i].end()))
+    }
+}
+impl<const N: usize> From<([i64; N], [i64; N])> for Region<N> {
+    fn from((min, max): ([i64; N], [i64; N])) -> Self {
+        Self::new(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_region() {
+        let region = Region::from([-1..=1, 3..=4]);
+        assert_eq!(region, Region::new([-1, 3], [1, 4]));
+        assert_eq!(region.min(), [-1, 3]);
+        assert_eq!(region.max(), [1, 4]);
+        assert!(!region.is_empty());
+        assert!(region.contains(&[-1, 4]));
+        assert!(!region.contains(&[-2, 4]));
+        assert_eq!(region.iter_cells().collect::<Vec<_>>(), vec![[-1, 3], [-1, 4], [0, 3], [0, 4], [1, 3], [1, 4]]);
+
+        let (start, end) = (5, 4);
+        let empty = Region::from([0..=3, start..=end]);
+        assert!(empty.is_empty());
+        assert!(!empty.contains(&[0, 4]));
+        assert_eq!(empty.iter_cells().count(), 0);
+        assert_eq!(empty.inflate(1), empty);
+    }
+
+    #[test]
+    fn test_volume() {
+        assert_eq!(Region::new([0, -4], [9, 5]).volume(), Some(100));
+        assert_eq!(Region::new([0, 5], [9, 4]).volume(), Some(0));
+        assert_eq!(Region::new([i64::MIN; 2], [i64::MAX; 2]).volume(), None);
+        assert_eq!(Region::new([i64::MIN], [i64::MAX]).volume(), Some(1 << 64));
+    }
+
+    #[test]
+    fn test_bounding() {
+        assert_eq!(Region::<2>::bounding(&[]), None);
+        assert_eq!(Region::bounding(&[[3, -1], [0, 5], [2, 2]]), Some(Region::new([0, -1], [3, 5])));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = Region::new([0, 0], [5, 5]);
+        let b = Region::new([3, -2], [8, 4]);
+        assert_eq!(a.intersection(&b), Region::new([3, 0], [5, 4]));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+        assert!(a.intersection(&Region::new([6, 0], [7, 5])).is_empty());
+    }
+
+    #[test]
+    fn test_inflate_deflate() {
+        let region = Region::new([0, 0, 0], [4, 4, 2]);
+        assert_eq!(region.inflate(3), Region::new([-3, -3, -3], [7, 7, 5]));
+        assert_eq!(region.deflate(1), Region::new([1, 1, 1], [3, 3, 1]));
+        assert!(region.deflate(2).is_empty());
+        assert_eq!(Region::new([-1; 3], [1; 3]).inflate(u64::MAX), Region::new([i64::MIN; 3], [i64::MAX; 3]));
+    }
+}
@@ -0,0 +1,512 @@
+//! Logs of the operations changing a game of life, to reproduce sessions exactly
+
+use crate::collections::HashSet;
+use crate::error::{Error, ParseError};
+use crate::life::Life;
+use crate::region::Region;
+use crate::rulestring;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Operation changing the state of a game of life, recorded in an [EventLog]
+///
+/// Every event is deterministic, random fills included as they carry their seed, so applying the same events
+/// to the same state always gives the same state. Events are saved one per line: `rules B3/S23`, `cells 0.0,1.0`,
+/// `age 5`, `set 1.2 alive`, `toggle 1.2`, `fill 0.0 3.3`, `clear 0.0 3.3`, `randomize 0.0 15.15 0.5 42` and `step 10`,
+/// with the coordinates of a cell separated by dots and regions given by their corners.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::region::Region;
+/// use ndlife::replay::Event;
+///
+/// let event: Event<2> = "randomize 0.0 7.7 0.5 42".parse().unwrap();
+/// assert_eq!(event, Event::Randomize { region: Region::new([0, 0], [7, 7]), density: 0.5, seed: 42 });
+/// assert_eq!(event.to_string(), "randomize 0.0 7.7 0.5 42");
+///
+/// let mut life = conways_game_of_life();
+/// event.apply(&mut life).unwrap();
+/// let mut same_life = conways_game_of_life();
+/// same_life.randomize_region([0..=7, 0..=7], 0.5, 42);
+/// assert_eq!(life, same_life);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<const N: usize> {
+    /// Replace the birth and the survival rules.
+    Rules {
+        /// The new birth rules.
+        birth_rules: HashSet<usize>,
+        /// The new survival rules.
+        survival_rules: HashSet<usize>,
+    },
+    /// Replace all the alive cells, as [set_alive_cells](Life::set_alive_cells).
+    Cells(HashSet<[i64; N]>),
+    /// Set the age, as [set_age](Life::set_age).
+    Age(u64),
+    /// Make a cell alive or dead, as [set_cell](Life::set_cell).
+    SetCell {
+        /// The coordinates of the cell.
+        cell: [i64; N],
+        /// Whether the cell becomes alive.
+        alive: bool,
+    },
+    /// Toggle a cell, as [toggle_cell](Life::toggle_cell).
+    ToggleCell([i64; N]),
+    /// Make every cell of a region alive, as [fill_region](Life::fill_region).
+    FillRegion(Region<N>),
+    /// Make every cell of a region dead, as [clear_region](Life::clear_region).
+    ClearRegion(Region<N>),
+    /// Fill a region with a random soup, as [randomize_region](Life::randomize_region).
+    Randomize {
+        /// The region of the soup.
+        region: Region<N>,
+        /// The probability of a cell being alive.
+        density: f64,
+        /// The seed of the pseudo-random number generator.
+        seed: u64,
+    },
+    /// Advance by a number of generations.
+    Step(u64),
+}
+impl<const N: usize> Event<N> {
+    /// Apply the event to a game of life.
+    /// # Arguments
+    /// * `life` - The game of life to change.
+    /// # Returns
+    /// A [Result] which is an error if the event sets invalid rules, leaving the game of life unchanged.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    pub fn apply(&self, life: &mut Life<N>) -> Result<(), Error> {
+        match self {
+            Self::Rules { birth_rules, survival_rules } => {
+                Life::<N>::new(birth_rules.clone(), survival_rules.clone())?;
+                life.set_birth_rules(birth_rules.clone())?;
+                life.set_survival_rules(survival_rules.clone())?;
+            }
+            Self::Cells(cells) => life.set_alive_cells(cells.clone()),
+            Self::Age(age) => life.set_age(*age),
+            Self::SetCell { cell, alive } => {
+                life.set_cell(cell, *alive);
+            }
+            Self::ToggleCell(cell) => life.toggle_cell(cell),
+            Self::FillRegion(region) => life.fill_region(*region),
+            Self::ClearRegion(region) => life.clear_region(*region),
+            Self::Randomize { region, density, seed } => life.randomize_region(*region, *density, *seed),
+            Self::Step(generations) => {
+                for _ in 0..*generations {
+                    life.next_generation();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+impl<const N: usize> fmt::Display for Event<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rules { birth_rules, survival_rules } => write!(f, "rules {}", rulestring::format(birth_rules, survival_rules)),
+            Self::Cells(cells) => {
+                f.write_str("cells")?;
+                let mut cells: Vec<&[i64; N]> = cells.iter().collect();
+                cells.sort_unstable();
+                for (index, cell) in cells.into_iter().enumerate() {
+                    write!(f, "{}{}", if index == 0 { " " } else { "," }, coordinates(cell))?;
+                }
+                Ok(())
+            }
+            Self::Age(age) => write!(f, "age {}", age),
+            Self::SetCell { cell, alive } => write!(f, "set {} {}", coordinates(cell), if *alive { "alive" } else { "dead" }),
+            Self::ToggleCell(cell) => write!(f, "toggle {}", coordinates(cell)),
+            Self::FillRegion(region) => write!(f, "fill {} {}", coordinates(&region.min()), coordinates(&region.max())),
+            Self::ClearRegion(region) => write!(f, "clear {} {}", coordinates(&region.min()), coordinates(&region.max())),
+            Self::Randomize { region, density, seed } => {
+                write!(f, "randomize {} {} {} {}", coordinates(&region.min()), coordinates(&region.max()), density, seed)
+            }
+            Self::Step(generations) => write!(f, "step {}", generations),
+        }
+    }
+}
+impl<const N: usize> FromStr for Event<N> {
+    type Err = Error;
+
+    /// Parse an event from its line in a saved log.
+    /// # Errors
+    /// * [ParseError](Error::ParseError) - If the line is malformed, on line 1.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = |column: usize, message: &str| Error::ParseError(ParseError::new(1, column, message));
+        let mut words = Vec::new();
+        let mut column = 1;
+        for word in line.split(' ') {
+            if !word.is_empty() {
+                words.push((column, word));
+            }
+            column += word.len() + 1;
+        }
+        let Some((&(_, name), arguments)) = words.split_first() else {
+            return Err(error(1, "expected an event"));
+        };
+        let argument = |index: usize| arguments.get(index).copied().ok_or_else(|| error(line.len() + 1, "expected more arguments"));
+        let cell = |index: usize| {
+            let (column, word) = argument(index)?;
+            parse_cell(word).ok_or_else(|| error(column, "expected the coordinates of a cell"))
+        };
+        let number = |index: usize| {
+            let (column, word) = argument(index)?;
+            word.parse::<u64>().map_err(|_| error(column, "expected a number"))
+        };
+        let (event, count) = match name {
+            "rules" => {
+                let (column, word) = argument(0)?;
+                let (birth_rules, survival_rules) = rulestring::parse(word).map_err(|error| match error {
+                    Error::InvalidRulestring(offset) => Error::ParseError(ParseError::new(1, column + offset - 1, "invalid rulestring")),
+                    error => error,
+                })?;
+                (Self::Rules { birth_rules, survival_rules }, 1)
+            }
+            "cells" if arguments.is_empty() => (Self::Cells(HashSet::new()), 0),
+            "cells" => {
+                let (mut column, word) = argument(0)?;
+                let mut cells = HashSet::new();
+                for cell in word.split(',') {
+                    cells.insert(parse_cell(cell).ok_or_else(|| error(column, "expected the coordinates of a cell"))?);
+                    column += cell.len() + 1;
+                }
+                (Self::Cells(cells), 1)
+            }
+            "age" => (Self::Age(number(0)?), 1),
+            "set" => {
+                let alive = match argument(1)? {
+                    (_, "alive") => true,
+                    (_, "dead") => false,
+                    (column, _) => return Err(error(column, "expected alive or dead")),
+                };
+                (Self::SetCell { cell: cell(0)?, alive }, 2)
+            }
+            "toggle" => (Self::ToggleCell(cell(0)?), 1),
+            "fill" => (Self::FillRegion(Region::new(cell(0)?, cell(1)?)), 2),
+            "clear" => (Self::ClearRegion(Region::new(cell(0)?, cell(1)?)), 2),
+            "randomize" => {
+                let (column, word) = argument(2)?;
+                let density = word.parse().map_err(|_| error(column, "expected a density"))?;
+                (
+                    Self::Randomize {
+                        region: Region::new(cell(0)?, cell(1)?),
+                        density,
+                        seed: number(3)?,
+                    },
+                    4,
+                )
+            }
+            "step" => (Self::Step(number(0)?), 1),
+            _ => return Err(error(1, "unknown event")),
+        };
+        match arguments.get(count) {
+            Some((column, _)) => Err(error(*column, "unexpected argument")),
+            None => Ok(event),
+        }
+    }
+}
+
+/// Get the coordinates of a cell separated by dots.
+fn coordinates<const N: usize>(cell: &[i64; N]) -> String {
+    cell.iter().map(i64::to_string).collect::<Vec<_>>().join(".")
+}
+
+/// Parse the coordinates of a cell separated by dots, or get [None] if they are malformed.
+fn parse_cell<const N: usize>(text: &str) -> Option<[i64; N]> {
+    let coordinates: Vec<i64> = text.split('.').map(str::parse).collect::<Result<_, _>>().ok()?;
+    coordinates.try_into().ok()
+}
+
+/// Sequence of [Event]s which reproduces a session when replayed
+///
+/// A log is saved as text with one event per line, and parsed back with [str::parse]; blank lines are ignored.
+/// # Example
+/// ```
+/// use ndlife::replay::EventLog;
+///
+/// let log: EventLog<2> = "rules B3/S23\ncells 0.0,1.0,2.0\nstep 1\ntoggle 5.5\n".parse().unwrap();
+/// let life = log.replay().unwrap();
+/// assert_eq!(life.age(), 1);
+/// assert_eq!(life.alive_cells(), &[[1, 1], [1, 0], [1, -1], [5, 5]].into_iter().collect());
+/// assert_eq!(log.to_string(), "rules B3/S23\ncells 0.0,1.0,2.0\nstep 1\ntoggle 5.5\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EventLog<const N: usize> {
+    /// The events, in order.
+    events: Vec<Event<N>>,
+}
+impl<const N: usize> EventLog<N> {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    /// Get the events, in order.
+    pub fn events(&self) -> &[Event<N>] {
+        &self.events
+    }
+
+    /// Add an event at the end of the log.
+    pub fn push(&mut self, event: Event<N>) {
+        self.events.push(event);
+    }
+
+    /// Apply the events in order to a game of life.
+    /// # Arguments
+    /// * `life` - The game of life to change.
+    /// # Returns
+    /// A [Result] which is an error if an event sets invalid rules, after applying the events before it.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If any birth rules contain 0.
+    pub fn replay_onto(&self, life: &mut Life<N>) -> Result<(), Error> {
+        self.events.iter().try_for_each(|event| event.apply(life))
+    }
+
+    /// Apply the events in order to an empty game of life without rules.
+    /// # Returns
+    /// A [Result] containing the game of life at the end of the log, or an error if an event sets invalid rules.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If any birth rules contain 0.
+    pub fn replay(&self) -> Result<Life<N>, Error> {
+        let mut life = Life::new(HashSet::new(), HashSet::new())?;
+        self.replay_onto(&mut life)?;
+        Ok(life)
+    }
+}
+impl<const N: usize> fmt::Display for EventLog<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+impl<const N: usize> FromStr for EventLog<N> {
+    type Err = Error;
+
+    /// Parse a log saved as text.
+    /// # Errors
+    /// * [ParseError](Error::ParseError) - If a line is malformed.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut log = Self::new();
+        for (index, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            log.push(line.parse().map_err(|error| match error {
+                Error::ParseError(error) => Error::ParseError(ParseError::new(index + 1, error.column(), error.message())),
+                error => error,
+            })?);
+        }
+        Ok(log)
+    }
+}
+
+/// Game of life recording every change made through it into an [EventLog]
+///
+/// The log starts with the rules, alive cells and age of the game of life, so [replaying](EventLog::replay) it
+/// rebuilds the current state from nothing. Interactive tools make their edits through the recorder,
+/// save the log with a bug report and replay it to reproduce the session.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::replay::Recorder;
+///
+/// let mut recorder = Recorder::new(conways_game_of_life());
+/// recorder.randomize_region([0..=15, 0..=15], 0.4, 7);
+/// recorder.step(10);
+/// recorder.set_cell(&[20, 20], true);
+/// recorder.step(5);
+///
+/// let saved = recorder.log().to_string();
+/// let replayed = saved.parse::<ndlife::replay::EventLog<2>>().unwrap().replay().unwrap();
+/// assert_eq!(&replayed, recorder.life());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorder<const N: usize> {
+    /// The game of life.
+    life: Life<N>,
+    /// The events applied to it, starting with its initial state.
+    log: EventLog<N>,
+}
+impl<const N: usize> Recorder<N> {
+    /// Create a recorder of a game of life, logging its initial state.
+    /// # Arguments
+    /// * `life` - The game of life to record.
+    pub fn new(life: Life<N>) -> Self {
+        let mut log = EventLog::new();
+        log.push(Event::Rules {
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+        });
+        log.push(Event::Cells(life.alive_cells().clone()));
+        log.push(Event::Age(life.age()));
+        Self { life, log }
+    }
+
+    /// Get the game of life.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Get the log of the events applied so far.
+    pub fn log(&self) -> &EventLog<N> {
+        &self.log
+    }
+
+    /// Get the game of life and the log, consuming the recorder.
+    pub fn into_parts(self) -> (Life<N>, EventLog<N>) {
+        (self.life, self.log)
+    }
+
+    /// Apply an event to the game of life and log it.
+    /// # Returns
+    /// A [Result] which is an error if the event sets invalid rules, in which case it is not logged.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    pub fn apply(&mut self, event: Event<N>) -> Result<(), Error> {
+        event.apply(&mut self.life)?;
+        self.log.push(event);
+        Ok(())
+    }
+
+    /// Set the birth and survival rules, as [set_birth_rules](Life::set_birth_rules) and [set_survival_rules](Life::set_survival_rules).
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If the birth rules contain 0.
+    pub fn set_rules(&mut self, birth_rules: HashSet<usize>, survival_rules: HashSet<usize>) -> Result<(), Error> {
+        self.apply(Event::Rules { birth_rules, survival_rules })
+    }
+
+    /// Replace all the alive cells, as [set_alive_cells](Life::set_alive_cells).
+    pub fn set_alive_cells(&mut self, alive_cells: HashSet<[i64; N]>) {
+        self.record(Event::Cells(alive_cells));
+    }
+
+    /// Make a cell alive or dead, as [set_cell](Life::set_cell).
+    pub fn set_cell(&mut self, cell: &[i64; N], alive: bool) {
+        self.record(Event::SetCell { cell: *cell, alive });
+    }
+
+    /// Toggle a cell between alive and dead, as [toggle_cell](Life::toggle_cell).
+    pub fn toggle_cell(&mut self, cell: &[i64; N]) {
+        self.record(Event::ToggleCell(*cell));
+    }
+
+    /// Make every cell of a region alive, as [fill_region](Life::fill_region).
+    pub fn fill_region(&mut self, region: impl Into<Region<N>>) {
+        self.record(Event::FillRegion(region.into()));
+    }
+
+    /// Make every cell of a region dead, as [clear_region](Life::clear_region).
+    pub fn clear_region(&mut self, region: impl Into<Region<N>>) {
+        self.record(Event::ClearRegion(region.into()));
+    }
+
+    /// Fill a region with a random soup, as [randomize_region](Life::randomize_region).
+    pub fn randomize_region(&mut self, region: impl Into<Region<N>>, density: f64, seed: u64) {
+        self.record(Event::Randomize {
+            region: region.into(),
+            density,
+            seed,
+        });
+    }
+
+    /// Advance the game of life by a number of generations.
+    pub fn step(&mut self, generations: u64) {
+        self.record(Event::Step(generations));
+    }
+
+    /// Apply and log an event which cannot fail.
+    fn record(&mut self, event: Event<N>) {
+        self.apply(event).expect("only rules can be invalid");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_event_from_str() {
+        let birth_rules: HashSet<usize> = [3, 10].into_iter().collect();
+        let events: [Event<3>; 9] = [
+            Event::Rules {
+                birth_rules,
+                survival_rules: HashSet::new(),
+            },
+            Event::Cells([[0, 0, 0], [-1, 2, 3]].into_iter().collect()),
+            Event::Cells(HashSet::new()),
+            Event::Age(7),
+            Event::SetCell { cell: [1, 2, 3], alive: false },
+            Event::ToggleCell([-1, -1, -1]),
+            Event::FillRegion(Region::new([0, 0, 0], [1, 1, 1])),
+            Event::ClearRegion(Region::new([1, 0, 0], [0, 1, 1])),
+            Event::Step(0),
+        ];
+        for event in events {
+            assert_eq!(event.to_string().parse(), Ok(event.clone()), "{}", event);
+        }
+        assert_eq!(Event::<3>::Cells([[0, 0, 1], [-1, 2, 3]].into_iter().collect()).to_string(), "cells -1.2.3,0.0.1");
+
+        for (line, column) in [
+            ("", 1),
+            ("jump 1", 1),
+            ("rules B3/S2x", 12),
+            ("cells 0.0,1", 11),
+            ("age", 4),
+            ("age -1", 5),
+            ("set 0.0 maybe", 9),
+            ("toggle 0.0 0.0", 12),
+            ("fill 0.0", 9),
+            ("randomize 0.0 1.1 half 1", 19),
+            ("step  1 2", 9),
+        ] {
+            match line.parse::<Event<2>>() {
+                Err(Error::ParseError(error)) => assert_eq!(error.column(), column, "{:?}", line),
+                other => panic!("{:?}: {:?}", line, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::new(conways_game_of_life());
+        recorder.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        recorder.fill_region([5..=6, 5..=6]);
+        recorder.clear_region([6..=6, 6..=6]);
+        recorder.toggle_cell(&[6, 6]);
+        recorder.step(3);
+        assert_eq!(recorder.set_rules([0].into_iter().collect(), HashSet::new()), Err(Error::ZeroNeighbourBirthRule));
+        recorder.set_rules([3, 6].into_iter().collect(), [2, 3].into_iter().collect()).unwrap();
+        recorder.randomize_region([-4..=4, -4..=4], 0.3, 11);
+        recorder.step(20);
+
+        let (life, log) = recorder.into_parts();
+        assert_eq!(log.events().len(), 11);
+        assert_eq!(log.to_string().parse::<EventLog<2>>(), Ok(log.clone()));
+        assert_eq!(log.replay(), Ok(life.clone()));
+        // replaying onto another state gives another result
+        let mut other = conways_game_of_life();
+        other.set_age(100);
+        log.events()[3..].iter().try_for_each(|event| event.apply(&mut other)).unwrap();
+        assert_eq!(other.age(), 123);
+        assert_ne!(other, life);
+
+        let log: Result<EventLog<2>, _> = "step 1\n\nstep x\n".parse();
+        match log {
+            Err(Error::ParseError(error)) => assert_eq!((error.line(), error.column()), (3, 6)),
+            other => panic!("{:?}", other),
+        }
+        let mut life = conways_game_of_life();
+        let log: EventLog<2> = "step 2\nrules B0/S\nstep 1\n".parse().unwrap();
+        assert_eq!(log.replay_onto(&mut life), Err(Error::ZeroNeighbourBirthRule));
+        assert_eq!(life.age(), 2);
+    }
+}
@@ -0,0 +1,127 @@
+//! Parsing of birth/survival rulestrings
+
+use crate::collections::HashSet;
+use crate::error::Error;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Parse a rulestring in B/S notation into birth and survival rules.
+///
+/// The rulestring consists of a birth part starting with `B` and a survival part starting with `S`,
+/// in either order and separated by `/` (letters are case-insensitive, surrounding whitespace is ignored).
+/// Each part lists neighbour counts either as single digits (`B3/S23`) or, to allow counts above 9,
+/// as comma-separated numbers and inclusive ranges (`B5-7,10/S4,6`).
+/// # Errors
+/// * [InvalidRulestring](Error::InvalidRulestring) - If the rulestring is malformed, with the 1-based column of the problem.
+pub(crate) fn parse(rulestring: &str) -> Result<(HashSet<usize>, HashSet<usize>), Error> {
+    let offset = rulestring.len() - rulestring.trim_start().len();
+    let trimmed = rulestring.trim();
+    let mut birth = None;
+    let mut survival = None;
+    let mut start = offset;
+    for part in trimmed.split('/') {
+        let column = start + 1;
+        let mut chars = part.chars();
+        let target = match chars.next().map(|letter| letter.to_ascii_uppercase()) {
+            Some('B') => &mut birth,
+            Some('S') => &mut survival,
+            _ => return Err(Error::InvalidRulestring(column)),
+        };
+        if target.is_some() {
+            return Err(Error::InvalidRulestring(column));
+        }
+        *target = Some(parse_counts(chars.as_str(), column + 1)?);
+        start += part.len() + 1;
+    }
+    match (birth, survival) {
+        (Some(birth), Some(survival)) => Ok((birth, survival)),
+        _ => Err(Error::InvalidRulestring(offset + trimmed.len() + 1)),
+    }
+}
+
+/// Parse the neighbour counts of one part of a rulestring, starting at the given 1-based column.
+fn parse_counts(counts: &str, column: usize) -> Result<HashSet<usize>, Error> {
+    if !counts.contains([',', '-']) {
+        return counts
+            .chars()
+            .enumerate()
+            .map(|(i, digit)| digit.to_digit(10).map(|digit| digit as usize).ok_or(Error::InvalidRulestring(column + i)))
+            .collect();
+    }
+    let mut rules = HashSet::new();
+    let mut start = column;
+    for item in counts.split(',') {
+        let number = |text: &str, column: usize| text.parse::<usize>().map_err(|_| Error::InvalidRulestring(column));
+        match item.split_once('-') {
+            Some((low, high)) => rules.extend(number(low, start)?..=number(high, start + low.len() + 1)?),
+            None => {
+                rules.insert(number(item, start)?);
+            }
+        }
+        start += item.len() + 1;
+    }
+    Ok(rules)
+}
+
+/// Format birth and survival rules in B/S notation, with comma-separated counts if any count is above 9.
+/// The result is always parsed back into the same rules.
+pub(crate) fn format(birth_rules: &HashSet<usize>, survival_rules: &HashSet<usize>) -> String {
+    let counts = |rules: &HashSet<usize>| {
+        let mut rules: Vec<usize> = rules.iter().copied().collect();
+        rules.sort_unstable();
+        match rules[..] {
+            // a lone count above 9 would be read as digits
+            [rule] if rule > 9 => format!("{0}-{0}", rule),
+            _ => rules
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(if rules.iter().any(|rule| *rule > 9) { "," } else { "" }),
+        }
+    };
+    format!("B{}/S{}", counts(birth_rules), counts(survival_rules))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(birth: &[usize], survival: &[usize]) -> (HashSet<usize>, HashSet<usize>) {
+        (birth.iter().copied().collect(), survival.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("B3/S23"), Ok(rules(&[3], &[2, 3])));
+        assert_eq!(parse(" s23/b36 "), Ok(rules(&[3, 6], &[2, 3])));
+        assert_eq!(parse("B3/S"), Ok(rules(&[3], &[])));
+        assert_eq!(parse("B5-7,10/S4,6"), Ok(rules(&[5, 6, 7, 10], &[4, 6])));
+        assert_eq!(parse("B12-12/S"), Ok(rules(&[12], &[])));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err(Error::InvalidRulestring(1)));
+        assert_eq!(parse("B3"), Err(Error::InvalidRulestring(3)));
+        assert_eq!(parse("B3/S2x"), Err(Error::InvalidRulestring(6)));
+        assert_eq!(parse("B3/B3"), Err(Error::InvalidRulestring(4)));
+        assert_eq!(parse("X3/S23"), Err(Error::InvalidRulestring(1)));
+        assert_eq!(parse("B3/S2,x"), Err(Error::InvalidRulestring(7)));
+        assert_eq!(parse("B3/S1-y"), Err(Error::InvalidRulestring(7)));
+        assert_eq!(parse("  B3/S23/"), Err(Error::InvalidRulestring(10)));
+    }
+
+    #[test]
+    fn test_format() {
+        let (birth, survival) = rules(&[6, 3], &[3, 2]);
+        assert_eq!(format(&birth, &survival), "B36/S23");
+        let (birth, survival) = rules(&[5, 10], &[]);
+        assert_eq!(format(&birth, &survival), "B5,10/S");
+        assert_eq!(parse(&format(&birth, &survival)), Ok((birth, survival)));
+        let (birth, survival) = rules(&[12], &[10, 11]);
+        assert_eq!(format(&birth, &survival), "B12-12/S10,11");
+        assert_eq!(parse(&format(&birth, &survival)), Ok((birth, survival)));
+    }
+}
@@ -0,0 +1,794 @@
+//! Reverse searches encoded as boolean satisfiability, such as finding the predecessors of a pattern
+
+use crate::collections::{HashMap, HashSet};
+use crate::life::Life;
+use crate::region::Region;
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::Not;
+
+/// The factor dividing the activity increment after every conflict.
+const ACTIVITY_DECAY: f64 = 0.95;
+/// The number of conflicts in the shortest run between two restarts, multiplied by the Luby sequence.
+const RESTART_INTERVAL: u64 = 100;
+
+/// Variable of a [Solver], or its negation
+///
+/// Literals are created with [new_variable](Solver::new_variable) and negated with `!`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Literal(u32);
+impl Literal {
+    /// Get the index of the variable of the literal, in order of creation.
+    pub fn variable(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    /// Check whether the literal is the negation of its variable.
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// Get the index of the literal among the literals of all the variables.
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+impl Not for Literal {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0 ^ 1)
+    }
+}
+
+/// Entry of the queue of the variables to decide, ordered by activity
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Candidate {
+    /// The activity of the variable when it was queued.
+    activity: f64,
+    /// The variable.
+    variable: usize,
+}
+impl Eq for Candidate {}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.activity.total_cmp(&other.activity).then(other.variable.cmp(&self.variable))
+    }
+}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Conflict-driven clause learning SAT solver
+///
+/// A small solver in the style of MiniSat, with two watched literals per clause, learning of first unique implication
+/// point clauses, activity-based decisions, saved phases and Luby restarts, so search problems can be encoded without an
+/// external dependency. Clauses can be added between calls to [solve](Self::solve), to exclude the solutions already found.
+/// Variables are decided false first, which suits sparse patterns where most cells are dead.
+/// # Example
+/// ```
+/// use ndlife::sat::Solver;
+///
+/// let mut solver = Solver::new();
+/// let (a, b) = (solver.new_variable(), solver.new_variable());
+/// solver.add_clause([a, b]);
+/// solver.add_clause([!a, b]);
+/// assert_eq!(solver.solve(), Some(true));
+/// assert_eq!(solver.value(b), Some(true));
+///
+/// solver.add_clause([!b]);
+/// assert_eq!(solver.solve(), Some(false));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    /// The original and learnt clauses, with their watched literals first.
+    clauses: Vec<Vec<Literal>>,
+    /// The clauses watching each literal, visited when it becomes false.
+    watches: Vec<Vec<usize>>,
+    /// The value of each variable, if it is assigned.
+    assignment: Vec<Option<bool>>,
+    /// The decision level at which each variable was assigned.
+    levels: Vec<usize>,
+    /// The clause which implied each variable, or [None] for decisions and facts.
+    reasons: Vec<Option<usize>>,
+    /// The true literals, in order of assignment.
+    trail: Vec<Literal>,
+    /// The length of the trail at the start of each decision level.
+    trail_limits: Vec<usize>,
+    /// The number of literals of the trail whose consequences were propagated.
+    propagated: usize,
+    /// The activity of each variable, bumped when it takes part in a conflict.
+    activity: Vec<f64>,
+    /// The amount added to the activity of a bumped variable.
+    increment: f64,
+    /// The variables to decide, by activity, with stale entries skipped.
+    queue: BinaryHeap<Candidate>,
+    /// The last value of each variable, tried first when it is decided again.
+    phases: Vec<bool>,
+    /// Whether the clauses are known to be unsatisfiable.
+    unsatisfiable: bool,
+    /// The value of each variable in the last solution found.
+    model: Vec<bool>,
+    /// The largest number of conflicts of a call to [solve](Self::solve), if limited.
+    conflict_limit: Option<u64>,
+}
+impl Solver {
+    /// Create a solver without variables or clauses.
+    pub fn new() -> Self {
+        Self {
+            increment: 1.0,
+            ..Self::default()
+        }
+    }
+
+    /// Create a variable.
+    /// # Returns
+    /// The positive literal of the variable.
+    pub fn new_variable(&mut self) -> Literal {
+        let variable = self.assignment.len();
+        self.assignment.push(None);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.activity.push(0.0);
+        self.phases.push(false);
+        self.watches.extend([Vec::new(), Vec::new()]);
+        self.queue.push(Candidate { activity: 0.0, variable });
+        Literal(u32::try_from(variable << 1).expect("fewer than 2^31 variables"))
+    }
+
+    /// Get the number of variables.
+    pub fn variables(&self) -> usize {
+        self.assignment.len()
+    }
+
+    /// Set the largest number of conflicts of a call to [solve](Self::solve), or [None] to search until an answer is found.
+    pub fn set_conflict_limit(&mut self, conflict_limit: Option<u64>) {
+        self.conflict_limit = conflict_limit;
+    }
+
+    /// Add a clause, satisfied when at least one of its literals is true.
+    /// # Arguments
+    /// * `clause` - The literals of the clause; an empty clause makes the problem unsatisfiable.
+    /// # Panics
+    /// If a literal belongs to a variable of another solver.
+    pub fn add_clause(&mut self, clause: impl IntoIterator<Item = Literal>) {
+        self.backtrack(0);
+        if self.unsatisfiable {
+            return;
+        }
+        let mut clause: Vec<Literal> = clause.into_iter().collect();
+        assert!(clause.iter().all(|literal| literal.variable() < self.variables()), "the literal of an unknown variable");
+        clause.sort_unstable();
+        clause.dedup();
+        if clause.windows(2).any(|pair| pair[1] == !pair[0]) || clause.iter().any(|literal| self.literal_value(*literal) == Some(true)) {
+            return;
+        }
+        clause.retain(|literal| self.literal_value(*literal).is_none());
+        match clause.len() {
+            0 => self.unsatisfiable = true,
+            1 => {
+                self.assign(clause[0], None);
+                if self.propagate().is_some() {
+                    self.unsatisfiable = true;
+                }
+            }
+            _ => {
+                self.watch(clause);
+            }
+        }
+    }
+
+    /// Search for values of the variables satisfying all the clauses.
+    /// # Returns
+    /// [Some] with whether a solution exists, or [None] if the conflict limit was reached first.
+    pub fn solve(&mut self) -> Option<bool> {
+        self.backtrack(0);
+        if self.unsatisfiable || self.propagate().is_some() {
+            self.unsatisfiable = true;
+            return Some(false);
+        }
+        let (mut conflicts, mut restarts, mut until_restart) = (0, 0, RESTART_INTERVAL);
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.trail_limits.is_empty() {
+                    self.unsatisfiable = true;
+                    return Some(false);
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                if learnt.len() == 1 {
+                    self.assign(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let clause = self.watch(learnt);
+                    self.assign(asserting, Some(clause));
+                }
+                self.increment /= ACTIVITY_DECAY;
+                conflicts += 1;
+                until_restart = until_restart.saturating_sub(1);
+                if self.conflict_limit.is_some_and(|limit| conflicts > limit) {
+                    self.backtrack(0);
+                    return None;
+                }
+                continue;
+            }
+            if until_restart == 0 {
+                restarts += 1;
+                until_restart = RESTART_INTERVAL * luby(restarts);
+                self.backtrack(0);
+                continue;
+            }
+            let Some(variable) = self.pick() else {
+                self.model = self.assignment.iter().map(|value| value == &Some(true)).collect();
+                return Some(true);
+            };
+            self.trail_limits.push(self.trail.len());
+            let literal = Literal((variable << 1) as u32);
+            self.assign(if self.phases[variable] { literal } else { !literal }, None);
+        }
+    }
+
+    /// Get the value of a literal in the last solution found.
+    /// # Returns
+    /// [Some] with the value, or [None] if no solution was found since the variable was created.
+    pub fn value(&self, literal: Literal) -> Option<bool> {
+        self.model.get(literal.variable()).map(|value| *value != literal.is_negated())
+    }
+
+    /// Get the current value of a literal, if its variable is assigned.
+    fn literal_value(&self, literal: Literal) -> Option<bool> {
+        self.assignment[literal.variable()].map(|value| value != literal.is_negated())
+    }
+
+    /// Store a clause of at least two literals, watching its first two.
+    /// # Returns
+    /// The index of the clause.
+    fn watch(&mut self, clause: Vec<Literal>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0].index()].push(index);
+        self.watches[clause[1].index()].push(index);
+        self.clauses.push(clause);
+        index
+    }
+
+    /// Make a literal true at the current decision level.
+    fn assign(&mut self, literal: Literal, reason: Option<usize>) {
+        let variable = literal.variable();
+        self.assignment[variable] = Some(!literal.is_negated());
+        self.levels[variable] = self.trail_limits.len();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    /// Assign the literals implied by the clauses whose other literals are all false.
+    /// # Returns
+    /// The index of a clause whose literals are all false, or [None] if there is no conflict.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = !self.trail[self.propagated];
+            self.propagated += 1;
+            let watching = core::mem::take(&mut self.watches[falsified.index()]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for (position, &index) in watching.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watching[position..]);
+                    break;
+                }
+                let clause = &mut self.clauses[index];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if self.assignment[first.variable()].map(|value| value != first.is_negated()) == Some(true) {
+                    kept.push(index);
+                    continue;
+                }
+                let replacement = (2..clause.len()).find(|k| {
+                    let literal = clause[*k];
+                    self.assignment[literal.variable()].map(|value| value != literal.is_negated()) != Some(false)
+                });
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    let watched = clause[1];
+                    self.watches[watched.index()].push(index);
+                    continue;
+                }
+                kept.push(index);
+                match self.literal_value(first) {
+                    Some(false) => conflict = Some(index),
+                    _ => self.assign(first, Some(index)),
+                }
+            }
+            self.watches[falsified.index()] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Learn the first unique implication point clause of a conflict.
+    /// # Returns
+    /// The learnt clause, with the literal it asserts first and the literal of the highest other level second,
+    /// and the level to backtrack to.
+    fn analyze(&mut self, conflict: usize) -> (Vec<Literal>, usize) {
+        let level = self.trail_limits.len();
+        let mut seen = vec![false; self.variables()];
+        let mut learnt = vec![Literal(0)];
+        let (mut pending, mut clause, mut position) = (0, conflict, self.trail.len());
+        let mut implied: Option<Literal> = None;
+        loop {
+            let skip = usize::from(implied.is_some());
+            for k in skip..self.clauses[clause].len() {
+                let literal = self.clauses[clause][k];
+                let variable = literal.variable();
+                if seen[variable] || self.levels[variable] == 0 {
+                    continue;
+                }
+                seen[variable] = true;
+                self.bump(variable);
+                if self.levels[variable] == level {
+                    pending += 1;
+                } else {
+                    learnt.push(literal);
+                }
+            }
+            loop {
+                position -= 1;
+                if seen[self.trail[position].variable()] {
+                    break;
+                }
+            }
+            let literal = self.trail[position];
+            seen[literal.variable()] = false;
+            implied = Some(literal);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            clause = self.reasons[literal.variable()].expect("implied literals have a reason");
+        }
+        learnt[0] = !implied.expect("the conflict has a literal of the current level");
+        let mut backtrack_level = 0;
+        for k in 1..learnt.len() {
+            let other = self.levels[learnt[k].variable()];
+            if other > backtrack_level {
+                backtrack_level = other;
+                learnt.swap(1, k);
+            }
+        }
+        (learnt, backtrack_level)
+    }
+
+    /// Increase the activity of a variable taking part in a conflict.
+    fn bump(&mut self, variable: usize) {
+        self.activity[variable] += self.increment;
+        if self.activity[variable] > 1e100 {
+            for activity in self.activity.iter_mut() {
+                *activity *= 1e-100;
+            }
+            self.increment *= 1e-100;
+            self.queue = (0..self.variables())
+                .filter(|variable| self.assignment[*variable].is_none())
+                .map(|variable| Candidate {
+                    activity: self.activity[variable],
+                    variable,
+                })
+                .collect();
+        } else if self.assignment[variable].is_none() {
+            self.queue.push(Candidate {
+                activity: self.activity[variable],
+                variable,
+            });
+        }
+    }
+
+    /// Get the unassigned variable with the highest activity, or [None] if all the variables are assigned.
+    fn pick(&mut self) -> Option<usize> {
+        while let Some(candidate) = self.queue.pop() {
+            if self.assignment[candidate.variable].is_none() && candidate.activity == self.activity[candidate.variable] {
+                return Some(candidate.variable);
+            }
+        }
+        None
+    }
+
+    /// Unassign the variables of the levels above a level, saving their phases.
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+        let start = self.trail_limits[level];
+        for literal in self.trail.drain(start..) {
+            let variable = literal.variable();
+            self.phases[variable] = !literal.is_negated();
+            self.assignment[variable] = None;
+            self.queue.push(Candidate {
+                activity: self.activity[variable],
+                variable,
+            });
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = self.propagated.min(start);
+    }
+}
+
+/// Get the element of the Luby sequence 1, 1, 2, 1, 1, 2, 4, … at an index starting from 1.
+fn luby(index: u64) -> u64 {
+    let mut size = 1;
+    while size < index {
+        size = 2 * size + 1;
+    }
+    if size == index {
+        size.div_ceil(2)
+    } else {
+        luby(index - size / 2)
+    }
+}
+
+/// Result of a [PredecessorSearch]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Predecessor<const N: usize> {
+    /// The alive cells of a state inside the region which evolves into the target.
+    Found(HashSet<[i64; N]>),
+    /// No state inside the region evolves into the target.
+    Impossible,
+    /// The conflict limit was reached before an answer was found.
+    Unknown,
+}
+
+/// Search for the states which evolve into a target pattern, with every alive cell inside a region
+///
+/// The cells of every generation from the predecessor up to the target are the variables of a [Solver]: the cells inside
+/// the region in the first generation, one more cell around it in each following generation, as no cell is born farther
+/// than that, and the transition of every cell is encoded with a unary count of its alive neighbours.
+/// Finding a predecessor a few generations back, a garden of eden proof for a box or a synthesis with few cells
+/// are then a single call, and [exclude](Self::exclude) enumerates the predecessors one by one.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::sat::{Predecessor, PredecessorSearch};
+///
+/// let mut life = conways_game_of_life();
+/// let block: std::collections::HashSet<_> = [[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect();
+/// life.set_alive_cells(block.clone());
+///
+/// let mut search = PredecessorSearch::new(&life, [-1..=2, -1..=2]);
+/// search.set_max_population(Some(3));
+/// let Predecessor::Found(cells) = search.solve() else { panic!("three cells make a block") };
+/// life.set_alive_cells(cells);
+/// life.next_generation();
+/// assert_eq!(life.alive_cells(), &block);
+///
+/// // two cells make nothing
+/// search.set_max_population(Some(2));
+/// assert_eq!(search.solve(), Predecessor::Impossible);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PredecessorSearch<const N: usize> {
+    /// The survival and birth rules.
+    rules: (HashSet<usize>, HashSet<usize>),
+    /// The alive cells to evolve into.
+    target: HashSet<[i64; N]>,
+    /// The region of the alive cells of the predecessor.
+    region: Region<N>,
+    /// The number of generations from the predecessor to the target.
+    generations: u64,
+    /// The largest number of alive cells of the predecessor, if limited.
+    max_population: Option<usize>,
+    /// The largest number of conflicts of the solver, if limited.
+    conflict_limit: Option<u64>,
+    /// The states inside the region which are not accepted as predecessors.
+    excluded: Vec<HashSet<[i64; N]>>,
+}
+impl<const N: usize> PredecessorSearch<N> {
+    /// Create a search for the states one generation before the alive cells of a game of life.
+    /// # Arguments
+    /// * `life` - The game of life whose rules and alive cells are the target.
+    /// * `region` - The region of the alive cells of the predecessors, such as the inclusive range of coordinates along each axis.
+    pub fn new(life: &Life<N>, region: impl Into<Region<N>>) -> Self {
+        Self {
+            rules: (life.survival_rules().clone(), life.birth_rules().clone()),
+            target: life.alive_cells().clone(),
+            region: region.into(),
+            generations: 1,
+            max_population: None,
+            conflict_limit: None,
+            excluded: Vec::new(),
+        }
+    }
+
+    /// Set the number of generations from the predecessor to the target, 1 by default; 0 checks that the target fits in the region.
+    pub fn set_generations(&mut self, generations: u64) {
+        self.generations = generations;
+    }
+
+    /// Set the largest number of alive cells of the predecessor, or [None] for any number.
+    pub fn set_max_population(&mut self, max_population: Option<usize>) {
+        self.max_population = max_population;
+    }
+
+    /// Set the largest number of conflicts of the solver, or [None] to search until an answer is found.
+    pub fn set_conflict_limit(&mut self, conflict_limit: Option<u64>) {
+        self.conflict_limit = conflict_limit;
+    }
+
+    /// Exclude a predecessor, such as one already found or the target itself; only its cells inside the region matter.
+    pub fn exclude(&mut self, cells: HashSet<[i64; N]>) {
+        self.excluded.push(cells);
+    }
+
+    /// Search for a predecessor.
+    /// # Returns
+    /// The [Predecessor] found, or why there is none.
+    pub fn solve(&self) -> Predecessor<N> {
+        let last = self.region.inflate(self.generations);
+        if self.target.iter().any(|cell| !last.contains(cell)) {
+            return Predecessor::Impossible;
+        }
+        let mut solver = Solver::new();
+        solver.set_conflict_limit(self.conflict_limit);
+        let truth = solver.new_variable();
+        solver.add_clause([truth]);
+
+        let first: HashMap<[i64; N], Literal> = self.region.iter_cells().map(|cell| (cell, solver.new_variable())).collect();
+        let mut previous = first.clone();
+        for generation in 1..=self.generations {
+            let area = self.region.inflate(generation);
+            let current: HashMap<[i64; N], Literal> = area
+                .iter_cells()
+                .map(|cell| {
+                    let literal = match generation == self.generations {
+                        true if self.target.contains(&cell) => truth,
+                        true => !truth,
+                        false => solver.new_variable(),
+                    };
+                    (cell, literal)
+                })
+                .collect();
+            for cell in area.iter_cells() {
+                let inputs: Vec<Literal> = Life::<N>::neighbours(&cell).filter_map(|neighbour| previous.get(&neighbour).copied()).collect();
+                self.transition(&mut solver, previous.get(&cell).copied().unwrap_or(!truth), &inputs, current[&cell]);
+            }
+            previous = current;
+        }
+        let mut cells: Vec<([i64; N], Literal)> = first.into_iter().collect();
+        cells.sort_unstable();
+        if self.generations == 0 {
+            for (cell, literal) in &cells {
+                solver.add_clause([if self.target.contains(cell) { *literal } else { !*literal }]);
+            }
+        }
+        if let Some(max_population) = self.max_population {
+            at_most(&mut solver, &cells.iter().map(|(_, literal)| *literal).collect::<Vec<_>>(), max_population);
+        }
+        for excluded in &self.excluded {
+            solver.add_clause(cells.iter().map(|(cell, literal)| if excluded.contains(cell) { !*literal } else { *literal }));
+        }
+        match solver.solve() {
+            Some(true) => Predecessor::Found(
+                cells
+                    .into_iter()
+                    .filter(|(_, literal)| solver.value(*literal) == Some(true))
+                    .map(|(cell, _)| cell)
+                    .collect(),
+            ),
+            Some(false) => Predecessor::Impossible,
+            None => Predecessor::Unknown,
+        }
+    }
+
+    /// Add the clauses making a cell alive in the next generation exactly when the rules say so.
+    /// # Arguments
+    /// * `solver` - The solver to add the clauses to.
+    /// * `centre` - The literal of the cell in the current generation.
+    /// * `inputs` - The literals of the neighbours of the cell which can be alive in the current generation.
+    /// * `next` - The literal of the cell in the next generation.
+    fn transition(&self, solver: &mut Solver, centre: Literal, inputs: &[Literal], next: Literal) {
+        let at_least = count(solver, inputs);
+        let (survival, birth) = &self.rules;
+        for neighbours in 0..=inputs.len() {
+            // exactly this many alive neighbours
+            let mut exactly = Vec::with_capacity(2);
+            if neighbours > 0 {
+                exactly.push(!at_least[neighbours - 1]);
+            }
+            if neighbours < inputs.len() {
+                exactly.push(at_least[neighbours]);
+            }
+            for (alive, rules) in [(true, survival), (false, birth)] {
+                let state = if alive { !centre } else { centre };
+                let outcome = if rules.contains(&neighbours) { next } else { !next };
+                solver.add_clause(exactly.iter().copied().chain([state, outcome]));
+            }
+        }
+    }
+}
+
+/// Add a unary count of the true inputs to a solver.
+/// # Returns
+/// The literals true exactly when at least 1, 2, … and all the inputs are true.
+fn count(solver: &mut Solver, inputs: &[Literal]) -> Vec<Literal> {
+    let mut at_least: Vec<Literal> = Vec::with_capacity(inputs.len());
+    for (i, input) in inputs.iter().enumerate() {
+        let next: Vec<Literal> = (0..=i).map(|_| solver.new_variable()).collect();
+        for j in 0..=i {
+            // at least j + 1 of the first i + 1 inputs
+            let kept = at_least.get(j).copied();
+            let below = if j == 0 { None } else { Some(at_least[j - 1]) };
+            if let Some(kept) = kept {
+                solver.add_clause([!kept, next[j]]);
+            }
+            match below {
+                Some(below) => {
+                    solver.add_clause([!*input, !below, next[j]]);
+                    solver.add_clause(kept.into_iter().chain([below, !next[j]]));
+                }
+                None => solver.add_clause([!*input, next[j]]),
+            }
+            solver.add_clause(kept.into_iter().chain([*input, !next[j]]));
+        }
+        at_least = next;
+    }
+    at_least
+}
+
+/// Add clauses allowing at most a number of true inputs to a solver, with a sequential counter.
+fn at_most(solver: &mut Solver, inputs: &[Literal], max: usize) {
+    if max == 0 {
+        for input in inputs {
+            solver.add_clause([!*input]);
+        }
+        return;
+    }
+    // whether at least j + 1 of the inputs so far are true
+    let mut counter: Vec<Literal> = Vec::new();
+    for input in inputs {
+        let next: Vec<Literal> = (0..max).map(|_| solver.new_variable()).collect();
+        solver.add_clause([!*input, next[0]]);
+        for j in 0..max {
+            if let Some(previous) = counter.get(j) {
+                solver.add_clause([!*previous, next[j]]);
+                if j + 1 < max {
+                    solver.add_clause([!*input, !*previous, next[j + 1]]);
+                }
+            }
+        }
+        if let Some(full) = counter.get(max - 1) {
+            solver.add_clause([!*input, !*full]);
+        }
+        counter = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_luby() {
+        assert_eq!((1..=15).map(luby).collect::<Vec<_>>(), [1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_solver() {
+        // pigeonhole principle: 4 pigeons do not fit in 3 holes, which needs conflicts to find out
+        let mut solver = Solver::new();
+        solver.set_conflict_limit(Some(0));
+        let pigeons: Vec<Vec<Literal>> = (0..4).map(|_| (0..3).map(|_| solver.new_variable()).collect()).collect();
+        for holes in &pigeons {
+            solver.add_clause(holes.iter().copied());
+        }
+        for hole in 0..3 {
+            for (a, holes) in pigeons.iter().enumerate() {
+                for other in &pigeons[a + 1..] {
+                    solver.add_clause([!holes[hole], !other[hole]]);
+                }
+            }
+        }
+        assert_eq!(solver.solve(), None);
+        solver.set_conflict_limit(None);
+        assert_eq!(solver.solve(), Some(false));
+        assert_eq!(pigeons[0][0].variable(), 0);
+
+        // every solution of a chain of implications
+        let mut solver = Solver::new();
+        let chain: Vec<Literal> = (0..5).map(|_| solver.new_variable()).collect();
+        for pair in chain.windows(2) {
+            solver.add_clause([!pair[0], pair[1]]);
+        }
+        let mut solutions = 0;
+        while solver.solve() == Some(true) {
+            solutions += 1;
+            let values: Vec<bool> = chain.iter().map(|literal| solver.value(*literal).unwrap()).collect();
+            assert!(values.windows(2).all(|pair| !pair[0] || pair[1]));
+            solver.add_clause(chain.iter().zip(values).map(|(literal, value)| if value { !*literal } else { *literal }));
+        }
+        assert_eq!(solutions, 6);
+        assert!((!chain[1]).is_negated());
+        solver.add_clause([]);
+        assert_eq!(solver.solve(), Some(false));
+    }
+
+    #[test]
+    fn test_counters() {
+        for inputs in 0..5 {
+            for max in 0..5 {
+                let mut solver = Solver::new();
+                let literals: Vec<Literal> = (0..inputs).map(|_| solver.new_variable()).collect();
+                let at_least = count(&mut solver, &literals);
+                at_most(&mut solver, &literals, max);
+                let mut solutions = 0;
+                while solver.solve() == Some(true) {
+                    solutions += 1;
+                    let alive = literals.iter().filter(|literal| solver.value(**literal) == Some(true)).count();
+                    assert!(alive <= max);
+                    assert!(at_least.iter().enumerate().all(|(j, literal)| solver.value(*literal) == Some(alive > j)));
+                    let blocking: Vec<Literal> = literals
+                        .iter()
+                        .map(|literal| if solver.value(*literal) == Some(true) { !*literal } else { *literal })
+                        .collect();
+                    solver.add_clause(blocking);
+                }
+                let expected: usize = (0..=max.min(inputs)).map(|k| (0..k).fold(1, |c, i| c * (inputs - i) / (i + 1))).sum();
+                assert_eq!(solutions, expected, "{} inputs, at most {}", inputs, max);
+            }
+        }
+    }
+
+    #[test]
+    fn test_predecessor_search() {
+        let mut life = conways_game_of_life();
+        let glider: HashSet<[i64; 2]> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+        life.set_alive_cells(glider.clone());
+        let mut search = PredecessorSearch::new(&life, [-1..=3, -1..=3]);
+        search.set_generations(2);
+        let Predecessor::Found(cells) = search.solve() else {
+            panic!("the glider has predecessors")
+        };
+        let mut predecessor = life.clone();
+        predecessor.set_alive_cells(cells.clone());
+        predecessor.next_generation();
+        predecessor.next_generation();
+        assert_eq!(predecessor.alive_cells(), &glider);
+
+        // enumerate the predecessors one generation back inside the bounding box of the glider
+        search.set_generations(1);
+        search.region = Region::new([0, 0], [2, 2]);
+        let mut found = HashSet::new();
+        while let Predecessor::Found(cells) = search.solve() {
+            let mut predecessor = life.clone();
+            predecessor.set_alive_cells(cells.clone());
+            predecessor.next_generation();
+            assert_eq!(predecessor.alive_cells(), &glider);
+            assert!(found.insert(cells.iter().copied().collect::<std::collections::BTreeSet<_>>()));
+            search.exclude(cells);
+        }
+        assert!(!found.is_empty());
+
+        // a block does not fit in a single cell, nor does it in a cell each generation back
+        life.set_alive_cells([[0, 0], [1, 0], [0, 1], [1, 1]].into_iter().collect());
+        let mut search = PredecessorSearch::new(&life, [0..=0, 0..=0]);
+        assert_eq!(search.solve(), Predecessor::Impossible);
+        search.set_generations(0);
+        assert_eq!(search.solve(), Predecessor::Impossible);
+        let mut search = PredecessorSearch::new(&life, [0..=1, 0..=1]);
+        search.set_generations(0);
+        assert_eq!(search.solve(), Predecessor::Found(life.alive_cells().clone()));
+        // the block and the 4 triples of its cells in its own box
+        search.set_generations(3);
+        let mut predecessors = 0;
+        while let Predecessor::Found(cells) = search.solve() {
+            assert!(cells.len() >= 3);
+            predecessors += 1;
+            search.exclude(cells);
+        }
+        assert_eq!(predecessors, 5);
+    }
+}
@@ -0,0 +1,977 @@
+//! Searches for the objects of a rule, in random soups or among all small patterns
+
+use crate::error::{Error, ParseError};
+use crate::life::{Life, StabilityConfig};
+use crate::pattern::{normalized, Pattern};
+use crate::region::Region;
+use crate::symmetry::Symmetry;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+/// Still life, oscillator or spaceship, identified up to the symmetries of the lattice
+///
+/// Objects are named by their kind and size: `xs` and the population for still lifes, `xp` and the period for oscillators,
+/// `xq` and the period for spaceships, followed by the cells of their canonical phase. Cells are separated by commas,
+/// with their coordinates separated by dots, so the name can be parsed back and is the same in every dimension.
+/// The names are not apgcodes and cannot be looked up in Catagolue.
+/// The canonical phase is the phase whose [canonical form](Pattern::canonical_form) comes first.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::search::Object;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let blinker = Object::identify(&life, 30).unwrap();
+/// assert!(blinker.is_oscillator());
+/// assert_eq!(blinker.period(), 2);
+/// assert_eq!(blinker.to_string(), "xp2_0.0,0.1,0.2");
+/// assert_eq!("xp2_0.0,0.1,0.2".parse(), Ok(blinker));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Object<const N: usize> {
+    /// The smallest number of generations after which the object repeats.
+    period: u64,
+    /// Whether the object moves in each period.
+    moving: bool,
+    /// The sorted cells of the canonical phase.
+    cells: Vec<[i64; N]>,
+}
+impl<const N: usize> Object<N> {
+    /// Identify the alive cells of a game of life as one object, evolving a copy of them without changing the game of life.
+    /// # Arguments
+    /// * `life` - The game of life holding the object; its rules decide how the object evolves.
+    /// * `max_period` - The largest period to detect.
+    /// # Returns
+    /// [Some] with the object, or [None] if the cells do not repeat within `max_period` generations, possibly moved.
+    pub fn identify(life: &Life<N>, max_period: u64) -> Option<Self> {
+        let mut alone = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), life.alive_cells().clone()).ok()?;
+        let origin = Region::bounding(alone.alive_cells())?.min();
+        let start = normalized(alone.alive_cells().iter().copied());
+        let mut phases = vec![Pattern::new(alone.alive_cells().clone())];
+        for period in 1..=max_period {
+            alone.next_generation();
+            let bounds = Region::bounding(alone.alive_cells())?;
+            if alone.alive_cells().len() == start.len() && normalized(alone.alive_cells().iter().copied()) == start {
+                return Some(Self {
+                    period,
+                    moving: bounds.min() != origin,
+                    cells: phases.iter().map(Pattern::canonical_cells).min()?,
+                });
+            }
+            phases.push(Pattern::new(alone.alive_cells().clone()));
+        }
+        None
+    }
+
+    /// Get the smallest number of generations after which the object repeats.
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Get the population of the canonical phase.
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Get whether the object never changes.
+    pub fn is_still_life(&self) -> bool {
+        self.period == 1 && !self.moving
+    }
+
+    /// Get whether the object changes but repeats in place.
+    pub fn is_oscillator(&self) -> bool {
+        self.period > 1 && !self.moving
+    }
+
+    /// Get whether the object repeats moved.
+    pub fn is_spaceship(&self) -> bool {
+        self.moving
+    }
+
+    /// Get the canonical phase of the object, with its bounding box starting at the origin.
+    pub fn pattern(&self) -> Pattern<N> {
+        self.cells.iter().copied().collect()
+    }
+}
+impl<const N: usize> fmt::Display for Object<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.moving, self.period) {
+            (true, period) => write!(f, "xq{}_", period)?,
+            (false, 1) => write!(f, "xs{}_", self.cells.len())?,
+            (false, period) => write!(f, "xp{}_", period)?,
+        }
+        for (index, cell) in self.cells.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            let coordinates: Vec<String> = cell.iter().map(i64::to_string).collect();
+            f.write_str(&coordinates.join("."))?;
+        }
+        Ok(())
+    }
+}
+impl<const N: usize> FromStr for Object<N> {
+    type Err = Error;
+
+    /// Parse an object from its name.
+    /// # Errors
+    /// * [ParseError](Error::ParseError) - If the name is malformed, on line 1.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |column: usize, message: &str| Error::ParseError(ParseError::new(1, column, message));
+        let (head, body) = text.split_once('_').ok_or_else(|| error(1, "expected an underscore after the kind of object"))?;
+        let number: u64 = head
+            .get(2..)
+            .and_then(|number| number.parse().ok())
+            .filter(|number| *number > 0)
+            .ok_or_else(|| error(3, "expected a positive number"))?;
+        let mut column = head.len() + 2;
+        let mut cells = Vec::new();
+        for cell in body.split(',') {
+            let coordinates: Vec<i64> = cell
+                .split('.')
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| error(column, "expected a coordinate"))?;
+            cells.push(<[i64; N]>::try_from(coordinates).map_err(|_| error(column, "wrong number of coordinates"))?);
+            column += cell.len() + 1;
+        }
+        let sorted = normalized(cells.iter().copied());
+        if sorted != cells {
+            return Err(error(head.len() + 2, "cells are not sorted from the origin"));
+        }
+        let (period, moving) = match &head[..2] {
+            "xs" if number == cells.len() as u64 => (1, false),
+            "xs" => return Err(error(3, "the population does not match the cells")),
+            "xp" if number > 1 => (number, false),
+            "xq" => (number, true),
+            _ => return Err(error(1, "expected xs, xp with a period above 1, or xq")),
+        };
+        Ok(Self { period, moving, cells })
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Identify every connected component of the alive cells as an object, as when taking a census.
+    /// # Arguments
+    /// * `max_period` - The largest period to detect.
+    /// # Returns
+    /// The components, each with [Some] object or [None] if it is not an object on its own,
+    /// such as an unfinished reaction or a part of an object made of several components.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// // block and glider
+    /// life.set_alive_cells([[0, 0], [1, 0], [0, 1], [1, 1], [10, 0], [11, 0], [12, 0], [12, 1], [11, 2]].into_iter().collect());
+    ///
+    /// let mut names: Vec<String> = life.identify_objects(30).into_iter().map(|(_, object)| object.unwrap().to_string()).collect();
+    /// names.sort();
+    /// assert_eq!(names, ["xq4_0.0,0.1,0.2,1.0,2.1", "xs4_0.0,0.1,1.0,1.1"]);
+    /// ```
+    pub fn identify_objects(&self, max_period: u64) -> Vec<(HashSet<[i64; N]>, Option<Object<N>>)> {
+        self.connected_components()
+            .into_iter()
+            .map(|component| {
+                let object = Life::new_with_alive_cells(self.birth_rules().clone(), self.survival_rules().clone(), component.clone())
+                    .ok()
+                    .and_then(|life| Object::identify(&life, max_period));
+                (component, object)
+            })
+            .collect()
+    }
+}
+
+/// Find the oscillators of a period that fit in a box, by evolving every pattern in it.
+///
+/// Patterns are only evolved once up to translations and the symmetries of the lattice that keep them in the box,
+/// so the search handles boxes of up to about 25 cells. A pattern is an oscillator if all its cells, possibly
+/// several separate oscillators, repeat in place with the period as the smallest period; every phase is reported once.
+/// # Arguments
+/// * `life` - The game of life whose rules the patterns follow.
+/// * `size` - The number of cells along each axis of the box.
+/// * `period` - The period of the oscillators, 1 for still lifes.
+/// # Returns
+/// The oscillators, in the order of their periods (all the same), then their canonical phases.
+/// # Panics
+/// If the box has more than 63 cells.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::search::oscillator_search;
+///
+/// let oscillators = oscillator_search(&conways_game_of_life(), [4, 4], 2);
+/// let names: Vec<String> = oscillators.iter().map(|oscillator| oscillator.to_string()).collect();
+/// // the blinker, the toad, the beacon and the clock
+/// assert_eq!(names, ["xp2_0.0,0.1,0.2", "xp2_0.0,0.1,0.2,1.1,1.2,1.3", "xp2_0.0,0.1,1.0,1.1,2.2,2.3,3.2,3.3", "xp2_0.1,1.1,1.3,2.0,2.2,3.2"]);
+/// ```
+pub fn oscillator_search<const N: usize>(life: &Life<N>, size: [u64; N], period: u64) -> Vec<Object<N>> {
+    let max = size.map(|size| i64::try_from(size).unwrap_or(i64::MAX) - 1);
+    let cells: Vec<[i64; N]> = Region::new([0; N], max).iter_cells().collect();
+    assert!(cells.len() < 64, "the box has {} cells, more than 63", cells.len());
+    let elements = Symmetry::SignedPermutations.elements::<N>().expect("signed permutations exist in every dimension");
+    let mut oscillators = BTreeSet::new();
+    for bits in 1..1u64 << cells.len() {
+        let pattern: Vec<[i64; N]> = (0..cells.len()).filter(|i| bits >> i & 1 == 1).map(|i| cells[i]).collect();
+        // the first of its translations in the box
+        if (0..N).any(|axis| pattern.iter().all(|cell| cell[axis] > 0)) {
+            continue;
+        }
+        let pattern = normalized(pattern);
+        let smaller_image = elements.iter().any(|element| {
+            let image = normalized(pattern.iter().map(|cell| element.apply(cell)));
+            image.iter().all(|cell| (0..N).all(|axis| cell[axis] <= max[axis])) && image < pattern
+        });
+        if smaller_image {
+            continue;
+        }
+        let Ok(candidate) = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), pattern.into_iter().collect()) else {
+            break;
+        };
+        if let Some(object) = Object::identify(&candidate, period) {
+            if object.period == period && !object.moving {
+                oscillators.insert(object);
+            }
+        }
+    }
+    oscillators.into_iter().collect()
+}
+
+/// Find a spaceship of a period and displacement whose phases all fit in a box, with a depth-first search as lifesrc does.
+///
+/// The unknowns are the states of the cells of the box in every generation of a period; cells outside the box are dead,
+/// and the generation after the last is the first moved by the displacement. Cells are set one at a time, dead first,
+/// along with every state the rule then forces, and the search backtracks as soon as the rule cannot turn a generation
+/// into the next, so small boxes are searched quickly.
+/// A zero displacement finds oscillators and still lifes instead.
+/// # Arguments
+/// * `life` - The game of life whose rules the spaceship follows.
+/// * `size` - The number of cells along each axis of the box, such as the width and height of a 2-dimensional spaceship.
+/// * `period` - The number of generations after which the spaceship repeats moved, at least 1.
+/// * `displacement` - How far the spaceship moves in each period.
+/// # Returns
+/// [Some] with the first phase of the first spaceship found, with the box starting at the origin, or [None] if there is none.
+/// The period in which the spaceship repeats can be a divisor of the requested one.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::search::{spaceship_search, Object};
+///
+/// let life = conways_game_of_life();
+/// let mut glider = life.clone();
+/// glider.set_alive_cells(spaceship_search(&life, [4, 4], 4, [1, 1]).unwrap().into_cells());
+///
+/// let object = Object::identify(&glider, 4).unwrap();
+/// assert!(object.is_spaceship());
+/// assert_eq!(object.population(), 5);
+///
+/// // there is no spaceship moving orthogonally by one cell in two generations
+/// assert_eq!(spaceship_search(&life, [4, 4], 2, [1, 0]), None);
+/// ```
+pub fn spaceship_search<const N: usize>(life: &Life<N>, size: [u64; N], period: u64, displacement: [i64; N]) -> Option<Pattern<N>> {
+    let mut search = PhaseSearch::new(life, size, usize::try_from(period.max(1)).ok()?, displacement)?;
+    if !search.search(0, &mut |search| search.states.contains(&ALIVE)) {
+        return None;
+    }
+    Some(search.alive_cells().collect())
+}
+
+/// Find the strict still lifes with at most a number of cells that fit in a box, up to the symmetries of the lattice.
+///
+/// The cells of the box are set with the depth-first search of [spaceship_search], so boxes much larger than those of
+/// [oscillator_search] can be enumerated. Pseudo still lifes, whose islands can be split into still lifes on their own
+/// such as two blocks side by side, are left out, so the counts for each population can be checked against the published
+/// ones, such as 2, 1, 5, 4 and 9 still lifes of 4 to 8 cells in Conway's game of life.
+/// # Arguments
+/// * `life` - The game of life whose rules the still lifes follow.
+/// * `size` - The number of cells along each axis of the box.
+/// * `max_population` - The largest number of alive cells of the still lifes.
+/// # Returns
+/// The still lifes found, by population and then by name.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::search::still_life_search;
+///
+/// let still_lifes = still_life_search(&conways_game_of_life(), [4, 4], 6);
+/// let names: Vec<String> = still_lifes.iter().map(|still_life| still_life.to_string()).collect();
+/// // the block, the tub, the boat and 5 still lifes of 6 cells
+/// assert_eq!(names.len(), 8);
+/// assert_eq!(names[0], "xs4_0.0,0.1,1.0,1.1");
+/// assert!(still_lifes.iter().all(|still_life| still_life.population() <= 6));
+/// ```
+pub fn still_life_search<const N: usize>(life: &Life<N>, size: [u64; N], max_population: usize) -> Vec<Object<N>> {
+    let Some(mut search) = PhaseSearch::new(life, size, 1, [0; N]) else {
+        return Vec::new();
+    };
+    search.max_population = max_population;
+    let mut candidates = Vec::new();
+    search.search(0, &mut |search| {
+        let cells: HashSet<[i64; N]> = search.alive_cells().collect();
+        // the first of its translations in the box
+        if !cells.is_empty() && (0..N).all(|axis| cells.iter().any(|cell| cell[axis] == 0)) {
+            candidates.push(cells);
+        }
+        false
+    });
+    let mut still_lifes = BTreeSet::new();
+    for cells in candidates {
+        let Ok(candidate) = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), cells) else {
+            break;
+        };
+        if splits(life, &candidate.connected_components(), 2) {
+            continue;
+        }
+        if let Some(object) = Object::identify(&candidate, 1) {
+            still_lifes.insert(object);
+        }
+    }
+    let mut still_lifes: Vec<Object<N>> = still_lifes.into_iter().collect();
+    still_lifes.sort_by_key(Object::population);
+    still_lifes
+}
+
+/// Check whether islands can be grouped into at least a number of still lifes.
+fn splits<const N: usize>(life: &Life<N>, islands: &[HashSet<[i64; N]>], groups: usize) -> bool {
+    let Some((first, rest)) = islands.split_first() else {
+        return groups == 0;
+    };
+    (0..1u64 << rest.len()).any(|bits| {
+        let (inside, outside): (Vec<_>, Vec<_>) = rest.iter().enumerate().partition(|(i, _)| bits >> i & 1 == 1);
+        let cells = first.iter().chain(inside.into_iter().flat_map(|(_, island)| island)).copied().collect();
+        let group = Life::new_with_alive_cells(life.birth_rules().clone(), life.survival_rules().clone(), cells);
+        let outside: Vec<HashSet<[i64; N]>> = outside.into_iter().map(|(_, island)| island.clone()).collect();
+        group.is_ok_and(|group| group.is_still_life()) && splits(life, &outside, groups.saturating_sub(1))
+    })
+}
+
+/// State of a cell not set yet in a [PhaseSearch].
+const UNKNOWN: i8 = -1;
+/// State of a dead cell in a [PhaseSearch].
+const DEAD: i8 = 0;
+/// State of an alive cell in a [PhaseSearch].
+const ALIVE: i8 = 1;
+
+/// State of a [spaceship_search] or a [still_life_search]
+///
+/// The states of the cells of the box in each generation are the unknowns, indexed by slot, with the generations of a cell next to each other.
+struct PhaseSearch<const N: usize> {
+    /// Whether a dead cell with each number of alive neighbours is born.
+    birth: Vec<bool>,
+    /// Whether an alive cell with each number of alive neighbours survives.
+    survival: Vec<bool>,
+    /// The box of the phases.
+    region: Region<N>,
+    /// The cells of the box, by position.
+    cells: Vec<[i64; N]>,
+    /// The number of generations of a period.
+    period: usize,
+    /// How far the spaceship moves in each period.
+    displacement: [i64; N],
+    /// The offsets of the neighbours of a cell.
+    deltas: Vec<[i64; N]>,
+    /// The states of the slots.
+    states: Vec<i8>,
+    /// The slots set so far, in order, to undo them when backtracking.
+    trail: Vec<usize>,
+    /// The largest number of alive cells in the first generation.
+    max_population: usize,
+    /// The number of alive cells set in the first generation.
+    population: usize,
+}
+impl<const N: usize> PhaseSearch<N> {
+    /// Create a search with the states of all the cells of a box unknown, or [None] if the box is too large.
+    fn new(life: &Life<N>, size: [u64; N], period: usize, displacement: [i64; N]) -> Option<Self> {
+        let region = Region::new([0; N], size.map(|size| i64::try_from(size).unwrap_or(i64::MAX) - 1));
+        let rule = |rules: &HashSet<usize>| (0..=Life::<N>::MAX_NEIGHBOURS).map(|count| rules.contains(&count)).collect();
+        let mut search = Self {
+            birth: rule(life.birth_rules()),
+            survival: rule(life.survival_rules()),
+            region,
+            cells: Vec::new(),
+            period,
+            displacement,
+            deltas: Life::<N>::neighbours(&[0; N]).collect(),
+            states: Vec::new(),
+            trail: Vec::new(),
+            max_population: usize::MAX,
+            population: 0,
+        };
+        let mut cells = vec![[0; N]; usize::try_from(region.volume()?).ok()?];
+        for cell in region.iter_cells() {
+            let position = search.position(&cell)?;
+            cells[position] = cell;
+        }
+        search.states = vec![UNKNOWN; cells.len().checked_mul(period)?];
+        search.cells = cells;
+        Some(search)
+    }
+
+    /// Iterate over the alive cells of the first generation.
+    fn alive_cells(&self) -> impl Iterator<Item = [i64; N]> + '_ {
+        self.cells.iter().filter(|cell| self.state(cell, 0) == ALIVE).copied()
+    }
+
+    /// Get the position of a cell in the box, or [None] if it is outside.
+    fn position(&self, cell: &[i64; N]) -> Option<usize> {
+        if !self.region.contains(cell) {
+            return None;
+        }
+        let max = self.region.max();
+        Some((0..N).rev().fold(0, |position, axis| position * (max[axis] as usize + 1) + cell[axis] as usize))
+    }
+
+    /// Get the slot of a cell in a generation, up to the first generation of the next period, or [None] if the cell is outside the box.
+    fn slot(&self, cell: &[i64; N], generation: usize) -> Option<usize> {
+        if generation == self.period {
+            let previous: [i64; N] = std::array::from_fn(|axis| cell[axis].wrapping_sub(self.displacement[axis]));
+            return self.slot(&previous, 0);
+        }
+        Some(self.position(cell)? * self.period + generation)
+    }
+
+    /// Get the state of a cell in a generation, dead outside the box.
+    fn state(&self, cell: &[i64; N], generation: usize) -> i8 {
+        self.slot(cell, generation).map_or(DEAD, |slot| self.states[slot])
+    }
+
+    /// Get the states forced by the transition of a cell from a generation to the next.
+    /// # Returns
+    /// [Some] with the slots of unknown states and the states they must have, or [None] if the rule cannot make the transition.
+    fn forced(&self, cell: &[i64; N], generation: usize) -> Option<Vec<(usize, i8)>> {
+        let (centre, next) = (self.state(cell, generation), self.state(cell, generation + 1));
+        let (mut alive, mut unknown) = (0, Vec::new());
+        for delta in &self.deltas {
+            let neighbour: [i64; N] = std::array::from_fn(|axis| cell[axis] + delta[axis]);
+            match self.slot(&neighbour, generation) {
+                Some(slot) if self.states[slot] == ALIVE => alive += 1,
+                Some(slot) if self.states[slot] == UNKNOWN => unknown.push(slot),
+                _ => {}
+            }
+        }
+        let (mut centres, mut outcomes, mut counts) = ([false; 2], [false; 2], (usize::MAX, 0));
+        for candidate in [DEAD, ALIVE].into_iter().filter(|candidate| centre == UNKNOWN || centre == *candidate) {
+            for count in alive..=alive + unknown.len() {
+                let outcome = i8::from(if candidate == ALIVE { self.survival[count] } else { self.birth[count] });
+                if next == UNKNOWN || next == outcome {
+                    centres[candidate as usize] = true;
+                    outcomes[outcome as usize] = true;
+                    counts = (counts.0.min(count), counts.1.max(count));
+                }
+            }
+        }
+        if centres == [false; 2] {
+            return None;
+        }
+        let mut forced = Vec::new();
+        if next == UNKNOWN && outcomes != [true; 2] {
+            forced.push((self.slot(cell, generation + 1)?, i8::from(outcomes[1])));
+        }
+        if centre == UNKNOWN && centres != [true; 2] {
+            forced.push((self.slot(cell, generation)?, i8::from(centres[1])));
+        }
+        if counts.1 == alive {
+            forced.extend(unknown.iter().map(|slot| (*slot, DEAD)));
+        } else if counts.0 == alive + unknown.len() {
+            forced.extend(unknown.iter().map(|slot| (*slot, ALIVE)));
+        }
+        Some(forced)
+    }
+
+    /// Queue the transitions involving a cell in a generation: its own, those of its neighbours and the one leading to it.
+    fn queue_around(&self, queue: &mut Vec<([i64; N], usize)>, cell: [i64; N], generation: usize) {
+        queue.push((cell, generation));
+        queue.extend(self.deltas.iter().map(|delta| (std::array::from_fn(|axis| cell[axis] + delta[axis]), generation)));
+        match generation {
+            0 => queue.push((std::array::from_fn(|axis| cell[axis] + self.displacement[axis]), self.period - 1)),
+            generation => queue.push((cell, generation - 1)),
+        }
+    }
+
+    /// Set a slot and every state it forces, recording them on the trail.
+    /// # Returns
+    /// Whether the states are still consistent with the rule.
+    fn set(&mut self, slot: usize, state: i8) -> bool {
+        let mut queue = Vec::new();
+        let mut pending = vec![(slot, state)];
+        while let Some((slot, state)) = pending.pop() {
+            match self.states[slot] {
+                UNKNOWN => {
+                    self.states[slot] = state;
+                    self.trail.push(slot);
+                    if state == ALIVE && slot.is_multiple_of(self.period) {
+                        self.population += 1;
+                        if self.population > self.max_population {
+                            return false;
+                        }
+                    }
+                    self.queue_around(&mut queue, self.cells[slot / self.period], slot % self.period);
+                }
+                current if current != state => return false,
+                _ => {}
+            }
+            while let Some((cell, generation)) = queue.pop() {
+                match self.forced(&cell, generation) {
+                    Some(forced) => pending.extend(forced),
+                    None => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// Set the unknown slots from a slot on, depth first, passing every solution to a function until it accepts one.
+    /// # Returns
+    /// Whether a solution was accepted, which is left in the states.
+    fn search(&mut self, from: usize, accept: &mut impl FnMut(&Self) -> bool) -> bool {
+        let Some(slot) = (from..self.states.len()).find(|slot| self.states[*slot] == UNKNOWN) else {
+            return accept(self);
+        };
+        for state in [DEAD, ALIVE] {
+            let mark = self.trail.len();
+            if self.set(slot, state) && self.search(slot + 1, accept) {
+                return true;
+            }
+            while self.trail.len() > mark {
+                let undone = self.trail.pop().expect("the trail is longer than the mark");
+                if self.states[undone] == ALIVE && undone.is_multiple_of(self.period) {
+                    self.population -= 1;
+                }
+                self.states[undone] = UNKNOWN;
+            }
+        }
+        false
+    }
+}
+
+/// Counts of the objects found in many soups, which can be saved as text to resume a search
+///
+/// The text has a `soups`, a `next_seed` and an `unidentified` line, followed by one line per object with its name and count,
+/// the most common objects first.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Census<const N: usize> {
+    /// The number of soups searched.
+    soups: u64,
+    /// The seed of the next soup to search.
+    next_seed: u64,
+    /// The number of components that were not objects.
+    unidentified: u64,
+    /// The number of times each object was found.
+    objects: HashMap<Object<N>, u64>,
+}
+impl<const N: usize> Census<N> {
+    /// Create an empty census, starting the search at seed 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of soups searched.
+    pub fn soups(&self) -> u64 {
+        self.soups
+    }
+
+    /// Get the seed of the next soup to search.
+    pub fn next_seed(&self) -> u64 {
+        self.next_seed
+    }
+
+    /// Get the number of components that were not objects, such as unfinished reactions in unsettled soups.
+    pub fn unidentified(&self) -> u64 {
+        self.unidentified
+    }
+
+    /// Get the number of times an object was found.
+    pub fn count(&self, object: &Object<N>) -> u64 {
+        self.objects.get(object).copied().unwrap_or(0)
+    }
+
+    /// Get the objects found and their counts, the most common first, then by name.
+    pub fn objects(&self) -> Vec<(&Object<N>, u64)> {
+        let mut objects: Vec<(&Object<N>, u64)> = self.objects.iter().map(|(object, count)| (object, *count)).collect();
+        objects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        objects
+    }
+
+    /// Add the counts of another census, for soups with other seeds.
+    fn merge(&mut self, other: Self) {
+        self.soups += other.soups;
+        self.unidentified += other.unidentified;
+        for (object, count) in other.objects {
+            *self.objects.entry(object).or_insert(0) += count;
+        }
+    }
+}
+impl<const N: usize> fmt::Display for Census<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "soups {}", self.soups)?;
+        writeln!(f, "next_seed {}", self.next_seed)?;
+        writeln!(f, "unidentified {}", self.unidentified)?;
+        for (object, count) in self.objects() {
+            writeln!(f, "{} {}", object, count)?;
+        }
+        Ok(())
+    }
+}
+impl<const N: usize> FromStr for Census<N> {
+    type Err = Error;
+
+    /// Parse a census saved as text.
+    /// # Errors
+    /// * [ParseError](Error::ParseError) - If the text is malformed.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut census = Self::new();
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        for (key, field) in [
+            ("soups", &mut census.soups),
+            ("next_seed", &mut census.next_seed),
+            ("unidentified", &mut census.unidentified),
+        ] {
+            let (index, line) = lines
+                .next()
+                .ok_or_else(|| Error::ParseError(ParseError::new(text.lines().count() + 1, 1, format!("expected {}", key))))?;
+            *field = line
+                .trim()
+                .strip_prefix(key)
+                .and_then(|value| value.strip_prefix(' '))
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| Error::ParseError(ParseError::new(index + 1, 1, format!("expected {} and a number", key))))?;
+        }
+        for (index, line) in lines {
+            let (name, count) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| Error::ParseError(ParseError::new(index + 1, 1, "expected an object and its count")))?;
+            let object: Object<N> = name.parse().map_err(|error| match error {
+                Error::ParseError(error) => Error::ParseError(ParseError::new(index + 1, error.column(), error.message())),
+                error => error,
+            })?;
+            let count = count
+                .parse()
+                .map_err(|_| Error::ParseError(ParseError::new(index + 1, name.len() + 2, "expected a count")))?;
+            census.objects.insert(object, count);
+        }
+        Ok(census)
+    }
+}
+
+/// Search running seeded random soups to stability and taking a census of the objects they settle into
+///
+/// This is the workflow of apgsearch: every soup fills a hypercube with random cells, depending only on its seed,
+/// runs until it is [stable](Life::run_until_stable) and is split into [objects](Life::identify_objects).
+/// Soups are searched in parallel on several threads, and a [Census] records the next seed, so a search can be resumed.
+/// # Example
+/// ```
+/// use ndlife::life::{conways_game_of_life, StabilityConfig};
+/// use ndlife::search::{Census, Object, SoupSearch};
+///
+/// let mut search = SoupSearch::new(&conways_game_of_life());
+/// search.set_soup_size(8);
+/// search.set_stability(StabilityConfig { max_generations: 300, max_period: 15 });
+///
+/// let mut census = Census::new();
+/// search.search(&mut census, 20);
+/// assert_eq!((census.soups(), census.next_seed()), (20, 20));
+///
+/// // the block is the most common still life
+/// let block: Object<2> = "xs4_0.0,0.1,1.0,1.1".parse().unwrap();
+/// assert!(census.count(&block) > 0);
+///
+/// // resume the search later
+/// let mut census: Census<2> = census.to_string().parse().unwrap();
+/// search.search(&mut census, 20);
+/// assert_eq!(census.soups(), 40);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupSearch<const N: usize> {
+    /// The birth rules of the soups.
+    birth_rules: HashSet<usize>,
+    /// The survival rules of the soups.
+    survival_rules: HashSet<usize>,
+    /// The number of cells along each axis of a soup.
+    soup_size: u64,
+    /// The probability of a cell of a soup being alive.
+    density: f64,
+    /// The limits on running a soup to stability.
+    stability: StabilityConfig,
+    /// The number of threads searching soups.
+    threads: usize,
+}
+impl<const N: usize> SoupSearch<N> {
+    /// Create a search of soups of 16 cells along each axis, half alive, with the rules of a game of life,
+    /// on as many threads as the machine runs in parallel.
+    /// # Arguments
+    /// * `life` - The game of life whose rules the soups follow.
+    pub fn new(life: &Life<N>) -> Self {
+        Self {
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+            soup_size: 16,
+            density: 0.5,
+            stability: StabilityConfig::default(),
+            threads: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Set the number of cells along each axis of a soup, at least 1.
+    pub fn set_soup_size(&mut self, soup_size: u64) {
+        self.soup_size = soup_size.max(1);
+    }
+
+    /// Set the probability of a cell of a soup being alive, clamped to `0.0..=1.0`.
+    pub fn set_density(&mut self, density: f64) {
+        self.density = density.clamp(0.0, 1.0);
+    }
+
+    /// Set the limits on running a soup to stability; its maximum period is also the largest period of the objects.
+    pub fn set_stability(&mut self, stability: StabilityConfig) {
+        self.stability = stability;
+    }
+
+    /// Set the number of threads searching soups, at least 1.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Create the soup of a seed, in the hypercube from the origin.
+    pub fn soup(&self, seed: u64) -> Life<N> {
+        let mut life = Life::new(self.birth_rules.clone(), self.survival_rules.clone()).expect("the rules come from a valid game of life");
+        let max = i64::try_from(self.soup_size - 1).unwrap_or(i64::MAX);
+        life.randomize_region(Region::new([0; N], [max; N]), self.density, seed);
+        life
+    }
+
+    /// Run the soup of a seed to stability and take the census of its objects.
+    /// # Arguments
+    /// * `seed` - The seed of the soup.
+    /// # Returns
+    /// The census of the one soup, with the following seed as its next seed.
+    pub fn census_soup(&self, seed: u64) -> Census<N> {
+        let mut life = self.soup(seed);
+        life.run_until_stable(self.stability);
+        let mut census = Census {
+            soups: 1,
+            next_seed: seed.wrapping_add(1),
+            ..Census::new()
+        };
+        for (_, object) in life.identify_objects(self.stability.max_period) {
+            match object {
+                Some(object) => *census.objects.entry(object).or_insert(0) += 1,
+                None => census.unidentified += 1,
+            }
+        }
+        census
+    }
+
+    /// Search soups, continuing from the next seed of a census and adding to it.
+    /// # Arguments
+    /// * `census` - The census to add the soups to.
+    /// * `soups` - The number of soups to search.
+    pub fn search(&self, census: &mut Census<N>, soups: u64) {
+        let (start, next) = (census.next_seed, AtomicU64::new(0));
+        let partial: Vec<Census<N>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(soups.try_into().unwrap_or(usize::MAX)))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut partial = Census::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            if index >= soups {
+                                break partial;
+                            }
+                            partial.merge(self.census_soup(start.wrapping_add(index)));
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join().expect("a soup search thread panicked")).collect()
+        });
+        for partial in partial {
+            census.merge(partial);
+        }
+        census.next_seed = start.wrapping_add(soups);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    fn object(cells: impl IntoIterator<Item = [i64; 2]>) -> Option<Object<2>> {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells(cells.into_iter().collect());
+        Object::identify(&life, 30)
+    }
+
+    #[test]
+    fn test_identify() {
+        let block = object([[5, 5], [6, 5], [5, 6], [6, 6]]).unwrap();
+        assert!(block.is_still_life() && !block.is_oscillator() && !block.is_spaceship());
+        assert_eq!((block.period(), block.population()), (1, 4));
+        assert_eq!(block.to_string(), "xs4_0.0,0.1,1.0,1.1");
+
+        // every phase and orientation of a glider is the same object
+        let glider = object([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]]).unwrap();
+        assert!(glider.is_spaceship());
+        assert_eq!(glider.period(), 4);
+        assert_eq!(object([[0, 0], [1, 0], [2, 0], [0, 1], [1, 2]]), Some(glider.clone()));
+        let mut life = conways_game_of_life();
+        life.set_alive_cells(glider.pattern().into_cells());
+        life.next_generation();
+        assert_eq!(Object::identify(&life, 30), Some(glider));
+
+        // dies, or does not repeat soon enough
+        assert_eq!(object([[0, 0], [1, 0]]), None);
+        assert_eq!(object([[1, 0], [0, 1], [1, 1], [1, 2], [2, 2]]), None);
+        assert_eq!(object([]), None);
+    }
+
+    #[test]
+    fn test_oscillator_search() {
+        let life = conways_game_of_life();
+        // the block, the tub, the boat, the ship, the beehive, the snake, the carrier, the barge, the long boat, the long ship,
+        // the eater, the loaf and the pond
+        let still_lifes = oscillator_search(&life, [4, 4], 1);
+        assert_eq!(still_lifes.len(), 13);
+        assert!(still_lifes.iter().all(Object::is_still_life));
+        assert_eq!(still_lifes.iter().map(Object::population).max(), Some(8));
+        assert_eq!(oscillator_search(&life, [3, 3], 2).len(), 1);
+        assert_eq!(oscillator_search(&life, [4, 4], 3), Vec::new());
+
+        // lone cells and pairs of cells too far apart for births
+        let life = Life::<1>::empty_with_rulestring("B2/S0").unwrap();
+        let names: Vec<String> = oscillator_search(&life, [5], 1).iter().map(Object::to_string).collect();
+        assert_eq!(names, ["xs1_0", "xs2_0,3", "xs2_0,4"]);
+    }
+
+    #[test]
+    fn test_spaceship_search() {
+        let life = conways_game_of_life();
+        let lwss = |displacement| {
+            let mut ship = life.clone();
+            ship.set_alive_cells(spaceship_search(&life, [6, 5], 4, displacement)?.into_cells());
+            Object::identify(&ship, 4)
+        };
+        let found = lwss([2, 0]).unwrap();
+        assert!(found.is_spaceship());
+        assert_eq!(found.population(), 9);
+        assert_eq!(lwss([-2, 0]), Some(found));
+        // a zero displacement finds a still life
+        let still_life = spaceship_search(&life, [2, 2], 1, [0, 0]).unwrap();
+        assert_eq!(still_life.cells().len(), 4);
+
+        let life = Life::<1>::empty_with_rulestring("B1/S").unwrap();
+        assert_eq!(spaceship_search(&life, [3], 1, [1]), None);
+        assert_eq!(spaceship_search(&life, [0], 1, [0]), None);
+    }
+
+    #[test]
+    fn test_still_life_search() {
+        let life = conways_game_of_life();
+        let still_lifes = still_life_search(&life, [6, 6], 8);
+        let mut counts = [0; 9];
+        for still_life in &still_lifes {
+            counts[still_life.population()] += 1;
+        }
+        assert_eq!(counts, [0, 0, 0, 0, 2, 1, 5, 4, 9]);
+        assert!(still_lifes.windows(2).all(|pair| pair[0].population() <= pair[1].population()));
+        // the bi-block is a pseudo still life
+        let mut bi_block = life.clone();
+        bi_block.set_alive_cells([[0, 0], [0, 1], [1, 0], [1, 1], [3, 0], [3, 1], [4, 0], [4, 1]].into_iter().collect());
+        assert!(bi_block.is_still_life());
+        assert!(splits(&life, &bi_block.connected_components(), 2));
+        assert!(!still_lifes.contains(&Object::identify(&bi_block, 1).unwrap()));
+        assert_eq!(still_life_search(&life, [6, 6], 3), Vec::new());
+
+        let life = Life::<1>::empty_with_rulestring("B2/S0").unwrap();
+        let names: Vec<String> = still_life_search(&life, [5], 5).iter().map(Object::to_string).collect();
+        assert_eq!(names, ["xs1_0"]);
+    }
+
+    #[test]
+    fn test_object_from_str() {
+        for name in ["xs4_0.0,0.1,1.0,1.1", "xp2_0.0,0.1,0.2", "xq4_0.0,0.1,0.2,1.0,2.1"] {
+            assert_eq!(name.parse::<Object<2>>().unwrap().to_string(), name);
+        }
+        for (name, column) in [
+            ("xs4", 1),
+            ("xs_0.0", 3),
+            ("xs3_0.0", 3),
+            ("xp1_0.0", 1),
+            ("xr2_0.0", 1),
+            ("xs1_0", 5),
+            ("xs2_0.0,a.1", 9),
+            ("xs1_1.1", 5),
+        ] {
+            match name.parse::<Object<2>>() {
+                Err(Error::ParseError(error)) => assert_eq!(error.column(), column, "{}", name),
+                other => panic!("{}: {:?}", name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let mut search = SoupSearch::new(&conways_game_of_life());
+        search.set_soup_size(6);
+        search.set_density(2.0);
+        // a full square settles into a pond
+        let pond: Object<2> = "xs8_0.1,0.2,1.0,1.3,2.0,2.3,3.1,3.2".parse().unwrap();
+        assert_eq!(search.census_soup(3).objects(), vec![(&pond, 1)]);
+        search.set_density(0.4);
+        search.set_stability(StabilityConfig {
+            max_generations: 200,
+            max_period: 15,
+        });
+
+        let mut parallel = Census::new();
+        search.set_threads(3);
+        search.search(&mut parallel, 12);
+        let mut resumed = Census::new();
+        search.set_threads(1);
+        search.search(&mut resumed, 5);
+        search.search(&mut resumed, 7);
+        assert_eq!(parallel, resumed);
+        assert_eq!(parallel.next_seed(), 12);
+        let counted: u64 = parallel.objects().iter().map(|(_, count)| count).sum::<u64>() + parallel.unidentified();
+        let components: usize = (0..12)
+            .map(|seed| {
+                let mut life = search.soup(seed);
+                life.run_until_stable(search.stability);
+                life.connected_components().len()
+            })
+            .sum();
+        assert_eq!(counted, components as u64);
+    }
+
+    #[test]
+    fn test_census_from_str() {
+        let mut census = Census::<2>::new();
+        census.soups = 3;
+        census.next_seed = 10;
+        census.unidentified = 1;
+        census.objects.insert("xs4_0.0,0.1,1.0,1.1".parse().unwrap(), 5);
+        census.objects.insert("xp2_0.0,0.1,0.2".parse().unwrap(), 7);
+        let text = census.to_string();
+        assert_eq!(text, "soups 3\nnext_seed 10\nunidentified 1\nxp2_0.0,0.1,0.2 7\nxs4_0.0,0.1,1.0,1.1 5\n");
+        assert_eq!(text.parse(), Ok(census));
+
+        for (text, line, column) in [
+            ("soups 3\n", 2, 1),
+            ("soups 3\nnext_seed x\n", 2, 1),
+            ("soups 3\nnext_seed 1\nunidentified 0\nxs4_0.0,0.1,1.0,1.1\n", 4, 1),
+            ("soups 3\nnext_seed 1\nunidentified 0\nxs4_0.0,0.1,1.0,1.1 x\n", 4, 21),
+            ("soups 3\nnext_seed 1\nunidentified 0\nxs4_0.0,0.1 1\n", 4, 3),
+        ] {
+            match text.parse::<Census<2>>() {
+                Err(Error::ParseError(error)) => assert_eq!((error.line(), error.column()), (line, column), "{:?}", text),
+                other => panic!("{:?}: {:?}", text, other),
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Sharing a game of life between one stepping thread and many reading threads
+
+use crate::life::Life;
+use crate::region::Region;
+use crate::view::View;
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Completed generation published by a [SharedLife], read through a [LifeReader]
+///
+/// A frame is immutable, so readers can render or analyse it for as long as they like
+/// without blocking the writer or seeing a generation that is only partly computed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Frame<const N: usize> {
+    /// The age of the game of life in this generation.
+    age: u64,
+    /// The alive cells, shared with the game of life until it steps away from them or edits them.
+    alive_cells: Arc<HashSet<[i64; N]>>,
+}
+impl<const N: usize> Frame<N> {
+    /// Get the age of the game of life in this generation.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the alive cells.
+    pub fn alive_cells(&self) -> &HashSet<[i64; N]> {
+        &self.alive_cells
+    }
+
+    /// Get the number of alive cells.
+    pub fn population(&self) -> usize {
+        self.alive_cells.len()
+    }
+
+    /// Get a view of the alive cells inside a region, for rendering.
+    /// # Arguments
+    /// * `region` - The region to view.
+    pub fn view(&self, region: impl Into<Region<N>>) -> View<'_, N> {
+        View::new(&self.alive_cells, region.into())
+    }
+}
+
+/// The latest frame, swapped by the writer and cloned (as a pointer) by the readers.
+type Latest<const N: usize> = Arc<RwLock<Arc<Frame<N>>>>;
+
+/// Game of life stepped by one writer thread and read by any number of reader threads
+///
+/// The writer owns the game of life and publishes a [Frame] of the latest completed generation after every update.
+/// Readers obtained from [reader](SharedLife::reader) get that frame as an [Arc], so a frame is never copied for a reader;
+/// the lock is held only to swap or clone the pointer, never while stepping or rendering.
+/// Frames are double buffered without copying: a game of life computes every generation from its previous cell set
+/// into the other one, and a frame shares the set of its generation. Stepping reuses the set of the frame before last
+/// once no reader holds it, and editing the cells of the latest frame copies them first, so frames never change.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::shared::SharedLife;
+/// use std::thread;
+///
+/// let mut life = conways_game_of_life();
+/// // a blinker
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+///
+/// let mut shared = SharedLife::new(life);
+/// let reader = shared.reader();
+/// let renderer = thread::spawn(move || {
+///     let frame = reader.latest();
+///     assert_eq!(frame.population(), 3);
+///     frame.age()
+/// });
+/// shared.step(10);
+///
+/// assert!(renderer.join().unwrap() <= 10);
+/// assert_eq!(shared.reader().latest().age(), 10);
+/// ```
+#[derive(Debug)]
+pub struct SharedLife<const N: usize> {
+    /// The game of life, owned by the writer.
+    life: Life<N>,
+    /// The latest published frame.
+    latest: Latest<N>,
+}
+impl<const N: usize> SharedLife<N> {
+    /// Start sharing a game of life, publishing its current generation.
+    /// # Arguments
+    /// * `life` - The game of life to share.
+    pub fn new(life: Life<N>) -> Self {
+        let frame = Frame {
+            age: life.age(),
+            alive_cells: life.shared_alive_cells(),
+        };
+        Self {
+            life,
+            latest: Arc::new(RwLock::new(Arc::new(frame))),
+        }
+    }
+
+    /// Get a handle reading the latest published generation, which can be sent to another thread.
+    pub fn reader(&self) -> LifeReader<N> {
+        LifeReader { latest: Arc::clone(&self.latest) }
+    }
+
+    /// Get the game of life, which is always at the latest published generation.
+    pub fn life(&self) -> &Life<N> {
+        &self.life
+    }
+
+    /// Advance the game of life and publish the generation reached.
+    /// # Arguments
+    /// * `generations` - The number of generations to advance before publishing.
+    pub fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.life.next_generation();
+        }
+        self.publish();
+    }
+
+    /// Modify the game of life (e.g. edit cells or change rules) and publish the result.
+    /// # Arguments
+    /// * `f` - The modification.
+    /// # Returns
+    /// The value returned by `f`.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut Life<N>) -> R) -> R {
+        let result = f(&mut self.life);
+        self.publish();
+        result
+    }
+
+    /// Stop sharing and get back the game of life. Readers keep the last published frame.
+    pub fn into_inner(self) -> Life<N> {
+        self.life
+    }
+
+    /// Publish the current generation as the latest frame.
+    fn publish(&mut self) {
+        let frame = Arc::new(Frame {
+            age: self.life.age(),
+            alive_cells: self.life.shared_alive_cells(),
+        });
+        // the replaced frame is dropped outside the lock
+        let replaced = std::mem::replace(&mut *self.latest.write().unwrap_or_else(PoisonError::into_inner), frame);
+        drop(replaced);
+    }
+}
+
+/// Handle reading the latest generation published by a [SharedLife]
+#[derive(Debug, Clone)]
+pub struct LifeReader<const N: usize> {
+    /// The latest published frame.
+    latest: Latest<N>,
+}
+impl<const N: usize> LifeReader<N> {
+    /// Get the latest published generation.
+    ///
+    /// The frame stays valid however far the writer advances afterwards; call again to see newer generations.
+    pub fn latest(&self) -> Arc<Frame<N>> {
+        Arc::clone(&self.latest.read().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use std::thread;
+
+    #[test]
+    fn test_shared_life() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        // a clone would share the cells, keeping them from being reused
+        let mut expected = conways_game_of_life();
+        expected.set_alive_cells(life.alive_cells().clone());
+
+        let mut shared = SharedLife::new(life);
+        let reader = shared.reader();
+        let first = reader.latest();
+        assert_eq!(first.age(), 0);
+
+        shared.step(1);
+        expected.next_generation();
+        let second = reader.latest();
+        assert_eq!(second.age(), 1);
+        assert_eq!(second.alive_cells(), expected.alive_cells());
+        // frames held by readers are left untouched
+        assert_eq!(first.age(), 0);
+        assert_eq!(first.alive_cells(), &[[0, 0], [1, 0], [2, 0]].into_iter().collect());
+
+        // publishing shares the cells of the generation instead of copying them
+        assert!(Arc::ptr_eq(&second.alive_cells, &shared.life().shared_alive_cells()));
+        let buffer = Arc::as_ptr(&first.alive_cells);
+        drop(first);
+        shared.step(1);
+        assert_eq!(reader.latest().age(), 2);
+        assert_eq!(second.age(), 1);
+        // the next generation went into the set of the dropped frame
+        assert_eq!(Arc::as_ptr(&reader.latest().alive_cells), buffer);
+
+        let changed = shared.update(|life| life.set_cell(&[10, 10], true));
+        assert!(changed);
+        assert_eq!(reader.latest().population(), 4);
+        assert!(reader.latest().view([10..=10, 10..=10]).is_alive(&[10, 10]));
+        assert_eq!(shared.life().age(), 2);
+        assert_eq!(shared.into_inner().alive_cells(), reader.latest().alive_cells());
+    }
+
+    #[test]
+    fn test_concurrent_readers() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        let mut shared = SharedLife::new(life);
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = shared.reader();
+                thread::spawn(move || {
+                    let mut last_age = 0;
+                    for _ in 0..1000 {
+                        let frame = reader.latest();
+                        // a blinker always has three cells in a line
+                        assert_eq!(frame.population(), 3);
+                        assert!(frame.age() >= last_age);
+                        let horizontal = frame.alive_cells().iter().all(|cell| cell[1] == 0);
+                        assert_eq!(horizontal, frame.age() % 2 == 0);
+                        last_age = frame.age();
+                    }
+                })
+            })
+            .collect();
+        for _ in 0..1000 {
+            shared.step(1);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(shared.reader().latest().age(), 1000);
+    }
+}
@@ -0,0 +1,302 @@
+//! Built-in spaceships of Conway's game of life and their placement
+
+use crate::error::Error;
+use crate::life::{conways_game_of_life, Centre, Life};
+use crate::pattern;
+use crate::pattern::{PasteMode, Pattern};
+
+/// Spaceship of Conway's game of life
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Spaceship {
+    /// The glider, moving diagonally by one cell every 4 generations.
+    Glider,
+    /// The lightweight spaceship, moving orthogonally by two cells every 4 generations.
+    Lwss,
+    /// The middleweight spaceship, moving orthogonally by two cells every 4 generations.
+    Mwss,
+    /// The heavyweight spaceship, moving orthogonally by two cells every 4 generations.
+    Hwss,
+}
+impl Spaceship {
+    /// Get the spaceship in phase 0, moving south-east (glider) or east (other spaceships),
+    /// with the minimum corner of its bounding box at the origin.
+    pub fn pattern(self) -> Pattern<2> {
+        match self {
+            Self::Glider => pattern!(
+                "
+                .O.
+                ..O
+                OOO
+                "
+            ),
+            Self::Lwss => pattern!(
+                "
+                O..O.
+                ....O
+                O...O
+                .OOOO
+                "
+            ),
+            Self::Mwss => pattern!(
+                "
+                ..O...
+                O...O.
+                .....O
+                O....O
+                .OOOOO
+                "
+            ),
+            Self::Hwss => pattern!(
+                "
+                ..OO...
+                O....O.
+                ......O
+                O.....O
+                .OOOOOO
+                "
+            ),
+        }
+    }
+
+    /// Get the number of generations after which the spaceship reappears displaced.
+    pub fn period(self) -> u64 {
+        4
+    }
+
+    /// Get whether the spaceship moves diagonally (otherwise it moves orthogonally).
+    pub fn is_diagonal(self) -> bool {
+        self == Self::Glider
+    }
+}
+
+/// Handedness of a spaceship, swapped by reflecting it across its direction of movement
+///
+/// The glider and the spaceships are not symmetric about their direction of movement,
+/// so each of them comes in two mirror images moving the same way.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Chirality {
+    /// The handedness of the [pattern](Spaceship::pattern) of the spaceship.
+    #[default]
+    Standard,
+    /// The mirror image of the pattern.
+    Mirrored,
+}
+
+/// Direction of movement on a 2-dimensional grid, with north being the positive `y` direction
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
+    /// Towards positive `y`.
+    North,
+    /// Towards positive `x` and positive `y`.
+    NorthEast,
+    /// Towards positive `x`.
+    East,
+    /// Towards positive `x` and negative `y`.
+    SouthEast,
+    /// Towards negative `y`.
+    South,
+    /// Towards negative `x` and negative `y`.
+    SouthWest,
+    /// Towards negative `x`.
+    West,
+    /// Towards negative `x` and positive `y`.
+    NorthWest,
+}
+impl Direction {
+    /// Get whether the direction is diagonal.
+    pub fn is_diagonal(self) -> bool {
+        matches!(self, Self::NorthEast | Self::SouthEast | Self::SouthWest | Self::NorthWest)
+    }
+
+    /// Get the number of counter-clockwise quarter turns from the base direction of the spaceships.
+    fn quarter_turns(self) -> i64 {
+        match self {
+            Self::East | Self::SouthEast => 0,
+            Self::North | Self::NorthEast => 1,
+            Self::West | Self::NorthWest => 2,
+            Self::South | Self::SouthWest => 3,
+        }
+    }
+}
+
+impl Life<2> {
+    /// Place a built-in spaceship of Conway's game of life, oriented, of a handedness and in a given phase.
+    ///
+    /// In phase 0, the minimum corner of the bounding box of the spaceship is at `position`.
+    /// Placing a spaceship in phase `p` gives the same cells as placing it in phase 0
+    /// and advancing Conway's game of life by `p` generations, so salvos can be timed by phase.
+    /// The spaceship is combined with the cells already present using [Or](PasteMode::Or).
+    /// # Arguments
+    /// * `kind` - The spaceship to place.
+    /// * `position` - The position of the spaceship in phase 0.
+    /// * `direction` - The direction the spaceship moves in.
+    /// * `chirality` - The handedness of the spaceship, the mirror images moving in the same direction.
+    /// * `phase` - The phase of the spaceship, taken modulo its [period](Spaceship::period).
+    /// # Returns
+    /// A [Result] containing `()` if successful, or an error.
+    /// # Errors
+    /// * [InvalidDirection](Error::InvalidDirection) - If the spaceship cannot move in `direction`
+    ///   (gliders move only diagonally, the other spaceships only orthogonally).
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    /// use ndlife::spaceship::{Chirality, Direction, Spaceship};
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.place_spaceship(Spaceship::Glider, [0, 0], Direction::NorthWest, Chirality::Standard, 0).unwrap();
+    /// for _ in 0..4 {
+    ///     life.next_generation();
+    /// }
+    ///
+    /// let mut expected = conways_game_of_life();
+    /// expected.place_spaceship(Spaceship::Glider, [-1, 1], Direction::NorthWest, Chirality::Standard, 0).unwrap();
+    /// assert_eq!(life.alive_cells(), expected.alive_cells());
+    ///
+    /// // the mirror image moves the same way
+    /// let mut mirrored = conways_game_of_life();
+    /// mirrored.place_spaceship(Spaceship::Glider, [-1, 1], Direction::NorthWest, Chirality::Mirrored, 4).unwrap();
+    /// assert_ne!(mirrored.alive_cells(), expected.alive_cells());
+    /// assert_eq!(mirrored.bounding_box(), expected.bounding_box());
+    /// ```
+    pub fn place_spaceship(&mut self, kind: Spaceship, position: [i64; 2], direction: Direction, chirality: Chirality, phase: u64) -> Result<(), Error> {
+        if kind.is_diagonal() != direction.is_diagonal() {
+            return Err(Error::InvalidDirection);
+        }
+        // reflecting the pattern across the x axis turns the glider from south-east to north-east, another quarter turn
+        let orient = |life: &mut Life<2>| match chirality {
+            Chirality::Standard => life.rotate(0, 1, direction.quarter_turns()),
+            Chirality::Mirrored => {
+                life.reflect_about(1, 0)?;
+                life.rotate(0, 1, direction.quarter_turns() - i64::from(kind.is_diagonal()))
+            }
+        };
+
+        // orient phase 0 to find the offset moving it to the origin
+        let mut oriented = conways_game_of_life();
+        oriented.set_alive_cells(kind.pattern().into_cells());
+        orient(&mut oriented)?;
+        let offset = oriented.recenter(Centre::MinCorner);
+
+        let mut spaceship = conways_game_of_life();
+        spaceship.set_alive_cells(kind.pattern().into_cells());
+        for _ in 0..phase % kind.period() {
+            spaceship.next_generation();
+        }
+        orient(&mut spaceship)?;
+        spaceship.translate([offset[0] + position[0], offset[1] + position[1]]);
+        self.paste(&spaceship.to_pattern(), [0, 0], PasteMode::Or);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::HashSet;
+
+    #[test]
+    fn test_pattern() {
+        let displacements = [
+            (Spaceship::Glider, [1, -1]),
+            (Spaceship::Lwss, [2, 0]),
+            (Spaceship::Mwss, [2, 0]),
+            (Spaceship::Hwss, [2, 0]),
+        ];
+        for (kind, displacement) in displacements {
+            let pattern = kind.pattern();
+            assert_eq!(pattern.bounding_box().unwrap().0, [0, 0]);
+            let mut life = conways_game_of_life();
+            life.set_alive_cells(pattern.cells().clone());
+            for _ in 0..kind.period() {
+                life.next_generation();
+            }
+            life.translate([-displacement[0], -displacement[1]]);
+            assert_eq!(life.to_pattern(), pattern);
+        }
+    }
+
+    #[test]
+    fn test_place_spaceship() {
+        let directions = [
+            Direction::North,
+            Direction::NorthEast,
+            Direction::East,
+            Direction::SouthEast,
+            Direction::South,
+            Direction::SouthWest,
+            Direction::West,
+            Direction::NorthWest,
+        ];
+        for kind in [Spaceship::Glider, Spaceship::Lwss, Spaceship::Mwss, Spaceship::Hwss] {
+            for (direction, chirality) in directions
+                .into_iter()
+                .flat_map(|direction| [(direction, Chirality::Standard), (direction, Chirality::Mirrored)])
+            {
+                let mut life = conways_game_of_life();
+                if kind.is_diagonal() != direction.is_diagonal() {
+                    assert_eq!(life.place_spaceship(kind, [0, 0], direction, chirality, 0), Err(Error::InvalidDirection));
+                    continue;
+                }
+                life.place_spaceship(kind, [3, -7], direction, chirality, 0).unwrap();
+                assert_eq!(life.bounding_box().unwrap().0, [3, -7]);
+
+                // later phases match evolution from phase 0
+                for phase in 1..4 {
+                    life.next_generation();
+                    let mut placed = conways_game_of_life();
+                    placed.place_spaceship(kind, [3, -7], direction, chirality, phase).unwrap();
+                    assert_eq!(placed.alive_cells(), life.alive_cells());
+                }
+
+                // the spaceship moves in the requested direction
+                let before = life.bounding_box().unwrap().0;
+                for _ in 0..4 {
+                    life.next_generation();
+                }
+                let after = life.bounding_box().unwrap().0;
+                let signum = [(after[0] - before[0]).signum(), (after[1] - before[1]).signum()];
+                let expected = match direction {
+                    Direction::North => [0, 1],
+                    Direction::NorthEast => [1, 1],
+                    Direction::East => [1, 0],
+                    Direction::SouthEast => [1, -1],
+                    Direction::South => [0, -1],
+                    Direction::SouthWest => [-1, -1],
+                    Direction::West => [-1, 0],
+                    Direction::NorthWest => [-1, 1],
+                };
+                assert_eq!(signum, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chirality() {
+        for kind in [Spaceship::Glider, Spaceship::Lwss, Spaceship::Mwss, Spaceship::Hwss] {
+            let direction = if kind.is_diagonal() { Direction::NorthEast } else { Direction::North };
+            let mut standard = conways_game_of_life();
+            standard.place_spaceship(kind, [0, 0], direction, Chirality::Standard, 0).unwrap();
+            let mut mirrored = conways_game_of_life();
+            mirrored.place_spaceship(kind, [0, 0], direction, Chirality::Mirrored, 0).unwrap();
+
+            // the mirror image is the reflection across the direction of movement, in the same bounding box
+            let (min, max) = standard.bounding_box().unwrap();
+            let reflected: HashSet<[i64; 2]> = if kind.is_diagonal() {
+                standard.alive_cells().iter().map(|&[x, y]| [y - min[1] + min[0], x - min[0] + min[1]]).collect()
+            } else {
+                standard.alive_cells().iter().map(|&[x, y]| [min[0] + max[0] - x, y]).collect()
+            };
+            assert_eq!(mirrored.alive_cells(), &reflected);
+            assert_ne!(mirrored.alive_cells(), standard.alive_cells());
+
+            // both move the same way
+            let (mut standard, mut mirrored) = (standard.clone(), mirrored.clone());
+            for _ in 0..kind.period() {
+                standard.next_generation();
+                mirrored.next_generation();
+            }
+            assert_eq!(standard.bounding_box(), mirrored.bounding_box());
+        }
+        assert_eq!(Chirality::default(), Chirality::Standard);
+    }
+}
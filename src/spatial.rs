@@ -0,0 +1,403 @@
+//! Queries of the alive cells by distance and by region, such as the alive cell nearest to a cursor
+
+use crate::collections::HashMap;
+use crate::life::Life;
+use crate::region::Region;
+use alloc::vec::Vec;
+
+/// Index of the alive cells of a game of life by chunk, for repeated queries by distance and by region
+///
+/// The alive cells are kept by chunk, a hypercube of cells, so a query looks at the chunks near a point rather than at every
+/// alive cell, and the population of a region is counted by chunk, only the cells of chunks on its boundary one by one. Distances are Euclidean and compared exactly, as squared distances; ties are broken by lexicographic order.
+/// After a generation, [update](Self::update) applies the born and died cells instead of indexing every cell again.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::spatial::SpatialIndex;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [100, 100], [100, 101], [101, 100], [101, 101]].into_iter().collect());
+///
+/// let mut index = SpatialIndex::new(&life);
+/// assert_eq!(index.nearest(&[90, 95]), Some([100, 100]));
+/// assert_eq!(index.within_radius(&[1, 0], 1), vec![[1, 0], [0, 0], [2, 0]]);
+///
+/// // the blinker turns
+/// life.next_generation();
+/// index.update(&life);
+/// assert_eq!(index.within_radius(&[1, 0], 1), vec![[1, 0], [1, -1], [1, 1]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<const N: usize> {
+    /// The age of the indexed generation.
+    age: u64,
+    /// The number of indexed cells.
+    population: usize,
+    /// The alive cells of every chunk with any, by the coordinates of the chunk.
+    chunks: HashMap<[i64; N], Vec<[i64; N]>>,
+}
+impl<const N: usize> SpatialIndex<N> {
+    /// The number of cells along each axis of a chunk.
+    const SIDE: i64 = match N {
+        1 => 256,
+        2 => 16,
+        3 => 8,
+        4 => 4,
+        _ => 2,
+    };
+
+    /// Create an index of the alive cells of a game of life.
+    /// # Arguments
+    /// * `life` - The game of life.
+    /// # Returns
+    /// The index of its current generation.
+    pub fn new(life: &Life<N>) -> Self {
+        let mut index = Self {
+            age: life.age(),
+            population: 0,
+            chunks: HashMap::new(),
+        };
+        for cell in life.alive_cells() {
+            index.insert(*cell);
+        }
+        index
+    }
+
+    /// Get the age of the indexed generation.
+    pub fn age(&self) -> u64 {
+        self.age
+    }
+
+    /// Get the number of indexed cells.
+    pub fn len(&self) -> usize {
+        self.population
+    }
+
+    /// Get whether no cells are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.population == 0
+    }
+
+    /// Bring the index up to date with a game of life.
+    ///
+    /// If the index holds the previous generation of the game of life, as when it was created or updated
+    /// before the last call of [next_generation](Life::next_generation), only the born and died cells are applied;
+    /// otherwise, such as after skipping generations, every cell is indexed again.
+    /// Cells edited before the last generation are seen only by indexing every cell again, so the index should be recreated after edits.
+    /// # Arguments
+    /// * `life` - The game of life.
+    /// # Returns
+    /// Whether the born and died cells were applied, rather than every cell indexed again.
+    pub fn update(&mut self, life: &Life<N>) -> bool {
+        if self.age.checked_add(1) == Some(life.age()) && self.population == life.previous_alive_cells().len() {
+            for cell in life.died_cells() {
+                self.remove(cell);
+            }
+            for cell in life.born_cells() {
+                self.insert(*cell);
+            }
+            self.age = life.age();
+            if self.population == life.alive_cells().len() {
+                return true;
+            }
+        }
+        *self = Self::new(life);
+        false
+    }
+
+    /// Find the indexed cell nearest to a point.
+    /// # Arguments
+    /// * `from` - The point.
+    /// # Returns
+    /// [Some] with the nearest cell, the first in lexicographic order among equally near cells, or [None] if no cells are indexed.
+    pub fn nearest(&self, from: &[i64; N]) -> Option<[i64; N]> {
+        let home = Self::chunk(from);
+        let mut best: Option<(u128, [i64; N])> = None;
+        for shell in 0u64.. {
+            // once the shell holds more places than there are chunks, look at the chunks instead
+            let places = (2 * u128::from(shell) + 1).saturating_pow(N as u32);
+            if places > self.chunks.len() as u128 {
+                for (chunk, cells) in &self.chunks {
+                    if best.is_none_or(|(distance, _)| Self::chunk_distance(chunk, from) <= distance) {
+                        Self::closer(&mut best, cells, from);
+                    }
+                }
+                break;
+            }
+            for chunk in Self::shell(&home, shell) {
+                if let Some(cells) = self.chunks.get(&chunk) {
+                    Self::closer(&mut best, cells, from);
+                }
+            }
+            // cells of the following shells are farther than the shell reaches along some axis
+            let reach = u128::from(shell) * Self::SIDE as u128;
+            if best.is_some_and(|(distance, _)| distance <= reach * reach) {
+                break;
+            }
+        }
+        best.map(|(_, cell)| cell)
+    }
+
+    /// Find the indexed cells within a distance of a point.
+    /// # Arguments
+    /// * `center` - The point.
+    /// * `radius` - The largest distance of a cell from the point.
+    /// # Returns
+    /// The cells within the distance, the nearest first and then in lexicographic order.
+    pub fn within_radius(&self, center: &[i64; N], radius: u64) -> Vec<[i64; N]> {
+        let limit = u128::from(radius) * u128::from(radius);
+        let low = Self::chunk(&center.map(|coordinate| coordinate.saturating_sub_unsigned(radius)));
+        let high = Self::chunk(&center.map(|coordinate| coordinate.saturating_add_unsigned(radius)));
+        let mut found: Vec<(u128, [i64; N])> = Vec::new();
+        self.for_each_chunk(low, high, |chunk, cells| {
+            if Self::chunk_distance(chunk, center) <= limit {
+                found.extend(cells.iter().map(|cell| (squared_distance(cell, center), *cell)).filter(|(distance, _)| *distance <= limit));
+            }
+        });
+        found.sort_unstable();
+        found.into_iter().map(|(_, cell)| cell).collect()
+    }
+
+    /// Count the indexed cells in a region.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// The number of cells in the region, counted in time proportional to the number of chunks it touches
+    /// and the cells of the chunks on its boundary.
+    /// # Example
+    /// ```
+    /// use ndlife::life::Life;
+    /// use ndlife::spatial::SpatialIndex;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut life = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+    /// life.fill_region([0..=999, 0..=999]);
+    /// let index = SpatialIndex::new(&life);
+    ///
+    /// assert_eq!(index.population_in([-5..=4, -5..=4]), 25);
+    /// assert_eq!(index.population_in([0..=499, 100..=999]), 450_000);
+    /// ```
+    pub fn population_in(&self, region: impl Into<Region<N>>) -> usize {
+        let region = region.into();
+        if region.is_empty() {
+            return 0;
+        }
+        let mut population = 0;
+        self.for_each_chunk(Self::chunk(&region.min()), Self::chunk(&region.max()), |chunk, cells| {
+            let inside = (0..N).all(|axis| chunk[axis] * Self::SIDE >= region.min()[axis] && chunk[axis] * Self::SIDE + (Self::SIDE - 1) <= region.max()[axis]);
+            population += if inside {
+                cells.len()
+            } else {
+                cells.iter().filter(|cell| region.contains(cell)).count()
+            };
+        });
+        population
+    }
+
+    /// Visit the indexed chunks between two chunks, inclusive, by looking up every place between them
+    /// or by going through every indexed chunk, whichever are fewer.
+    fn for_each_chunk(&self, low: [i64; N], high: [i64; N], mut visit: impl FnMut(&[i64; N], &[[i64; N]])) {
+        let places = (0..N).fold(1u128, |places, axis| places.saturating_mul(high[axis].abs_diff(low[axis]) as u128 + 1));
+        if places > self.chunks.len() as u128 {
+            for (chunk, cells) in &self.chunks {
+                if (0..N).all(|axis| (low[axis]..=high[axis]).contains(&chunk[axis])) {
+                    visit(chunk, cells);
+                }
+            }
+            return;
+        }
+        let mut chunk = low;
+        loop {
+            if let Some(cells) = self.chunks.get(&chunk) {
+                visit(&chunk, cells);
+            }
+            // advance like an odometer, the first axis fastest
+            let Some(axis) = (0..N).find(|&axis| chunk[axis] < high[axis]) else {
+                break;
+            };
+            chunk[axis] += 1;
+            chunk[..axis].copy_from_slice(&low[..axis]);
+        }
+    }
+
+    /// Get the coordinates of the chunk of a cell.
+    fn chunk(cell: &[i64; N]) -> [i64; N] {
+        cell.map(|coordinate| coordinate.div_euclid(Self::SIDE))
+    }
+
+    /// Get the smallest squared distance from a point to a cell of a chunk.
+    fn chunk_distance(chunk: &[i64; N], point: &[i64; N]) -> u128 {
+        let nearest: [i64; N] = core::array::from_fn(|axis| point[axis].clamp(chunk[axis] * Self::SIDE, chunk[axis] * Self::SIDE + (Self::SIDE - 1)));
+        squared_distance(&nearest, point)
+    }
+
+    /// Get the chunks at a Chebyshev distance from a chunk, those out of range left out.
+    fn shell(home: &[i64; N], shell: u64) -> impl Iterator<Item = [i64; N]> + '_ {
+        let side = 2 * shell + 1;
+        (0..side.pow(N as u32)).filter_map(move |mut place| {
+            let mut on_surface = shell == 0;
+            let mut chunk = [0; N];
+            for axis in 0..N {
+                let step = place % side;
+                place /= side;
+                on_surface |= step == 0 || step == side - 1;
+                chunk[axis] = home[axis].checked_add_unsigned(step)?.checked_sub_unsigned(shell)?;
+            }
+            on_surface.then_some(chunk)
+        })
+    }
+
+    /// Keep the nearest of the cells to a point, if nearer than the best so far.
+    fn closer(best: &mut Option<(u128, [i64; N])>, cells: &[[i64; N]], point: &[i64; N]) {
+        for cell in cells {
+            let candidate = (squared_distance(cell, point), *cell);
+            if best.is_none_or(|best| candidate < best) {
+                *best = Some(candidate);
+            }
+        }
+    }
+
+    /// Add a cell.
+    fn insert(&mut self, cell: [i64; N]) {
+        self.chunks.entry(Self::chunk(&cell)).or_default().push(cell);
+        self.population += 1;
+    }
+
+    /// Remove a cell, if indexed.
+    fn remove(&mut self, cell: &[i64; N]) {
+        let chunk = Self::chunk(cell);
+        let Some(cells) = self.chunks.get_mut(&chunk) else {
+            return;
+        };
+        if let Some(position) = cells.iter().position(|other| other == cell) {
+            cells.swap_remove(position);
+            self.population -= 1;
+            if cells.is_empty() {
+                self.chunks.remove(&chunk);
+            }
+        }
+    }
+}
+
+impl<const N: usize> Life<N> {
+    /// Find the alive cell nearest to a point, such as to snap a cursor to the pattern.
+    ///
+    /// Every alive cell is looked at; for many queries of the same generation, a [SpatialIndex] is faster.
+    /// # Arguments
+    /// * `from` - The point.
+    /// # Returns
+    /// [Some] with the nearest alive cell by Euclidean distance, the first in lexicographic order among equally near cells,
+    /// or [None] if there are no alive cells.
+    /// # Example
+    /// ```
+    /// use ndlife::life::conways_game_of_life;
+    ///
+    /// let mut life = conways_game_of_life();
+    /// life.set_alive_cells([[0, 0], [3, 4], [-5, 0]].into_iter().collect());
+    ///
+    /// assert_eq!(life.nearest_alive(&[2, 2]), Some([3, 4]));
+    /// assert_eq!(life.alive_within_radius(&[0, 0], 5), vec![[0, 0], [-5, 0], [3, 4]]);
+    /// ```
+    pub fn nearest_alive(&self, from: &[i64; N]) -> Option<[i64; N]> {
+        self.alive_cells().iter().map(|cell| (squared_distance(cell, from), *cell)).min().map(|(_, cell)| cell)
+    }
+
+    /// Find the alive cells within a distance of a point.
+    ///
+    /// Every alive cell is looked at; for many queries of the same generation, a [SpatialIndex] is faster.
+    /// # Arguments
+    /// * `center` - The point.
+    /// * `radius` - The largest Euclidean distance of a cell from the point.
+    /// # Returns
+    /// The alive cells within the distance, the nearest first and then in lexicographic order.
+    pub fn alive_within_radius(&self, center: &[i64; N], radius: u64) -> Vec<[i64; N]> {
+        let limit = u128::from(radius) * u128::from(radius);
+        let mut found: Vec<(u128, [i64; N])> = self
+            .alive_cells()
+            .iter()
+            .map(|cell| (squared_distance(cell, center), *cell))
+            .filter(|(distance, _)| *distance <= limit)
+            .collect();
+        found.sort_unstable();
+        found.into_iter().map(|(_, cell)| cell).collect()
+    }
+}
+
+/// Get the squared Euclidean distance between two cells, saturating at [u128::MAX].
+fn squared_distance<const N: usize>(a: &[i64; N], b: &[i64; N]) -> u128 {
+    (0..N).fold(0, |sum: u128, axis| {
+        let difference = u128::from(a[axis].abs_diff(b[axis]));
+        sum.saturating_add(difference * difference)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::HashSet;
+    use crate::random::SplitMix64;
+
+    /// Get the indexed cells, sorted.
+    fn indexed<const N: usize>(index: &SpatialIndex<N>) -> Vec<[i64; N]> {
+        let mut cells: Vec<[i64; N]> = index.chunks.values().flatten().copied().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut random = SplitMix64::new(5);
+        let mut life = Life::<3>::new([5].into_iter().collect(), [4, 5].into_iter().collect()).unwrap();
+        life.randomize_region([-20..=20, -20..=20, 0..=3], 0.05, 3);
+        life.extend([[500, 500, 500], [-1000, 0, 0]]);
+        let index = SpatialIndex::new(&life);
+        assert_eq!(index.len(), life.alive_cells().len());
+        for _ in 0..200 {
+            let point: [i64; 3] = std::array::from_fn(|_| (random.next_u64() % 200) as i64 - 100);
+            assert_eq!(index.nearest(&point), life.nearest_alive(&point));
+            let radius = random.next_u64() % 30;
+            assert_eq!(index.within_radius(&point, radius), life.alive_within_radius(&point, radius));
+        }
+        // far away, and at the edges of the coordinates
+        for region in [
+            Region::new([-20, -20, 0], [20, 20, 3]),
+            Region::new([-7, 3, 1], [15, 9, 2]),
+            Region::new([-2000, 0, 0], [0, 0, 0]),
+        ] {
+            assert_eq!(index.population_in(region), life.alive_cells_in(region).count());
+        }
+        assert_eq!(index.population_in([i64::MIN..=i64::MAX, i64::MIN..=i64::MAX, i64::MIN..=i64::MAX]), index.len());
+        assert_eq!(index.population_in(Region::new([1, 0, 0], [0, 0, 0])), 0);
+        assert_eq!(index.nearest(&[10_000, 0, 0]), Some([500, 500, 500]));
+        assert_eq!(index.nearest(&[i64::MIN, 0, 0]), Some([-1000, 0, 0]));
+        assert_eq!(
+            index.within_radius(&[i64::MAX, i64::MAX, i64::MAX], u64::MAX),
+            life.alive_within_radius(&[i64::MAX; 3], u64::MAX)
+        );
+
+        let empty = Life::<2>::new(HashSet::new(), HashSet::new()).unwrap();
+        assert_eq!(SpatialIndex::new(&empty).nearest(&[0, 0]), None);
+        assert_eq!(empty.nearest_alive(&[0, 0]), None);
+        assert!(SpatialIndex::new(&empty).within_radius(&[0, 0], 10).is_empty());
+    }
+
+    #[test]
+    fn test_update() {
+        let mut life = Life::<2>::new([3].into_iter().collect(), [2, 3].into_iter().collect()).unwrap();
+        life.randomize_region([0..=40, 0..=40], 0.4, 11);
+        let mut index = SpatialIndex::new(&life);
+        for _ in 0..20 {
+            life.next_generation();
+            assert!(index.update(&life));
+            assert_eq!(indexed(&index), life.alive_cells_sorted());
+        }
+        // skipped generations and edits are indexed again
+        life.next_generation();
+        life.next_generation();
+        assert!(!index.update(&life));
+        assert_eq!((index.age(), indexed(&index)), (life.age(), life.alive_cells_sorted()));
+        life.toggle_cell(&[100, 100]);
+        assert!(!index.update(&life));
+        assert_eq!(index.nearest(&[99, 99]), Some([100, 100]));
+    }
+}
@@ -0,0 +1,324 @@
+//! Interning of patterns up to congruence, so that many references to the same object share one copy of its cells
+//!
+//! A saved store is the magic bytes `NDLP`, a version byte (1) and the number of dimensions, followed by the number of slots
+//! and every slot: its reference count, 0 for a free slot, then for a used slot the cells as in a [delta stream](crate::delta).
+//! Integers are LEB128 variable-length integers.
+
+use crate::delta::{read_cells, read_unsigned, write_cells, write_unsigned};
+use crate::pattern::Pattern;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+/// Magic bytes at the start of a saved store.
+const MAGIC: &[u8; 4] = b"NDLP";
+/// Version of the format.
+const VERSION: u8 = 1;
+
+/// Canonical cells of a stored pattern and its number of references.
+type Slot<const N: usize> = (Arc<[[i64; N]]>, u64);
+
+/// Identifier of a pattern interned in a [PatternStore]
+///
+/// An identifier stays valid as long as the pattern is referenced, and across saving and loading the store.
+/// Once the pattern is released for the last time, the identifier may be given to another pattern,
+/// as new patterns take the free identifier of the smallest index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PatternId(u32);
+impl PatternId {
+    /// Get the index of the slot of the pattern in the store, which is smaller than the number of slots ever used.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+impl fmt::Display for PatternId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Reference counted set of patterns, each stored once as the sorted cells of its [canonical form](Pattern::canonical_form)
+///
+/// Interning a pattern congruent to a stored one returns the identifier of the stored one and counts another reference;
+/// releasing the last reference frees the slot. This keeps pipelines which find the same few objects millions of times
+/// from holding a set of cells for every occurrence.
+/// # Example
+/// ```
+/// use ndlife::pattern::Pattern;
+/// use ndlife::store::PatternStore;
+///
+/// let mut store = PatternStore::new();
+/// let glider: Pattern<2> = [[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect();
+/// let flipped: Pattern<2> = [[10, 10], [9, 10], [8, 10], [8, 11], [9, 12]].into_iter().collect();
+/// let block: Pattern<2> = [[5, 5], [6, 5], [5, 6], [6, 6]].into_iter().collect();
+///
+/// let id = store.intern(&glider);
+/// assert_eq!(store.intern(&flipped), id);
+/// let block_id = store.intern(&block);
+/// assert_eq!((store.len(), store.references(id)), (2, 2));
+/// assert!(store.pattern(id).unwrap().is_congruent(&glider));
+///
+/// // save and load, keeping the identifiers
+/// let mut bytes = Vec::new();
+/// store.save(&mut bytes).unwrap();
+/// let mut store = PatternStore::<2>::load(bytes.as_slice()).unwrap();
+/// assert_eq!(store.get(&block), Some(block_id));
+///
+/// assert_eq!(store.release(id), Some(1));
+/// assert_eq!(store.release(id), Some(0));
+/// assert_eq!(store.get(&glider), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternStore<const N: usize> {
+    /// The slots, each with the canonical cells and reference count of a pattern, or [None] if free.
+    slots: Vec<Option<Slot<N>>>,
+    /// The identifier of each stored pattern, sharing the cells of its slot.
+    ids: HashMap<Arc<[[i64; N]]>, PatternId>,
+    /// The free slots, reused smallest first.
+    free: BTreeSet<u32>,
+}
+impl<const N: usize> PatternStore<N> {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of stored patterns.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Get whether no pattern is stored.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Intern a pattern, counting a reference to it.
+    /// # Arguments
+    /// * `pattern` - The pattern, in any orientation and position.
+    /// # Returns
+    /// The identifier of the pattern, the same for all congruent patterns.
+    /// # Panics
+    /// If more than [u32::MAX] patterns are stored at once.
+    pub fn intern(&mut self, pattern: &Pattern<N>) -> PatternId {
+        let cells = pattern.canonical_cells();
+        if let Some(&id) = self.ids.get(cells.as_slice()) {
+            self.slots[id.index()].as_mut().expect("an interned pattern has a slot").1 += 1;
+            return id;
+        }
+        self.insert(cells.into(), 1)
+    }
+
+    /// Count another reference to a stored pattern, such as when copying its identifier.
+    /// # Arguments
+    /// * `id` - The identifier of the pattern.
+    /// # Returns
+    /// [Some] with the number of references afterwards, or [None] if no pattern has the identifier.
+    pub fn retain(&mut self, id: PatternId) -> Option<u64> {
+        let (_, references) = self.slots.get_mut(id.index())?.as_mut()?;
+        *references += 1;
+        Some(*references)
+    }
+
+    /// Drop a reference to a stored pattern, removing the pattern with its last reference.
+    /// # Arguments
+    /// * `id` - The identifier of the pattern.
+    /// # Returns
+    /// [Some] with the number of references afterwards, 0 if the pattern was removed, or [None] if no pattern has the identifier.
+    pub fn release(&mut self, id: PatternId) -> Option<u64> {
+        let slot = self.slots.get_mut(id.index())?;
+        let (_, references) = slot.as_mut()?;
+        *references -= 1;
+        if *references > 0 {
+            return Some(*references);
+        }
+        let (cells, _) = slot.take().expect("the slot was just borrowed");
+        self.ids.remove(&cells);
+        self.free.insert(id.0);
+        Some(0)
+    }
+
+    /// Get the identifier of a stored pattern without counting a reference.
+    /// # Arguments
+    /// * `pattern` - The pattern, in any orientation and position.
+    /// # Returns
+    /// [Some] with the identifier, or [None] if no congruent pattern is stored.
+    pub fn get(&self, pattern: &Pattern<N>) -> Option<PatternId> {
+        self.ids.get(pattern.canonical_cells().as_slice()).copied()
+    }
+
+    /// Get the sorted cells of the canonical form of a stored pattern.
+    pub fn cells(&self, id: PatternId) -> Option<&[[i64; N]]> {
+        self.slots.get(id.index())?.as_ref().map(|(cells, _)| &cells[..])
+    }
+
+    /// Get the canonical form of a stored pattern, with its bounding box starting at the origin.
+    pub fn pattern(&self, id: PatternId) -> Option<Pattern<N>> {
+        self.cells(id).map(|cells| cells.iter().copied().collect())
+    }
+
+    /// Get the number of references to a pattern, 0 if no pattern has the identifier.
+    pub fn references(&self, id: PatternId) -> u64 {
+        self.slots.get(id.index()).and_then(Option::as_ref).map_or(0, |(_, references)| *references)
+    }
+
+    /// Get an iterator over the stored patterns, with their identifiers, sorted cells and numbers of references, by identifier.
+    pub fn iter(&self) -> impl Iterator<Item = (PatternId, &[[i64; N]], u64)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.as_ref().map(|(cells, references)| (PatternId(index as u32), &cells[..], *references)))
+    }
+
+    /// Save the store with its identifiers and numbers of references.
+    /// # Arguments
+    /// * `output` - The output to write to, such as a buffered file.
+    /// # Returns
+    /// An [io::Result] which is an error if the output fails.
+    pub fn save(&self, mut output: impl Write) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_unsigned(&mut bytes, N as u64);
+        write_unsigned(&mut bytes, self.slots.len() as u64);
+        for slot in &self.slots {
+            match slot {
+                Some((cells, references)) => {
+                    write_unsigned(&mut bytes, *references);
+                    write_cells(&mut bytes, cells);
+                }
+                None => write_unsigned(&mut bytes, 0),
+            }
+            if bytes.len() >= 1 << 16 {
+                output.write_all(&bytes)?;
+                bytes.clear();
+            }
+        }
+        output.write_all(&bytes)
+    }
+
+    /// Load a saved store, with the same identifiers and numbers of references.
+    ///
+    /// The cells are trusted to be canonical forms, as checking them would take as long as interning them again.
+    /// # Arguments
+    /// * `input` - The input to read from, such as a buffered file.
+    /// # Returns
+    /// An [io::Result] containing the store, or an error.
+    /// # Errors
+    /// * [InvalidData](io::ErrorKind::InvalidData) - If the input is not a store of version 1 in `N` dimensions,
+    ///   or stores a pattern twice.
+    /// * Any error of the input, [UnexpectedEof](io::ErrorKind::UnexpectedEof) if it ends early.
+    pub fn load(mut input: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 5];
+        input.read_exact(&mut magic)?;
+        if &magic[..4] != MAGIC || magic[4] != VERSION {
+            return Err(invalid("not a pattern store of version 1"));
+        }
+        let dimensions = read_unsigned(&mut input)?;
+        if dimensions != N as u64 {
+            return Err(invalid(&format!("expected a {}-dimensional pattern store, found {} dimensions", N, dimensions)));
+        }
+        let slots = read_unsigned(&mut input)?;
+        if slots > u64::from(u32::MAX) + 1 {
+            return Err(invalid("more slots than identifiers"));
+        }
+        let mut store = Self::new();
+        for index in 0..slots {
+            let index = u32::try_from(index).expect("the identifiers of the slots were checked to fit");
+            let references = read_unsigned(&mut input)?;
+            if references == 0 {
+                store.slots.push(None);
+                store.free.insert(index);
+                continue;
+            }
+            let cells: Arc<[[i64; N]]> = read_cells(&mut input)?.into();
+            if store.ids.insert(cells.clone(), PatternId(index)).is_some() {
+                return Err(invalid("pattern stored twice"));
+            }
+            store.slots.push(Some((cells, references)));
+        }
+        Ok(store)
+    }
+
+    /// Store new cells, in a free slot if there is one.
+    fn insert(&mut self, cells: Arc<[[i64; N]]>, references: u64) -> PatternId {
+        let id = match self.free.pop_first() {
+            Some(index) => {
+                self.slots[index as usize] = Some((cells.clone(), references));
+                PatternId(index)
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("more patterns stored than identifiers");
+                self.slots.push(Some((cells.clone(), references)));
+                PatternId(index)
+            }
+        };
+        self.ids.insert(cells, id);
+        id
+    }
+}
+
+/// Get an error of malformed data.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut store = PatternStore::<3>::new();
+        let rod: Pattern<3> = [[0, 0, 0], [1, 0, 0], [2, 0, 0]].into_iter().collect();
+        let upright: Pattern<3> = [[4, 4, -1], [4, 4, 0], [4, 4, 1]].into_iter().collect();
+        let corner: Pattern<3> = [[0, 0, 0], [1, 0, 0], [0, 1, 0]].into_iter().collect();
+        let rod_id = store.intern(&rod);
+        assert_eq!(store.intern(&upright), rod_id);
+        let corner_id = store.intern(&corner);
+        assert_ne!(corner_id, rod_id);
+        assert_eq!(store.cells(rod_id), Some(&[[0, 0, 0], [0, 0, 1], [0, 0, 2]][..]));
+        assert_eq!(store.retain(rod_id), Some(3));
+        assert_eq!(store.iter().map(|(id, _, references)| (id, references)).collect::<Vec<_>>(), [(rod_id, 3), (corner_id, 1)]);
+
+        // a freed identifier is given to the next new pattern
+        assert_eq!(store.release(corner_id), Some(0));
+        assert_eq!((store.release(corner_id), store.retain(corner_id), store.references(corner_id)), (None, None, 0));
+        assert_eq!(store.pattern(corner_id), None);
+        let empty_id = store.intern(&Pattern::default());
+        assert_eq!(empty_id, corner_id);
+        assert_eq!(store.cells(empty_id), Some(&[][..]));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(&corner), None);
+        assert_eq!(store.release(PatternId(7)), None);
+    }
+
+    #[test]
+    fn test_save() {
+        let mut store = PatternStore::<2>::new();
+        let patterns: Vec<Pattern<2>> = (1..=5).map(|length| (0..length).map(|x| [x, x % 2]).collect()).collect();
+        let ids: Vec<PatternId> = patterns.iter().map(|pattern| store.intern(pattern)).collect();
+        store.retain(ids[0]);
+        store.release(ids[1]);
+        store.release(ids[3]);
+        let mut bytes = Vec::new();
+        store.save(&mut bytes).unwrap();
+        let mut loaded = PatternStore::<2>::load(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.iter().collect::<Vec<_>>(), store.iter().collect::<Vec<_>>());
+        assert_eq!(loaded.references(ids[0]), 2);
+        // the free slots are reused in the same order
+        assert_eq!(loaded.intern(&patterns[1]), store.intern(&patterns[1]));
+        assert_eq!(loaded, store);
+
+        assert_eq!(PatternStore::<3>::load(bytes.as_slice()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(PatternStore::<2>::load(&bytes[..bytes.len() - 1]).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        let twice = [b'N', b'D', b'L', b'P', 1, 2, 2, 1, 0, 1, 0];
+        assert_eq!(PatternStore::<2>::load(&twice[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        // every identifier has a slot, and one more slot than identifiers is rejected
+        let full = [b'N', b'D', b'L', b'P', 1, 2, 0x80, 0x80, 0x80, 0x80, 0x10];
+        assert_eq!(PatternStore::<2>::load(&full[..]).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        let overfull = [b'N', b'D', b'L', b'P', 1, 2, 0x81, 0x80, 0x80, 0x80, 0x10];
+        assert_eq!(PatternStore::<2>::load(&overfull[..]).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
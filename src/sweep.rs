@@ -0,0 +1,395 @@
+//! Runs of one seed under many rules, for exploring rule space
+
+use crate::error::Error;
+use crate::life::{Life, Stability, StabilityConfig};
+use crate::pattern::Pattern;
+use crate::region::Region;
+use crate::rulestring;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Initial alive cells of every run of a [Sweep]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Seed<const N: usize> {
+    /// The same pattern for every rule.
+    Pattern(Pattern<N>),
+    /// The same random soup for every rule, filling a hypercube from the origin as [randomize_region](Life::randomize_region).
+    Soup {
+        /// The number of cells along each axis.
+        size: u64,
+        /// The probability of a cell being alive.
+        density: f64,
+        /// The seed of the pseudo-random number generator.
+        seed: u64,
+    },
+}
+
+/// Iterator over all outer-totalistic rules whose birth and survival counts are taken from given sets
+///
+/// Every combination of subsets is yielded once, as birth and survival rules, starting with the rule without any counts.
+/// # Example
+/// ```
+/// use ndlife::sweep::RuleSpace;
+///
+/// let rules: Vec<_> = RuleSpace::new([3, 6], [2]).collect();
+/// assert_eq!(rules.len(), 8);
+/// assert!(rules.contains(&([3, 6].into_iter().collect(), [2].into_iter().collect())));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RuleSpace {
+    /// The sorted birth counts to choose from.
+    birth_counts: Vec<usize>,
+    /// The sorted survival counts to choose from.
+    survival_counts: Vec<usize>,
+    /// The bits of the next combination, the lowest for the birth counts.
+    next: u128,
+    /// The number of combinations.
+    end: u128,
+}
+impl RuleSpace {
+    /// Create an iterator over the rules with birth and survival counts from given sets.
+    /// # Arguments
+    /// * `birth_counts` - The birth counts to choose from; duplicates are ignored.
+    /// * `survival_counts` - The survival counts to choose from; duplicates are ignored.
+    /// # Panics
+    /// If there are more than 127 distinct counts in total.
+    pub fn new(birth_counts: impl IntoIterator<Item = usize>, survival_counts: impl IntoIterator<Item = usize>) -> Self {
+        let distinct = |counts: HashSet<usize>| {
+            let mut counts: Vec<usize> = counts.into_iter().collect();
+            counts.sort_unstable();
+            counts
+        };
+        let (birth_counts, survival_counts) = (distinct(birth_counts.into_iter().collect()), distinct(survival_counts.into_iter().collect()));
+        let bits = birth_counts.len() + survival_counts.len();
+        assert!(bits < 128, "a rule space has at most 127 counts, found {}", bits);
+        Self {
+            birth_counts,
+            survival_counts,
+            next: 0,
+            end: 1 << bits,
+        }
+    }
+
+    /// Create an iterator over every valid rule of a game of life in `N` dimensions, with birth counts from 1 and survival counts from 0.
+    /// # Panics
+    /// If `N` is more than 3, as the rule space would have more than 127 counts.
+    pub fn all<const N: usize>() -> Self {
+        Self::new(1..=Life::<N>::MAX_NEIGHBOURS, 0..=Life::<N>::MAX_NEIGHBOURS)
+    }
+}
+impl Iterator for RuleSpace {
+    type Item = (HashSet<usize>, HashSet<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let bits = self.next;
+        self.next += 1;
+        let chosen = |counts: &[usize], offset: usize| counts.iter().enumerate().filter(|(i, _)| bits >> (offset + i) & 1 == 1).map(|(_, count)| *count).collect();
+        Some((chosen(&self.birth_counts, 0), chosen(&self.survival_counts, self.birth_counts.len())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.end - self.next).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+/// Results of one rule in a [SweepTable]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRow {
+    /// The birth rules.
+    pub birth_rules: HashSet<usize>,
+    /// The survival rules.
+    pub survival_rules: HashSet<usize>,
+    /// The stable state reached.
+    pub stability: Stability,
+    /// The number of alive cells at the end of the run.
+    pub population: usize,
+    /// The values of the metrics at the end of the run, in the order of [metrics](SweepTable::metrics).
+    pub values: Vec<f64>,
+}
+impl SweepRow {
+    /// Get the rules in B/S notation.
+    pub fn rulestring(&self) -> String {
+        rulestring::format(&self.birth_rules, &self.survival_rules)
+    }
+}
+
+/// Results of a [Sweep], one row per rule in the order the rules were given
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepTable {
+    /// The names of the metrics.
+    metrics: Vec<String>,
+    /// The results of the rules.
+    rows: Vec<SweepRow>,
+}
+impl SweepTable {
+    /// Get the names of the metrics.
+    pub fn metrics(&self) -> &[String] {
+        &self.metrics
+    }
+
+    /// Get the results of the rules.
+    pub fn rows(&self) -> &[SweepRow] {
+        &self.rows
+    }
+
+    /// Get the values of a metric, one per row, or [None] if there is no metric with that name.
+    pub fn column(&self, metric: &str) -> Option<Vec<f64>> {
+        let index = self.metrics.iter().position(|name| name == metric)?;
+        Some(self.rows.iter().map(|row| row.values[index]).collect())
+    }
+
+    /// Write the table as comma-separated values, with a header row.
+    ///
+    /// The columns are the rule, the outcome (`extinct`, `still_life`, `oscillator` or `unsettled`), the age at which
+    /// the outcome was reached, the period, the population and the metrics; the age and period are empty when unknown.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("rule,outcome,age,period,population");
+        for name in &self.metrics {
+            csv.push(',');
+            csv.push_str(&csv_field(name));
+        }
+        csv.push('\n');
+        for row in &self.rows {
+            let (outcome, age, period) = match row.stability {
+                Stability::Extinct { age } => ("extinct", age.to_string(), String::new()),
+                Stability::StillLife { age } => ("still_life", age.to_string(), String::from("1")),
+                Stability::Oscillator { age, period } => ("oscillator", age.to_string(), period.to_string()),
+                Stability::Unsettled => ("unsettled", String::new(), String::new()),
+            };
+            csv.push_str(&format!("{},{},{},{},{}", csv_field(&row.rulestring()), outcome, age, period, row.population));
+            for value in &row.values {
+                csv.push_str(&format!(",{}", value));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quote a field of comma-separated values if needed.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Metric evaluated on the game of life at the end of every run.
+type Metric<const N: usize> = Box<dyn Fn(&Life<N>) -> f64 + Send + Sync>;
+
+/// Runner of one seed under many rules in parallel, collecting the outcome and metrics of every rule
+///
+/// Each rule runs the [seed](Seed) until it is [stable](Life::run_until_stable) within the limits of the sweep,
+/// then every metric is evaluated on the game of life reached. Rules are run on several threads, and the
+/// [table](SweepTable) lists them in the order they were given.
+/// # Example
+/// ```
+/// use ndlife::life::StabilityConfig;
+/// use ndlife::pattern::Pattern;
+/// use ndlife::sweep::{RuleSpace, Seed, Sweep};
+///
+/// // the R-pentomino under every rule with births on 3 and some of 2, 4 and 6 neighbours, survivals on 2 or 3
+/// let r_pentomino: Pattern<2> = [[1, 0], [0, 1], [1, 1], [1, 2], [2, 2]].into_iter().collect();
+/// let mut sweep = Sweep::new(Seed::Pattern(r_pentomino));
+/// sweep.set_stability(StabilityConfig { max_generations: 100, max_period: 10 });
+/// sweep.add_metric("width", |life| life.bounding_box().map_or(0.0, |(min, max)| (max[0] - min[0] + 1) as f64));
+///
+/// let rules = RuleSpace::new([2, 4, 6], [2, 3]).filter(|(birth, _)| !birth.contains(&2)).map(|(mut birth, survival)| {
+///     birth.insert(3);
+///     (birth, survival)
+/// });
+/// let table = sweep.run(rules).unwrap();
+/// assert_eq!(table.rows().len(), 16);
+/// assert_eq!(table.metrics(), ["width"]);
+/// assert!(table.to_csv().starts_with("rule,outcome,age,period,population,width\nB3/S,"));
+/// ```
+pub struct Sweep<const N: usize> {
+    /// The initial alive cells of every run.
+    seed: Seed<N>,
+    /// The limits on running a rule to stability.
+    stability: StabilityConfig,
+    /// The names and functions of the metrics.
+    metrics: Vec<(String, Metric<N>)>,
+    /// The number of threads running rules.
+    threads: usize,
+}
+impl<const N: usize> Sweep<N> {
+    /// Create a sweep of a seed without metrics, with the default limits of stability,
+    /// on as many threads as the machine runs in parallel.
+    /// # Arguments
+    /// * `seed` - The initial alive cells of every run.
+    pub fn new(seed: Seed<N>) -> Self {
+        Self {
+            seed,
+            stability: StabilityConfig::default(),
+            metrics: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Set the limits on running a rule to stability.
+    pub fn set_stability(&mut self, stability: StabilityConfig) {
+        self.stability = stability;
+    }
+
+    /// Set the number of threads running rules, at least 1.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Add a metric evaluated on the game of life at the end of every run.
+    /// # Arguments
+    /// * `name` - The name of the metric, its column in the table.
+    /// * `metric` - The function computing the metric.
+    pub fn add_metric(&mut self, name: impl Into<String>, metric: impl Fn(&Life<N>) -> f64 + Send + Sync + 'static) {
+        self.metrics.push((name.into(), Box::new(metric)));
+    }
+
+    /// Run the seed under every rule.
+    /// # Arguments
+    /// * `rules` - The birth and survival rules, such as a [RuleSpace].
+    /// # Returns
+    /// A [Result] containing the table of results, or an error if any rule is invalid, before running any of them.
+    /// # Errors
+    /// * [TooHighRule](Error::TooHighRule) - If any rule is greater than [MAX_NEIGHBOURS](Life::MAX_NEIGHBOURS).
+    /// * [ZeroNeighbourBirthRule](Error::ZeroNeighbourBirthRule) - If any birth rules contain 0.
+    pub fn run(&self, rules: impl IntoIterator<Item = (HashSet<usize>, HashSet<usize>)>) -> Result<SweepTable, Error> {
+        let rules: Vec<(HashSet<usize>, HashSet<usize>)> = rules.into_iter().collect();
+        let mut cells = HashSet::new();
+        for (index, (birth, survival)) in rules.iter().enumerate() {
+            let life = Life::<N>::new(birth.clone(), survival.clone())?;
+            if index == 0 {
+                cells = self.seed_cells(life);
+            }
+        }
+        let next = AtomicUsize::new(0);
+        let (rules, cells) = (&rules, &cells);
+        let mut rows: Vec<(usize, SweepRow)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(rules.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut rows = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some((birth, survival)) = rules.get(index) else {
+                                break rows;
+                            };
+                            let mut life = Life::new(birth.clone(), survival.clone()).expect("the rules were checked");
+                            life.set_alive_cells(cells.clone());
+                            rows.push((index, self.run_rule(life)));
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().expect("a sweep thread panicked")).collect()
+        });
+        rows.sort_unstable_by_key(|(index, _)| *index);
+        Ok(SweepTable {
+            metrics: self.metrics.iter().map(|(name, _)| name.clone()).collect(),
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+        })
+    }
+
+    /// Get the initial alive cells, using an empty game of life to fill soups.
+    fn seed_cells(&self, mut soup: Life<N>) -> HashSet<[i64; N]> {
+        match &self.seed {
+            Seed::Pattern(pattern) => pattern.cells().clone(),
+            Seed::Soup { size, density, seed } => {
+                let max = i64::try_from(size.saturating_sub(1)).unwrap_or(i64::MAX);
+                if *size > 0 {
+                    soup.randomize_region(Region::new([0; N], [max; N]), *density, *seed);
+                }
+                soup.into_alive_cells()
+            }
+        }
+    }
+
+    /// Run one rule to stability and evaluate the metrics.
+    fn run_rule(&self, mut life: Life<N>) -> SweepRow {
+        let stability = life.run_until_stable(self.stability);
+        SweepRow {
+            stability,
+            population: life.alive_cells().len(),
+            values: self.metrics.iter().map(|(_, metric)| metric(&life)).collect(),
+            birth_rules: life.birth_rules().clone(),
+            survival_rules: life.survival_rules().clone(),
+        }
+    }
+}
+impl<const N: usize> fmt::Debug for Sweep<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sweep")
+            .field("seed", &self.seed)
+            .field("stability", &self.stability)
+            .field("metrics", &self.metrics.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("threads", &self.threads)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(birth: &[usize], survival: &[usize]) -> (HashSet<usize>, HashSet<usize>) {
+        (birth.iter().copied().collect(), survival.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_rule_space() {
+        let mut space = RuleSpace::new([3, 3], [0, 2]);
+        assert_eq!(space.size_hint(), (8, Some(8)));
+        assert_eq!(space.next(), Some(rules(&[], &[])));
+        assert_eq!(space.next(), Some(rules(&[3], &[])));
+        assert_eq!(space.next(), Some(rules(&[], &[0])));
+        assert_eq!(space.last(), Some(rules(&[3], &[0, 2])));
+        assert_eq!(RuleSpace::all::<1>().count(), 32);
+        let all: HashSet<_> = RuleSpace::all::<2>().map(|(birth, survival)| rulestring::format(&birth, &survival)).collect();
+        assert_eq!(all.len(), 1 << 17);
+    }
+
+    #[test]
+    fn test_run() {
+        let blinker: Pattern<2> = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+        let mut sweep = Sweep::new(Seed::Pattern(blinker));
+        sweep.add_metric("cells", |life| life.alive_cells().len() as f64);
+        sweep.add_metric("age, final", |life| life.age() as f64);
+        sweep.set_threads(2);
+        let table = sweep.run([rules(&[3], &[2, 3]), rules(&[3], &[]), rules(&[], &[1, 2])]).unwrap();
+        assert_eq!(
+            table.rows().iter().map(|row| row.stability).collect::<Vec<_>>(),
+            vec![Stability::Oscillator { age: 2, period: 2 }, Stability::Extinct { age: 2 }, Stability::StillLife { age: 1 }]
+        );
+        assert_eq!(table.column("cells"), Some(vec![3.0, 0.0, 3.0]));
+        assert_eq!(table.column("missing"), None);
+        assert_eq!(
+            table.to_csv(),
+            "rule,outcome,age,period,population,cells,\"age, final\"\nB3/S23,oscillator,2,2,3,3,2\nB3/S,extinct,2,,0,0,2\nB/S12,still_life,1,1,3,3,1\n"
+        );
+        assert_eq!(sweep.run([rules(&[0], &[])]), Err(Error::ZeroNeighbourBirthRule));
+        assert_eq!(sweep.run([]).unwrap().rows(), []);
+        assert!(format!("{:?}", sweep).contains("[\"cells\", \"age, final\"]"));
+    }
+
+    #[test]
+    fn test_soup_seed() {
+        let mut sweep = Sweep::<2>::new(Seed::Soup { size: 8, density: 0.5, seed: 7 });
+        sweep.set_stability(StabilityConfig {
+            max_generations: 0,
+            max_period: 0,
+        });
+        sweep.add_metric("cells", |life| life.alive_cells().len() as f64);
+        let table = sweep.run([rules(&[3], &[2, 3]), rules(&[1], &[])]).unwrap();
+        let mut soup = crate::life::conways_game_of_life();
+        soup.randomize_region([0..=7, 0..=7], 0.5, 7);
+        assert_eq!(table.column("cells"), Some(vec![soup.alive_cells().len() as f64; 2]));
+        assert_eq!(table.rows()[1].rulestring(), "B1/S");
+    }
+}
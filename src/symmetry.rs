@@ -0,0 +1,162 @@
+//! Symmetry groups of the lattice
+
+use crate::error::Error;
+use crate::transform::Transform;
+use alloc::vec::Vec;
+
+/// Symmetry group used to build symmetric patterns
+///
+/// All symmetries fix the origin. The 2-dimensional groups act in the plane of the first two axes
+/// (`x` is axis 0, `y` is axis 1) and leave the other axes unchanged; their names follow apgsearch.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Symmetry {
+    /// Rotation by 180° (2 elements).
+    C2,
+    /// Rotations by multiples of 90° (4 elements).
+    C4,
+    /// Mirror across the `y` axis, mapping `x` to `-x` (2 elements).
+    D2,
+    /// Mirror across the diagonal, swapping `x` and `y` (2 elements).
+    D2Diagonal,
+    /// Mirrors across both the `x` and the `y` axis (4 elements).
+    D4,
+    /// Mirrors across both diagonals (4 elements).
+    D4Diagonal,
+    /// All symmetries of the square (8 elements).
+    D8,
+    /// Mirrors along every combination of axes (`2^N` elements).
+    Reflections,
+    /// All signed permutations of the axes, the full symmetry group of the N-dimensional lattice (`2^N * N!` elements).
+    SignedPermutations,
+}
+impl Symmetry {
+    /// Get the elements of the group in `N` dimensions, each as a transformation fixing the origin.
+    /// # Returns
+    /// A [Result] containing the elements of the group, starting with the identity, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If the group acts in a plane and `N` is less than 2.
+    /// # Example
+    /// ```
+    /// use ndlife::symmetry::Symmetry;
+    /// use std::collections::HashSet;
+    ///
+    /// let elements = Symmetry::C4.elements::<2>().unwrap();
+    /// let images: HashSet<[i64; 2]> = elements.iter().map(|element| element.apply(&[1, 2])).collect();
+    /// assert_eq!(images, [[1, 2], [-2, 1], [-1, -2], [2, -1]].into_iter().collect());
+    /// ```
+    pub fn elements<const N: usize>(self) -> Result<Vec<Transform<N>>, Error> {
+        // elements of the 2-dimensional groups as (swap axes, negate x, negate y)
+        let planar: &[(bool, bool, bool)] = match self {
+            Self::C2 => &[(false, false, false), (false, true, true)],
+            Self::C4 => &[(false, false, false), (true, true, false), (false, true, true), (true, false, true)],
+            Self::D2 => &[(false, false, false), (false, true, false)],
+            Self::D2Diagonal => &[(false, false, false), (true, false, false)],
+            Self::D4 => &[(false, false, false), (false, true, false), (false, false, true), (false, true, true)],
+            Self::D4Diagonal => &[(false, false, false), (true, false, false), (true, true, true), (false, true, true)],
+            Self::D8 => &[
+                (false, false, false),
+                (false, true, false),
+                (false, false, true),
+                (false, true, true),
+                (true, false, false),
+                (true, true, false),
+                (true, false, true),
+                (true, true, true),
+            ],
+            Self::Reflections => {
+                return Self::negations()
+                    .into_iter()
+                    .map(|negate| Transform::signed_permutation(core::array::from_fn(|i| i), negate))
+                    .collect()
+            }
+            Self::SignedPermutations => {
+                let negations = Self::negations::<N>();
+                return Self::permutations::<N>()
+                    .into_iter()
+                    .flat_map(|permutation| negations.iter().map(move |negate| Transform::signed_permutation(permutation, *negate)))
+                    .collect();
+            }
+        };
+        if N < 2 {
+            return Err(Error::InvalidAxis(1, N));
+        }
+        planar
+            .iter()
+            .map(|&(swap, negate_x, negate_y)| {
+                let mut permutation: [usize; N] = core::array::from_fn(|i| i);
+                let mut negate = [false; N];
+                if swap {
+                    permutation.swap(0, 1);
+                }
+                (negate[0], negate[1]) = (negate_x, negate_y);
+                Transform::signed_permutation(permutation, negate)
+            })
+            .collect()
+    }
+
+    /// Get all `2^N` combinations of negated axes.
+    fn negations<const N: usize>() -> Vec<[bool; N]> {
+        (0..1usize << N).map(|bits| core::array::from_fn(|i| bits >> i & 1 == 1)).collect()
+    }
+
+    /// Get all `N!` permutations of the axes.
+    fn permutations<const N: usize>() -> Vec<[usize; N]> {
+        let mut permutations = Vec::new();
+        let mut permutation: [usize; N] = core::array::from_fn(|i| i);
+        Self::permute(&mut permutation, 0, &mut permutations);
+        permutations
+    }
+
+    /// Collect the permutations of `permutation[start..]`, keeping the first `start` axes in place.
+    fn permute<const N: usize>(permutation: &mut [usize; N], start: usize, permutations: &mut Vec<[usize; N]>) {
+        if start == N {
+            permutations.push(*permutation);
+            return;
+        }
+        for i in start..N {
+            permutation.swap(start, i);
+            Self::permute(permutation, start + 1, permutations);
+            permutation.swap(start, i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_elements() {
+        let orders = [
+            (Symmetry::C2, 2),
+            (Symmetry::C4, 4),
+            (Symmetry::D2, 2),
+            (Symmetry::D2Diagonal, 2),
+            (Symmetry::D4, 4),
+            (Symmetry::D4Diagonal, 4),
+            (Symmetry::D8, 8),
+            (Symmetry::Reflections, 4),
+            (Symmetry::SignedPermutations, 8),
+        ];
+        for (symmetry, order) in orders {
+            let elements = symmetry.elements::<2>().unwrap();
+            assert_eq!(elements.len(), order);
+            assert_eq!(elements[0], Transform::identity());
+            let unique: HashSet<Transform<2>> = elements.iter().copied().collect();
+            assert_eq!(unique.len(), order);
+            // the elements form a group, so they are closed under composition and inversion
+            for a in elements.iter() {
+                assert!(unique.contains(&a.inverse()));
+                for b in elements.iter() {
+                    assert!(unique.contains(&a.then(b)));
+                }
+            }
+        }
+        assert_eq!(Symmetry::SignedPermutations.elements::<3>().unwrap().len(), 48);
+        assert_eq!(Symmetry::Reflections.elements::<3>().unwrap().len(), 8);
+        assert_eq!(Symmetry::C4.elements::<3>().unwrap().len(), 4);
+        assert_eq!(Symmetry::C4.elements::<1>(), Err(Error::InvalidAxis(1, 1)));
+        assert_eq!(Symmetry::Reflections.elements::<1>().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,140 @@
+//! Assertions for tests of evolving patterns
+
+use crate::collections::HashSet;
+use crate::life::Life;
+use crate::region::Region;
+use crate::view::View;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// The largest region rendered as a grid in the failure message of [assert_evolution!](crate::assert_evolution!).
+const MAX_RENDERED_CELLS: u128 = 10_000;
+
+/// Assert that a pattern evolves into an expected pattern.
+///
+/// Takes the initial cells, a rulestring in B/S notation, the number of generations and the expected cells.
+/// The cells are anything iterating over coordinates, such as a [Pattern](crate::pattern::Pattern)
+/// created with [pattern!](crate::pattern!) or an array of cells.
+/// On failure, the message lists the missing and unexpected cells and, for small patterns, draws a grid of the difference
+/// with `O` for cells alive as expected, `+` for unexpected alive cells, `-` for missing cells and `.` for dead cells.
+/// # Panics
+/// If the resulting cells are not the expected ones, or if the rulestring is invalid.
+/// # Example
+/// ```
+/// use ndlife::{assert_evolution, pattern};
+///
+/// // a glider moves one cell diagonally every 4 generations
+/// assert_evolution!(
+///     pattern!(
+///         "
+///         .O.
+///         ..O
+///         OOO
+///         "
+///     ),
+///     "B3/S23",
+///     4,
+///     [[1, -1], [2, -1], [3, -1], [3, 0], [2, 1]]
+/// );
+///
+/// // cells work too, in any dimension
+/// assert_evolution!([[0, 0, 0], [1, 0, 0]], "B3/S23", 1, []);
+/// ```
+#[macro_export]
+macro_rules! assert_evolution {
+    ($initial:expr, $rule:expr, $generations:expr, $expected:expr $(,)?) => {
+        $crate::testing::__assert_evolution($initial, $rule, $generations, $expected)
+    };
+}
+
+/// Check the evolution of a pattern, used by the [assert_evolution!](crate::assert_evolution!) macro.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_evolution<const N: usize>(initial: impl IntoIterator<Item = [i64; N]>, rule: &str, generations: u64, expected: impl IntoIterator<Item = [i64; N]>) {
+    let mut life = Life::<N>::empty_with_rulestring(rule).unwrap_or_else(|error| panic!("invalid rulestring {:?}: {}", rule, error));
+    life.extend(initial);
+    for _ in 0..generations {
+        life.next_generation();
+    }
+    let expected: HashSet<[i64; N]> = expected.into_iter().collect();
+    if life.alive_cells() != &expected {
+        panic!("{}", evolution_failure(life.alive_cells(), &expected, rule, generations));
+    }
+}
+
+/// Describe the difference between the evolved and the expected cells.
+fn evolution_failure<const N: usize>(actual: &HashSet<[i64; N]>, expected: &HashSet<[i64; N]>, rule: &str, generations: u64) -> String {
+    let sorted = |cells: crate::collections::hash_set::Difference<'_, [i64; N], _>| {
+        let mut cells: Vec<[i64; N]> = cells.copied().collect();
+        cells.sort_unstable();
+        cells
+    };
+    let missing = sorted(expected.difference(actual));
+    let unexpected = sorted(actual.difference(expected));
+
+    let mut message = format!("evolution under {} for {} generations did not give the expected cells\n", rule, generations);
+    let _ = writeln!(message, "missing cells ({}): {:?}", missing.len(), missing);
+    let _ = writeln!(message, "unexpected cells ({}): {:?}", unexpected.len(), unexpected);
+
+    let region = Region::bounding(actual.iter().chain(expected.iter())).expect("differing sets are not both empty");
+    if region.volume().is_some_and(|volume| volume <= MAX_RENDERED_CELLS) {
+        let _ = writeln!(message, "difference (O alive, + unexpected, - missing, . dead), each row from its first cell:");
+        let actual_rows = View::new(actual, region).rows();
+        let expected_rows = View::new(expected, region).rows();
+        for ((start, actual_row), (_, expected_row)) in actual_rows.zip(expected_rows) {
+            let row: String = actual_row
+                .zip(expected_row)
+                .map(|states| match states {
+                    (true, true) => 'O',
+                    (true, false) => '+',
+                    (false, true) => '-',
+                    (false, false) => '.',
+                })
+                .collect();
+            let _ = writeln!(message, "{:?} {}", start, row);
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern;
+
+    #[test]
+    fn test_assert_evolution() {
+        assert_evolution!([[0, 0], [1, 0], [2, 0]], "B3/S23", 1, [[1, -1], [1, 0], [1, 1]]);
+        assert_evolution!(pattern!("OO\nOO"), "B3/S23", 10, pattern!("OO\nOO"));
+        assert_evolution!([[0]], "B1/S", 2, [[-2], [2]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid rulestring")]
+    fn test_invalid_rulestring() {
+        assert_evolution!([[0, 0]], "B3/X", 1, []);
+    }
+
+    #[test]
+    fn test_failure_message() {
+        let actual = [[1, -1], [1, 0], [1, 1]].into_iter().collect();
+        let expected = [[0, 0], [1, 0], [2, 0]].into_iter().collect();
+        let message = evolution_failure(&actual, &expected, "B3/S23", 1);
+        assert_eq!(
+            message,
+            "evolution under B3/S23 for 1 generations did not give the expected cells\n\
+             missing cells (2): [[0, 0], [2, 0]]\n\
+             unexpected cells (2): [[1, -1], [1, 1]]\n\
+             difference (O alive, + unexpected, - missing, . dead), each row from its first cell:\n\
+             [0, 1] .+.\n\
+             [0, 0] -O-\n\
+             [0, -1] .+.\n"
+        );
+
+        // large regions are not drawn
+        let far: HashSet<[i64; 2]> = [[0, 0], [1000, 1000]].into_iter().collect();
+        assert!(!evolution_failure(&far, &HashSet::new(), "B3/S23", 0).contains("difference"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Real-time stepping of a game of life at a target rate
+
+use crate::life::Life;
+use std::time::{Duration, Instant};
+
+/// Policy of a [Ticker] for generations that became due while the ticker was not ticked
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CatchUp {
+    /// Advance every overdue generation, but at most `max_generations` per tick, dropping the rest.
+    ///
+    /// The simulation keeps pace with wall-clock time after short stalls,
+    /// while the cap keeps a long stall from freezing the frontend.
+    Burst {
+        /// The maximum number of generations advanced by a single tick.
+        max_generations: u64,
+    },
+    /// Advance at most one generation per tick, dropping overdue generations.
+    ///
+    /// The simulation slows down instead of jumping ahead when ticks are late.
+    Skip,
+}
+
+/// Rate limiter advancing a game of life at a target number of generations per second
+///
+/// Frontends call [tick](Ticker::tick) once per frame, and the ticker advances the game of life
+/// by however many generations became due since the previous tick, according to its [CatchUp] policy.
+/// The schedule starts at the first tick, so the first tick never advances.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::ticker::{CatchUp, Ticker};
+/// use std::time::{Duration, Instant};
+///
+/// let mut life = conways_game_of_life();
+/// let mut ticker = Ticker::per_second(10, CatchUp::Burst { max_generations: 5 });
+///
+/// let start = Instant::now();
+/// assert_eq!(ticker.tick_at(&mut life, start), 0);
+/// assert_eq!(ticker.tick_at(&mut life, start + Duration::from_millis(250)), 2);
+/// // a long stall is capped
+/// assert_eq!(ticker.tick_at(&mut life, start + Duration::from_secs(10)), 5);
+/// assert_eq!(life.age(), 7);
+/// assert_eq!(ticker.time_until_next(start + Duration::from_secs(10)), Duration::from_millis(100));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ticker {
+    /// The time between generations.
+    interval: Duration,
+    /// The policy for overdue generations.
+    policy: CatchUp,
+    /// The time the next generation is due, or [None] if the schedule has not started.
+    next_due: Option<Instant>,
+}
+impl Ticker {
+    /// Create a ticker advancing one generation per interval.
+    /// # Arguments
+    /// * `interval` - The time between generations (a zero interval advances as fast as the policy allows).
+    /// * `policy` - The policy for overdue generations.
+    pub fn new(interval: Duration, policy: CatchUp) -> Self {
+        Self { interval, policy, next_due: None }
+    }
+
+    /// Create a ticker advancing a given number of generations per second.
+    /// # Arguments
+    /// * `generations_per_second` - The target rate (0 removes the limit, as a zero interval).
+    /// * `policy` - The policy for overdue generations.
+    pub fn per_second(generations_per_second: u32, policy: CatchUp) -> Self {
+        Self::new(Duration::from_secs(1).checked_div(generations_per_second).unwrap_or(Duration::ZERO), policy)
+    }
+
+    /// Get the time between generations.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Set the time between generations, keeping the time the next generation is due.
+    /// # Arguments
+    /// * `interval` - The new time between generations.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Get the policy for overdue generations.
+    pub fn policy(&self) -> CatchUp {
+        self.policy
+    }
+
+    /// Set the policy for overdue generations.
+    /// # Arguments
+    /// * `policy` - The new policy.
+    pub fn set_policy(&mut self, policy: CatchUp) {
+        self.policy = policy;
+    }
+
+    /// Restart the schedule, so the next tick starts it again without advancing (e.g. after pausing).
+    pub fn reset(&mut self) {
+        self.next_due = None;
+    }
+
+    /// Advance the game of life by the generations due now.
+    /// # Arguments
+    /// * `life` - The game of life to advance.
+    /// # Returns
+    /// The number of generations advanced.
+    pub fn tick<const N: usize>(&mut self, life: &mut Life<N>) -> u64 {
+        self.tick_at(life, Instant::now())
+    }
+
+    /// Advance the game of life by the generations due at a given time.
+    ///
+    /// This is [tick](Ticker::tick) with an explicit clock, for frontends that already read the time each frame.
+    /// # Arguments
+    /// * `life` - The game of life to advance.
+    /// * `now` - The current time.
+    /// # Returns
+    /// The number of generations advanced.
+    pub fn tick_at<const N: usize>(&mut self, life: &mut Life<N>, now: Instant) -> u64 {
+        let generations = self.due_generations(now);
+        for _ in 0..generations {
+            life.next_generation();
+        }
+        generations
+    }
+
+    /// Get the time left until the next generation is due, for frontends that sleep between ticks.
+    /// # Arguments
+    /// * `now` - The current time.
+    /// # Returns
+    /// The time left, which is zero if a generation is already due or the schedule has not started.
+    pub fn time_until_next(&self, now: Instant) -> Duration {
+        self.next_due.map_or(Duration::ZERO, |next_due| next_due.saturating_duration_since(now))
+    }
+
+    /// Count the generations to advance at a given time and move the schedule past them.
+    fn due_generations(&mut self, now: Instant) -> u64 {
+        let Some(next_due) = self.next_due else {
+            self.next_due = Some(now + self.interval);
+            return 0;
+        };
+        if now < next_due {
+            return 0;
+        }
+        if self.interval.is_zero() {
+            self.next_due = Some(now);
+            return match self.policy {
+                CatchUp::Burst { max_generations } => max_generations,
+                CatchUp::Skip => 1,
+            };
+        }
+
+        // every interval elapsed since the due time makes one more generation overdue
+        let overdue = u64::try_from((now - next_due).as_nanos() / self.interval.as_nanos() + 1).unwrap_or(u64::MAX);
+        self.next_due = Some(next_due + self.interval * u32::try_from(overdue).unwrap_or(u32::MAX));
+        if self.next_due.is_some_and(|next_due| next_due <= now) {
+            // too many intervals for a single multiplication
+            self.next_due = Some(now + self.interval);
+        }
+        match self.policy {
+            CatchUp::Burst { max_generations } => overdue.min(max_generations),
+            CatchUp::Skip => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+
+    #[test]
+    fn test_burst() {
+        let mut life = conways_game_of_life();
+        let mut ticker = Ticker::new(Duration::from_millis(100), CatchUp::Burst { max_generations: 3 });
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert_eq!(ticker.time_until_next(start), Duration::ZERO);
+        assert_eq!(ticker.tick_at(&mut life, start), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(99)), 0);
+        assert_eq!(ticker.time_until_next(at(99)), Duration::from_millis(1));
+        assert_eq!(ticker.tick_at(&mut life, at(100)), 1);
+        assert_eq!(ticker.tick_at(&mut life, at(150)), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(390)), 2);
+        assert_eq!(ticker.tick_at(&mut life, at(2000)), 3);
+        // the dropped generations do not accumulate
+        assert_eq!(ticker.tick_at(&mut life, at(2050)), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(2100)), 1);
+        assert_eq!(life.age(), 7);
+
+        ticker.reset();
+        assert_eq!(ticker.tick_at(&mut life, at(5000)), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(5100)), 1);
+    }
+
+    #[test]
+    fn test_skip() {
+        let mut life = conways_game_of_life();
+        let mut ticker = Ticker::per_second(10, CatchUp::Skip);
+        assert_eq!(ticker.interval(), Duration::from_millis(100));
+        assert_eq!(ticker.policy(), CatchUp::Skip);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert_eq!(ticker.tick_at(&mut life, start), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(1000)), 1);
+        assert_eq!(ticker.tick_at(&mut life, at(1050)), 0);
+        assert_eq!(ticker.tick_at(&mut life, at(1100)), 1);
+        assert_eq!(life.age(), 2);
+
+        ticker.set_interval(Duration::from_millis(300));
+        ticker.set_policy(CatchUp::Burst { max_generations: 10 });
+        assert_eq!(ticker.tick_at(&mut life, at(1800)), 3);
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let mut life = conways_game_of_life();
+        let mut ticker = Ticker::per_second(0, CatchUp::Burst { max_generations: 4 });
+        assert_eq!(ticker.interval(), Duration::ZERO);
+        let start = Instant::now();
+        assert_eq!(ticker.tick_at(&mut life, start), 0);
+        assert_eq!(ticker.tick_at(&mut life, start), 4);
+        ticker.set_policy(CatchUp::Skip);
+        assert_eq!(ticker.tick(&mut life), 1);
+        assert_eq!(life.age(), 5);
+    }
+}
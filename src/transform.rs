@@ -0,0 +1,264 @@
+//! Exact symmetries of the lattice as signed permutations of the axes followed by a translation
+
+use crate::error::Error;
+
+/// Lattice transformation: a signed permutation of the axes followed by a translation
+///
+/// A transformation maps a cell to the cell whose `i`-th coordinate is
+/// `sign[i] * cell[permutation[i]] + translation[i]`, where every sign is `1` or `-1`.
+/// These are exactly the transformations mapping the lattice onto itself while preserving neighbourhoods,
+/// so every rotation, reflection and translation of a pattern is a [Transform].
+/// Coordinates are computed with wrapping arithmetic, so an image is exact whenever it fits in [i64],
+/// even if intermediate values such as the translation of a composition do not.
+/// # Example
+/// ```
+/// use ndlife::transform::Transform;
+///
+/// let rotation = Transform::<2>::rotation(0, 1, 1).unwrap();
+/// let translation = Transform::translation([10, 0]);
+///
+/// // rotate by 90° counter-clockwise, then translate
+/// let transform = rotation.then(&translation);
+/// assert_eq!(transform.apply(&[1, 2]), [8, 1]);
+/// assert_eq!(transform.inverse().apply(&[8, 1]), [1, 2]);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Transform<const N: usize> {
+    /// The axis of the original cell each coordinate of the image is taken from.
+    permutation: [usize; N],
+    /// The sign each coordinate of the image is multiplied by (1 or -1).
+    signs: [i64; N],
+    /// The translation applied after the signed permutation.
+    translation: [i64; N],
+}
+impl<const N: usize> Transform<N> {
+    /// Get the identity transformation, mapping every cell onto itself.
+    pub fn identity() -> Self {
+        Self {
+            permutation: core::array::from_fn(|i| i),
+            signs: [1; N],
+            translation: [0; N],
+        }
+    }
+
+    /// Create a transformation shifting every cell by an offset.
+    /// # Arguments
+    /// * `offset` - The offset to add to the coordinates of every cell.
+    pub fn translation(offset: [i64; N]) -> Self {
+        Self {
+            translation: offset,
+            ..Self::identity()
+        }
+    }
+
+    /// Create a transformation permuting and negating the axes.
+    /// # Arguments
+    /// * `permutation` - The axis of the original cell each coordinate of the image is taken from.
+    /// * `negate` - Whether each coordinate of the image is negated.
+    /// # Returns
+    /// A [Result] containing the transformation if successful, or an error.
+    /// # Errors
+    /// * [IdenticalAxes](Error::IdenticalAxes) - If an axis appears more than once in `permutation`.
+    /// * [InvalidAxis](Error::InvalidAxis) - If any axis in `permutation` is not less than `N`.
+    /// # Example
+    /// ```
+    /// use ndlife::transform::Transform;
+    ///
+    /// let transform = Transform::signed_permutation([2, 0, 1], [false, true, false]).unwrap();
+    /// assert_eq!(transform.apply(&[1, 2, 3]), [3, -1, 2]);
+    /// ```
+    pub fn signed_permutation(permutation: [usize; N], negate: [bool; N]) -> Result<Self, Error> {
+        let mut used = [false; N];
+        for &axis in permutation.iter() {
+            if axis >= N {
+                return Err(Error::InvalidAxis(axis, N));
+            }
+            if core::mem::replace(&mut used[axis], true) {
+                return Err(Error::IdenticalAxes(axis));
+            }
+        }
+        Ok(Self {
+            permutation,
+            signs: core::array::from_fn(|i| if negate[i] { -1 } else { 1 }),
+            translation: [0; N],
+        })
+    }
+
+    /// Create a rotation by a multiple of 90° about the origin, in the plane spanned by two axes.
+    ///
+    /// A positive quarter turn rotates `axis_a` onto `axis_b`, so in 2D `rotation(0, 1, 1)`
+    /// is a counter-clockwise rotation mapping `[x, y]` to `[-y, x]`.
+    /// # Arguments
+    /// * `axis_a` - The first axis spanning the plane of rotation.
+    /// * `axis_b` - The second axis spanning the plane of rotation.
+    /// * `quarter_turns` - The number of quarter turns to rotate by (negative values rotate the other way).
+    /// # Returns
+    /// A [Result] containing the rotation if successful, or an error.
+    /// # Errors
+    /// * [IdenticalAxes](Error::IdenticalAxes) - If `axis_a` and `axis_b` are the same.
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis_a` or `axis_b` is not less than `N`.
+    pub fn rotation(axis_a: usize, axis_b: usize, quarter_turns: i64) -> Result<Self, Error> {
+        for axis in [axis_a, axis_b] {
+            if axis >= N {
+                return Err(Error::InvalidAxis(axis, N));
+            }
+        }
+        if axis_a == axis_b {
+            return Err(Error::IdenticalAxes(axis_a));
+        }
+        let mut transform = Self::identity();
+        match quarter_turns.rem_euclid(4) {
+            0 => {}
+            1 => {
+                transform.permutation.swap(axis_a, axis_b);
+                transform.signs[axis_a] = -1;
+            }
+            2 => {
+                transform.signs[axis_a] = -1;
+                transform.signs[axis_b] = -1;
+            }
+            _ => {
+                transform.permutation.swap(axis_a, axis_b);
+                transform.signs[axis_b] = -1;
+            }
+        }
+        Ok(transform)
+    }
+
+    /// Create a reflection across the hyperplane through the origin perpendicular to an axis.
+    /// # Arguments
+    /// * `axis` - The axis perpendicular to the mirror, whose coordinate is negated.
+    /// # Returns
+    /// A [Result] containing the reflection if successful, or an error.
+    /// # Errors
+    /// * [InvalidAxis](Error::InvalidAxis) - If `axis` is not less than `N`.
+    pub fn reflection(axis: usize) -> Result<Self, Error> {
+        if axis >= N {
+            return Err(Error::InvalidAxis(axis, N));
+        }
+        let mut transform = Self::identity();
+        transform.signs[axis] = -1;
+        Ok(transform)
+    }
+
+    /// Get the axis of the original cell each coordinate of the image is taken from.
+    pub fn permutation(&self) -> [usize; N] {
+        self.permutation
+    }
+
+    /// Get the sign (1 or -1) each coordinate of the image is multiplied by.
+    pub fn signs(&self) -> [i64; N] {
+        self.signs
+    }
+
+    /// Get the translation applied after the signed permutation.
+    pub fn translation_offset(&self) -> [i64; N] {
+        self.translation
+    }
+
+    /// Apply the transformation to a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// The coordinates of the image of the cell.
+    pub fn apply(&self, cell: &[i64; N]) -> [i64; N] {
+        core::array::from_fn(|i| self.signs[i].wrapping_mul(cell[self.permutation[i]]).wrapping_add(self.translation[i]))
+    }
+
+    /// Compose two transformations.
+    /// # Arguments
+    /// * `next` - The transformation applied after this one.
+    /// # Returns
+    /// The transformation equivalent to applying `self` and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            permutation: core::array::from_fn(|i| self.permutation[next.permutation[i]]),
+            signs: core::array::from_fn(|i| next.signs[i] * self.signs[next.permutation[i]]),
+            translation: core::array::from_fn(|i| next.signs[i].wrapping_mul(self.translation[next.permutation[i]]).wrapping_add(next.translation[i])),
+        }
+    }
+
+    /// Get the inverse transformation, which undoes this one.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = Self::identity();
+        for i in 0..N {
+            let axis = self.permutation[i];
+            inverse.permutation[axis] = i;
+            inverse.signs[axis] = self.signs[i];
+            inverse.translation[axis] = self.signs[i].wrapping_neg().wrapping_mul(self.translation[i]);
+        }
+        inverse
+    }
+}
+impl<const N: usize> Default for Transform<N> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity() {
+        let transform = Transform::<3>::identity();
+        assert_eq!(transform.apply(&[1, -2, 3]), [1, -2, 3]);
+        assert_eq!(transform, Transform::default());
+        assert_eq!(transform.inverse(), transform);
+    }
+
+    #[test]
+    fn test_signed_permutation() {
+        let transform = Transform::signed_permutation([1, 0], [true, false]).unwrap();
+        assert_eq!(transform.apply(&[1, 2]), [-2, 1]);
+        assert_eq!(transform, Transform::rotation(0, 1, 1).unwrap());
+        assert_eq!(transform.permutation(), [1, 0]);
+        assert_eq!(transform.signs(), [-1, 1]);
+        assert_eq!(transform.translation_offset(), [0, 0]);
+        assert_eq!(Transform::signed_permutation([1, 1], [false, false]), Err(Error::IdenticalAxes(1)));
+        assert_eq!(Transform::signed_permutation([0, 2], [false, false]), Err(Error::InvalidAxis(2, 2)));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let quarter = Transform::<3>::rotation(0, 2, 1).unwrap();
+        assert_eq!(quarter.apply(&[1, 2, 3]), [-3, 2, 1]);
+        assert_eq!(Transform::<3>::rotation(0, 2, 2).unwrap().apply(&[1, 2, 3]), [-1, 2, -3]);
+        assert_eq!(Transform::<3>::rotation(0, 2, -1).unwrap().apply(&[1, 2, 3]), [3, 2, -1]);
+        assert_eq!(Transform::<3>::rotation(0, 2, 4).unwrap(), Transform::identity());
+        assert_eq!(quarter.then(&quarter), Transform::rotation(0, 2, 2).unwrap());
+        assert_eq!(quarter.inverse(), Transform::rotation(2, 0, 1).unwrap());
+        assert_eq!(Transform::<3>::rotation(1, 1, 1), Err(Error::IdenticalAxes(1)));
+        assert_eq!(Transform::<3>::rotation(0, 3, 1), Err(Error::InvalidAxis(3, 3)));
+    }
+
+    #[test]
+    fn test_reflection() {
+        let reflection = Transform::<2>::reflection(1).unwrap();
+        assert_eq!(reflection.apply(&[1, 2]), [1, -2]);
+        assert_eq!(reflection.then(&reflection), Transform::identity());
+
+        // images fitting in i64 are exact, even when the translation or the negated coordinate overflows
+        let mirror = reflection.then(&Transform::translation([0, -1]));
+        assert_eq!(mirror.apply(&[0, i64::MIN]), [0, i64::MAX]);
+        assert_eq!(mirror.inverse().apply(&[0, i64::MAX]), [0, i64::MIN]);
+        let back = Transform::translation([0, i64::MAX]).then(&Transform::translation([0, i64::MAX]));
+        assert_eq!(back.apply(&[0, 5]), [0, 3]);
+        assert_eq!(Transform::<2>::reflection(2), Err(Error::InvalidAxis(2, 2)));
+    }
+
+    #[test]
+    fn test_then_and_inverse() {
+        let a = Transform::signed_permutation([2, 0, 1], [false, true, false])
+            .unwrap()
+            .then(&Transform::translation([1, 2, 3]));
+        let b = Transform::rotation(1, 2, 1).unwrap().then(&Transform::translation([-5, 0, 7]));
+        let cell = [4, -6, 9];
+        assert_eq!(a.then(&b).apply(&cell), b.apply(&a.apply(&cell)));
+        assert_eq!(b.then(&a).apply(&cell), a.apply(&b.apply(&cell)));
+        assert_eq!(a.inverse().apply(&a.apply(&cell)), cell);
+        assert_eq!(a.then(&a.inverse()), Transform::identity());
+        assert_eq!(a.inverse().then(&a), Transform::identity());
+    }
+}
@@ -0,0 +1,354 @@
+//! Ordered sets of cells in a bitwise radix trie over interleaved coordinates
+
+use crate::region::Region;
+use alloc::vec::Vec;
+
+/// Node of a [CellTrie]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node<const N: usize> {
+    /// A cell of the set.
+    Leaf([i64; N]),
+    /// A split of the cells below by a bit of their keys.
+    Branch {
+        /// The index of the first bit of the keys in which the cells below differ.
+        bit: usize,
+        /// A cell below, sharing the bits of the keys before the split with every cell below.
+        prefix: [i64; N],
+        /// The nodes of the cells whose bit is 0 and 1.
+        children: [usize; 2],
+    },
+}
+
+/// Set of cells stored in a bitwise radix trie over their Morton keys, as an alternative to a hash set
+///
+/// The Morton key of a cell interleaves the bits of its coordinates, the highest bits of every axis first
+/// (with the sign bit flipped so that negative coordinates come first), so cells that are near each other have keys
+/// sharing long prefixes. The trie is a crit-bit tree: every branch splits its cells by the first bit of their keys
+/// in which they differ, and the nodes are kept in one arena. Cells are iterated in the order of their keys,
+/// which is a Z-order curve, and the cells in a region are found by visiting only the branches overlapping it.
+/// # Example
+/// ```
+/// use ndlife::trie::CellTrie;
+///
+/// let mut trie: CellTrie<2> = [[3, 3], [0, 0], [1, 0], [0, 1], [-1, -1], [100, 0]].into_iter().collect();
+/// assert!(trie.contains(&[1, 0]));
+/// assert!(!trie.insert([1, 0]));
+///
+/// // in Z-order
+/// assert_eq!(trie.iter().collect::<Vec<_>>(), [[-1, -1], [0, 0], [0, 1], [1, 0], [3, 3], [100, 0]]);
+/// assert_eq!(trie.cells_in([0..=3, 0..=3]).collect::<Vec<_>>(), [[0, 0], [0, 1], [1, 0], [3, 3]]);
+///
+/// assert!(trie.remove(&[0, 0]));
+/// assert_eq!(trie.len(), 5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CellTrie<const N: usize> {
+    /// The nodes, with free slots left by removed nodes.
+    nodes: Vec<Node<N>>,
+    /// The indices of the free slots of the nodes.
+    free: Vec<usize>,
+    /// The index of the root node, if there are any cells.
+    root: Option<usize>,
+    /// The number of cells.
+    len: usize,
+}
+impl<const N: usize> CellTrie<N> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    /// Get the number of cells.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get whether there are no cells.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get whether a cell is in the set.
+    pub fn contains(&self, cell: &[i64; N]) -> bool {
+        self.root.is_some_and(|root| self.leaf(root, cell) == cell)
+    }
+
+    /// Add a cell.
+    /// # Arguments
+    /// * `cell` - The cell.
+    /// # Returns
+    /// Whether the cell was not in the set before.
+    pub fn insert(&mut self, cell: [i64; N]) -> bool {
+        let Some(root) = self.root else {
+            self.root = Some(self.allocate(Node::Leaf(cell)));
+            self.len = 1;
+            return true;
+        };
+        let Some(split) = first_difference(self.leaf(root, &cell), &cell) else {
+            return false;
+        };
+        // the new branch goes above the first node splitting at a later bit
+        let (mut parent, mut node) = (None, root);
+        while let Node::Branch { bit, children, .. } = &self.nodes[node] {
+            if *bit > split {
+                break;
+            }
+            let side = key_bit(&cell, *bit);
+            (parent, node) = (Some((node, side)), children[side]);
+        }
+        let leaf = self.allocate(Node::Leaf(cell));
+        let mut children = [node; 2];
+        children[key_bit(&cell, split)] = leaf;
+        let branch = self.allocate(Node::Branch {
+            bit: split,
+            prefix: cell,
+            children,
+        });
+        self.replace_child(parent, branch);
+        self.len += 1;
+        true
+    }
+
+    /// Remove a cell.
+    /// # Arguments
+    /// * `cell` - The cell.
+    /// # Returns
+    /// Whether the cell was in the set.
+    pub fn remove(&mut self, cell: &[i64; N]) -> bool {
+        let Some(root) = self.root else {
+            return false;
+        };
+        let (mut grandparent, mut parent, mut node) = (None, None, root);
+        while let Node::Branch { bit, children, .. } = &self.nodes[node] {
+            let side = key_bit(cell, *bit);
+            (grandparent, parent, node) = (parent, Some((node, side)), children[side]);
+        }
+        if !matches!(&self.nodes[node], Node::Leaf(leaf) if leaf == cell) {
+            return false;
+        }
+        self.free.push(node);
+        match parent {
+            None => self.root = None,
+            Some((parent, side)) => {
+                let Node::Branch { children, .. } = &self.nodes[parent] else {
+                    unreachable!("the parent of a node is a branch");
+                };
+                let sibling = children[1 - side];
+                self.free.push(parent);
+                self.replace_child(grandparent, sibling);
+            }
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Remove every cell, keeping the allocated nodes.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = None;
+        self.len = 0;
+    }
+
+    /// Iterate over the cells in the order of their Morton keys.
+    pub fn iter(&self) -> impl Iterator<Item = [i64; N]> + '_ {
+        self.cells_in(Region::new([i64::MIN; N], [i64::MAX; N]))
+    }
+
+    /// Iterate over the cells inside a region in the order of their Morton keys,
+    /// visiting only the branches of the trie whose cells may be inside it.
+    /// # Arguments
+    /// * `region` - The region, such as the inclusive range of coordinates along each axis.
+    /// # Returns
+    /// An iterator over the cells in the region.
+    pub fn cells_in(&self, region: impl Into<Region<N>>) -> impl Iterator<Item = [i64; N]> + '_ {
+        let region = region.into();
+        // the nodes left to visit, and whether they are known to be inside the region
+        let mut stack: Vec<(usize, bool)> = self.root.filter(|_| !region.is_empty()).map(|root| (root, false)).into_iter().collect();
+        core::iter::from_fn(move || loop {
+            let (node, inside) = stack.pop()?;
+            match &self.nodes[node] {
+                Node::Leaf(cell) => {
+                    if inside || region.contains(cell) {
+                        return Some(*cell);
+                    }
+                }
+                Node::Branch { bit, prefix, children } => {
+                    let inside = inside || {
+                        let (min, max) = subtree_bounds(prefix, *bit);
+                        if (0..N).any(|axis| max[axis] < region.min()[axis] || min[axis] > region.max()[axis]) {
+                            continue;
+                        }
+                        (0..N).all(|axis| min[axis] >= region.min()[axis] && max[axis] <= region.max()[axis])
+                    };
+                    stack.push((children[1], inside));
+                    stack.push((children[0], inside));
+                }
+            }
+        })
+    }
+
+    /// Get an estimate of the heap memory used by the nodes, in bytes.
+    pub fn memory_estimate(&self) -> usize {
+        self.nodes.capacity() * size_of::<Node<N>>() + self.free.capacity() * size_of::<usize>()
+    }
+
+    /// Get the cell of the leaf reached from a node by following the bits of the key of a cell,
+    /// the cell of the set sharing the longest prefix of its key with it if it is below.
+    fn leaf(&self, mut node: usize, cell: &[i64; N]) -> &[i64; N] {
+        loop {
+            match &self.nodes[node] {
+                Node::Leaf(leaf) => return leaf,
+                Node::Branch { bit, children, .. } => node = children[key_bit(cell, *bit)],
+            }
+        }
+    }
+
+    /// Store a node, in a free slot if there is one.
+    fn allocate(&mut self, node: Node<N>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Make a node the child of a branch on a side, or the root if there is no branch.
+    fn replace_child(&mut self, parent: Option<(usize, usize)>, child: usize) {
+        match parent {
+            None => self.root = Some(child),
+            Some((parent, side)) => {
+                if let Node::Branch { children, .. } = &mut self.nodes[parent] {
+                    children[side] = child;
+                }
+            }
+        }
+    }
+}
+impl<const N: usize> FromIterator<[i64; N]> for CellTrie<N> {
+    fn from_iter<T: IntoIterator<Item = [i64; N]>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+impl<const N: usize> Extend<[i64; N]> for CellTrie<N> {
+    fn extend<T: IntoIterator<Item = [i64; N]>>(&mut self, iter: T) {
+        for cell in iter {
+            self.insert(cell);
+        }
+    }
+}
+
+/// Get a coordinate as an unsigned integer of the same order.
+fn unsigned(coordinate: i64) -> u64 {
+    coordinate as u64 ^ (1 << 63)
+}
+
+/// Get a bit of the Morton key of a cell, bit 0 being the highest bit of the first axis.
+fn key_bit<const N: usize>(cell: &[i64; N], bit: usize) -> usize {
+    (unsigned(cell[bit % N]) >> (63 - bit / N) & 1) as usize
+}
+
+/// Get the index of the first bit in which the Morton keys of two cells differ, or [None] if the cells are equal.
+fn first_difference<const N: usize>(a: &[i64; N], b: &[i64; N]) -> Option<usize> {
+    (0..N)
+        .filter_map(|axis| {
+            let difference = unsigned(a[axis]) ^ unsigned(b[axis]);
+            (difference != 0).then(|| difference.leading_zeros() as usize * N + axis)
+        })
+        .min()
+}
+
+/// Get the corners of the smallest region containing every cell sharing the bits of the Morton key of a cell before a bit.
+fn subtree_bounds<const N: usize>(prefix: &[i64; N], bit: usize) -> ([i64; N], [i64; N]) {
+    let mut min = [0; N];
+    let mut max = [0; N];
+    for axis in 0..N {
+        let shared = bit.saturating_sub(axis).div_ceil(N).min(64);
+        let mask = u64::MAX.checked_shl(64 - shared as u32).unwrap_or(0);
+        let low = unsigned(prefix[axis]) & mask;
+        min[axis] = (low ^ (1 << 63)) as i64;
+        max[axis] = ((low | !mask) ^ (1 << 63)) as i64;
+    }
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::SplitMix64;
+    use std::collections::HashSet;
+
+    /// Get the Morton key of a 2-dimensional cell.
+    fn key(cell: &[i64; 2]) -> u128 {
+        (0..128).fold(0, |key, bit| key << 1 | key_bit(cell, bit) as u128)
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut random = SplitMix64::new(3);
+        let mut trie = CellTrie::<2>::new();
+        let mut set = HashSet::new();
+        for round in 0..4000 {
+            let spread = if round % 7 == 0 { u64::MAX } else { 64 };
+            let cell = [(random.next_u64() % spread) as i64 - 32, (random.next_u64() % spread) as i64 - 32];
+            if random.next_u64().is_multiple_of(3) {
+                assert_eq!(trie.remove(&cell), set.remove(&cell));
+            } else {
+                assert_eq!(trie.insert(cell), set.insert(cell));
+            }
+            assert_eq!(trie.len(), set.len());
+        }
+        assert!(set.iter().all(|cell| trie.contains(cell)));
+        let mut sorted: Vec<[i64; 2]> = set.iter().copied().collect();
+        sorted.sort_by_key(key);
+        assert_eq!(trie.iter().collect::<Vec<_>>(), sorted);
+
+        // the slots of removed nodes are reused
+        let nodes = trie.nodes.len();
+        for cell in &sorted {
+            assert!(trie.remove(cell));
+        }
+        assert!(trie.is_empty() && trie.iter().next().is_none());
+        trie.extend(sorted.iter().copied());
+        assert_eq!(trie.nodes.len(), nodes);
+        trie.clear();
+        assert!(!trie.contains(&sorted[0]));
+    }
+
+    #[test]
+    fn test_cells_in() {
+        let mut random = SplitMix64::new(8);
+        let cells: Vec<[i64; 3]> = (0..2000).map(|_| std::array::from_fn(|_| (random.next_u64() % 100) as i64 - 50)).collect();
+        let mut trie: CellTrie<3> = cells.iter().copied().collect();
+        trie.extend([[i64::MIN, 0, i64::MAX], [i64::MAX; 3]]);
+        let expected = |region: Region<3>| {
+            let mut cells: Vec<[i64; 3]> = trie.iter().filter(|cell| region.contains(cell)).collect();
+            cells.sort_unstable();
+            cells
+        };
+        for _ in 0..100 {
+            let min: [i64; 3] = std::array::from_fn(|_| (random.next_u64() % 120) as i64 - 60);
+            let max: [i64; 3] = std::array::from_fn(|axis| min[axis] + (random.next_u64() % 40) as i64);
+            let region = Region::new(min, max);
+            let mut found: Vec<[i64; 3]> = trie.cells_in(region).collect();
+            found.sort_unstable();
+            assert_eq!(found, expected(region));
+        }
+        assert_eq!(trie.cells_in([i64::MIN..=i64::MIN, 0..=0, 0..=i64::MAX]).collect::<Vec<_>>(), [[i64::MIN, 0, i64::MAX]]);
+        assert_eq!(trie.cells_in(Region::new([1, 0, 0], [0, 0, 0])).count(), 0);
+        assert_eq!(subtree_bounds(&[5, -3, 0], 0), ([i64::MIN; 3], [i64::MAX; 3]));
+        assert_eq!(subtree_bounds(&[5, -3, 0], 3 * 64), ([5, -3, 0], [5, -3, 0]));
+    }
+}
@@ -0,0 +1,243 @@
+//! Common interface of the game of life engines
+
+use crate::collections::HashSet;
+use crate::compact::CompactLife;
+use crate::dynlife::DynLife;
+use crate::error::Error;
+use crate::life::Life;
+use alloc::boxed::Box;
+
+/// Game of life engine, so frontends can be written once for every engine
+///
+/// Implemented by [Life], whose cells are arrays, [CompactLife], whose cells are arrays of [i32],
+/// and [DynLife], whose cells are slices of a length chosen at runtime. Cell access returns a [Result] so that engines can reject malformed cells;
+/// [Life] never fails.
+/// # Example
+/// ```
+/// use ndlife::dynlife::DynLife;
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::universe::Universe;
+///
+/// // a frontend written once for every engine
+/// fn population_after<U: Universe + ?Sized>(universe: &mut U, generations: u64) -> usize {
+///     universe.advance(generations);
+///     universe.population()
+/// }
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+/// let mut dynlife = DynLife::from(life.clone());
+///
+/// assert_eq!(population_after(&mut life, 10), 5);
+/// assert_eq!(population_after(&mut dynlife, 10), 5);
+/// ```
+pub trait Universe {
+    /// The coordinates of a cell.
+    type Cell: ?Sized;
+
+    /// Get the age of the universe.
+    fn age(&self) -> u64;
+
+    /// Get the rules for a dead cell to become alive.
+    fn birth_rules(&self) -> &HashSet<usize>;
+
+    /// Get the rules for an alive cell to stay alive.
+    fn survival_rules(&self) -> &HashSet<usize>;
+
+    /// Advance the universe by one generation.
+    fn step(&mut self);
+
+    /// Advance the universe by a number of generations.
+    /// # Arguments
+    /// * `generations` - The number of generations to advance.
+    fn advance(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Get the state of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// A [Result] containing whether the cell is alive, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the engine checks the dimension of cells and `cell` does not match it.
+    fn state(&self, cell: &Self::Cell) -> Result<bool, Error>;
+
+    /// Set the state of a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// * `state` - The state of the cell (true = alive, false = dead).
+    /// # Returns
+    /// A [Result] containing whether the state of the cell changed, or an error.
+    /// # Errors
+    /// * [DimensionMismatch](Error::DimensionMismatch) - If the engine checks the dimension of cells and `cell` does not match it.
+    fn set_state(&mut self, cell: &Self::Cell, state: bool) -> Result<bool, Error>;
+
+    /// Get the number of alive cells.
+    fn population(&self) -> usize;
+
+    /// Iterate over the alive cells, in no particular order.
+    fn iter_alive(&self) -> Box<dyn Iterator<Item = &Self::Cell> + '_>;
+}
+
+impl<const N: usize> Universe for Life<N> {
+    type Cell = [i64; N];
+
+    fn age(&self) -> u64 {
+        self.age()
+    }
+
+    fn birth_rules(&self) -> &HashSet<usize> {
+        self.birth_rules()
+    }
+
+    fn survival_rules(&self) -> &HashSet<usize> {
+        self.survival_rules()
+    }
+
+    fn step(&mut self) {
+        self.next_generation();
+    }
+
+    fn state(&self, cell: &Self::Cell) -> Result<bool, Error> {
+        Ok(self.get_cell(cell))
+    }
+
+    fn set_state(&mut self, cell: &Self::Cell, state: bool) -> Result<bool, Error> {
+        Ok(self.set_cell(cell, state))
+    }
+
+    fn population(&self) -> usize {
+        self.alive_cells().len()
+    }
+
+    fn iter_alive(&self) -> Box<dyn Iterator<Item = &Self::Cell> + '_> {
+        Box::new(self.alive_cells().iter())
+    }
+}
+
+impl<const N: usize> Universe for CompactLife<N> {
+    type Cell = [i32; N];
+
+    fn age(&self) -> u64 {
+        self.age()
+    }
+
+    fn birth_rules(&self) -> &HashSet<usize> {
+        self.birth_rules()
+    }
+
+    fn survival_rules(&self) -> &HashSet<usize> {
+        self.survival_rules()
+    }
+
+    fn step(&mut self) {
+        self.next_generation();
+    }
+
+    fn state(&self, cell: &Self::Cell) -> Result<bool, Error> {
+        Ok(self.alive_cells().contains(cell))
+    }
+
+    fn set_state(&mut self, cell: &Self::Cell, state: bool) -> Result<bool, Error> {
+        self.set_cell(&cell.map(i64::from), state)
+    }
+
+    fn population(&self) -> usize {
+        self.alive_cells().len()
+    }
+
+    fn iter_alive(&self) -> Box<dyn Iterator<Item = &Self::Cell> + '_> {
+        Box::new(self.alive_cells().iter())
+    }
+}
+
+impl Universe for DynLife {
+    type Cell = [i64];
+
+    fn age(&self) -> u64 {
+        self.age()
+    }
+
+    fn birth_rules(&self) -> &HashSet<usize> {
+        self.birth_rules()
+    }
+
+    fn survival_rules(&self) -> &HashSet<usize> {
+        self.survival_rules()
+    }
+
+    fn step(&mut self) {
+        self.next_generation();
+    }
+
+    fn state(&self, cell: &Self::Cell) -> Result<bool, Error> {
+        self.get_cell(cell)
+    }
+
+    fn set_state(&mut self, cell: &Self::Cell, state: bool) -> Result<bool, Error> {
+        self.set_cell(cell, state)
+    }
+
+    fn population(&self) -> usize {
+        self.alive_cells().len()
+    }
+
+    fn iter_alive(&self) -> Box<dyn Iterator<Item = &Self::Cell> + '_> {
+        Box::new(self.alive_cells().iter().map(|cell| &**cell))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Place a blinker through the trait, advance it and get its sorted cells.
+    fn blinker<U: Universe<Cell = C> + ?Sized, C: ?Sized + AsRef<[i64]>>(universe: &mut U, cells: [&C; 3]) -> Vec<Vec<i64>> {
+        for cell in cells {
+            assert_eq!(universe.set_state(cell, true), Ok(true));
+        }
+        assert_eq!(universe.state(cells[1]), Ok(true));
+        universe.advance(3);
+        assert_eq!(universe.age(), 3);
+        assert_eq!(universe.population(), 3);
+        let mut alive: Vec<Vec<i64>> = universe.iter_alive().map(|cell| cell.as_ref().to_vec()).collect();
+        alive.sort();
+        alive
+    }
+
+    #[test]
+    fn test_universe() {
+        let expected = vec![vec![1, -1], vec![1, 0], vec![1, 1]];
+        let mut life = conways_game_of_life();
+        assert_eq!(blinker(&mut life, [&[0, 0], &[1, 0], &[2, 0]]), expected);
+        assert_eq!(Universe::birth_rules(&life), &[3].into_iter().collect());
+        assert_eq!(Universe::survival_rules(&life), &[2, 3].into_iter().collect());
+
+        let mut dynlife = DynLife::with_rulestring(2, "B3/S23").unwrap();
+        let cells: [&[i64]; 3] = [&[0, 0], &[1, 0], &[2, 0]];
+        assert_eq!(blinker(&mut dynlife, cells), expected);
+        assert_eq!(Universe::birth_rules(&dynlife), &[3].into_iter().collect());
+        assert_eq!(dynlife.state(&[0, 0, 0]), Err(Error::DimensionMismatch(2, 3)));
+
+        let mut compact = CompactLife::<2>::with_rulestring("B3/S23").unwrap();
+        for cell in [[0, 0], [1, 0], [2, 0]] {
+            assert_eq!(compact.set_state(&cell, true), Ok(true));
+        }
+        compact.advance(3);
+        let mut alive: Vec<Vec<i64>> = compact.iter_alive().map(|cell| cell.iter().map(|&coordinate| i64::from(coordinate)).collect()).collect();
+        alive.sort();
+        assert_eq!((Universe::age(&compact), alive), (3, expected));
+        assert_eq!(compact.state(&[1, 1]), Ok(true));
+
+        // engines can be used as trait objects
+        let mut universes: Vec<Box<dyn Universe<Cell = [i64]>>> = vec![Box::new(dynlife)];
+        universes[0].step();
+        assert_eq!(universes[0].age(), 4);
+    }
+}
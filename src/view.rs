@@ -0,0 +1,159 @@
+//! Borrowed viewports of sets of cells for rendering
+
+use crate::collections::HashSet;
+use crate::region::Region;
+use core::fmt;
+
+/// Viewport borrowing the alive cells of a game of life or pattern, restricted to a region
+///
+/// A view copies no cells, so one can be created and handed to a renderer every frame.
+/// Rows run along axis 0 and are visited in reading order: the rows with the largest coordinates
+/// along the other axes come first, so in 2D the top row (largest `y`) is first, as in plaintext art.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+///
+/// let view = life.view([0..=2, 0..=2]);
+/// assert!(view.is_alive(&[1, 2]));
+/// assert!(!view.is_alive(&[0, 2]));
+///
+/// let rows: Vec<String> = view.rows().map(|(_, row)| row.map(|alive| if alive { '#' } else { ' ' }).collect()).collect();
+/// assert_eq!(rows, vec![" # ", "  #", "###"]);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct View<'a, const N: usize> {
+    /// The alive cells.
+    cells: &'a HashSet<[i64; N]>,
+    /// The region of the view.
+    region: Region<N>,
+}
+impl<'a, const N: usize> View<'a, N> {
+    /// Create a view of a set of alive cells restricted to a region.
+    pub(crate) fn new(cells: &'a HashSet<[i64; N]>, region: Region<N>) -> Self {
+        Self { cells, region }
+    }
+
+    /// Get the region of the view.
+    pub fn region(&self) -> Region<N> {
+        self.region
+    }
+
+    /// Get whether a cell is alive and inside the view.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// # Returns
+    /// Whether the cell is alive, or `false` if it lies outside the region of the view.
+    pub fn is_alive(&self, cell: &[i64; N]) -> bool {
+        self.region.contains(cell) && self.cells.contains(cell)
+    }
+
+    /// Iterate over the alive cells inside the view, in no particular order.
+    ///
+    /// Either the cells of the region or the alive cells are scanned, whichever are fewer.
+    pub fn alive_cells(&self) -> impl Iterator<Item = [i64; N]> + 'a {
+        let (cells, region) = (self.cells, self.region);
+        let scan_region = region.volume().is_some_and(|volume| volume < cells.len() as u128);
+        let in_region = scan_region.then(|| region.iter_cells().filter(|cell| cells.contains(cell)));
+        let in_population = (!scan_region).then(|| cells.iter().copied().filter(move |cell| region.contains(cell)));
+        in_region.into_iter().flatten().chain(in_population.into_iter().flatten())
+    }
+
+    /// Iterate over the rows of the view in reading order.
+    /// # Returns
+    /// An iterator over the rows, each given as the coordinates of its first cell (the smallest along axis 0)
+    /// and an iterator over the states of its cells in increasing order along axis 0.
+    pub fn rows(&self) -> impl Iterator<Item = ([i64; N], impl Iterator<Item = bool> + 'a)> + 'a {
+        let (cells, region) = (self.cells, self.region);
+        let (min, max) = (region.min(), region.max());
+        // the complements of the coordinates of the rows, increasing as the coordinates decrease without overflowing
+        // even over the whole range of i64, with no rows at all if the region is empty
+        let complements = if region.is_empty() {
+            Region::new([0; N], [-1; N])
+        } else {
+            Region::new(
+                core::array::from_fn(|i| if i == 0 { 0 } else { !max[i] }),
+                core::array::from_fn(|i| if i == 0 { 0 } else { !min[i] }),
+            )
+        };
+        complements.iter_cells().map(move |complement| {
+            let start: [i64; N] = core::array::from_fn(|i| if i == 0 { min[0] } else { !complement[i] });
+            let row = (min[0]..=max[0]).map(move |x| {
+                let mut cell = start;
+                cell[0] = x;
+                cells.contains(&cell)
+            });
+            (start, row)
+        })
+    }
+}
+impl fmt::Display for View<'_, 2> {
+    /// Render the view as plaintext ASCII art, with alive cells as `O`, dead cells as `.` and the top row first.
+    ///
+    /// An empty view renders as an empty string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (_, row)) in self.rows().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            for alive in row {
+                f.write_str(if alive { "O" } else { "." })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_view() {
+        let cells: HashSet<[i64; 2]> = [[0, 0], [5, 5], [1, 1]].into_iter().collect();
+        let view = View::new(&cells, Region::new([0, 0], [2, 1]));
+        assert_eq!(view.region(), Region::new([0, 0], [2, 1]));
+        assert!(view.is_alive(&[1, 1]));
+        assert!(!view.is_alive(&[5, 5]));
+        let mut alive_cells: Vec<[i64; 2]> = view.alive_cells().collect();
+        alive_cells.sort();
+        assert_eq!(alive_cells, vec![[0, 0], [1, 1]]);
+        let rows: Vec<([i64; 2], Vec<bool>)> = view.rows().map(|(start, row)| (start, row.collect())).collect();
+        assert_eq!(rows, vec![([0, 1], vec![false, true, false]), ([0, 0], vec![true, false, false])]);
+        assert_eq!(view.to_string(), ".O.\nO..");
+
+        let (start, end) = (5, 4);
+        let empty = View::new(&cells, Region::from([0..=2, start..=end]));
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.to_string(), "");
+
+        // the rows of a view spanning the whole range of coordinates are numbered without overflowing
+        let cells: HashSet<[i64; 2]> = [[0, i64::MAX], [1, i64::MIN]].into_iter().collect();
+        let whole = View::new(&cells, Region::new([0, i64::MIN], [1, i64::MAX]));
+        let rows: Vec<([i64; 2], Vec<bool>)> = whole.rows().take(2).map(|(start, row)| (start, row.collect())).collect();
+        assert_eq!(rows, vec![([0, i64::MAX], vec![true, false]), ([0, i64::MAX - 1], vec![false, false])]);
+        let narrow = View::new(&cells, Region::new([0, i64::MIN], [1, i64::MIN + 1]));
+        assert_eq!(narrow.to_string(), "..\n.O");
+    }
+
+    #[test]
+    fn test_rows_3d() {
+        let cells: HashSet<[i64; 3]> = [[0, 0, 0], [1, 1, 1]].into_iter().collect();
+        let view = View::new(&cells, Region::new([0, 0, 0], [1, 1, 1]));
+        let rows: Vec<([i64; 3], Vec<bool>)> = view.rows().map(|(start, row)| (start, row.collect())).collect();
+        assert_eq!(
+            rows,
+            vec![
+                ([0, 1, 1], vec![false, true]),
+                ([0, 1, 0], vec![false, false]),
+                ([0, 0, 1], vec![false, false]),
+                ([0, 0, 0], vec![true, false]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Streaming generations to browsers over WebSocket, enabled by the `websocket` feature
+//!
+//! # Message schema
+//! Every message is a JSON text frame; cells are arrays of coordinates, sorted lexicographically.
+//! * `{"type":"snapshot","age":12,"alive":[[0,1],[2,3]]}` - All alive cells. Sent first to every new subscriber,
+//!   and to everyone when the published generation was not advanced from the last published one,
+//!   such as after the game of life was edited or generations were not published.
+//! * `{"type":"diff","age":13,"born":[[1,1]],"died":[[0,1]]}` - The cells born and died in the generation `age`,
+//!   relative to the generation before it.
+//!
+//! The server only writes; messages sent by the clients are ignored.
+
+use crate::life::Life;
+use crate::listener::Acceptor;
+use crate::lockstep::cells_hash;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+/// The GUID appended to the key of the client in the WebSocket handshake (RFC 6455).
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// How long the handshake or a write to a client can block before the client is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The most handshakes read at a time, each on its own thread.
+const MAX_HANDSHAKES: usize = 64;
+
+/// Clients that received the last published generation, and that generation.
+#[derive(Debug, Default)]
+struct Subscribers {
+    /// The connections of the clients.
+    streams: Vec<TcpStream>,
+    /// The age and the [hash](cells_hash) of the alive cells of the last published generation.
+    last: Option<(u64, u64)>,
+}
+
+/// WebSocket server streaming the generations of a headless simulation
+///
+/// Connections are accepted on a background thread and upgraded each on its own thread, so a client which is slow
+/// to send its handshake does not hold up the others; the simulation calls [publish](Self::publish) after every generation
+/// to send the diff to the subscribers (see the [module documentation](self) for the messages).
+/// Clients that disconnect or stop reading are dropped, and dropping the server stops listening.
+/// # Example
+/// ```no_run
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::websocket::WebSocketServer;
+///
+/// let server = WebSocketServer::bind("127.0.0.1:9001").unwrap();
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0], [2, 1], [1, 2]].into_iter().collect());
+/// loop {
+///     life.next_generation();
+///     server.publish(&life);
+///     std::thread::sleep(std::time::Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WebSocketServer {
+    /// The thread accepting the connections.
+    acceptor: Acceptor,
+    /// The subscribed clients, locked while a generation is published to them.
+    subscribers: Mutex<Subscribers>,
+    /// Clients upgraded since the last published generation, waiting for a snapshot, shared with the handshaking threads.
+    pending: Arc<Mutex<Vec<TcpStream>>>,
+}
+impl WebSocketServer {
+    /// Create a server listening on an address and start accepting clients.
+    /// # Arguments
+    /// * `addr` - The address to listen on, port 0 picks a free port.
+    /// # Returns
+    /// An [io::Result] containing the server if successful, or the error of binding the address.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let upgraded = Arc::clone(&pending);
+        let acceptor = Acceptor::spawn(TcpListener::bind(addr)?, MAX_HANDSHAKES, move |stream| {
+            if let Ok(stream) = handshake(stream) {
+                upgraded.lock().unwrap_or_else(PoisonError::into_inner).push(stream);
+            }
+        })?;
+        Ok(Self {
+            acceptor,
+            subscribers: Mutex::new(Subscribers::default()),
+            pending,
+        })
+    }
+
+    /// Get the address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.acceptor.local_addr()
+    }
+
+    /// Get the number of connected clients, once any generation being published has been sent.
+    pub fn client_count(&self) -> usize {
+        let subscribers = self.subscribers.lock().unwrap_or_else(PoisonError::into_inner);
+        subscribers.streams.len() + self.pending.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Send the current generation to the clients.
+    ///
+    /// Subscribers get a diff if the game of life was advanced by one generation from the last published state,
+    /// and a snapshot otherwise, such as when it was edited in between; new clients always get a snapshot.
+    /// Clients upgraded while the generation is being sent get the next one.
+    /// # Arguments
+    /// * `life` - The game of life to publish.
+    /// # Returns
+    /// The number of clients the generation was sent to.
+    pub fn publish<const N: usize>(&self, life: &Life<N>) -> usize {
+        // the subscribers stay locked so that concurrent publishes reach them in order,
+        // but the pending clients are only locked to take them, so the handshaking threads do not wait for the writes
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(PoisonError::into_inner);
+        let subscribers = &mut *subscribers;
+        let published = (life.age(), cells_hash(life.alive_cells()));
+        let follows = subscribers
+            .last
+            .is_some_and(|(age, hash)| age.checked_add(1) == Some(life.age()) && hash == cells_hash(life.previous_alive_cells()));
+        subscribers.last = Some(published);
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+
+        let mut snapshot = None;
+        let mut snapshot_frame = || snapshot.get_or_insert_with(|| text_frame(&snapshot_message(life))).clone();
+        let update = if follows { text_frame(&diff_message(life)) } else { snapshot_frame() };
+        subscribers.streams.retain_mut(|stream| stream.write_all(&update).is_ok());
+        if !pending.is_empty() {
+            let frame = snapshot_frame();
+            for mut stream in pending {
+                if stream.write_all(&frame).is_ok() {
+                    subscribers.streams.push(stream);
+                }
+            }
+        }
+        subscribers.streams.len()
+    }
+}
+
+/// Read the opening handshake of a client and upgrade the connection.
+fn handshake(stream: TcpStream) -> io::Result<TcpStream> {
+    stream.set_read_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut key = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+    let mut stream = reader.into_inner();
+    let Some(key) = key else {
+        stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "missing Sec-WebSocket-Key"));
+    };
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    )?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    Ok(stream)
+}
+
+/// Get the `Sec-WebSocket-Accept` value answering the key of a client.
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// Encode an unmasked, unfragmented text frame.
+fn text_frame(payload: &str) -> Vec<u8> {
+    let length = payload.len();
+    let mut frame = Vec::with_capacity(length + 10);
+    frame.push(0x81);
+    if length < 126 {
+        frame.push(length as u8);
+    } else if length <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend((length as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend((length as u64).to_be_bytes());
+    }
+    frame.extend(payload.as_bytes());
+    frame
+}
+
+/// Get the snapshot message of a game of life.
+fn snapshot_message<const N: usize>(life: &Life<N>) -> String {
+    let mut message = format!("{{\"type\":\"snapshot\",\"age\":{},\"alive\":", life.age());
+    write_cells(&mut message, life.alive_cells_sorted());
+    message.push('}');
+    message
+}
+
+/// Get the diff message of the last generation of a game of life.
+fn diff_message<const N: usize>(life: &Life<N>) -> String {
+    let sorted = |cells: &mut dyn Iterator<Item = &[i64; N]>| {
+        let mut cells: Vec<[i64; N]> = cells.copied().collect();
+        cells.sort_unstable();
+        cells
+    };
+    let mut message = format!("{{\"type\":\"diff\",\"age\":{},\"born\":", life.age());
+    write_cells(&mut message, sorted(&mut life.born_cells()));
+    message.push_str(",\"died\":");
+    write_cells(&mut message, sorted(&mut life.died_cells()));
+    message.push('}');
+    message
+}
+
+/// Write cells as a JSON array of coordinate arrays.
+fn write_cells<const N: usize>(message: &mut String, cells: Vec<[i64; N]>) {
+    message.push('[');
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            message.push(',');
+        }
+        message.push('[');
+        for (j, coordinate) in cell.iter().enumerate() {
+            if j > 0 {
+                message.push(',');
+            }
+            let _ = write!(message, "{}", coordinate);
+        }
+        message.push(']');
+    }
+    message.push(']');
+}
+
+/// Get the SHA-1 digest of bytes.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend((data.len() as u64 * 8).to_be_bytes());
+
+    for block in padded.chunks_exact(64) {
+        let mut words = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, temp);
+        }
+        for (value, new) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(new);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (bytes, value) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+/// Encode bytes in padded standard base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::conways_game_of_life;
+    use std::io::Read;
+    use std::thread;
+
+    /// Read one unmasked text frame sent by the server.
+    fn read_frame(stream: &mut impl Read) -> String {
+        let mut header = [0; 2];
+        stream.read_exact(&mut header).unwrap();
+        assert_eq!(header[0], 0x81);
+        let length = match header[1] {
+            126 => {
+                let mut length = [0; 2];
+                stream.read_exact(&mut length).unwrap();
+                u16::from_be_bytes(length) as usize
+            }
+            127 => {
+                let mut length = [0; 8];
+                stream.read_exact(&mut length).unwrap();
+                u64::from_be_bytes(length) as usize
+            }
+            length => length as usize,
+        };
+        let mut payload = vec![0; length];
+        stream.read_exact(&mut payload).unwrap();
+        String::from_utf8(payload).unwrap()
+    }
+
+    #[test]
+    fn test_accept_key() {
+        // the example of RFC 6455
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(sha1(&[b'a'; 100])[..4], [0x7F, 0x90, 0x00, 0x25]);
+    }
+
+    #[test]
+    fn test_text_frame() {
+        assert_eq!(text_frame("hi"), vec![0x81, 2, b'h', b'i']);
+        let frame = text_frame(&"x".repeat(300));
+        assert_eq!(frame[..4], [0x81, 126, 1, 44]);
+        assert_eq!(frame.len(), 304);
+        let frame = text_frame(&"x".repeat(70_000));
+        assert_eq!(frame[..10], [0x81, 127, 0, 0, 0, 0, 0, 1, 0x11, 0x70]);
+    }
+
+    #[test]
+    fn test_messages() {
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        assert_eq!(snapshot_message(&life), r#"{"type":"snapshot","age":0,"alive":[[0,0],[1,0],[2,0]]}"#);
+        life.next_generation();
+        assert_eq!(diff_message(&life), r#"{"type":"diff","age":1,"born":[[1,-1],[1,1]],"died":[[0,0],[2,0]]}"#);
+
+        let empty = crate::life::Life::<3>::new([3].into_iter().collect(), [2, 3].into_iter().collect()).unwrap();
+        assert_eq!(snapshot_message(&empty), r#"{"type":"snapshot","age":0,"alive":[]}"#);
+    }
+
+    #[test]
+    fn test_server() {
+        let server = WebSocketServer::bind("127.0.0.1:0").unwrap();
+        // a client which never sends its handshake does not hold up the others
+        let _stalled = TcpStream::connect(server.local_addr()).unwrap();
+        let mut client = TcpStream::connect(server.local_addr()).unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nsec-websocket-key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n")
+            .unwrap();
+        let mut reader = BufReader::new(client);
+        let mut response = String::new();
+        while !response.ends_with("\r\n\r\n") {
+            reader.read_line(&mut response).unwrap();
+        }
+        assert!(response.starts_with("HTTP/1.1 101"));
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        while server.client_count() == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let mut life = conways_game_of_life();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+        assert_eq!(server.publish(&life), 1);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"snapshot","age":0,"alive":[[0,0],[1,0],[2,0]]}"#);
+        life.next_generation();
+        server.publish(&life);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"diff","age":1,"born":[[1,-1],[1,1]],"died":[[0,0],[2,0]]}"#);
+
+        // skipping generations resends everything
+        life.next_generation();
+        life.next_generation();
+        server.publish(&life);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"snapshot","age":3,"alive":[[1,-1],[1,0],[1,1]]}"#);
+
+        // so does editing before the next generation, which was not advanced from the published state
+        life.set_cell(&[1, 1], false);
+        life.next_generation();
+        server.publish(&life);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"snapshot","age":4,"alive":[]}"#);
+        life.set_cell(&[0, 0], true);
+        life.next_generation();
+        server.publish(&life);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"snapshot","age":5,"alive":[]}"#);
+        life.next_generation();
+        server.publish(&life);
+        assert_eq!(read_frame(&mut reader), r#"{"type":"diff","age":6,"born":[],"died":[]}"#);
+
+        // a connection without a key is refused
+        let mut refused = TcpStream::connect(server.local_addr()).unwrap();
+        refused.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        refused.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"));
+
+        // dropping the server stops listening
+        let addr = server.local_addr();
+        drop(server);
+        let mut attempts = 0;
+        while TcpStream::connect(addr).is_ok() {
+            attempts += 1;
+            assert!(attempts < 1000, "the server still listens");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Zoomable, pannable and editable viewer state for embedding in GUI toolkits
+
+use crate::life::Life;
+use crate::region::Region;
+
+/// The smallest and largest sizes of a cell, in pixels.
+const CELL_SIZES: (f32, f32) = (0.25, 256.0);
+
+/// Viewer of a 2-dimensional game of life for GUI applications
+///
+/// The widget keeps the zoom and pan of a viewer and converts between screen points and cells,
+/// so an immediate-mode toolkit only has to forward its input and paint the rectangles from
+/// [alive_rects](Self::alive_rects) each frame. Screen points are in pixels relative to the top-left corner
+/// of the area the widget is drawn in, with `y` down; cells keep their `y` up.
+///
+/// It is independent of any toolkit: a response's drag delta goes to [pan](Self::pan), the scroll delta to
+/// [zoom](Self::zoom), clicks to [toggle_at](Self::toggle_at), and the rectangles to the painter.
+/// With the `egui` feature, `widget` does all of this as an egui widget.
+/// # Example
+/// ```
+/// use ndlife::life::conways_game_of_life;
+/// use ndlife::widget::LifeView;
+///
+/// let mut life = conways_game_of_life();
+/// life.set_alive_cells([[0, 0], [1, 0], [2, 0]].into_iter().collect());
+/// let size = [200.0, 100.0];
+///
+/// let mut view = LifeView::new();
+/// view.fit(&life, size);
+/// // clicking the centre of the widget kills the middle cell of the blinker
+/// assert_eq!(view.toggle_at(&mut life, [100.0, 50.0], size), [1, 0]);
+/// assert!(!life.get_cell(&[1, 0]));
+///
+/// for [left, top, width, height] in view.alive_rects(&life, size) {
+///     // paint the rectangle of an alive cell
+///     assert!(left >= 0.0 && top >= 0.0 && left + width <= 200.0 && top + height <= 100.0);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LifeView {
+    /// The point of the plane at the centre of the widget, in cells; cell `[x, y]` covers `[x, x + 1) × [y, y + 1)`.
+    centre: [f64; 2],
+    /// The size of a cell, in pixels.
+    cell_size: f32,
+}
+impl LifeView {
+    /// Create a viewer centred on the cell at the origin, with cells of 8 pixels.
+    pub fn new() -> Self {
+        Self {
+            centre: [0.5, 0.5],
+            cell_size: 8.0,
+        }
+    }
+
+    /// Get the point of the plane at the centre of the widget, in cells.
+    pub fn centre(&self) -> [f64; 2] {
+        self.centre
+    }
+
+    /// Set the point of the plane at the centre of the widget, in cells.
+    pub fn set_centre(&mut self, centre: [f64; 2]) {
+        self.centre = centre;
+    }
+
+    /// Get the size of a cell, in pixels.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Set the size of a cell, clamped to between a quarter of a pixel and 256 pixels.
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.clamp(CELL_SIZES.0, CELL_SIZES.1);
+    }
+
+    /// Get the point of the plane under a screen point, in cells.
+    fn plane_point(&self, point: [f32; 2], size: [f32; 2]) -> [f64; 2] {
+        [
+            self.centre[0] + f64::from(point[0] - size[0] / 2.0) / f64::from(self.cell_size),
+            self.centre[1] - f64::from(point[1] - size[1] / 2.0) / f64::from(self.cell_size),
+        ]
+    }
+
+    /// Get the cell under a screen point.
+    ///
+    /// Like a pixel, a cell contains the left and top edges of its [rectangle](Self::cell_rect).
+    /// # Arguments
+    /// * `point` - The screen point, relative to the top-left corner of the widget.
+    /// * `size` - The width and height of the widget.
+    pub fn cell_at(&self, point: [f32; 2], size: [f32; 2]) -> [i64; 2] {
+        let [x, y] = self.plane_point(point, size);
+        [x.floor() as i64, y.ceil() as i64 - 1]
+    }
+
+    /// Get the screen rectangle covered by a cell.
+    /// # Arguments
+    /// * `cell` - Coordinates of the cell.
+    /// * `size` - The width and height of the widget.
+    /// # Returns
+    /// The rectangle as `[left, top, width, height]`.
+    pub fn cell_rect(&self, cell: [i64; 2], size: [f32; 2]) -> [f32; 4] {
+        let cell_size = f64::from(self.cell_size);
+        let left = f64::from(size[0] / 2.0) + (cell[0] as f64 - self.centre[0]) * cell_size;
+        let top = f64::from(size[1] / 2.0) - (cell[1] as f64 + 1.0 - self.centre[1]) * cell_size;
+        [left as f32, top as f32, self.cell_size, self.cell_size]
+    }
+
+    /// Get the region of the cells at least partly visible in the widget.
+    /// # Arguments
+    /// * `size` - The width and height of the widget.
+    pub fn visible_region(&self, size: [f32; 2]) -> Region<2> {
+        let [left, top] = self.plane_point([0.0, 0.0], size);
+        let [right, bottom] = self.plane_point(size, size);
+        // cells only touching the right or top edge are not visible
+        Region::new([left.floor() as i64, bottom.floor() as i64], [right.ceil() as i64 - 1, top.ceil() as i64 - 1])
+    }
+
+    /// Pan the view by dragging, so the plane follows the pointer.
+    /// # Arguments
+    /// * `delta` - The movement of the pointer, in pixels.
+    pub fn pan(&mut self, delta: [f32; 2]) {
+        self.centre[0] -= f64::from(delta[0]) / f64::from(self.cell_size);
+        self.centre[1] += f64::from(delta[1]) / f64::from(self.cell_size);
+    }
+
+    /// Zoom the view, keeping the point of the plane under a screen point in place.
+    /// # Arguments
+    /// * `factor` - The factor the cell size is multiplied by, greater than 1 to zoom in.
+    /// * `anchor` - The screen point that stays in place, usually the pointer.
+    /// * `size` - The width and height of the widget.
+    pub fn zoom(&mut self, factor: f32, anchor: [f32; 2], size: [f32; 2]) {
+        let before = self.plane_point(anchor, size);
+        self.set_cell_size(self.cell_size * factor);
+        let after = self.plane_point(anchor, size);
+        self.centre = [self.centre[0] + before[0] - after[0], self.centre[1] + before[1] - after[1]];
+    }
+
+    /// Centre the view on the alive cells and zoom so they fit the widget, or reset the view if there are none.
+    /// # Arguments
+    /// * `life` - The game of life to fit.
+    /// * `size` - The width and height of the widget.
+    pub fn fit(&mut self, life: &Life<2>, size: [f32; 2]) {
+        let Some(bounds) = Region::bounding(life.alive_cells()) else {
+            *self = Self::new();
+            return;
+        };
+        let (min, max) = (bounds.min(), bounds.max());
+        let extent = [(max[0] - min[0] + 1) as f32, (max[1] - min[1] + 1) as f32];
+        self.centre = [(min[0] as f64 + max[0] as f64 + 1.0) / 2.0, (min[1] as f64 + max[1] as f64 + 1.0) / 2.0];
+        self.set_cell_size((size[0] / extent[0]).min(size[1] / extent[1]));
+    }
+
+    /// Toggle the cell under a screen point, for editing with clicks.
+    /// # Arguments
+    /// * `life` - The game of life to edit.
+    /// * `point` - The screen point, relative to the top-left corner of the widget.
+    /// * `size` - The width and height of the widget.
+    /// # Returns
+    /// The toggled cell.
+    pub fn toggle_at(&self, life: &mut Life<2>, point: [f32; 2], size: [f32; 2]) -> [i64; 2] {
+        let cell = self.cell_at(point, size);
+        life.toggle_cell(&cell);
+        cell
+    }
+
+    /// Create an [egui] widget showing and editing a game of life with this view, to add to a `Ui` every frame.
+    /// # Arguments
+    /// * `life` - The game of life.
+    #[cfg(feature = "egui")]
+    pub fn widget<'a>(&'a mut self, life: &'a mut Life<2>) -> LifeWidget<'a> {
+        LifeWidget { view: self, life, colour: None }
+    }
+
+    /// Get the screen rectangles of the visible alive cells, in no particular order.
+    /// # Arguments
+    /// * `life` - The game of life to render.
+    /// * `size` - The width and height of the widget.
+    /// # Returns
+    /// An iterator over the rectangles as `[left, top, width, height]`, clipped to the widget.
+    pub fn alive_rects<'a>(&'a self, life: &'a Life<2>, size: [f32; 2]) -> impl Iterator<Item = [f32; 4]> + 'a {
+        life.view(self.visible_region(size)).alive_cells().map(move |cell| {
+            let [left, top, width, height] = self.cell_rect(cell, size);
+            let (clipped_left, clipped_top) = (left.max(0.0), top.max(0.0));
+            [
+                clipped_left,
+                clipped_top,
+                (left + width).min(size[0]) - clipped_left,
+                (top + height).min(size[1]) - clipped_top,
+            ]
+        })
+    }
+}
+impl Default for LifeView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The scroll distance, in points, which zooms the [LifeWidget] in twice.
+#[cfg(feature = "egui")]
+const SCROLL_PER_DOUBLING: f32 = 100.0;
+
+/// [egui] widget showing and editing a 2-dimensional game of life through a [LifeView]
+///
+/// The widget fills the available space, paints the visible alive cells, pans the view when dragged,
+/// zooms it around the pointer when scrolled and toggles the clicked cell. The response is marked as changed
+/// when a cell is toggled. It is created with [LifeView::widget] every frame.
+/// # Example
+/// ```no_run
+/// use ndlife::life::Life;
+/// use ndlife::widget::LifeView;
+///
+/// fn show(ui: &mut egui::Ui, view: &mut LifeView, life: &mut Life<2>) {
+///     if ui.add(view.widget(life)).changed() {
+///         // a cell was toggled
+///     }
+/// }
+/// ```
+#[cfg(feature = "egui")]
+#[derive(Debug)]
+pub struct LifeWidget<'a> {
+    /// The viewer state.
+    view: &'a mut LifeView,
+    /// The game of life.
+    life: &'a mut Life<2>,
+    /// The colour of the alive cells, or [None] for the text colour of the style.
+    colour: Option<egui::Color32>,
+}
+#[cfg(feature = "egui")]
+impl LifeWidget<'_> {
+    /// Set the colour of the alive cells, instead of the text colour of the style.
+    /// # Arguments
+    /// * `colour` - The colour.
+    pub fn colour(mut self, colour: egui::Color32) -> Self {
+        self.colour = Some(colour);
+        self
+    }
+}
+#[cfg(feature = "egui")]
+impl egui::Widget for LifeWidget<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, mut response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+        let size = [rect.width(), rect.height()];
+        let local = |pos: egui::Pos2| [pos.x - rect.min.x, pos.y - rect.min.y];
+
+        let drag = response.drag_delta();
+        self.view.pan([drag.x, drag.y]);
+        if let Some(pointer) = response.hover_pos() {
+            let scroll = ui.input(|input| input.smooth_scroll_delta().y);
+            if scroll != 0.0 {
+                self.view.zoom((scroll / SCROLL_PER_DOUBLING).exp2(), local(pointer), size);
+            }
+        }
+        if response.clicked() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                self.view.toggle_at(self.life, local(pointer), size);
+                response.mark_changed();
+            }
+        }
+
+        if ui.is_rect_visible(rect) {
+            let colour = self.colour.unwrap_or_else(|| ui.visuals().text_color());
+            let painter = ui.painter_at(rect);
+            for [left, top, width, height] in self.view.alive_rects(self.life, size) {
+                let min = rect.min + egui::vec2(left, top);
+                painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(width, height)), 0.0, colour);
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: [f32; 2] = [80.0, 40.0];
+
+    #[test]
+    fn test_conversions() {
+        let view = LifeView::new();
+        assert_eq!(view.cell_rect([0, 0], SIZE), [36.0, 16.0, 8.0, 8.0]);
+        assert_eq!(view.cell_at([36.0, 16.0], SIZE), [0, 0]);
+        assert_eq!(view.cell_at([43.9, 23.9], SIZE), [0, 0]);
+        assert_eq!(view.cell_at([44.0, 24.0], SIZE), [1, -1]);
+        assert_eq!(view.cell_at([0.0, 0.0], SIZE), [-5, 2]);
+        assert_eq!(view.visible_region(SIZE), Region::new([-5, -2], [5, 2]));
+    }
+
+    #[test]
+    fn test_pan_and_zoom() {
+        let mut view = LifeView::new();
+        // dragging right by two cells moves the plane with the pointer
+        view.pan([16.0, 8.0]);
+        assert_eq!(view.centre(), [-1.5, 1.5]);
+        assert_eq!(view.cell_rect([0, 0], SIZE)[..2], [52.0, 24.0]);
+
+        let anchor = [10.0, 30.0];
+        let under = view.cell_at(anchor, SIZE);
+        view.zoom(2.0, anchor, SIZE);
+        assert_eq!(view.cell_size(), 16.0);
+        assert_eq!(view.cell_at(anchor, SIZE), under);
+        view.zoom(1e9, anchor, SIZE);
+        assert_eq!(view.cell_size(), 256.0);
+        view.set_cell_size(0.0);
+        assert_eq!(view.cell_size(), 0.25);
+    }
+
+    #[test]
+    fn test_fit_and_edit() {
+        let mut life = Life::<2>::default();
+        let mut view = LifeView::new();
+        view.pan([100.0, 0.0]);
+        view.fit(&life, SIZE);
+        assert_eq!(view, LifeView::new());
+
+        life.set_alive_cells([[0, 0], [9, 0], [4, 1]].into_iter().collect());
+        view.fit(&life, SIZE);
+        assert_eq!(view.centre(), [5.0, 1.0]);
+        assert_eq!(view.cell_size(), 8.0);
+        let mut rects: Vec<[f32; 4]> = view.alive_rects(&life, SIZE).collect();
+        rects.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(rects, vec![[0.0, 20.0, 8.0, 8.0], [32.0, 12.0, 8.0, 8.0], [72.0, 20.0, 8.0, 8.0]]);
+
+        assert_eq!(view.toggle_at(&mut life, [36.0, 16.0], SIZE), [4, 1]);
+        assert!(!life.get_cell(&[4, 1]));
+        // clipped at the edges of the widget
+        view.pan([-4.0, 0.0]);
+        assert!(view.alive_rects(&life, SIZE).any(|rect| rect == [0.0, 20.0, 4.0, 8.0]));
+    }
+
+    #[cfg(feature = "egui")]
+    #[test]
+    fn test_egui_widget() {
+        use egui::{Color32, Event, PointerButton, RawInput};
+
+        let ctx = egui::Context::default();
+        let mut life = Life::<2>::default();
+        life.set_alive_cells([[0, 0], [1, 0], [2, 0], [100, 100]].into_iter().collect());
+        let mut view = LifeView::new();
+        let frame = |view: &mut LifeView, life: &mut Life<2>, events: Vec<Event>| {
+            let input = RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(SIZE[0], SIZE[1]))),
+                events,
+                ..Default::default()
+            };
+            let mut changed = false;
+            let mut output = ctx.run_ui(input, |ui| changed = ui.add(view.widget(life).colour(Color32::RED)).changed());
+            let cells = output
+                .shapes
+                .iter()
+                .filter(|shape| matches!(&shape.shape, egui::Shape::Rect(rect) if rect.fill == Color32::RED));
+            let cells = cells.count();
+            // there is no renderer to upload the font texture to
+            output.textures_delta.clear();
+            (cells, changed)
+        };
+
+        // the distant cell is not visible
+        assert_eq!(frame(&mut view, &mut life, Vec::new()), (3, false));
+
+        // clicking the middle cell kills it
+        let pos = egui::pos2(40.0, 20.0);
+        let button = |pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        };
+        frame(&mut view, &mut life, vec![Event::PointerMoved(pos), button(true)]);
+        assert!(frame(&mut view, &mut life, vec![button(false)]).1);
+        assert!(!life.get_cell(&[0, 0]));
+        assert_eq!(frame(&mut view, &mut life, Vec::new()), (2, false));
+
+        // dragging pans instead of toggling
+        frame(&mut view, &mut life, vec![button(true)]);
+        frame(&mut view, &mut life, vec![Event::PointerMoved(pos + egui::vec2(16.0, 0.0))]);
+        assert_eq!(frame(&mut view, &mut life, vec![button(false)]), (2, false));
+        assert_eq!(view.centre(), [-1.5, 0.5]);
+
+        // scrolling up zooms in around the pointer
+        let under = view.cell_at([pos.x, pos.y], SIZE);
+        let wheel = Event::MouseWheel {
+            unit: egui::MouseWheelUnit::Point,
+            delta: egui::vec2(0.0, 50.0),
+            phase: egui::TouchPhase::Move,
+            modifiers: Default::default(),
+        };
+        frame(&mut view, &mut life, vec![Event::PointerMoved(pos), wheel]);
+        assert!(view.cell_size() > 8.0);
+        assert_eq!(view.cell_at([pos.x, pos.y], SIZE), under);
+    }
+}